@@ -0,0 +1,152 @@
+// Addresses as they're typed and as the APIs want them.
+//
+// A wallet is known by its 0x Ethereum address, while GalaChain names users by
+// alias: `eth|<address>` for an Ethereum key, `client|<id>` for any other
+// identity. Every field that takes an address accepts all of these, in any
+// case and with or without `0x` after `eth|`. `parse_address` normalizes them
+// to EIP-55 checksum case and flags mixed-case input whose checksum doesn't
+// match, which usually means a typo. Screens show an Ethereum address with its
+// alias through `display`, so one wallet reads the same everywhere.
+
+use sha3::{Digest, Keccak256};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Address {
+    /// The 40 hex digits in checksum case, without 0x
+    Ethereum(String),
+    /// The id after `client|`
+    Client(String),
+}
+
+impl Address {
+    /// The alias the GalaChain APIs take
+    pub fn galachain(&self) -> String {
+        match self {
+            Address::Ethereum(hex) => format!("eth|{}", hex),
+            Address::Client(id) => format!("client|{}", id),
+        }
+    }
+
+    /// 0x form; client aliases have none
+    pub fn ethereum(&self) -> Option<String> {
+        match self {
+            Address::Ethereum(hex) => Some(format!("0x{}", hex)),
+            Address::Client(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.ethereum() {
+            Some(ethereum) => write!(f, "{} · {}", ethereum, self.galachain()),
+            None => write!(f, "{}", self.galachain()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedAddress {
+    pub address: Address,
+    /// Mixed-case hex that doesn't match its EIP-55 checksum
+    pub checksum_mismatch: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressError {
+    Empty,
+    InvalidHex,
+    InvalidClientId,
+    UnknownFormat,
+}
+
+impl fmt::Display for AddressError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AddressError::Empty => write!(f, "Enter an address"),
+            AddressError::InvalidHex => write!(f, "Ethereum addresses are 40 hex characters after 0x or eth|"),
+            AddressError::InvalidClientId => write!(f, "client| aliases need an id without spaces or |"),
+            AddressError::UnknownFormat => write!(f, "Use a 0x address, eth|<address> or client|<id>"),
+        }
+    }
+}
+
+pub fn parse_address(input: &str) -> Result<ParsedAddress, AddressError> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err(AddressError::Empty);
+    }
+
+    if let Some(id) = strip_prefix_ignore_case(input, "client|") {
+        if id.is_empty() || id.chars().any(|c| c.is_whitespace() || c == '|') {
+            return Err(AddressError::InvalidClientId);
+        }
+        return Ok(ParsedAddress { address: Address::Client(id.to_string()), checksum_mismatch: false });
+    }
+
+    let hex = if let Some(alias) = strip_prefix_ignore_case(input, "eth|") {
+        strip_prefix_ignore_case(alias, "0x").unwrap_or(alias)
+    } else if let Some(hex) = strip_prefix_ignore_case(input, "0x") {
+        hex
+    } else {
+        return Err(AddressError::UnknownFormat);
+    };
+    if hex.len() != 40 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(AddressError::InvalidHex);
+    }
+
+    // All-lowercase or all-uppercase input carries no checksum to verify
+    let checksummed = checksum(hex);
+    let mixed_case = hex.chars().any(|c| c.is_ascii_lowercase()) && hex.chars().any(|c| c.is_ascii_uppercase());
+    Ok(ParsedAddress { checksum_mismatch: mixed_case && hex != checksummed, address: Address::Ethereum(checksummed) })
+}
+
+/// EIP-55 case for 40 hex digits without 0x.
+pub fn checksum(hex: &str) -> String {
+    let hex = hex.to_ascii_lowercase();
+    let hash = hex::encode(Keccak256::digest(hex.as_bytes()));
+    hex.chars()
+        .zip(hash.chars())
+        .map(|(c, nibble)| if c.is_ascii_alphabetic() && nibble >= '8' { c.to_ascii_uppercase() } else { c })
+        .collect()
+}
+
+/// The alias for an address the wallet already holds; anything unparseable is
+/// treated as bare Ethereum hex, as it always was.
+pub fn galachain_alias(address: &str) -> String {
+    match parse_address(address) {
+        Ok(parsed) => parsed.address.galachain(),
+        Err(_) => format!("eth|{}", checksum(address.strip_prefix("0x").unwrap_or(address))),
+    }
+}
+
+/// "0x… · eth|…" for an Ethereum address in any form, the alias alone for
+/// client ids, and the input unchanged if it isn't an address.
+pub fn display(address: &str) -> String {
+    match parse_address(address) {
+        Ok(parsed) => parsed.address.to_string(),
+        Err(_) => address.to_string(),
+    }
+}
+
+/// "0x5aAe…eAed": the start and end of the 0x form, for the header bar. Client ids
+/// and anything unparseable are cut the same way when they're long.
+pub fn truncated(address: &str) -> String {
+    let shown = match parse_address(address) {
+        Ok(parsed) => parsed.address.ethereum().unwrap_or_else(|| parsed.address.galachain()),
+        Err(_) => address.to_string(),
+    };
+    let chars: Vec<char> = shown.chars().collect();
+    if chars.len() <= 13 {
+        return shown;
+    }
+    let head: String = chars[..6].iter().collect();
+    let tail: String = chars[chars.len() - 4..].iter().collect();
+    format!("{}…{}", head, tail)
+}
+
+fn strip_prefix_ignore_case<'a>(input: &'a str, prefix: &str) -> Option<&'a str> {
+    let head = input.get(..prefix.len())?;
+    head.eq_ignore_ascii_case(prefix).then(|| &input[prefix.len()..])
+}
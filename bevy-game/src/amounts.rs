@@ -0,0 +1,167 @@
+// Exact token quantities.
+//
+// GalaChain sends quantities as decimal strings (BigNumber on the chaincode side),
+// so they are parsed into `Decimal` rather than `f64`, which can't represent
+// large balances or 8-decimal GALA amounts exactly.
+//
+// Those strings are display units. Each token class also has `decimals`: the
+// chain keeps a whole number of its smallest unit, 10^decimals of them to one
+// token, so an amount with more places than that can't exist on chain. The
+// registry fetches each class's decimals, and Balance, Transfer and Burn format
+// and check amounts with the selected token's.
+//
+// The network fee is always paid in GALA: taken off the amount's own balance for
+// GALA, from the GALA balance for any other token. Either way Transfer and Burn
+// stay disabled while the GALA balance can't cover it.
+//
+// `format_amount`/`format_units` are for display and use the separators of the
+// current locale (locale.rs); inputs and exports take the plain "1234.5" form.
+
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::{Decimal, RoundingStrategy};
+use std::fmt;
+
+/// GALA has 8 decimal places; anything finer can't be sent.
+pub const GALA_DECIMALS: u32 = 8;
+
+/// The most decimals a token class can have, as for ERC-20 tokens.
+pub const MAX_DECIMALS: u32 = 18;
+
+/// Flat fee charged on top of every transfer and burn.
+pub const NETWORK_FEE: Decimal = Decimal::ONE;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AmountError {
+    Invalid,
+    TooManyDecimals { decimals: u32 },
+    Zero,
+    /// `max` is the most that can be sent once the fee is taken off.
+    ExceedsBalance { max: Decimal },
+    /// Less GALA than the network fee, whatever the amount
+    InsufficientFee { fee: Decimal },
+}
+
+impl fmt::Display for AmountError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AmountError::Invalid => write!(f, "Not a valid amount"),
+            AmountError::TooManyDecimals { decimals: 0 } => write!(f, "This token only comes in whole units"),
+            AmountError::TooManyDecimals { decimals } => {
+                let smallest = from_base_units(1, *decimals).unwrap_or_default();
+                write!(f, "At most {} decimal places (smallest unit {})", decimals, smallest)
+            }
+            AmountError::Zero => write!(f, "Amount must be greater than zero"),
+            AmountError::ExceedsBalance { max } => {
+                write!(f, "Amount plus fee exceeds your balance (max {})", format_amount(*max))
+            }
+            AmountError::InsufficientFee { fee } => write!(f, "Insufficient GALA for network fee ({} GALA needed)", format_amount(*fee)),
+        }
+    }
+}
+
+/// Parses a user-entered or API quantity. Rejects negatives, exponents and empty input.
+pub fn parse_amount(input: &str) -> Option<Decimal> {
+    let input = input.trim();
+    if input.is_empty() || input.starts_with('-') || input.starts_with('+') {
+        return None;
+    }
+    Decimal::from_str_exact(input).ok()
+}
+
+/// Formats with thousands separators and at least two decimals, e.g. "1,234,567.12345678"
+/// ("1.234.567,12345678" in German).
+pub fn format_amount(amount: Decimal) -> String {
+    crate::locale::current().number(&group_thousands(amount.normalize(), 2))
+}
+
+/// A quantity of a token with `decimals` places: cut to those places, and showing
+/// two decimals only when the token has them ("5" of a whole-unit token, not "5.00").
+pub fn format_units(amount: Decimal, decimals: u32) -> String {
+    let amount = amount.round_dp_with_strategy(decimals, RoundingStrategy::ToZero).normalize();
+    crate::locale::current().number(&group_thousands(amount, decimals.min(2)))
+}
+
+/// The chain's count of smallest units for `amount`, or None if it isn't a whole,
+/// non-negative number of them (or doesn't fit).
+pub fn to_base_units(amount: Decimal, decimals: u32) -> Option<u128> {
+    if amount.is_sign_negative() || decimals > MAX_DECIMALS {
+        return None;
+    }
+    let units = amount.checked_mul(Decimal::from_i128_with_scale(10i128.pow(decimals), 0))?;
+    if !units.fract().is_zero() {
+        return None;
+    }
+    units.to_u128()
+}
+
+/// Display units for a count of smallest units.
+pub fn from_base_units(units: u128, decimals: u32) -> Option<Decimal> {
+    let units = i128::try_from(units).ok()?;
+    Decimal::try_from_i128_with_scale(units, decimals).ok().map(|amount| amount.normalize())
+}
+
+fn group_thousands(normalized: Decimal, min_scale: u32) -> String {
+    let text = if normalized.scale() < min_scale {
+        format!("{:.*}", min_scale as usize, normalized)
+    } else {
+        normalized.to_string()
+    };
+
+    let (sign, unsigned) = match text.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", text.as_str()),
+    };
+    let (whole, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+
+    let mut grouped = String::with_capacity(whole.len() + whole.len() / 3);
+    for (i, digit) in whole.chars().enumerate() {
+        if i > 0 && (whole.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+
+    if fraction.is_empty() {
+        format!("{}{}", sign, grouped)
+    } else {
+        format!("{}{}.{}", sign, grouped, fraction)
+    }
+}
+
+/// Checks a typed amount of a token with `decimals` places. The balance check is
+/// skipped while `available` is unknown.
+pub fn validate_amount(input: &str, available: Option<Decimal>, fee: Decimal, decimals: u32) -> Result<Decimal, AmountError> {
+    let amount = parse_amount(input).ok_or(AmountError::Invalid)?;
+    if amount.normalize().scale() > decimals {
+        return Err(AmountError::TooManyDecimals { decimals });
+    }
+    to_base_units(amount, decimals).ok_or(AmountError::Invalid)?;
+    if amount.is_zero() {
+        return Err(AmountError::Zero);
+    }
+    if let Some(available) = available {
+        if available < fee {
+            return Err(AmountError::InsufficientFee { fee });
+        }
+        if amount + fee > available {
+            return Err(AmountError::ExceedsBalance { max: max_amount(available, fee, decimals) });
+        }
+    }
+    Ok(amount)
+}
+
+/// Whether `gala_available` covers the network `fee`; an unknown balance isn't held against the user.
+pub fn check_fee(gala_available: Option<Decimal>, fee: Decimal) -> Result<(), AmountError> {
+    match gala_available {
+        Some(available) if available < fee => Err(AmountError::InsufficientFee { fee }),
+        _ => Ok(()),
+    }
+}
+
+/// Largest amount of a token with `decimals` places that leaves enough for the fee, never negative.
+pub fn max_amount(available: Decimal, fee: Decimal, decimals: u32) -> Decimal {
+    (available - fee)
+        .max(Decimal::ZERO)
+        .round_dp_with_strategy(decimals, RoundingStrategy::ToZero)
+        .normalize()
+}
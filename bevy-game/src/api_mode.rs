@@ -0,0 +1,140 @@
+// Which API shape the chain endpoints speak.
+//
+// The localdev bundler and the public GalaChain gateway run the same contracts
+// behind different fronts. The gateway serves everything from one host, names
+// the channel and contracts differently (`asset`, `token-contract`,
+// `public-key-contract`), registers users through its own identity route,
+// expects credentials with every call (credentials.rs) and may wrap contract
+// responses as `{"data": ...}` or `{"error": ...}` instead of returning the
+// `{"Status", "Data"}` body itself. `ApiMode` holds the settings each one
+// starts from and puts gateway responses back into the contract shape, so the
+// parsers in HttpChain don't need to know which one they're talking to.
+//
+// Switching modes only replaces settings still at the other mode's preset: a
+// path or URL edited by hand survives the switch. The mode is saved with the
+// settings and travels in environment profiles.
+
+use crate::ApiSettings;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+pub const GATEWAY_BASE_URL: &str = "https://gateway-mainnet.galachain.com";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiMode {
+    /// The localdev bundler: operations and identity servers, plain contract responses
+    #[default]
+    LocalDev,
+    /// The public REST gateway
+    Gateway,
+}
+
+// The settings a mode starts from
+struct Preset {
+    operations_base_url: &'static str,
+    identity_base_url: &'static str,
+    registration_endpoint: &'static str,
+    channel_name: &'static str,
+    contract_name: &'static str,
+    identity_contract_name: &'static str,
+}
+
+impl ApiMode {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ApiMode::LocalDev => "Local dev server",
+            ApiMode::Gateway => "Public gateway",
+        }
+    }
+
+    pub fn toggled(&self) -> ApiMode {
+        match self {
+            ApiMode::LocalDev => ApiMode::Gateway,
+            ApiMode::Gateway => ApiMode::LocalDev,
+        }
+    }
+
+    /// For Settings, under the mode button
+    pub fn description(&self) -> &'static str {
+        match self {
+            ApiMode::LocalDev => "Operations and identity servers from the GalaChain SDK; no credentials needed",
+            ApiMode::Gateway => "One host, channel 'asset', gateway response envelopes; needs gateway credentials",
+        }
+    }
+
+    pub fn needs_credentials(&self) -> bool {
+        *self == ApiMode::Gateway
+    }
+
+    fn preset(&self) -> Preset {
+        match self {
+            ApiMode::LocalDev => Preset {
+                operations_base_url: "http://localhost:3000",
+                identity_base_url: "http://localhost:4000",
+                registration_endpoint: "/api/identities/register",
+                channel_name: "product",
+                contract_name: "GalaChainToken",
+                identity_contract_name: "PublicKeyContract",
+            },
+            ApiMode::Gateway => Preset {
+                operations_base_url: GATEWAY_BASE_URL,
+                identity_base_url: GATEWAY_BASE_URL,
+                registration_endpoint: "/api/identities/CreateHeadlessWallet",
+                channel_name: "asset",
+                contract_name: "token-contract",
+                identity_contract_name: "public-key-contract",
+            },
+        }
+    }
+
+    /// Moves `settings` to `to`, keeping anything that isn't the current mode's preset.
+    pub fn switch(settings: &mut ApiSettings, to: ApiMode) {
+        let (from, to_preset) = (settings.mode.preset(), to.preset());
+        let fields = [
+            (&mut settings.operations_base_url, from.operations_base_url, to_preset.operations_base_url),
+            (&mut settings.identity_base_url, from.identity_base_url, to_preset.identity_base_url),
+            (&mut settings.registration_endpoint, from.registration_endpoint, to_preset.registration_endpoint),
+            (&mut settings.channel_name, from.channel_name, to_preset.channel_name),
+            (&mut settings.contract_name, from.contract_name, to_preset.contract_name),
+            (&mut settings.identity_contract_name, from.identity_contract_name, to_preset.identity_contract_name),
+        ];
+        for (field, old, new) in fields {
+            if *field == old {
+                *field = new.to_string();
+            }
+        }
+        settings.mode = to;
+    }
+
+    /// The contract response inside a response body, or the error the gateway
+    /// reported instead of one. Local dev bodies are already the contract response.
+    pub fn unwrap_response(&self, body: String) -> Result<String, String> {
+        if *self == ApiMode::LocalDev {
+            return Ok(body);
+        }
+        let Ok(Value::Object(mut envelope)) = serde_json::from_str::<Value>(&body) else {
+            return Ok(body);
+        };
+        // Passed through as the contract returned it
+        if envelope.contains_key("Status") {
+            return Ok(body);
+        }
+        if let Some(error) = envelope.remove("error").filter(|error| !error.is_null()) {
+            return Err(match error {
+                Value::String(message) => message,
+                Value::Object(details) => details
+                    .get("message")
+                    .and_then(Value::as_str)
+                    .map(str::to_string)
+                    .unwrap_or_else(|| Value::Object(details).to_string()),
+                other => other.to_string(),
+            });
+        }
+        match envelope.remove("data") {
+            Some(Value::Object(inner)) if inner.contains_key("Status") => Ok(Value::Object(inner).to_string()),
+            Some(data) => Ok(serde_json::json!({ "Status": 1, "Data": data }).to_string()),
+            None => Ok(body),
+        }
+    }
+}
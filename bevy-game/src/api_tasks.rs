@@ -0,0 +1,227 @@
+// Background GalaChain API calls as entities.
+//
+// Each request is spawned as an entity holding an `ApiTask<T>` plus an
+// `ApiTaskHandle` naming the operation. `poll_api_tasks::<T>` turns finished
+// tasks into `ApiResult<T>` events and despawns the entity, so any number of
+// requests (of any result type) can be in flight at once. A new API call only
+// needs `add_api_task::<T>()` and a system reading its `ApiResult<T>` events.
+//
+// What's in flight is mirrored in the `InFlight` resource, which only changes
+// when a task starts or ends. Screens read it instead of keeping "loading"
+// flags of their own, and main.rs draws the busy overlay from it for the
+// operations that have a `busy_label`.
+
+use crate::{CancelToken, GalaChainClient, GalaChainError};
+use bevy::prelude::*;
+use bevy::tasks::{block_on, poll_once, IoTaskPool, Task};
+use std::time::{Duration, Instant};
+
+/// Which operation a task performs, for cancelling and "in flight" checks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskKind {
+    Balance,
+    RegistrationCheck,
+    Registration,
+    /// GetPublicKey for a transfer recipient rather than our own wallet
+    RecipientCheck,
+    /// One address on the address dashboard
+    DashboardBalance,
+    /// One address of the import screen's derivation preview
+    DerivationBalance,
+    /// One token at a legacy address, for the migration plan
+    Migration,
+    /// Token icon download for the registry
+    TokenIcon,
+    /// The chain's decimals for registry tokens
+    TokenClasses,
+    /// Posting or polling a pending-approval record
+    Approval,
+    /// Burning to enter an event, or claiming an entry
+    Event,
+    /// Registering a transfer recipient's public key on their behalf
+    SponsoredRegistration,
+    /// The holds behind a locked balance, for the Balance screen's table
+    Holds,
+    /// Settings' gateway credential check
+    ConnectionTest,
+    /// A Settings test request to one endpoint template
+    EndpointTest,
+    /// One token of the Balance screen's all-tokens list
+    TokenBalance,
+    /// The NFT instances of a class, for the Balance screen's drill-down
+    Instances,
+    /// One NFT instance's metadata
+    InstanceMetadata,
+    /// A Chain Explorer query
+    Explorer,
+    /// The Chain Explorer asking the contract for its methods (GetContractAPI)
+    ContractApi,
+}
+
+impl TaskKind {
+    /// What the busy overlay says while it runs; None for work that goes on in the background.
+    pub fn busy_label(self) -> Option<&'static str> {
+        match self {
+            TaskKind::Balance => Some("Fetching balance"),
+            TaskKind::RegistrationCheck => Some("Checking registration"),
+            TaskKind::Registration => Some("Registering public key"),
+            TaskKind::Migration => Some("Checking legacy balances"),
+            TaskKind::Event => Some("Submitting event transaction"),
+            TaskKind::SponsoredRegistration => Some("Registering recipient"),
+            TaskKind::ConnectionTest => Some("Testing gateway connection"),
+            TaskKind::EndpointTest => Some("Testing endpoint"),
+            TaskKind::Explorer => Some("Querying chain state"),
+            TaskKind::ContractApi => Some("Discovering contract methods"),
+            TaskKind::RecipientCheck
+            | TaskKind::DashboardBalance
+            | TaskKind::DerivationBalance
+            | TaskKind::TokenIcon
+            | TaskKind::TokenClasses
+            | TaskKind::Approval
+            | TaskKind::Holds
+            | TaskKind::TokenBalance
+            | TaskKind::Instances
+            | TaskKind::InstanceMetadata => None,
+        }
+    }
+}
+
+/// Non-generic part of a task entity, so tasks can be found without knowing their result type.
+#[derive(Component)]
+pub struct ApiTaskHandle {
+    pub kind: TaskKind,
+    pub started: Instant,
+    token: CancelToken,
+}
+
+/// Each kind of task in flight with when its oldest task started, oldest first.
+#[derive(Resource, Debug, Default, Clone, PartialEq)]
+pub struct InFlight {
+    pub tasks: Vec<(TaskKind, Instant)>,
+}
+
+impl InFlight {
+    pub fn from_tasks(tasks: impl IntoIterator<Item = (TaskKind, Instant)>) -> Self {
+        let mut kinds: Vec<(TaskKind, Instant)> = Vec::new();
+        for (kind, started) in tasks {
+            match kinds.iter_mut().find(|(seen, _)| *seen == kind) {
+                Some((_, oldest)) => *oldest = (*oldest).min(started),
+                None => kinds.push((kind, started)),
+            }
+        }
+        kinds.sort_by_key(|(_, started)| *started);
+        Self { tasks: kinds }
+    }
+
+    /// The oldest operation the overlay covers the screen for, and how many others are waiting.
+    pub fn busy(&self) -> Option<(TaskKind, Instant, usize)> {
+        let mut busy = self.tasks.iter().filter(|(kind, _)| kind.busy_label().is_some());
+        let (kind, started) = *busy.next()?;
+        Some((kind, started, busy.count()))
+    }
+}
+
+/// "4s", or "1m 05s" from a minute on.
+pub fn format_elapsed(elapsed: Duration) -> String {
+    let secs = elapsed.as_secs();
+    if secs < 60 {
+        format!("{}s", secs)
+    } else {
+        format!("{}m {:02}s", secs / 60, secs % 60)
+    }
+}
+
+#[derive(Component)]
+pub struct ApiTask<T: Send + 'static> {
+    task: Task<Result<T, GalaChainError>>,
+}
+
+/// Results are typed by payload; handlers also match `kind` since operations may share a payload type.
+#[derive(Event, Debug)]
+pub struct ApiResult<T: Send + Sync + 'static> {
+    pub kind: TaskKind,
+    pub result: Result<T, GalaChainError>,
+}
+
+/// Polling runs in this set; result handlers go `.after(ApiTaskSet)` to see results the same frame.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ApiTaskSet;
+
+/// Runs `job` on the IO pool with its own cancellable copy of the client.
+pub fn spawn_api_task<T: Send + Sync + 'static>(
+    commands: &mut Commands,
+    client: &GalaChainClient,
+    kind: TaskKind,
+    job: impl FnOnce(GalaChainClient) -> Result<T, GalaChainError> + Send + 'static,
+) {
+    let token = CancelToken::default();
+    let mut client = client.clone();
+    client.cancel = token.clone();
+
+    info!("Starting {:?} task", kind);
+    let task = IoTaskPool::get().spawn(async move {
+        let result = job(client);
+        // The window may be idle; polling only sees the result on the next frame
+        crate::redraw::wake_event_loop();
+        result
+    });
+    commands.spawn((ApiTaskHandle { kind, started: Instant::now(), token }, ApiTask { task }));
+}
+
+pub fn poll_api_tasks<T: Send + Sync + 'static>(
+    mut commands: Commands,
+    mut tasks: Query<(Entity, &ApiTaskHandle, &mut ApiTask<T>)>,
+    mut results: EventWriter<ApiResult<T>>,
+) {
+    for (entity, handle, mut api_task) in &mut tasks {
+        // Cancelled this frame; the despawn just hasn't been applied yet
+        if handle.token.is_cancelled() {
+            continue;
+        }
+        if let Some(result) = block_on(poll_once(&mut api_task.task)) {
+            info!("{:?} task finished", handle.kind);
+            commands.entity(entity).despawn();
+            results.send(ApiResult { kind: handle.kind, result });
+        }
+    }
+}
+
+// Runs after polling, so a result and the end of its task show up in the same frame
+fn track_in_flight(handles: Query<&ApiTaskHandle>, mut in_flight: ResMut<InFlight>) {
+    in_flight.set_if_neq(InFlight::from_tasks(handles.iter().map(|handle| (handle.kind, handle.started))));
+}
+
+/// True while a task of `kind` is in flight, so its button can stay disabled.
+pub fn is_task_running(handles: &Query<&ApiTaskHandle>, kind: TaskKind) -> bool {
+    handles.iter().any(|handle| handle.kind == kind)
+}
+
+/// Stops every running task of `kind`. Despawning drops the task, so no result is ever sent.
+/// Returns true if anything was cancelled.
+pub fn cancel_api_tasks(commands: &mut Commands, handles: &Query<(Entity, &ApiTaskHandle)>, kind: TaskKind) -> bool {
+    let mut cancelled = false;
+    for (entity, handle) in handles.iter().filter(|(_, handle)| handle.kind == kind) {
+        handle.token.cancel();
+        commands.entity(entity).despawn();
+        cancelled = true;
+    }
+    if cancelled {
+        info!("Cancelled {:?} task", kind);
+    }
+    cancelled
+}
+
+pub trait ApiTaskAppExt {
+    /// Registers the result event and polling system for tasks returning `T`.
+    fn add_api_task<T: Send + Sync + 'static>(&mut self) -> &mut Self;
+}
+
+impl ApiTaskAppExt for App {
+    fn add_api_task<T: Send + Sync + 'static>(&mut self) -> &mut Self {
+        if !self.world().contains_resource::<InFlight>() {
+            self.init_resource::<InFlight>().add_systems(Update, track_in_flight.after(ApiTaskSet));
+        }
+        self.add_event::<ApiResult<T>>()
+            .add_systems(Update, poll_api_tasks::<T>.in_set(ApiTaskSet))
+    }
+}
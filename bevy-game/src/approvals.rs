@@ -0,0 +1,234 @@
+// Second confirmation for large transfers.
+//
+// With the policy on, a transfer above the threshold isn't signed when Transfer
+// is pressed. It waits in the approval queue until it is confirmed a second
+// time, then it is signed and sent from the Approvals screen. Confirmation is
+// either the presence check on this device (Touch ID, Windows Hello or its
+// fallback passphrase - biometric.rs) followed by reading the wallet from the
+// keychain again, or a second registered device approving a pending-approval
+// record:
+//
+//   POST {endpoint}        the record below, as JSON
+//   GET  {endpoint}/{id}   {"status": "pending" | "approved" | "rejected",
+//                           "signature": personal_sign of the record id}
+//
+// The second device is registered by pasting its public key into Settings when
+// pairing. An "approved" answer only counts with a signature over the record id
+// from that key, so whoever runs the endpoint can't approve anything alone.
+//
+// The policy and the queue are saved together to `approvals.json`.
+
+use crate::signing;
+use crate::tokens::TokenInfo;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Thresholds Settings cycles through, in whole tokens.
+pub const THRESHOLD_OPTIONS: [u32; 5] = [100, 1_000, 10_000, 100_000, 1_000_000];
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ApprovalMethod {
+    /// Pass the presence check, then read the wallet from the keychain again
+    /// and check it's the one the transfer is from. Reading the keychain alone
+    /// asks for nothing once it's unlocked, so this can't approve anything
+    /// until a fallback passphrase is set in Settings
+    #[default]
+    Reauthenticate,
+    /// Wait for another device to approve the record posted to the endpoint
+    SecondDevice,
+}
+
+impl ApprovalMethod {
+    pub fn label(self) -> &'static str {
+        match self {
+            ApprovalMethod::Reauthenticate => "Approve by: presence check",
+            ApprovalMethod::SecondDevice => "Approve by: second device",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            ApprovalMethod::Reauthenticate => ApprovalMethod::SecondDevice,
+            ApprovalMethod::SecondDevice => ApprovalMethod::Reauthenticate,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct ApprovalPolicy {
+    pub enabled: bool,
+    /// Transfers of more than this many tokens need approval
+    #[serde(with = "rust_decimal::serde::str")]
+    pub threshold: Decimal,
+    pub method: ApprovalMethod,
+    /// Base URL for pending-approval records (second-device method only)
+    pub endpoint: String,
+    /// Public key of the paired second device; approvals must be signed by it
+    pub device_key: String,
+}
+
+impl Default for ApprovalPolicy {
+    fn default() -> Self {
+        Self { enabled: false, threshold: Decimal::from(THRESHOLD_OPTIONS[1]), method: ApprovalMethod::default(), endpoint: String::new(), device_key: String::new() }
+    }
+}
+
+impl ApprovalPolicy {
+    pub fn requires_approval(&self, quantity: Decimal) -> bool {
+        self.enabled && quantity > self.threshold
+    }
+
+    /// The next entry of `THRESHOLD_OPTIONS`, wrapping; a hand-edited value goes back to the first.
+    pub fn next_threshold(&self) -> Decimal {
+        let index = THRESHOLD_OPTIONS.iter().position(|option| Decimal::from(*option) == self.threshold);
+        Decimal::from(THRESHOLD_OPTIONS[index.map_or(0, |index| (index + 1) % THRESHOLD_OPTIONS.len())])
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApprovalStatus {
+    Pending,
+    Approved,
+    Rejected,
+    /// Signed and handed to the chain; kept so the queue shows what happened
+    Submitted,
+}
+
+impl ApprovalStatus {
+    pub fn label(self) -> &'static str {
+        match self {
+            ApprovalStatus::Pending => "⏳ Waiting for approval",
+            ApprovalStatus::Approved => "✅ Approved",
+            ApprovalStatus::Rejected => "❌ Rejected",
+            ApprovalStatus::Submitted => "📤 Sent",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PendingApproval {
+    pub id: String,
+    /// GalaChain addresses
+    pub from: String,
+    pub to: String,
+    pub token: TokenInfo,
+    #[serde(with = "rust_decimal::serde::str")]
+    pub quantity: Decimal,
+    pub created_unix: u64,
+    pub method: ApprovalMethod,
+    pub status: ApprovalStatus,
+    /// Whether the endpoint has accepted the record (second-device method only)
+    #[serde(default)]
+    pub posted: bool,
+    /// Last error, or the submission summary once sent
+    #[serde(default)]
+    pub note: Option<String>,
+    /// The user's own note from the Transfer screen; stays on this device
+    #[serde(default)]
+    pub memo: Option<String>,
+    /// The NFT instance being sent; None for fungible tokens
+    #[serde(default)]
+    pub instance: Option<String>,
+}
+
+impl PendingApproval {
+    /// What a second device is shown; nothing secret, nothing signed.
+    pub fn record(&self) -> Value {
+        json!({
+            "id": self.id,
+            "from": self.from,
+            "to": self.to,
+            "token": self.token.class_key(),
+            "symbol": self.token.symbol,
+            "instance": self.instance,
+            "quantity": self.quantity.to_string(),
+            "createdAt": self.created_unix,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct ApprovalQueue {
+    pub policy: ApprovalPolicy,
+    /// Oldest first
+    pub items: Vec<PendingApproval>,
+}
+
+impl ApprovalQueue {
+    pub fn load(path: Option<PathBuf>) -> Self {
+        path.and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: Option<PathBuf>) -> std::io::Result<()> {
+        let Some(path) = path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self).unwrap_or_default())
+    }
+
+    /// Queues a transfer under the current policy's method and returns it.
+    pub fn add(&mut self, id: String, from: String, to: String, token: TokenInfo, quantity: Decimal, memo: Option<String>) -> &mut PendingApproval {
+        let created_unix = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+        self.items.push(PendingApproval {
+            id,
+            from,
+            to,
+            token,
+            quantity,
+            created_unix,
+            method: self.policy.method,
+            status: ApprovalStatus::Pending,
+            posted: false,
+            note: None,
+            memo,
+            instance: None,
+        });
+        self.items.last_mut().expect("just pushed")
+    }
+
+    pub fn get_mut(&mut self, id: &str) -> Option<&mut PendingApproval> {
+        self.items.iter_mut().find(|item| item.id == id)
+    }
+
+    pub fn remove(&mut self, id: &str) {
+        self.items.retain(|item| item.id != id);
+    }
+}
+
+/// `{endpoint}/{id}`, without doubling a trailing slash.
+pub fn record_url(endpoint: &str, id: &str) -> String {
+    format!("{}/{}", endpoint.trim_end_matches('/'), id)
+}
+
+/// Reads `{"status": ...}` from the approval endpoint. "approved" also needs a
+/// `signature` over `id` from the paired device's `device_key`.
+pub fn parse_remote_status(body: &str, id: &str, device_key: &str) -> Result<ApprovalStatus, String> {
+    let value: Value = serde_json::from_str(body).map_err(|e| format!("Approval endpoint sent invalid JSON: {}", e))?;
+    match value.get("status").and_then(Value::as_str).map(str::to_ascii_lowercase).as_deref() {
+        Some("pending") => Ok(ApprovalStatus::Pending),
+        Some("approved") => {
+            if device_key.trim().is_empty() {
+                return Err("No second device is paired - add its public key in Settings".to_string());
+            }
+            let key = signing::decode_public_key(device_key).ok_or("The paired device key in Settings isn't a valid public key")?;
+            let signature = value.get("signature").and_then(Value::as_str).ok_or("Approval isn't signed by the paired device")?;
+            if !signing::verify_personal_message(id, signature, &key) {
+                return Err("Approval signature doesn't match the paired device".to_string());
+            }
+            Ok(ApprovalStatus::Approved)
+        }
+        Some("rejected") | Some("denied") => Ok(ApprovalStatus::Rejected),
+        Some(other) => Err(format!("Unknown approval status {:?}", other)),
+        None => Err("Approval endpoint response has no status".to_string()),
+    }
+}
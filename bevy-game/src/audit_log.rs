@@ -0,0 +1,108 @@
+// Audit log of what the user decided about requests from outside the wallet
+// (the local signing server in ipc.rs): what was asked, as shown on the
+// approval prompt, and whether it was approved, rejected, refused for a wrong
+// PIN or left to expire, with how it ended.
+//
+// One JSON object per line in `audit_log.jsonl`, appended as decisions are made.
+// Once the file holds `MAX_ENTRIES` it's rewritten with the newest ones, so it
+// can't grow without bound. Settings shows the most recent few.
+
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub const AUDIT_FILE: &str = "audit_log.jsonl";
+pub const MAX_ENTRIES: usize = 1_000;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Decision {
+    Approved,
+    Rejected,
+    /// Rejected after too many wrong PINs
+    WrongPin,
+    /// The caller gave up before the user answered
+    Expired,
+    /// The signing server was turned off with the call still waiting
+    Withdrawn,
+}
+
+impl Decision {
+    pub fn label(self) -> &'static str {
+        match self {
+            Decision::Approved => "Approved",
+            Decision::Rejected => "Rejected",
+            Decision::WrongPin => "Wrong PIN",
+            Decision::Expired => "Expired",
+            Decision::Withdrawn => "Withdrawn",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AuditEntry {
+    /// Unix seconds
+    pub at: u64,
+    /// Who asked, e.g. "local app"
+    pub source: String,
+    pub method: String,
+    /// The request as the prompt showed it, label and value
+    pub details: Vec<(String, String)>,
+    pub decision: Decision,
+    /// What happened next: the result or the error the caller got
+    pub outcome: String,
+}
+
+impl AuditEntry {
+    pub fn new(source: &str, method: &str, details: Vec<(String, String)>, decision: Decision, outcome: impl Into<String>) -> Self {
+        let at = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+        Self { at, source: source.to_string(), method: method.to_string(), details, decision, outcome: outcome.into() }
+    }
+
+    pub fn time(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(self.at)
+    }
+
+    /// One line for Settings: decision, method and the first detail that isn't the method.
+    pub fn line(&self, now: SystemTime) -> String {
+        let detail = self.details.iter().find(|(label, _)| label != "Method").map(|(label, value)| format!(" - {}: {}", label, value));
+        format!(
+            "{} {} {} from a {}{} ({})",
+            crate::relative_time::format_relative(self.time(), now),
+            self.decision.label(),
+            self.method,
+            self.source,
+            detail.unwrap_or_default(),
+            self.outcome
+        )
+    }
+}
+
+/// Appends `entry`, trimming the file to the newest `MAX_ENTRIES` once it's full.
+pub fn record(path: Option<PathBuf>, entry: &AuditEntry) -> std::io::Result<()> {
+    let Some(path) = path else {
+        return Ok(());
+    };
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let line = serde_json::to_string(entry).map_err(std::io::Error::other)?;
+    let existing = std::fs::read_to_string(&path).unwrap_or_default();
+    let count = existing.lines().count();
+    if count >= MAX_ENTRIES {
+        let mut kept: Vec<&str> = existing.lines().skip(count + 1 - MAX_ENTRIES).collect();
+        kept.push(&line);
+        return std::fs::write(path, kept.join("\n") + "\n");
+    }
+    let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", line)
+}
+
+/// The newest `count` entries, newest first; lines that don't parse are skipped.
+pub fn recent(path: Option<PathBuf>, count: usize) -> Vec<AuditEntry> {
+    let Some(contents) = path.and_then(|path| std::fs::read_to_string(path).ok()) else {
+        return Vec::new();
+    };
+    contents.lines().rev().filter_map(|line| serde_json::from_str(line).ok()).take(count).collect()
+}
@@ -0,0 +1,289 @@
+// Printable seed backup sheets.
+//
+// Renders the numbered seed words, derivation details, creation date and a QR
+// code of the wallet address to a PNG or PDF file. A QR code of the seed itself
+// is only drawn when the caller explicitly asks for it.
+
+use crate::derivation::Derivation;
+use crate::fonts::UI_FONT_DATA;
+use ab_glyph::{Font, FontRef, PxScale, ScaleFont, point};
+use image::{Rgba, RgbaImage};
+use printpdf::{BuiltinFont, Mm, PdfDocument, Rect};
+use qrcode::QrCode;
+use std::error::Error as StdError;
+use std::fmt;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+#[derive(Debug)]
+pub enum BackupError {
+    UnsupportedFormat(String),
+    Qr(String),
+    Render(String),
+    Io(std::io::Error),
+}
+
+impl fmt::Display for BackupError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BackupError::UnsupportedFormat(ext) => write!(f, "Unsupported backup format '{}' (use .pdf or .png)", ext),
+            BackupError::Qr(msg) => write!(f, "QR code error: {}", msg),
+            BackupError::Render(msg) => write!(f, "Render error: {}", msg),
+            BackupError::Io(e) => write!(f, "File error: {}", e),
+        }
+    }
+}
+
+impl StdError for BackupError {}
+
+impl From<std::io::Error> for BackupError {
+    fn from(e: std::io::Error) -> Self {
+        BackupError::Io(e)
+    }
+}
+
+pub struct BackupSheet {
+    pub words: Vec<String>,
+    pub address: String,
+    pub galachain_address: String,
+    pub created_at: u64,
+    /// How the key comes from the words, printed so they can be recovered elsewhere
+    pub derivation: Derivation,
+    pub include_seed_qr: bool,
+}
+
+impl BackupSheet {
+    /// Writes the sheet to `path`, picking PDF or PNG from the file extension.
+    pub fn save(&self, path: &Path) -> Result<(), BackupError> {
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+
+        match extension.as_str() {
+            "pdf" => self.save_pdf(path),
+            "png" => self.save_png(path),
+            other => Err(BackupError::UnsupportedFormat(other.to_string())),
+        }
+    }
+
+    fn text_lines(&self) -> Vec<String> {
+        vec![
+            format!("Address: {}", self.address),
+            format!("GalaChain: {}", self.galachain_address),
+            format!("Derivation: {}", self.derivation.description()),
+            format!("Created: {}", format_unix_date(self.created_at)),
+        ]
+    }
+
+    fn save_png(&self, path: &Path) -> Result<(), BackupError> {
+        const WIDTH: u32 = 1240;
+        const HEIGHT: u32 = 1754;
+        const MARGIN: f32 = 80.0;
+
+        let font = FontRef::try_from_slice(UI_FONT_DATA)
+            .map_err(|e| BackupError::Render(e.to_string()))?;
+        let mut image = RgbaImage::from_pixel(WIDTH, HEIGHT, Rgba([255, 255, 255, 255]));
+
+        draw_text(&mut image, &font, "GalaChain Wallet - Seed Backup", MARGIN, MARGIN, 44.0);
+        draw_text(
+            &mut image,
+            &font,
+            "Keep this sheet offline. Anyone who has these words controls the wallet.",
+            MARGIN,
+            MARGIN + 60.0,
+            22.0,
+        );
+
+        // Numbered words in three columns, reading order left to right
+        let column_width = (WIDTH as f32 - MARGIN * 2.0) / 3.0;
+        let words_top = MARGIN + 140.0;
+        for (index, word) in self.words.iter().enumerate() {
+            let x = MARGIN + (index % 3) as f32 * column_width;
+            let y = words_top + (index / 3) as f32 * 56.0;
+            draw_text(&mut image, &font, &format!("{:>2}. {}", index + 1, word), x, y, 34.0);
+        }
+
+        let details_top = words_top + self.words.len().div_ceil(3) as f32 * 56.0 + 40.0;
+        for (line_index, line) in self.text_lines().iter().enumerate() {
+            draw_text(&mut image, &font, line, MARGIN, details_top + line_index as f32 * 34.0, 22.0);
+        }
+
+        let qr_top = details_top + 4.0 * 34.0 + 40.0;
+        draw_text(&mut image, &font, "Address QR", MARGIN, qr_top, 24.0);
+        draw_qr(&mut image, &qr_modules(&self.address)?, MARGIN as u32, (qr_top + 40.0) as u32, 8)?;
+
+        if self.include_seed_qr {
+            let x = WIDTH / 2 + 40;
+            draw_text(&mut image, &font, "SEED QR - scanning this reveals the wallet", x as f32, qr_top, 24.0);
+            draw_qr(&mut image, &qr_modules(&self.words.join(" "))?, x, (qr_top + 40.0) as u32, 6)?;
+        }
+
+        image.save(path).map_err(|e| BackupError::Render(e.to_string()))
+    }
+
+    fn save_pdf(&self, path: &Path) -> Result<(), BackupError> {
+        // A4 portrait; PDF coordinates start at the bottom left
+        const PAGE_WIDTH: f32 = 210.0;
+        const PAGE_HEIGHT: f32 = 297.0;
+        const MARGIN: f32 = 20.0;
+
+        let (document, page, layer) = PdfDocument::new("GalaChain Wallet Seed Backup", Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Backup");
+        let font = document
+            .add_builtin_font(BuiltinFont::Helvetica)
+            .map_err(|e| BackupError::Render(e.to_string()))?;
+        let bold = document
+            .add_builtin_font(BuiltinFont::HelveticaBold)
+            .map_err(|e| BackupError::Render(e.to_string()))?;
+        let layer = document.get_page(page).get_layer(layer);
+
+        let mut y = PAGE_HEIGHT - MARGIN;
+        layer.use_text("GalaChain Wallet - Seed Backup", 20.0, Mm(MARGIN), Mm(y), &bold);
+        y -= 8.0;
+        layer.use_text(
+            "Keep this sheet offline. Anyone who has these words controls the wallet.",
+            10.0,
+            Mm(MARGIN),
+            Mm(y),
+            &font,
+        );
+
+        y -= 14.0;
+        let column_width = (PAGE_WIDTH - MARGIN * 2.0) / 3.0;
+        for (index, word) in self.words.iter().enumerate() {
+            let x = MARGIN + (index % 3) as f32 * column_width;
+            let row_y = y - (index / 3) as f32 * 10.0;
+            layer.use_text(format!("{:>2}. {}", index + 1, word), 14.0, Mm(x), Mm(row_y), &font);
+        }
+
+        y -= self.words.len().div_ceil(3) as f32 * 10.0 + 6.0;
+        for line in self.text_lines() {
+            layer.use_text(line, 9.0, Mm(MARGIN), Mm(y), &font);
+            y -= 6.0;
+        }
+
+        y -= 6.0;
+        layer.use_text("Address QR", 11.0, Mm(MARGIN), Mm(y), &bold);
+        let qr_top = y - 4.0;
+        let address_qr = qr_modules(&self.address)?;
+        for (x, row) in dark_modules(&address_qr) {
+            fill_module(&layer, MARGIN, qr_top, x, row, 1.2);
+        }
+
+        if self.include_seed_qr {
+            let left = PAGE_WIDTH / 2.0 + 5.0;
+            layer.use_text("SEED QR - scanning this reveals the wallet", 11.0, Mm(left), Mm(y), &bold);
+            let seed_qr = qr_modules(&self.words.join(" "))?;
+            for (x, row) in dark_modules(&seed_qr) {
+                fill_module(&layer, left, qr_top, x, row, 1.0);
+            }
+        }
+
+        let mut writer = BufWriter::new(File::create(path)?);
+        document.save(&mut writer).map_err(|e| BackupError::Render(e.to_string()))
+    }
+}
+
+// Light border around an on-screen QR code, in modules, so scanners can find its edges
+const QR_QUIET_ZONE: u32 = 4;
+
+/// Black-on-white QR code of `data` with `scale` pixels per module, for showing on screen.
+pub fn qr_image(data: &str, scale: u32) -> Result<RgbaImage, BackupError> {
+    let modules = qr_modules(data)?;
+    let size = (modules.width as u32 + 2 * QR_QUIET_ZONE) * scale;
+    let mut image = RgbaImage::from_pixel(size, size, Rgba([255, 255, 255, 255]));
+    draw_qr(&mut image, &modules, QR_QUIET_ZONE * scale, QR_QUIET_ZONE * scale, scale)?;
+    Ok(image)
+}
+
+// Square grid of QR modules, true = dark
+struct QrModules {
+    width: usize,
+    dark: Vec<bool>,
+}
+
+fn qr_modules(data: &str) -> Result<QrModules, BackupError> {
+    let code = QrCode::new(data.as_bytes()).map_err(|e| BackupError::Qr(e.to_string()))?;
+    Ok(QrModules {
+        width: code.width(),
+        dark: code.to_colors().into_iter().map(|color| color == qrcode::Color::Dark).collect(),
+    })
+}
+
+fn dark_modules(modules: &QrModules) -> impl Iterator<Item = (usize, usize)> + '_ {
+    modules
+        .dark
+        .iter()
+        .enumerate()
+        .filter(|(_, dark)| **dark)
+        .map(|(index, _)| (index % modules.width, index / modules.width))
+}
+
+fn fill_module(layer: &printpdf::PdfLayerReference, left: f32, top: f32, x: usize, row: usize, size: f32) {
+    let x0 = left + x as f32 * size;
+    let y0 = top - (row + 1) as f32 * size;
+    layer.add_rect(Rect::new(Mm(x0), Mm(y0), Mm(x0 + size), Mm(y0 + size)));
+}
+
+fn draw_qr(image: &mut RgbaImage, modules: &QrModules, left: u32, top: u32, scale: u32) -> Result<(), BackupError> {
+    let size = modules.width as u32 * scale;
+    if left + size > image.width() || top + size > image.height() {
+        return Err(BackupError::Render("QR code does not fit on the sheet".to_string()));
+    }
+
+    for (x, y) in dark_modules(modules) {
+        for dy in 0..scale {
+            for dx in 0..scale {
+                image.put_pixel(left + x as u32 * scale + dx, top + y as u32 * scale + dy, Rgba([0, 0, 0, 255]));
+            }
+        }
+    }
+    Ok(())
+}
+
+// Draws black text with its top-left corner at (x, y)
+fn draw_text(image: &mut RgbaImage, font: &FontRef, text: &str, x: f32, y: f32, size: f32) {
+    let scale = PxScale::from(size);
+    let scaled = font.as_scaled(scale);
+    let mut caret = point(x, y + scaled.ascent());
+
+    for c in text.chars() {
+        let glyph_id = scaled.glyph_id(c);
+        let glyph = glyph_id.with_scale_and_position(scale, caret);
+        caret.x += scaled.h_advance(glyph_id);
+
+        if let Some(outlined) = font.outline_glyph(glyph) {
+            let bounds = outlined.px_bounds();
+            outlined.draw(|gx, gy, coverage| {
+                let px = bounds.min.x as i32 + gx as i32;
+                let py = bounds.min.y as i32 + gy as i32;
+                if px < 0 || py < 0 || px >= image.width() as i32 || py >= image.height() as i32 {
+                    return;
+                }
+                let pixel = image.get_pixel_mut(px as u32, py as u32);
+                let shade = (255.0 * (1.0 - coverage)) as u8;
+                pixel.0[0] = pixel.0[0].min(shade);
+                pixel.0[1] = pixel.0[1].min(shade);
+                pixel.0[2] = pixel.0[2].min(shade);
+            });
+        }
+    }
+}
+
+/// Formats a Unix timestamp as a UTC calendar date (YYYY-MM-DD).
+pub fn format_unix_date(timestamp: u64) -> String {
+    // Days-to-civil conversion from Howard Hinnant's date algorithms
+    let days = (timestamp / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
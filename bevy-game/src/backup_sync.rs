@@ -0,0 +1,291 @@
+// Cloud copies of the encrypted backup bundle (wallet_bundle.rs), uploaded on
+// demand to a WebDAV folder or an S3-compatible bucket the user provides.
+//
+// Only sealed bundles are uploaded; there's no way to send anything else. The
+// target is kept in backup_sync.json and its credentials (a username and
+// password, or an access key pair) in the keychain, per profile like the
+// gateway credentials. Rotation needs no listing: uploads cycle through `keep`
+// numbered objects, each overwriting the oldest. S3 requests are signed with
+// AWS Signature Version 4 against path-style URLs, so the endpoint URL names
+// the bucket (https://s3.us-east-1.amazonaws.com/my-bucket).
+
+use crate::backup::format_unix_date;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::path::PathBuf;
+
+pub const SYNC_FILE: &str = "backup_sync.json";
+/// What the Keep button cycles through
+pub const KEEP_OPTIONS: [usize; 4] = [1, 3, 5, 10];
+pub const DEFAULT_KEEP: usize = 3;
+pub const DEFAULT_REGION: &str = "us-east-1";
+const OBJECT_PREFIX: &str = "galachain-wallet-backup";
+const KEYCHAIN_USERNAME: &str = "backup-target-credentials";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TargetKind {
+    #[default]
+    WebDav,
+    S3,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct SyncTarget {
+    pub kind: TargetKind,
+    /// WebDAV folder URL, or the S3 endpoint with the bucket as its path
+    pub url: String,
+    /// Only S3 signs with it
+    pub region: String,
+    /// Uploads kept before the oldest is overwritten
+    pub keep: usize,
+}
+
+impl Default for SyncTarget {
+    fn default() -> Self {
+        Self { kind: TargetKind::WebDav, url: String::new(), region: DEFAULT_REGION.to_string(), keep: DEFAULT_KEEP }
+    }
+}
+
+/// WebDAV username and password, or S3 access key ID and secret key.
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct SyncCredentials {
+    pub id: String,
+    pub secret: String,
+}
+
+impl fmt::Debug for SyncCredentials {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "SyncCredentials({}, [redacted])", self.id)
+    }
+}
+
+/// The last upload, shown on Overview.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SyncRecord {
+    /// Unix seconds
+    pub at: u64,
+    pub object: String,
+    /// Why it failed; None when it was uploaded
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct SyncConfig {
+    pub target: Option<SyncTarget>,
+    /// Slot the next upload goes to
+    pub next_slot: usize,
+    pub last: Option<SyncRecord>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyncError {
+    InvalidUrl(String),
+    MissingCredentials,
+    Upload(String),
+}
+
+impl fmt::Display for SyncError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SyncError::InvalidUrl(reason) => write!(f, "Invalid backup target URL: {}", reason),
+            SyncError::MissingCredentials => write!(f, "The backup target has no credentials saved"),
+            SyncError::Upload(reason) => write!(f, "Upload failed: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for SyncError {}
+
+/// The keychain entry's username for a profile's backup target credentials.
+pub fn keychain_username(profile: Option<&str>) -> String {
+    match profile {
+        Some(profile) => format!("{}-{}", KEYCHAIN_USERNAME, profile),
+        None => KEYCHAIN_USERNAME.to_string(),
+    }
+}
+
+impl SyncConfig {
+    pub fn load(path: Option<PathBuf>) -> Self {
+        path.and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: Option<PathBuf>) -> std::io::Result<()> {
+        let Some(path) = path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self).unwrap_or_default())
+    }
+
+    /// Object the next upload writes, or None without a target.
+    pub fn next_object(&self) -> Option<String> {
+        let target = self.target.as_ref()?;
+        Some(format!("{}-{}.json", OBJECT_PREFIX, self.next_slot % target.keep.max(1) + 1))
+    }
+
+    /// Keeps an upload's outcome; a successful one moves on to the next slot.
+    pub fn record(&mut self, record: SyncRecord) {
+        if record.error.is_none() {
+            let keep = self.target.as_ref().map_or(1, |target| target.keep.max(1));
+            self.next_slot = (self.next_slot + 1) % keep;
+        }
+        self.last = Some(record);
+    }
+}
+
+/// A PUT ready to send; the headers include the credentials.
+pub struct UploadRequest {
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+}
+
+impl SyncTarget {
+    /// Scheme, host and path of the URL. Plain http is only allowed to this machine,
+    /// since the request carries the credentials.
+    pub fn endpoint(&self) -> Result<(&str, &str, &str), SyncError> {
+        let url = self.url.trim();
+        let (scheme, rest) = url.split_once("://").ok_or_else(|| SyncError::InvalidUrl("it needs https://".to_string()))?;
+        let (host, path) = rest.split_once('/').map_or((rest, ""), |(host, path)| (host, path.trim_end_matches('/')));
+        if host.is_empty() {
+            return Err(SyncError::InvalidUrl("there's no host".to_string()));
+        }
+        let local = ["localhost", "127.0.0.1", "[::1]"].iter().any(|name| host == *name || host.starts_with(&format!("{}:", name)));
+        match scheme {
+            "https" => {}
+            "http" if local => {}
+            "http" => return Err(SyncError::InvalidUrl("plain http is only allowed to localhost".to_string())),
+            other => return Err(SyncError::InvalidUrl(format!("'{}' isn't http or https", other))),
+        }
+        if self.kind == TargetKind::S3 && path.is_empty() {
+            return Err(SyncError::InvalidUrl("the S3 URL needs the bucket as its path".to_string()));
+        }
+        Ok((scheme, host, path))
+    }
+}
+
+pub fn upload_request(
+    target: &SyncTarget,
+    credentials: &SyncCredentials,
+    object: &str,
+    body: &[u8],
+    unix_time: u64,
+) -> Result<UploadRequest, SyncError> {
+    let (scheme, host, path) = target.endpoint()?;
+    if credentials.id.trim().is_empty() || credentials.secret.is_empty() {
+        return Err(SyncError::MissingCredentials);
+    }
+    let uri: String = path.split('/').filter(|segment| !segment.is_empty()).chain([object]).map(|segment| format!("/{}", uri_encode(segment))).collect();
+    let url = format!("{}://{}{}", scheme, host, uri);
+
+    let headers = match target.kind {
+        TargetKind::WebDav => {
+            let basic = base64::engine::general_purpose::STANDARD.encode(format!("{}:{}", credentials.id.trim(), credentials.secret));
+            vec![("Authorization".to_string(), format!("Basic {}", basic)), ("Content-Type".to_string(), "application/json".to_string())]
+        }
+        TargetKind::S3 => {
+            let payload_hash = hex::encode(Sha256::digest(body));
+            let amz_date = amz_date(unix_time);
+            let signed = [("host", host), ("x-amz-content-sha256", payload_hash.as_str()), ("x-amz-date", amz_date.as_str())];
+            let request = CanonicalRequest { method: "PUT", uri: &uri, query: "", headers: &signed, payload_hash: &payload_hash };
+            let authorization = authorization(&request, credentials, &target.region, &amz_date);
+            vec![
+                ("x-amz-content-sha256".to_string(), payload_hash.clone()),
+                ("x-amz-date".to_string(), amz_date.clone()),
+                ("Authorization".to_string(), authorization),
+                ("Content-Type".to_string(), "application/json".to_string()),
+            ]
+        }
+    };
+    Ok(UploadRequest { url, headers })
+}
+
+/// What Signature Version 4 signs. `headers` are the signed ones, lowercase and
+/// sorted by name, including `x-amz-date`.
+pub struct CanonicalRequest<'a> {
+    pub method: &'a str,
+    pub uri: &'a str,
+    pub query: &'a str,
+    pub headers: &'a [(&'a str, &'a str)],
+    pub payload_hash: &'a str,
+}
+
+/// The S3 `Authorization` header for `request`, made at `amz_date`.
+pub fn authorization(request: &CanonicalRequest, credentials: &SyncCredentials, region: &str, amz_date: &str) -> String {
+    let date = &amz_date[..8];
+    let canonical_headers: String = request.headers.iter().map(|(name, value)| format!("{}:{}\n", name, value.trim())).collect();
+    let signed_headers = request.headers.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(";");
+    let canonical_request = format!(
+        "{}\n{}\n{}\n{}\n{}\n{}",
+        request.method, request.uri, request.query, canonical_headers, signed_headers, request.payload_hash
+    );
+    let scope = format!("{}/{}/s3/aws4_request", date, region);
+    let string_to_sign = format!("AWS4-HMAC-SHA256\n{}\n{}\n{}", amz_date, scope, hex::encode(Sha256::digest(canonical_request)));
+
+    let key = [date, region, "s3", "aws4_request"]
+        .iter()
+        .fold(format!("AWS4{}", credentials.secret).into_bytes(), |key, part| hmac_sha256(&key, part.as_bytes()));
+    format!(
+        "AWS4-HMAC-SHA256 Credential={}/{},SignedHeaders={},Signature={}",
+        credentials.id.trim(),
+        scope,
+        signed_headers,
+        hex::encode(hmac_sha256(&key, string_to_sign.as_bytes()))
+    )
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC takes keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+// `YYYYMMDDTHHMMSSZ`
+fn amz_date(unix_time: u64) -> String {
+    let seconds_of_day = unix_time % 86_400;
+    format!(
+        "{}T{:02}{:02}{:02}Z",
+        format_unix_date(unix_time).replace('-', ""),
+        seconds_of_day / 3_600,
+        seconds_of_day % 3_600 / 60,
+        seconds_of_day % 60
+    )
+}
+
+// Percent-encodes everything but RFC 3986 unreserved characters, as SigV4 wants
+fn uri_encode(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (byte as char).to_string(),
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+/// Sends the PUT. Blocking; run it off the main thread.
+pub fn upload(client: &Client, request: UploadRequest, body: Vec<u8>) -> Result<(), SyncError> {
+    let runtime = tokio::runtime::Runtime::new().map_err(|e| SyncError::Upload(e.to_string()))?;
+    runtime.block_on(async {
+        let mut builder = client.put(&request.url).body(body);
+        for (name, value) in &request.headers {
+            builder = builder.header(name, value);
+        }
+        let response = builder.send().await.map_err(|e| SyncError::Upload(e.without_url().to_string()))?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(SyncError::Upload(format!("the server answered {}", status)));
+        }
+        Ok(())
+    })
+}
@@ -0,0 +1,161 @@
+// Balance snapshots over time for the Balance screen's chart.
+//
+// Every balance the wallet fetches for its own address is a candidate snapshot.
+// One is kept per address and token every `SNAPSHOT_INTERVAL_SECS`, sooner if
+// the balance changed, and snapshots older than the longest chart range are
+// dropped. They're saved to `balance_history.json` and exported through
+// export.rs like a fetched balance.
+
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Shortest gap between two snapshots of an unchanged balance
+pub const SNAPSHOT_INTERVAL_SECS: u64 = 15 * 60;
+/// Columns in the chart, whatever the range
+pub const CHART_POINTS: usize = 48;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChartRange {
+    #[default]
+    Day,
+    Week,
+    Month,
+}
+
+impl ChartRange {
+    pub const ALL: [ChartRange; 3] = [ChartRange::Day, ChartRange::Week, ChartRange::Month];
+
+    pub fn secs(self) -> u64 {
+        match self {
+            ChartRange::Day => 24 * 60 * 60,
+            ChartRange::Week => 7 * 24 * 60 * 60,
+            ChartRange::Month => 30 * 24 * 60 * 60,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ChartRange::Day => "24h",
+            ChartRange::Week => "7d",
+            ChartRange::Month => "30d",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BalanceSnapshot {
+    pub unix_time: u64,
+    /// The wallet's Ethereum address
+    pub address: String,
+    /// `TokenInfo::class_key`
+    pub token: String,
+    pub symbol: String,
+    #[serde(with = "rust_decimal::serde::str")]
+    pub available: Decimal,
+    #[serde(with = "rust_decimal::serde::str")]
+    pub locked: Decimal,
+}
+
+impl BalanceSnapshot {
+    pub fn total(&self) -> Decimal {
+        self.available + self.locked
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct BalanceHistory {
+    /// Oldest first
+    pub snapshots: Vec<BalanceSnapshot>,
+}
+
+impl BalanceHistory {
+    pub fn load(path: Option<PathBuf>) -> Self {
+        path.and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: Option<PathBuf>) -> std::io::Result<()> {
+        let Some(path) = path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self).unwrap_or_default())
+    }
+
+    /// Keeps `snapshot` unless the last one for its address and token is recent and
+    /// shows the same balance. Returns whether it was kept.
+    pub fn record(&mut self, snapshot: BalanceSnapshot) -> bool {
+        let now = snapshot.unix_time;
+        let previous = self
+            .snapshots
+            .iter()
+            .rev()
+            .find(|previous| previous.address == snapshot.address && previous.token == snapshot.token);
+        if let Some(previous) = previous {
+            let unchanged = previous.available == snapshot.available && previous.locked == snapshot.locked;
+            if unchanged && now.saturating_sub(previous.unix_time) < SNAPSHOT_INTERVAL_SECS {
+                return false;
+            }
+        }
+        self.snapshots.retain(|kept| now.saturating_sub(kept.unix_time) < ChartRange::Month.secs());
+        self.snapshots.push(snapshot);
+        true
+    }
+
+    /// Snapshots of one address and token within `range` of `now`, oldest first.
+    pub fn series(&self, address: &str, token: &str, range: ChartRange, now: u64) -> Vec<&BalanceSnapshot> {
+        self.snapshots
+            .iter()
+            .filter(|snapshot| snapshot.address == address && snapshot.token == token)
+            .filter(|snapshot| now.saturating_sub(snapshot.unix_time) < range.secs())
+            .collect()
+    }
+}
+
+/// The total balance at the end of each of `points` equal slices of the range, oldest
+/// first. A slice with no snapshot carries the one before it; slices before the first
+/// snapshot are `None`.
+pub fn chart_values(series: &[&BalanceSnapshot], range: ChartRange, now: u64, points: usize) -> Vec<Option<Decimal>> {
+    let start = now.saturating_sub(range.secs());
+    let slice = (range.secs() / points.max(1) as u64).max(1);
+    let mut snapshots = series.iter().peekable();
+    let mut current = None;
+    (1..=points as u64)
+        .map(|index| {
+            // The last slice runs up to now, whatever the rounding
+            let slice_end = if index == points as u64 { now + 1 } else { start + slice * index };
+            while let Some(snapshot) = snapshots.next_if(|snapshot| snapshot.unix_time < slice_end) {
+                current = Some(snapshot.total());
+            }
+            current
+        })
+        .collect()
+}
+
+/// Column heights from 0 to 1 for `chart_values`, between the lowest and highest value
+/// shown. A flat series sits at half height.
+pub fn chart_heights(values: &[Option<Decimal>]) -> Vec<Option<f32>> {
+    let known = values.iter().flatten().copied();
+    let (Some(low), Some(high)) = (known.clone().min(), known.max()) else {
+        return vec![None; values.len()];
+    };
+    let spread = high - low;
+    values
+        .iter()
+        .map(|value| {
+            value.map(|value| {
+                if spread.is_zero() {
+                    0.5
+                } else {
+                    ((value - low) / spread).to_f32().unwrap_or(0.0)
+                }
+            })
+        })
+        .collect()
+}
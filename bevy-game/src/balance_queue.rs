@@ -0,0 +1,82 @@
+// Balances for every registry token, fetched a few at a time.
+//
+// The Balance screen lists all the tokens in the registry under the selected
+// one. Each token is a separate FetchBalances call; `BalanceQueue` hands them to
+// the task queue no more than `limit` at a time (set in Settings → Tokens) and
+// keeps each token's outcome, so one failing token class shows its own error
+// while the rest still load.
+
+use crate::tokens::TokenInfo;
+use rust_decimal::Decimal;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenBalanceStatus {
+    Queued,
+    Loading,
+    Loaded { available: Decimal, locked: Decimal },
+    Failed(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct BalanceQueue {
+    rows: Vec<(TokenInfo, TokenBalanceStatus)>,
+    limit: usize,
+}
+
+impl BalanceQueue {
+    pub fn new(tokens: &[TokenInfo], limit: usize) -> Self {
+        Self {
+            rows: tokens.iter().map(|token| (token.clone(), TokenBalanceStatus::Queued)).collect(),
+            limit: limit.max(1),
+        }
+    }
+
+    /// Whether `next_to_start` would hand out a token.
+    pub fn can_start(&self) -> bool {
+        self.in_flight() < self.limit && self.rows.iter().any(|(_, status)| *status == TokenBalanceStatus::Queued)
+    }
+
+    /// The next queued token, marked as loading, if fewer than `limit` are in flight.
+    pub fn next_to_start(&mut self) -> Option<TokenInfo> {
+        if self.in_flight() >= self.limit {
+            return None;
+        }
+        let (token, status) = self.rows.iter_mut().find(|(_, status)| *status == TokenBalanceStatus::Queued)?;
+        *status = TokenBalanceStatus::Loading;
+        Some(token.clone())
+    }
+
+    /// Records a token's outcome; tokens no longer in the queue are ignored.
+    pub fn finish(&mut self, token: &TokenInfo, result: Result<(Decimal, Decimal), String>) {
+        let class_key = token.class_key();
+        if let Some((_, status)) = self.rows.iter_mut().find(|(listed, _)| listed.class_key() == class_key) {
+            *status = match result {
+                Ok((available, locked)) => TokenBalanceStatus::Loaded { available, locked },
+                Err(error) => TokenBalanceStatus::Failed(error),
+            };
+        }
+    }
+
+    pub fn in_flight(&self) -> usize {
+        self.count(|status| *status == TokenBalanceStatus::Loading)
+    }
+
+    pub fn rows(&self) -> &[(TokenInfo, TokenBalanceStatus)] {
+        &self.rows
+    }
+
+    /// "3 of 5 tokens loaded, 1 failed"
+    pub fn summary(&self) -> String {
+        let loaded = self.count(|status| matches!(status, TokenBalanceStatus::Loaded { .. }));
+        let failed = self.count(|status| matches!(status, TokenBalanceStatus::Failed(_)));
+        let mut summary = format!("{} of {} tokens loaded", loaded, self.rows.len());
+        if failed > 0 {
+            summary.push_str(&format!(", {} failed", failed));
+        }
+        summary
+    }
+
+    fn count(&self, matches: impl Fn(&TokenBalanceStatus) -> bool) -> usize {
+        self.rows.iter().filter(|(_, status)| matches(status)).count()
+    }
+}
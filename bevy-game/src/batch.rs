@@ -0,0 +1,99 @@
+// Batch transfers from the Transfer screen.
+//
+// Rows are queued by hand or imported from a CSV recipient list and sent one
+// after another. GalaChain's TransferToken takes a single recipient, so a batch
+// is a sequence of transfers rather than one DTO; each row keeps its own status.
+
+use crate::amounts::validate_amount;
+use crate::recipient::parse_recipient;
+use crate::tokens::TokenInfo;
+use rust_decimal::Decimal;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RowStatus {
+    /// Not sent yet
+    Pending,
+    Queued,
+    /// Transfers are simulated in this reference implementation, so a sent row is only recorded
+    Recorded,
+    /// Over the approval threshold; moved to the Approvals screen instead of being sent
+    AwaitingApproval,
+    Failed(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchRow {
+    pub recipient: String,
+    pub amount: String,
+    pub status: RowStatus,
+}
+
+impl BatchRow {
+    pub fn new(recipient: impl Into<String>, amount: impl Into<String>) -> Self {
+        Self {
+            recipient: recipient.into(),
+            amount: amount.into(),
+            status: RowStatus::Pending,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/// Reads `recipient,amount` lines. Blank lines, `#` comments and a leading
+/// header row are skipped. Values are checked when the batch is sent, so a bad
+/// address only fails its own row.
+pub fn parse_batch_csv(contents: &str) -> Result<Vec<BatchRow>, CsvError> {
+    let mut rows = Vec::new();
+    for (index, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = line.split(',').map(|field| field.trim().trim_matches('"')).collect();
+        let [recipient, amount] = fields[..] else {
+            return Err(CsvError {
+                line: index + 1,
+                message: format!("expected 2 columns (recipient,amount), found {}", fields.len()),
+            });
+        };
+        if rows.is_empty() && is_header(recipient) {
+            continue;
+        }
+        rows.push(BatchRow::new(recipient, amount));
+    }
+    Ok(rows)
+}
+
+fn is_header(first_field: &str) -> bool {
+    ["recipient", "address", "to"].iter().any(|name| first_field.eq_ignore_ascii_case(name))
+}
+
+/// Checks one row of `token` against what's left of the balance after the rows before it.
+/// Each transfer also holds back the token's fee (zero for tokens other than GALA).
+/// Returns the recipient alias and the amount to send.
+pub fn check_row(row: &BatchRow, remaining: Option<Decimal>, token: &TokenInfo) -> Result<(String, Decimal), String> {
+    let recipient = parse_recipient(&row.recipient).map_err(|e| e.to_string())?;
+    let amount = validate_amount(&row.amount, remaining, token.fee(), token.decimals).map_err(|e| e.to_string())?;
+    Ok((recipient.gala_address, amount))
+}
+
+/// Amounts plus one fee per row, counting only rows whose amount parses.
+pub fn batch_total(rows: &[BatchRow], token: &TokenInfo) -> Decimal {
+    let fee = token.fee();
+    rows.iter()
+        .filter_map(|row| validate_amount(&row.amount, None, fee, token.decimals).ok())
+        .map(|amount| amount + fee)
+        .sum()
+}
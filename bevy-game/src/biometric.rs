@@ -0,0 +1,244 @@
+// Optional Touch ID / Windows Hello check before the seed phrase is read from
+// the keychain (Unlock, and approving a held transfer) and before a transaction
+// is signed, each turned on separately in Settings.
+//
+// The prompt is the platform's own: LocalAuthentication's biometrics policy on
+// macOS and UserConsentVerifier on Windows. Both block until the user answers,
+// so the app runs them on a background task. Turning a check on needs a
+// fallback passphrase first, used when the prompt is cancelled, fails or isn't
+// there at all (Linux, a Mac without Touch ID, Hello not set up), so the gate
+// can never lock anyone out of their own wallet. Only a PBKDF2 hash of the
+// passphrase is kept, in biometric.json. This is a presence check in front of
+// the app, not encryption: the keychain entry itself is unchanged.
+
+use crate::passphrase_strength;
+use crate::wallet_bundle::{KDF_ITERATIONS, MIN_PASSPHRASE_CHARS};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::fmt;
+use std::path::PathBuf;
+use std::time::Duration;
+
+pub const SETTINGS_FILE: &str = "biometric.json";
+/// How long a passed check waits for the action it was for
+pub const VERIFIED_FOR: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GatePurpose {
+    /// Reading the seed phrase from the keychain for Unlock
+    Unlock,
+    /// Reading it to approve a held transfer
+    Approve,
+    Sign,
+}
+
+impl GatePurpose {
+    pub fn reason(self) -> &'static str {
+        match self {
+            GatePurpose::Unlock => "unlock the wallet",
+            GatePurpose::Approve => "approve the held transfer",
+            GatePurpose::Sign => "sign the transaction",
+        }
+    }
+}
+
+/// PBKDF2-SHA256 of a passphrase or PIN; fields are base64.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PassphraseHash {
+    salt: String,
+    iterations: u32,
+    hash: String,
+}
+
+impl PassphraseHash {
+    pub fn new(passphrase: &str, iterations: u32) -> Self {
+        let salt: [u8; 16] = rand::random();
+        let base64 = base64::engine::general_purpose::STANDARD;
+        Self { salt: base64.encode(salt), iterations, hash: base64.encode(stretch(passphrase, &salt, iterations)) }
+    }
+
+    pub fn matches(&self, passphrase: &str) -> bool {
+        let base64 = base64::engine::general_purpose::STANDARD;
+        match (base64.decode(&self.salt), base64.decode(&self.hash)) {
+            (Ok(salt), Ok(hash)) => stretch(passphrase, &salt, self.iterations).as_slice() == hash.as_slice(),
+            _ => false,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct GateSettings {
+    pub unlock: bool,
+    pub signing: bool,
+    pub fallback: Option<PassphraseHash>,
+}
+
+impl GateSettings {
+    pub fn load(path: Option<PathBuf>) -> Self {
+        path.and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: Option<PathBuf>) -> std::io::Result<()> {
+        let Some(path) = path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self).unwrap_or_default())
+    }
+
+    /// Approving a held transfer is always checked once there's a fallback
+    /// passphrase: the check is what the approval is.
+    pub fn gates(&self, purpose: GatePurpose) -> bool {
+        self.fallback.is_some()
+            && match purpose {
+                GatePurpose::Unlock => self.unlock,
+                GatePurpose::Approve => true,
+                GatePurpose::Sign => self.signing,
+            }
+    }
+
+    /// Turns the check for `purpose` on or off (approvals go with Unlock, though
+    /// they're checked either way); on needs the fallback passphrase set.
+    pub fn set_gate(&mut self, purpose: GatePurpose, on: bool) -> Result<(), GateError> {
+        if on && self.fallback.is_none() {
+            return Err(GateError::NoFallback);
+        }
+        match purpose {
+            GatePurpose::Unlock | GatePurpose::Approve => self.unlock = on,
+            GatePurpose::Sign => self.signing = on,
+        }
+        Ok(())
+    }
+
+    pub fn set_fallback(&mut self, passphrase: &str) -> Result<(), GateError> {
+        self.set_fallback_with_iterations(passphrase, KDF_ITERATIONS)
+    }
+
+    /// `set_fallback` with a chosen PBKDF2 round count; tests use a low one.
+    pub fn set_fallback_with_iterations(&mut self, passphrase: &str, iterations: u32) -> Result<(), GateError> {
+        if passphrase.chars().count() < MIN_PASSPHRASE_CHARS {
+            return Err(GateError::PassphraseTooShort);
+        }
+        if !passphrase_strength::estimate(passphrase).acceptable() {
+            return Err(GateError::PassphraseTooWeak);
+        }
+        self.fallback = Some(PassphraseHash::new(passphrase, iterations));
+        Ok(())
+    }
+
+    pub fn check_fallback(&self, passphrase: &str) -> bool {
+        self.fallback.as_ref().is_some_and(|fallback| fallback.matches(passphrase))
+    }
+}
+
+fn stretch(passphrase: &str, salt: &[u8], iterations: u32) -> [u8; 32] {
+    let mut hash = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, iterations, &mut hash);
+    hash
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GateError {
+    NoFallback,
+    PassphraseTooShort,
+    PassphraseTooWeak,
+}
+
+impl fmt::Display for GateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GateError::NoFallback => write!(f, "Set a fallback passphrase first"),
+            GateError::PassphraseTooShort => write!(f, "The fallback passphrase needs at least {} characters", MIN_PASSPHRASE_CHARS),
+            GateError::PassphraseTooWeak => write!(f, "The fallback passphrase is too easy to guess - add another word, or some digits and symbols"),
+        }
+    }
+}
+
+/// Why the platform prompt didn't verify the user.
+// Only the Touch ID and Windows Hello prompts can be cancelled or fail
+#[cfg_attr(not(any(target_os = "macos", target_os = "windows")), allow(dead_code))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PromptError {
+    /// No biometric prompt here, or none set up
+    Unavailable(String),
+    Cancelled,
+    Failed(String),
+}
+
+impl fmt::Display for PromptError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PromptError::Unavailable(reason) => write!(f, "not available ({})", reason),
+            PromptError::Cancelled => write!(f, "cancelled"),
+            PromptError::Failed(reason) => write!(f, "failed ({})", reason),
+        }
+    }
+}
+
+/// The platform's biometric prompt, if it has one.
+pub fn platform_name() -> Option<&'static str> {
+    if cfg!(target_os = "macos") {
+        Some("Touch ID")
+    } else if cfg!(target_os = "windows") {
+        Some("Windows Hello")
+    } else {
+        None
+    }
+}
+
+/// Shows the platform prompt with `reason` and waits for the answer. Blocking.
+#[cfg(target_os = "macos")]
+pub fn prompt(reason: &str) -> Result<(), PromptError> {
+    use block2::RcBlock;
+    use objc2::runtime::Bool;
+    use objc2_foundation::{NSError, NSString};
+    use objc2_local_authentication::{LAContext, LAPolicy};
+
+    // LAError codes for the user, the app or the system dismissing the prompt
+    const CANCELLED: [isize; 3] = [-2, -4, -9];
+    let context = unsafe { LAContext::new() };
+    let policy = LAPolicy::DeviceOwnerAuthenticationWithBiometrics;
+    if let Err(e) = unsafe { context.canEvaluatePolicy_error(policy) } {
+        return Err(PromptError::Unavailable(e.localizedDescription().to_string()));
+    }
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let reply = RcBlock::new(move |success: Bool, error: *mut NSError| {
+        let result = match unsafe { error.as_ref() } {
+            _ if success.as_bool() => Ok(()),
+            Some(error) if CANCELLED.contains(&error.code()) => Err(PromptError::Cancelled),
+            Some(error) => Err(PromptError::Failed(error.localizedDescription().to_string())),
+            None => Err(PromptError::Failed("no reason given".to_string())),
+        };
+        let _ = sender.send(result);
+    });
+    unsafe { context.evaluatePolicy_localizedReason_reply(policy, &NSString::from_str(reason), &reply) };
+    receiver.recv().unwrap_or_else(|_| Err(PromptError::Failed("the prompt went away".to_string())))
+}
+
+#[cfg(target_os = "windows")]
+pub fn prompt(reason: &str) -> Result<(), PromptError> {
+    use windows::core::HSTRING;
+    use windows::Security::Credentials::UI::{UserConsentVerificationResult, UserConsentVerifier, UserConsentVerifierAvailability};
+
+    let failed = |e: windows::core::Error| PromptError::Failed(e.message());
+    let availability = UserConsentVerifier::CheckAvailabilityAsync().and_then(|check| check.get()).map_err(failed)?;
+    if availability != UserConsentVerifierAvailability::Available {
+        return Err(PromptError::Unavailable(format!("Windows Hello isn't set up ({:?})", availability)));
+    }
+    match UserConsentVerifier::RequestVerificationAsync(&HSTRING::from(reason)).and_then(|request| request.get()).map_err(failed)? {
+        UserConsentVerificationResult::Verified => Ok(()),
+        UserConsentVerificationResult::Canceled => Err(PromptError::Cancelled),
+        result => Err(PromptError::Failed(format!("{:?}", result))),
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub fn prompt(_reason: &str) -> Result<(), PromptError> {
+    Err(PromptError::Unavailable("no biometric prompt on this platform".to_string()))
+}
@@ -0,0 +1,133 @@
+// In-memory cache of balance and registration lookups, optionally kept on disk.
+//
+// Cached values are shown as soon as a screen opens. Anything older than the
+// TTL is still shown, but marked stale and refreshed in the background; with
+// auto refresh on, Balance and Registration refresh on every open.
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+pub const DEFAULT_CACHE_TTL_SECS: u64 = 60;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CachedValue<T> {
+    pub value: T,
+    pub fetched_at: SystemTime,
+}
+
+/// A cached value and whether it needs refreshing.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CacheHit<T> {
+    pub value: T,
+    pub fetched_at: SystemTime,
+    pub stale: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct ResponseCache {
+    /// None turns caching off.
+    pub ttl_secs: Option<u64>,
+    /// Keep cached responses in the config directory between runs.
+    pub persist: bool,
+    /// Fetch the balance each time Balance opens, even with a fresh cached one.
+    pub auto_refresh_balance: bool,
+    /// Check registration each time Registration opens, the same way.
+    pub auto_refresh_registration: bool,
+    // Keyed by `cache_key`
+    balances: HashMap<String, CachedValue<(Decimal, Decimal)>>,
+    registrations: HashMap<String, CachedValue<bool>>,
+}
+
+impl Default for ResponseCache {
+    fn default() -> Self {
+        Self {
+            ttl_secs: Some(DEFAULT_CACHE_TTL_SECS),
+            persist: false,
+            auto_refresh_balance: false,
+            auto_refresh_registration: false,
+            balances: HashMap::new(),
+            registrations: HashMap::new(),
+        }
+    }
+}
+
+/// Entries are per endpoint as well as per address, so switching servers never shows another server's data.
+pub fn cache_key(url: &str, gala_address: &str) -> String {
+    format!("{}|{}", url, gala_address)
+}
+
+impl ResponseCache {
+    pub fn enabled(&self) -> bool {
+        self.ttl_secs.is_some()
+    }
+
+    pub fn balance(&self, key: &str, now: SystemTime) -> Option<CacheHit<(Decimal, Decimal)>> {
+        self.hit(self.balances.get(key)?, now)
+    }
+
+    pub fn registration(&self, key: &str, now: SystemTime) -> Option<CacheHit<bool>> {
+        self.hit(self.registrations.get(key)?, now)
+    }
+
+    pub fn store_balance(&mut self, key: String, value: (Decimal, Decimal), now: SystemTime) {
+        if self.enabled() {
+            self.balances.insert(key, CachedValue { value, fetched_at: now });
+        }
+    }
+
+    pub fn store_registration(&mut self, key: String, value: bool, now: SystemTime) {
+        if self.enabled() {
+            self.registrations.insert(key, CachedValue { value, fetched_at: now });
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.balances.clear();
+        self.registrations.clear();
+    }
+
+    /// Older than the TTL. Nothing is stale while caching is off.
+    pub fn is_stale(&self, fetched_at: SystemTime, now: SystemTime) -> bool {
+        let Some(ttl_secs) = self.ttl_secs else {
+            return false;
+        };
+        // A clock that went backwards counts as stale
+        now.duration_since(fetched_at).map_or(true, |age| age > Duration::from_secs(ttl_secs))
+    }
+
+    fn hit<T: Copy>(&self, cached: &CachedValue<T>, now: SystemTime) -> Option<CacheHit<T>> {
+        if !self.enabled() {
+            return None;
+        }
+        Some(CacheHit {
+            value: cached.value,
+            fetched_at: cached.fetched_at,
+            stale: self.is_stale(cached.fetched_at, now),
+        })
+    }
+
+    pub fn load(path: Option<PathBuf>) -> Self {
+        path.and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    /// Always writes the preferences; entries only when `persist` is on.
+    pub fn save(&self, path: Option<PathBuf>) -> std::io::Result<()> {
+        let Some(path) = path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut on_disk = self.clone();
+        if !self.persist {
+            on_disk.clear();
+        }
+        std::fs::write(path, serde_json::to_string_pretty(&on_disk).unwrap_or_default())
+    }
+}
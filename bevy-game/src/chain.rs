@@ -0,0 +1,65 @@
+// The calls the wallet makes to a GalaChain network, independent of transport.
+//
+// `GalaChainClient` (the Bevy resource) forwards every chain call to one
+// `ChainApi` backend: `HttpChain` for the REST gateway, `FakeChain` in demo mode,
+// or a test double passed to `GalaChainClient::with_backend`. Calls block and run
+// on background tasks. `cancel` is set once the task is abandoned; backends that
+// wait on I/O should give up with `GalaChainError::Cancelled` when it is.
+
+use crate::holds::TokenHold;
+use crate::nft::InstanceList;
+use crate::signing::SignedDto;
+use crate::tokens::TokenInfo;
+use crate::{CancelToken, GalaChainError};
+use rust_decimal::Decimal;
+use serde_json::Value;
+use std::time::Duration;
+
+pub trait ChainApi: Send + Sync {
+    /// Available and locked quantity of `token` held by `owner`, a GalaChain address.
+    fn fetch_balance(&self, owner: &str, token: &TokenInfo, cancel: &CancelToken) -> Result<(Decimal, Decimal), GalaChainError>;
+
+    /// The holds behind the locked quantity. Backends that only track a total report none.
+    fn fetch_holds(&self, _owner: &str, _token: &TokenInfo, _cancel: &CancelToken) -> Result<Vec<TokenHold>, GalaChainError> {
+        Ok(Vec::new())
+    }
+
+    /// The public key registered for `owner`, as the chain encodes it, or None if it has none.
+    fn fetch_public_key(&self, owner: &str, cancel: &CancelToken) -> Result<Option<String>, GalaChainError>;
+
+    /// Whether `owner` has a public key on chain.
+    fn is_registered(&self, owner: &str, cancel: &CancelToken) -> Result<bool, GalaChainError> {
+        Ok(self.fetch_public_key(owner, cancel)?.is_some())
+    }
+
+    /// Registers an uncompressed secp256k1 public key, hex encoded.
+    fn register(&self, public_key: &str, cancel: &CancelToken) -> Result<(), GalaChainError>;
+
+    /// Submits a signed UpdatePublicKey DTO, replacing the signer's registered key.
+    fn update_public_key(&self, signed: &SignedDto, cancel: &CancelToken) -> Result<Value, GalaChainError>;
+
+    /// Submits a signed TransferToken DTO and returns the chain's response.
+    fn transfer(&self, signed: &SignedDto, cancel: &CancelToken) -> Result<Value, GalaChainError>;
+
+    /// Submits a signed BurnTokens DTO and returns the chain's response.
+    fn burn(&self, signed: &SignedDto, cancel: &CancelToken) -> Result<Value, GalaChainError>;
+
+    /// The chain's symbol, decimals and image for each of these token classes.
+    /// Classes the chain doesn't know are left out.
+    fn fetch_token_classes(&self, classes: &[TokenInfo], cancel: &CancelToken) -> Result<Vec<TokenInfo>, GalaChainError>;
+
+    /// The NFT instances of `token` held by `owner`. Backends without NFTs report none.
+    fn fetch_instances(&self, _owner: &str, _token: &TokenInfo, _cancel: &CancelToken) -> Result<InstanceList, GalaChainError> {
+        Ok(InstanceList::default())
+    }
+
+    /// Evaluates a read-only method of the token contract, such as GetObjectByKey, and
+    /// returns the response's Data. Nothing is signed or submitted. Backends without a
+    /// contract behind them refuse.
+    fn evaluate(&self, method: &str, _dto: &Value, _cancel: &CancelToken) -> Result<Value, GalaChainError> {
+        Err(GalaChainError::Api(format!("{} needs a chain to ask; it isn't available in demo mode", method)))
+    }
+
+    /// Round trip to the operations and identity servers, in that order.
+    fn ping(&self) -> (Result<Duration, GalaChainError>, Result<Duration, GalaChainError>);
+}
@@ -0,0 +1,66 @@
+// Channel/contract pairs an environment can switch between, so custom chaincode
+// can be tried out next to GalaChainToken without retyping the settings.
+//
+// The pair in use is still `ApiSettings::channel_name` / `contract_name`; the
+// saved pairs (`ApiSettings::chain_targets`, carried by environment profiles)
+// are what the picker on the Developer panel, Balance and Transfer screens
+// cycles through. The identity contract isn't switched - GetPublicKey and
+// UpdatePublicKey always go to `identity_contract_name`.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ChainTarget {
+    pub channel: String,
+    pub contract: String,
+}
+
+impl ChainTarget {
+    pub fn new(channel: &str, contract: &str) -> Self {
+        Self { channel: channel.to_string(), contract: contract.to_string() }
+    }
+
+    /// "channel/contract", as profiles and the picker write it.
+    pub fn parse(text: &str) -> Option<Self> {
+        let (channel, contract) = text.trim().split_once('/')?;
+        let (channel, contract) = (channel.trim(), contract.trim());
+        let valid = |name: &str| !name.is_empty() && !name.contains(['/', '{', '}']) && !name.chars().any(char::is_whitespace);
+        (valid(channel) && valid(contract)).then(|| Self::new(channel, contract))
+    }
+}
+
+impl fmt::Display for ChainTarget {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{}", self.channel, self.contract)
+    }
+}
+
+/// The saved pairs without blanks or repeats, in their original order.
+pub fn normalized(targets: &[ChainTarget]) -> Vec<ChainTarget> {
+    let mut kept: Vec<ChainTarget> = Vec::new();
+    for target in targets {
+        if let Some(target) = ChainTarget::parse(&target.to_string()) {
+            if !kept.contains(&target) {
+                kept.push(target);
+            }
+        }
+    }
+    kept
+}
+
+/// What the picker offers: the saved pairs, led by the one in use when it isn't saved.
+pub fn choices(saved: &[ChainTarget], current: &ChainTarget) -> Vec<ChainTarget> {
+    let mut choices = normalized(saved);
+    if !choices.contains(current) {
+        choices.insert(0, current.clone());
+    }
+    choices
+}
+
+/// The pair after `current` among `choices`, wrapping around.
+pub fn next(saved: &[ChainTarget], current: &ChainTarget) -> ChainTarget {
+    let choices = choices(saved, current);
+    let position = choices.iter().position(|target| target == current).unwrap_or(0);
+    choices[(position + 1) % choices.len()].clone()
+}
@@ -0,0 +1,195 @@
+// Splits keychain entries too big for the platform's credential store across
+// several entries.
+//
+// Windows Credential Manager refuses a credential blob over 2560 bytes, and
+// keyring stores passwords there as UTF-16, so anything past 1280 UTF-16 units
+// fails to save; a wallet entry with a few accounts and their metadata gets
+// there. A `ChunkedStore` writes such a secret as numbered chunk entries and
+// puts a small manifest in the entry itself with the generation, the chunk
+// count and the SHA-256 of the whole secret. Each write puts its chunks under
+// a new generation ("default-wallet#g2:1", "default-wallet#g2:2", ...) and only
+// then switches the manifest to it, so until that one entry is replaced the
+// old manifest still points at the old, untouched chunks: an interrupted or
+// failed write leaves the previous secret readable. The previous generation is
+// deleted afterwards. Manifests from before generations ("default-wallet#1"...)
+// still read. Secrets under the limit are stored as they always were, and
+// other platforms have no limit, so their entries never change shape.
+
+use crate::{KeychainError, SecretStore};
+use sha2::{Digest, Sha256};
+
+/// Prefix of a manifest entry: `galachain-chunks:v2:<generation>:<count>:<sha256 hex>`
+const MANIFEST_PREFIX: &str = "galachain-chunks:v2:";
+/// Manifests written before generations: `galachain-chunks:v1:<count>:<sha256 hex>`
+const LEGACY_MANIFEST_PREFIX: &str = "galachain-chunks:v1:";
+/// UTF-16 units per credential on Windows (2560 bytes), less room for keyring's own use
+pub const WINDOWS_CHUNK_UNITS: usize = 1200;
+
+/// The largest secret, in UTF-16 units, one entry holds here; None when there's no limit.
+pub fn platform_limit() -> Option<usize> {
+    cfg!(target_os = "windows").then_some(WINDOWS_CHUNK_UNITS)
+}
+
+/// Opens another entry of the same keychain by username
+type OpenEntry = Box<dyn Fn(&str) -> Box<dyn SecretStore> + Send + Sync>;
+
+/// A keychain entry that spreads secrets over `limit` across chunk entries.
+pub struct ChunkedStore {
+    name: String,
+    limit: Option<usize>,
+    open: OpenEntry,
+}
+
+impl ChunkedStore {
+    pub fn new<S: SecretStore + 'static>(name: impl Into<String>, limit: Option<usize>, open: impl Fn(&str) -> S + Send + Sync + 'static) -> Self {
+        Self { name: name.into(), limit, open: Box::new(move |name| Box::new(open(name))) }
+    }
+
+    fn chunk(&self, generation: Option<u64>, index: usize) -> Box<dyn SecretStore> {
+        (self.open)(&chunk_name(&self.name, generation, index))
+    }
+
+    // What the entry points at now, so a write or delete can remove the chunks it no longer needs
+    fn stored_manifest(&self, entry: &dyn SecretStore) -> Result<Option<Manifest>, KeychainError> {
+        match entry.get() {
+            Ok(stored) => Ok(Manifest::parse(&stored)),
+            Err(KeychainError::NotFound) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn delete_chunks(&self, generation: Option<u64>, range: std::ops::Range<usize>) -> Result<(), KeychainError> {
+        for index in range {
+            match self.chunk(generation, index).delete() {
+                Ok(()) | Err(KeychainError::NotFound) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
+    // Removes a generation nothing points at (any more). It no longer matters
+    // to reading, so a chunk that won't go is left behind rather than failing
+    // a write that has already succeeded.
+    fn discard(&self, manifest: Option<Manifest>) {
+        if let Some(manifest) = manifest {
+            let _ = self.delete_chunks(manifest.generation, 1..manifest.count + 1);
+        }
+    }
+}
+
+impl SecretStore for ChunkedStore {
+    fn get(&self) -> Result<String, KeychainError> {
+        let stored = (self.open)(&self.name).get()?;
+        let Some(manifest) = Manifest::parse(&stored) else {
+            return Ok(stored);
+        };
+        let mut secret = String::new();
+        for index in 1..=manifest.count {
+            match self.chunk(manifest.generation, index).get() {
+                Ok(chunk) => secret.push_str(&chunk),
+                Err(KeychainError::NotFound) => {
+                    return Err(KeychainError::Deserialize(format!("'{}' is missing chunk {} of {}", self.name, index, manifest.count)));
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        if hex::encode(Sha256::digest(secret.as_bytes())) != manifest.hash {
+            return Err(KeychainError::Deserialize(format!("the chunks of '{}' don't match its checksum", self.name)));
+        }
+        Ok(secret)
+    }
+
+    fn set(&self, secret: &str) -> Result<(), KeychainError> {
+        let entry = (self.open)(&self.name);
+        let previous = self.stored_manifest(&*entry)?;
+        let chunks = match self.limit {
+            Some(limit) if secret.encode_utf16().count() > limit => split(secret, limit),
+            _ => {
+                entry.set(secret)?;
+                self.discard(previous);
+                return Ok(());
+            }
+        };
+
+        let generation = previous.as_ref().and_then(|manifest| manifest.generation).unwrap_or(0) + 1;
+        let written = Manifest { generation: Some(generation), count: chunks.len(), hash: hex::encode(Sha256::digest(secret.as_bytes())) };
+        for (index, chunk) in chunks.iter().enumerate() {
+            if let Err(e) = self.chunk(Some(generation), index + 1).set(chunk) {
+                self.discard(Some(Manifest { count: index, ..written }));
+                return Err(e);
+            }
+        }
+        if let Err(e) = entry.set(&written.to_string()) {
+            self.discard(Some(written));
+            return Err(e);
+        }
+        self.discard(previous);
+        Ok(())
+    }
+
+    fn delete(&self) -> Result<(), KeychainError> {
+        let entry = (self.open)(&self.name);
+        let stored = self.stored_manifest(&*entry)?;
+        entry.delete()?;
+        match stored {
+            Some(manifest) => self.delete_chunks(manifest.generation, 1..manifest.count + 1),
+            None => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Manifest {
+    /// None for a manifest from before generations
+    generation: Option<u64>,
+    count: usize,
+    hash: String,
+}
+
+impl Manifest {
+    fn parse(stored: &str) -> Option<Self> {
+        let (generation, rest) = match stored.strip_prefix(MANIFEST_PREFIX) {
+            Some(rest) => {
+                let (generation, rest) = rest.split_once(':')?;
+                (Some(generation.parse().ok()?), rest)
+            }
+            None => (None, stored.strip_prefix(LEGACY_MANIFEST_PREFIX)?),
+        };
+        let (count, hash) = rest.split_once(':')?;
+        Some(Self { generation, count: count.parse().ok().filter(|count| *count > 0)?, hash: hash.to_string() })
+    }
+}
+
+impl std::fmt::Display for Manifest {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.generation {
+            Some(generation) => write!(f, "{}{}:{}:{}", MANIFEST_PREFIX, generation, self.count, self.hash),
+            None => write!(f, "{}{}:{}", LEGACY_MANIFEST_PREFIX, self.count, self.hash),
+        }
+    }
+}
+
+/// The username chunk `index` (from 1) of `name`'s `generation` is stored
+/// under; None for the unnumbered chunks of a manifest from before generations.
+pub fn chunk_name(name: &str, generation: Option<u64>, index: usize) -> String {
+    match generation {
+        Some(generation) => format!("{}#g{}:{}", name, generation, index),
+        None => format!("{}#{}", name, index),
+    }
+}
+
+// Splits at character boundaries into pieces of at most `limit` UTF-16 units
+fn split(secret: &str, limit: usize) -> Vec<String> {
+    let mut chunks = vec![String::new()];
+    let mut units = 0;
+    for c in secret.chars() {
+        if units + c.len_utf16() > limit {
+            chunks.push(String::new());
+            units = 0;
+        }
+        units += c.len_utf16();
+        chunks.last_mut().unwrap().push(c);
+    }
+    chunks
+}
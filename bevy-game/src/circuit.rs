@@ -0,0 +1,100 @@
+// Circuit breaker for chain endpoints, shared by every clone of the client.
+//
+// Each failed attempt (no response, or a 5xx) counts against the endpoint's
+// origin; a response of any other kind resets the count. Once `threshold`
+// attempts in a row have failed, the circuit opens and requests to that origin
+// fail fast with `GalaChainError::CircuitOpen` until the cooldown ends. After
+// that a request is let through as a probe: success closes the circuit, a
+// failure opens it for another cooldown. "Retry now" in the banner resets it.
+
+use reqwest::Url;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+pub const FAILURE_THRESHOLD: u32 = 5;
+pub const COOLDOWN: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    Closed,
+    /// Failing fast for the remaining time
+    Open(Duration),
+    /// Cooled down; the next request decides
+    HalfOpen,
+}
+
+#[derive(Debug, Default)]
+struct Circuit {
+    failures: u32,
+    open_until: Option<Instant>,
+}
+
+#[derive(Clone)]
+pub struct CircuitBreaker {
+    circuits: Arc<Mutex<HashMap<String, Circuit>>>,
+    threshold: u32,
+    cooldown: Duration,
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self::new(FAILURE_THRESHOLD, COOLDOWN)
+    }
+}
+
+impl CircuitBreaker {
+    pub fn new(threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            circuits: Arc::default(),
+            threshold: threshold.max(1),
+            cooldown,
+        }
+    }
+
+    /// Whether a request to `url` may go out; otherwise how long until it can.
+    pub fn check(&self, url: &str) -> Result<(), Duration> {
+        match self.state(url) {
+            CircuitState::Open(remaining) => Err(remaining),
+            CircuitState::Closed | CircuitState::HalfOpen => Ok(()),
+        }
+    }
+
+    /// Records how an attempt against `url` went.
+    pub fn record(&self, url: &str, succeeded: bool) {
+        let mut circuits = self.circuits.lock().unwrap();
+        let circuit = circuits.entry(origin(url)).or_default();
+        if succeeded {
+            *circuit = Circuit::default();
+            return;
+        }
+        circuit.failures += 1;
+        if circuit.failures >= self.threshold {
+            circuit.open_until = Some(Instant::now() + self.cooldown);
+        }
+    }
+
+    pub fn state(&self, url: &str) -> CircuitState {
+        let circuits = self.circuits.lock().unwrap();
+        match circuits.get(&origin(url)).and_then(|circuit| circuit.open_until) {
+            None => CircuitState::Closed,
+            Some(until) => match until.checked_duration_since(Instant::now()) {
+                Some(remaining) if !remaining.is_zero() => CircuitState::Open(remaining),
+                _ => CircuitState::HalfOpen,
+            },
+        }
+    }
+
+    /// Closes every circuit, for "Retry now".
+    pub fn reset(&self) {
+        self.circuits.lock().unwrap().clear();
+    }
+}
+
+/// scheme://host:port, so every path on a server shares one circuit.
+pub fn origin(url: &str) -> String {
+    match Url::parse(url) {
+        Ok(parsed) if parsed.has_host() => parsed.origin().ascii_serialization(),
+        _ => url.trim_end_matches('/').to_string(),
+    }
+}
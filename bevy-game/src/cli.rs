@@ -0,0 +1,149 @@
+// Launch options from the command line and GALA_* environment variables.
+//
+// They override the defaults the app would otherwise start with, so the wallet
+// can be pointed at a dev network or started in demo mode from a script. A flag
+// wins over its environment variable. Nothing given here is saved.
+//
+// A galachain:// link in place of an option is how the OS hands over a payment
+// link (see deeplink.rs).
+
+use crate::deeplink::is_deep_link;
+use crate::ApiSettings;
+use std::fmt;
+
+pub const USAGE: &str = "Usage: bevy-game [options] [galachain://transfer?to=...&amount=...]
+
+Options:
+  --operations-url <url>   Operations API base URL        (GALA_OPERATIONS_URL)
+  --identity-url <url>     Identity API base URL          (GALA_IDENTITY_URL)
+  --channel <name>         GalaChain channel              (GALA_CHANNEL)
+  --profile <name>         Keep settings in a separate profile directory (GALA_PROFILE)
+  --demo                   Start in demo mode with the in-memory chain  (GALA_DEMO=1)
+  --portable               Keep settings and an encrypted wallet file next to the
+                           executable, never in the OS keychain (GALA_PORTABLE=1)
+  --headless               Run without a window           (GALA_HEADLESS=1)
+  -h, --help               Show this help
+
+A galachain:// link opens the Transfer screen with its recipient and amount,
+once you confirm it.";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LaunchOptions {
+    pub operations_url: Option<String>,
+    pub identity_url: Option<String>,
+    pub channel: Option<String>,
+    pub profile: Option<String>,
+    pub demo: bool,
+    pub headless: bool,
+    /// Everything next to the executable instead of the config directory and keychain (portable.rs)
+    pub portable: bool,
+    /// galachain:// link the wallet was opened with
+    pub deep_link: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CliError {
+    /// --help was given; not a failure, but nothing should start
+    Help,
+    UnknownFlag(String),
+    MissingValue(String),
+    InvalidValue { name: String, value: String },
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CliError::Help => write!(f, "{}", USAGE),
+            CliError::UnknownFlag(flag) => write!(f, "Unknown option {}", flag),
+            CliError::MissingValue(flag) => write!(f, "{} needs a value", flag),
+            CliError::InvalidValue { name, value } => write!(f, "Invalid value for {}: {:?}", name, value),
+        }
+    }
+}
+
+/// Reads `args` (without the program name), falling back to `env` for anything not given.
+pub fn parse_launch_options(args: impl IntoIterator<Item = String>, env: impl Fn(&str) -> Option<String>) -> Result<LaunchOptions, CliError> {
+    // An exported-but-empty variable counts as unset
+    let text = |name: &str| env(name).filter(|value| !value.trim().is_empty());
+    let mut options = LaunchOptions {
+        operations_url: text("GALA_OPERATIONS_URL"),
+        identity_url: text("GALA_IDENTITY_URL"),
+        channel: text("GALA_CHANNEL"),
+        profile: text("GALA_PROFILE"),
+        demo: env_flag(&env, "GALA_DEMO")?,
+        headless: env_flag(&env, "GALA_HEADLESS")?,
+        portable: env_flag(&env, "GALA_PORTABLE")?,
+        deep_link: None,
+    };
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        // Links have '=' in their query string, so they're picked out before flags are split
+        if is_deep_link(&arg) {
+            options.deep_link = Some(arg);
+            continue;
+        }
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value.to_string())),
+            _ => (arg, None),
+        };
+        let slot = match flag.as_str() {
+            "-h" | "--help" => return Err(CliError::Help),
+            "--demo" | "--headless" | "--portable" => {
+                if let Some(value) = inline_value {
+                    return Err(CliError::InvalidValue { name: flag, value });
+                }
+                match flag.as_str() {
+                    "--demo" => options.demo = true,
+                    "--headless" => options.headless = true,
+                    _ => options.portable = true,
+                }
+                continue;
+            }
+            "--operations-url" => &mut options.operations_url,
+            "--identity-url" => &mut options.identity_url,
+            "--channel" => &mut options.channel,
+            "--profile" => &mut options.profile,
+            _ => return Err(CliError::UnknownFlag(flag)),
+        };
+        let value = inline_value.or_else(|| args.next()).filter(|value| !value.is_empty() && !value.starts_with("--"));
+        *slot = Some(value.ok_or(CliError::MissingValue(flag))?);
+    }
+
+    if let Some(profile) = &options.profile {
+        if !is_valid_profile_name(profile) {
+            return Err(CliError::InvalidValue { name: "profile".to_string(), value: profile.clone() });
+        }
+    }
+    Ok(options)
+}
+
+fn env_flag(env: &impl Fn(&str) -> Option<String>, name: &str) -> Result<bool, CliError> {
+    match env(name).as_deref().map(str::trim) {
+        None | Some("") | Some("0") | Some("false") | Some("no") => Ok(false),
+        Some("1") | Some("true") | Some("yes") => Ok(true),
+        Some(other) => Err(CliError::InvalidValue { name: name.to_string(), value: other.to_string() }),
+    }
+}
+
+/// Profile names become directory names, so they are kept to letters, digits, '-' and '_'.
+pub fn is_valid_profile_name(name: &str) -> bool {
+    !name.is_empty() && name.len() <= 64 && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+impl LaunchOptions {
+    pub fn apply(&self, settings: &mut ApiSettings) {
+        if let Some(url) = &self.operations_url {
+            settings.operations_base_url = url.clone();
+        }
+        if let Some(url) = &self.identity_url {
+            settings.identity_base_url = url.clone();
+        }
+        if let Some(channel) = &self.channel {
+            settings.channel_name = channel.clone();
+        }
+        if self.demo {
+            settings.demo_mode = true;
+        }
+    }
+}
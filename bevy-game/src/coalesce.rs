@@ -0,0 +1,152 @@
+// Request coalescing for GalaChainClient.
+//
+// Identical requests (same endpoint, same JSON payload) share one network call:
+// while a request is in flight, duplicates wait for its result instead of
+// sending their own, and a finished result is reused for `min_interval` so rapid
+// clicking can't hammer the API. Cancelled requests are never shared - a waiter
+// whose leader was cancelled sends the request itself.
+
+use crate::{CancelToken, GalaChainError};
+use serde::Serialize;
+use std::any::Any;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_MIN_INTERVAL: Duration = Duration::from_secs(2);
+const WAIT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RequestKey {
+    pub endpoint: String,
+    pub payload_hash: u64,
+}
+
+impl RequestKey {
+    pub fn new<P: Serialize + ?Sized>(endpoint: &str, payload: &P) -> Self {
+        let mut hasher = DefaultHasher::new();
+        serde_json::to_string(payload).unwrap_or_default().hash(&mut hasher);
+        Self {
+            endpoint: endpoint.to_string(),
+            payload_hash: hasher.finish(),
+        }
+    }
+}
+
+type SharedResult = Arc<dyn Any + Send + Sync>;
+
+enum Entry {
+    InFlight,
+    Done { finished: Instant, result: SharedResult },
+}
+
+/// Shared by all clones of the client, so requests from different tasks coalesce.
+#[derive(Clone)]
+pub struct RequestCoalescer {
+    entries: Arc<Mutex<HashMap<RequestKey, Entry>>>,
+    min_interval: Duration,
+}
+
+impl Default for RequestCoalescer {
+    fn default() -> Self {
+        Self::new(DEFAULT_MIN_INTERVAL)
+    }
+}
+
+impl RequestCoalescer {
+    pub fn new(min_interval: Duration) -> Self {
+        Self {
+            entries: Arc::default(),
+            min_interval,
+        }
+    }
+
+    /// Runs `operation` unless an identical request is in flight or finished less than
+    /// `min_interval` ago, in which case that request's result is returned.
+    /// Must be awaited inside a Tokio runtime.
+    pub async fn run<T, Fut>(&self, key: RequestKey, cancel: &CancelToken, operation: impl FnOnce() -> Fut) -> Result<T, GalaChainError>
+    where
+        T: Clone + Send + Sync + 'static,
+        Fut: std::future::Future<Output = Result<T, GalaChainError>>,
+    {
+        loop {
+            if cancel.is_cancelled() {
+                return Err(GalaChainError::Cancelled);
+            }
+            match self.claim(&key) {
+                Claim::Leader => break,
+                Claim::Shared(result) => {
+                    if let Some(result) = result.downcast_ref::<Result<T, GalaChainError>>() {
+                        return result.clone();
+                    }
+                    // Same endpoint and payload but a different result type; don't share
+                    break;
+                }
+                Claim::Wait => tokio::time::sleep(WAIT_POLL_INTERVAL).await,
+            }
+        }
+
+        let mut guard = LeaderGuard { coalescer: self, key: &key, finished: false };
+        let result = operation().await;
+        guard.finish(&result);
+        result
+    }
+
+    fn claim(&self, key: &RequestKey) -> Claim {
+        let Ok(mut entries) = self.entries.lock() else {
+            return Claim::Leader;
+        };
+        entries.retain(|_, entry| match entry {
+            Entry::InFlight => true,
+            Entry::Done { finished, .. } => finished.elapsed() < self.min_interval,
+        });
+        match entries.get(key) {
+            Some(Entry::InFlight) => Claim::Wait,
+            Some(Entry::Done { result, .. }) => Claim::Shared(result.clone()),
+            None => {
+                entries.insert(key.clone(), Entry::InFlight);
+                Claim::Leader
+            }
+        }
+    }
+}
+
+enum Claim {
+    Leader,
+    Wait,
+    Shared(SharedResult),
+}
+
+// Clears the in-flight marker even if the leading request is dropped part way
+struct LeaderGuard<'a> {
+    coalescer: &'a RequestCoalescer,
+    key: &'a RequestKey,
+    finished: bool,
+}
+
+impl LeaderGuard<'_> {
+    // A cancelled result isn't kept, so waiters take over and send the request themselves
+    fn finish<T: Clone + Send + Sync + 'static>(&mut self, result: &Result<T, GalaChainError>) {
+        if matches!(result, Err(GalaChainError::Cancelled)) {
+            return;
+        }
+        if let Ok(mut entries) = self.coalescer.entries.lock() {
+            let shared: SharedResult = Arc::new(result.clone());
+            entries.insert(self.key.clone(), Entry::Done { finished: Instant::now(), result: shared });
+            self.finished = true;
+        }
+    }
+}
+
+impl Drop for LeaderGuard<'_> {
+    fn drop(&mut self) {
+        if self.finished {
+            return;
+        }
+        if let Ok(mut entries) = self.coalescer.entries.lock() {
+            entries.remove(self.key);
+        }
+    }
+}
@@ -0,0 +1,268 @@
+// Connection reuse for the wallet's HTTP requests, and the Developer panel's
+// view of it.
+//
+// Every request runs on the one shared Tokio runtime (`run_with_tokio` in
+// main.rs), and the clients from http_options.rs keep idle connections open for
+// `POOL_IDLE_TIMEOUT`, so after the first request to a host the next ones skip
+// DNS, TCP and TLS. The configured hosts are also resolved ahead of the first
+// request - at startup and whenever the endpoints change - which fills the
+// system resolver's cache and shows a bad hostname before anything is sent.
+//
+// reqwest doesn't expose its pool, so `ConnectionStats` estimates it per origin
+// from when requests start and finish: a request that finds a connection idle
+// (finished less than the idle timeout ago and not in use) counts as reused,
+// any other as opening a new one. Like the runtime, the stats are process-wide.
+
+use crate::circuit::origin;
+use reqwest::Url;
+use std::collections::BTreeMap;
+use std::net::{IpAddr, ToSocketAddrs};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// How long an unused connection stays open for the next request
+pub const POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+/// Idle connections kept per host; more are closed as requests finish
+pub const POOL_MAX_IDLE_PER_HOST: usize = 8;
+/// TCP keep-alive probes, so a quiet connection isn't dropped by a NAT or proxy
+pub const TCP_KEEPALIVE: Duration = Duration::from_secs(60);
+
+/// One origin's requests, with an estimate of how many reused a connection. The
+/// pool itself is never seen: `reused`, `opened` and `idle` are what timing
+/// alone suggests, and can be wrong when the server or a proxy closes a
+/// connection early.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OriginPool {
+    pub requests: u64,
+    /// Requests estimated to have found an idle connection
+    pub reused: u64,
+    /// Requests estimated to have opened one
+    pub opened: u64,
+    pub in_flight: usize,
+    // Connections believed open, in use or idle
+    open: usize,
+    // When the last request finished; idle connections close POOL_IDLE_TIMEOUT after it
+    idle_since: Option<Instant>,
+}
+
+impl OriginPool {
+    pub fn begin(&mut self, now: Instant) {
+        if self.expired(now) {
+            self.open = self.in_flight;
+        }
+        if self.open > self.in_flight {
+            self.reused += 1;
+        } else {
+            self.opened += 1;
+            self.open += 1;
+        }
+        self.requests += 1;
+        self.in_flight += 1;
+    }
+
+    /// `connected` is false when no response arrived: that connection isn't kept.
+    pub fn finish(&mut self, now: Instant, connected: bool) {
+        self.in_flight = self.in_flight.saturating_sub(1);
+        if !connected {
+            self.open = self.open.saturating_sub(1);
+        }
+        self.open = self.open.clamp(self.in_flight, self.in_flight + POOL_MAX_IDLE_PER_HOST);
+        self.idle_since = Some(now);
+    }
+
+    /// Connections estimated open and waiting for the next request
+    pub fn idle(&self, now: Instant) -> usize {
+        if self.expired(now) {
+            0
+        } else {
+            self.open - self.in_flight
+        }
+    }
+
+    /// Estimated share of requests that went out on a kept-alive connection
+    pub fn reuse_rate(&self) -> f32 {
+        if self.requests == 0 {
+            return 0.0;
+        }
+        self.reused as f32 / self.requests as f32
+    }
+
+    fn expired(&self, now: Instant) -> bool {
+        self.idle_since.is_some_and(|since| now.saturating_duration_since(since) >= POOL_IDLE_TIMEOUT)
+    }
+}
+
+/// One host resolved ahead of its first request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lookup {
+    pub host: String,
+    pub result: Result<Vec<IpAddr>, String>,
+    pub took: Duration,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConnectionData {
+    /// Keyed by origin, e.g. "https://gateway.example.com"
+    pub origins: BTreeMap<String, OriginPool>,
+    /// The latest lookup of each configured host, in the order they were resolved
+    pub lookups: Vec<Lookup>,
+}
+
+impl ConnectionData {
+    /// What the Developer panel shows; the reuse figures are marked as estimates.
+    pub fn lines(&self, now: Instant) -> Vec<String> {
+        let mut lines = vec![format!(
+            "Keep-alive: idle connections kept {} s, up to {} per host (reuse estimated from request timing)",
+            POOL_IDLE_TIMEOUT.as_secs(),
+            POOL_MAX_IDLE_PER_HOST
+        )];
+        for (origin, pool) in &self.origins {
+            lines.push(format!(
+                "{}: {} requests, ~{} reused (est. {:.0}%), ~{} new connections, ~{} idle",
+                origin,
+                pool.requests,
+                pool.reused,
+                pool.reuse_rate() * 100.0,
+                pool.opened,
+                pool.idle(now)
+            ));
+        }
+        for lookup in &self.lookups {
+            lines.push(match &lookup.result {
+                Ok(addresses) => {
+                    let addresses: Vec<String> = addresses.iter().map(IpAddr::to_string).collect();
+                    format!("{} → {} ({} ms)", lookup.host, addresses.join(", "), lookup.took.as_millis())
+                }
+                Err(e) => format!("{}: lookup failed: {}", lookup.host, e),
+            });
+        }
+        if self.origins.is_empty() && self.lookups.is_empty() {
+            lines.push("No connections yet".to_string());
+        }
+        lines
+    }
+}
+
+/// Shared by every client and task; see `stats`.
+#[derive(Clone, Default)]
+pub struct ConnectionStats {
+    data: Arc<Mutex<ConnectionData>>,
+    // Bumped on every change so the Developer panel knows when to redraw
+    version: Arc<AtomicU64>,
+}
+
+impl ConnectionStats {
+    /// Counts a request to `url` as started; drop the returned guard (or `finish` it) when it's done.
+    pub fn begin(&self, url: &str) -> InFlight {
+        let origin = origin(url);
+        self.update(|data| data.origins.entry(origin.clone()).or_default().begin(Instant::now()));
+        InFlight { stats: self.clone(), origin, finished: false }
+    }
+
+    pub fn record_lookup(&self, lookup: Lookup) {
+        self.update(|data| {
+            data.lookups.retain(|known| known.host != lookup.host);
+            data.lookups.push(lookup);
+        });
+    }
+
+    /// A rebuilt client starts with an empty pool; the counts carry on.
+    pub fn client_rebuilt(&self) {
+        self.update(|data| {
+            for pool in data.origins.values_mut() {
+                pool.open = pool.in_flight;
+            }
+        });
+    }
+
+    pub fn snapshot(&self) -> ConnectionData {
+        self.data.lock().map(|data| data.clone()).unwrap_or_default()
+    }
+
+    pub fn version(&self) -> u64 {
+        self.version.load(Ordering::Relaxed)
+    }
+
+    fn update(&self, change: impl FnOnce(&mut ConnectionData)) {
+        if let Ok(mut data) = self.data.lock() {
+            change(&mut data);
+        }
+        self.version.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// A request being counted. Dropped without `finish` - cancelled, say - it counts as having got no response.
+pub struct InFlight {
+    stats: ConnectionStats,
+    origin: String,
+    finished: bool,
+}
+
+impl InFlight {
+    pub fn finish(mut self, connected: bool) {
+        self.record(connected);
+    }
+
+    fn record(&mut self, connected: bool) {
+        if self.finished {
+            return;
+        }
+        self.finished = true;
+        let origin = &self.origin;
+        self.stats.update(|data| {
+            if let Some(pool) = data.origins.get_mut(origin) {
+                pool.finish(Instant::now(), connected);
+            }
+        });
+    }
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        self.record(false);
+    }
+}
+
+/// The process-wide stats every client records into.
+pub fn stats() -> &'static ConnectionStats {
+    static STATS: OnceLock<ConnectionStats> = OnceLock::new();
+    STATS.get_or_init(ConnectionStats::default)
+}
+
+/// Host and port of each URL, each pair once, in the order given; URLs that don't parse are skipped.
+pub fn hosts(urls: &[&str]) -> Vec<(String, u16)> {
+    let mut hosts: Vec<(String, u16)> = Vec::new();
+    for url in urls {
+        let Ok(parsed) = Url::parse(url) else {
+            continue;
+        };
+        let (Some(host), Some(port)) = (parsed.host_str(), parsed.port_or_known_default()) else {
+            continue;
+        };
+        // IPv6 hosts come bracketed, which the resolver doesn't want
+        let host = host.trim_start_matches('[').trim_end_matches(']').to_string();
+        if !hosts.iter().any(|(known, known_port)| *known == host && *known_port == port) {
+            hosts.push((host, port));
+        }
+    }
+    hosts
+}
+
+/// Resolves `host` with the system resolver. Blocks, so it runs on a task thread.
+pub fn resolve(host: &str, port: u16) -> Lookup {
+    let started = Instant::now();
+    let result = (host, port)
+        .to_socket_addrs()
+        .map(|addresses| {
+            let mut ips: Vec<IpAddr> = Vec::new();
+            for address in addresses {
+                if !ips.contains(&address.ip()) {
+                    ips.push(address.ip());
+                }
+            }
+            ips
+        })
+        .map_err(|e| e.to_string());
+    Lookup { host: host.to_string(), result, took: started.elapsed() }
+}
@@ -0,0 +1,211 @@
+// Contract method discovery for the Chain Explorer.
+//
+// Every GalaChain contract answers GetContractAPI with its name, version and
+// methods, each with the JSON schema of the DTO it takes. The explorer asks the
+// contract it's pointed at and adds the read-only methods to its method menu;
+// picking one shows a form with a field per DTO property, and what's typed is
+// turned into the DTO by each property's type, so there's no JSON to write by
+// hand. Methods that write are counted but not offered - the explorer never
+// signs, which is also why the signature fields every DTO schema carries are
+// left out of the form.
+
+use serde_json::{Map, Value};
+
+/// The method every contract answers with its API
+pub const METHOD: &str = "GetContractAPI";
+
+// Filled in by signing, never typed
+const SIGNING_FIELDS: [&str; 6] = ["signature", "signerPublicKey", "signerAddress", "prefix", "signing", "uniqueKey"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldKind {
+    Text,
+    Number,
+    Integer,
+    Boolean,
+    /// Comma-separated words, or a JSON array
+    List,
+    /// Anything else - an object, or a property the schema doesn't type
+    Json,
+}
+
+impl FieldKind {
+    fn of(schema: &Value) -> Self {
+        // "type" can be a list like ["string", "null"]
+        let kind = match &schema["type"] {
+            Value::String(kind) => Some(kind.as_str()),
+            Value::Array(kinds) => kinds.iter().filter_map(Value::as_str).find(|kind| *kind != "null"),
+            _ => None,
+        };
+        match kind {
+            Some("string") => FieldKind::Text,
+            Some("number") => FieldKind::Number,
+            Some("integer") => FieldKind::Integer,
+            Some("boolean") => FieldKind::Boolean,
+            Some("array") => FieldKind::List,
+            _ => FieldKind::Json,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            FieldKind::Text => "text",
+            FieldKind::Number => "number",
+            FieldKind::Integer => "whole number",
+            FieldKind::Boolean => "true / false",
+            FieldKind::List => "a, b or a JSON list",
+            FieldKind::Json => "JSON",
+        }
+    }
+}
+
+/// One DTO property, as a form field.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaField {
+    pub name: String,
+    pub kind: FieldKind,
+    pub required: bool,
+    pub description: Option<String>,
+    /// The values the schema allows, when it lists them
+    pub choices: Vec<String>,
+}
+
+impl SchemaField {
+    /// "owner (text, required)"
+    pub fn label(&self) -> String {
+        let kind = if self.choices.is_empty() { self.kind.name().to_string() } else { self.choices.join(" / ") };
+        if self.required {
+            format!("{} ({}, required)", self.name, kind)
+        } else {
+            format!("{} ({})", self.name, kind)
+        }
+    }
+
+    /// The property's value from what was typed; None leaves an optional one out.
+    pub fn value(&self, text: &str) -> Result<Option<Value>, String> {
+        let text = text.trim();
+        if text.is_empty() {
+            return if self.required { Err(format!("{} is required", self.name)) } else { Ok(None) };
+        }
+        if !self.choices.is_empty() && !self.choices.iter().any(|choice| choice == text) {
+            return Err(format!("{} must be one of {}", self.name, self.choices.join(", ")));
+        }
+        let value = match self.kind {
+            FieldKind::Text => Value::String(text.to_string()),
+            FieldKind::Number => text
+                .parse::<f64>()
+                .ok()
+                .and_then(serde_json::Number::from_f64)
+                .map(Value::Number)
+                .ok_or_else(|| format!("{} must be a number", self.name))?,
+            FieldKind::Integer => text.parse::<i64>().map(Value::from).map_err(|_| format!("{} must be a whole number", self.name))?,
+            FieldKind::Boolean => match text.to_ascii_lowercase().as_str() {
+                "true" | "yes" => Value::Bool(true),
+                "false" | "no" => Value::Bool(false),
+                _ => return Err(format!("{} must be true or false", self.name)),
+            },
+            FieldKind::List if text.starts_with('[') => match serde_json::from_str(text) {
+                Ok(list @ Value::Array(_)) => list,
+                _ => return Err(format!("{} isn't a JSON list", self.name)),
+            },
+            FieldKind::List => Value::Array(text.split(',').map(str::trim).filter(|item| !item.is_empty()).map(|item| Value::String(item.to_string())).collect()),
+            FieldKind::Json => serde_json::from_str(text).map_err(|e| format!("{} isn't valid JSON: {}", self.name, e))?,
+        };
+        Ok(Some(value))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MethodApi {
+    pub name: String,
+    pub is_write: bool,
+    pub description: Option<String>,
+    /// Required ones first, then by name
+    pub fields: Vec<SchemaField>,
+}
+
+impl MethodApi {
+    fn parse(method: &Value) -> Option<Self> {
+        let name = method["methodName"].as_str().filter(|name| !name.is_empty())?.to_string();
+        let schema = &method["dtoSchema"];
+        let required: Vec<&str> = schema["required"].as_array().map(|names| names.iter().filter_map(Value::as_str).collect()).unwrap_or_default();
+        let mut fields: Vec<SchemaField> = schema["properties"]
+            .as_object()
+            .map(|properties| {
+                properties
+                    .iter()
+                    .filter(|(name, _)| !SIGNING_FIELDS.contains(&name.as_str()))
+                    .map(|(name, property)| SchemaField {
+                        name: name.clone(),
+                        kind: FieldKind::of(property),
+                        required: required.contains(&name.as_str()),
+                        description: property["description"].as_str().map(str::to_string),
+                        choices: property["enum"].as_array().map(|choices| choices.iter().map(|choice| choice.as_str().map_or_else(|| choice.to_string(), str::to_string)).collect()).unwrap_or_default(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        fields.sort_by(|a, b| (!a.required, &a.name).cmp(&(!b.required, &b.name)));
+        Some(Self {
+            name,
+            is_write: method["isWrite"].as_bool().unwrap_or(false),
+            description: method["description"].as_str().map(str::to_string).filter(|description| !description.trim().is_empty()),
+            fields,
+        })
+    }
+
+    /// The DTO from the form's inputs, one per field in order; the first problem if any.
+    pub fn dto(&self, inputs: &[String]) -> Result<Value, String> {
+        let mut dto = Map::new();
+        for (index, field) in self.fields.iter().enumerate() {
+            let text = inputs.get(index).map(String::as_str).unwrap_or_default();
+            if let Some(value) = field.value(text)? {
+                dto.insert(field.name.clone(), value);
+            }
+        }
+        Ok(Value::Object(dto))
+    }
+}
+
+/// A contract's GetContractAPI answer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContractApi {
+    pub contract_name: String,
+    pub contract_version: String,
+    /// By name
+    pub methods: Vec<MethodApi>,
+}
+
+impl ContractApi {
+    pub fn parse(data: &Value) -> Result<Self, String> {
+        let methods = data["methods"].as_array().ok_or("The answer has no methods list - is this a GalaChain contract?")?;
+        let mut methods: Vec<MethodApi> = methods.iter().filter_map(MethodApi::parse).collect();
+        methods.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(Self {
+            contract_name: data["contractName"].as_str().unwrap_or("Contract").to_string(),
+            contract_version: data["contractVersion"].as_str().unwrap_or_default().to_string(),
+            methods,
+        })
+    }
+
+    /// The methods the explorer offers
+    pub fn reads(&self) -> impl Iterator<Item = &MethodApi> {
+        self.methods.iter().filter(|method| !method.is_write)
+    }
+
+    pub fn read(&self, name: &str) -> Option<&MethodApi> {
+        self.reads().find(|method| method.name == name)
+    }
+
+    /// "GalaChainToken 1.4.2: 14 read methods (22 that write aren't offered)"
+    pub fn summary(&self) -> String {
+        let reads = self.reads().count();
+        let writes = self.methods.len() - reads;
+        let name = if self.contract_version.is_empty() { self.contract_name.clone() } else { format!("{} {}", self.contract_name, self.contract_version) };
+        if writes == 0 {
+            format!("{}: {} read methods", name, reads)
+        } else {
+            format!("{}: {} read methods ({} that write aren't offered)", name, reads, writes)
+        }
+    }
+}
@@ -0,0 +1,85 @@
+// Gateway credentials for hosted GalaChain gateways that want an API key or a
+// bearer token with each request.
+//
+// Each profile (`--profile`) has its own keychain entry next to the wallet's, so
+// a staging gateway's key is never sent to production. The credential reaches
+// requests as `HttpOptions::auth`, which is never written to a config file or a
+// crash report; only chain requests carry it.
+
+use crate::http_options::{check_header, HttpOptionsError};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+pub const DEFAULT_API_KEY_HEADER: &str = "X-API-Key";
+const KEYCHAIN_USERNAME: &str = "gateway-credentials";
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum GatewayCredential {
+    /// Sent as `{header}: {key}`
+    ApiKey { header: String, key: String },
+    /// Sent as `Authorization: Bearer {token}`
+    Bearer { token: String },
+}
+
+// Keeps secrets out of logs if a credential is ever printed with {:?}
+impl fmt::Debug for GatewayCredential {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GatewayCredential::ApiKey { header, .. } => write!(f, "ApiKey({}: [redacted])", header),
+            GatewayCredential::Bearer { .. } => write!(f, "Bearer([redacted])"),
+        }
+    }
+}
+
+impl GatewayCredential {
+    /// The header it is sent as, checked the way reqwest will check it.
+    pub fn header(&self) -> Result<AuthHeader, HttpOptionsError> {
+        let (name, secret) = match self {
+            GatewayCredential::ApiKey { header, key } => (header.trim(), key.trim()),
+            GatewayCredential::Bearer { token } => ("Authorization", token.trim()),
+        };
+        if secret.is_empty() {
+            return Err(HttpOptionsError::InvalidHeader("the credential is empty".to_string()));
+        }
+        let value = match self {
+            GatewayCredential::ApiKey { .. } => secret.to_string(),
+            GatewayCredential::Bearer { .. } => format!("Bearer {}", secret),
+        };
+        let name = name.to_string();
+        check_header(&name, &value)?;
+        Ok(AuthHeader { name, value })
+    }
+
+    /// What Settings shows for a saved credential: the kind, and the secret's last
+    /// four characters when it's long enough that they give little away.
+    pub fn summary(&self) -> String {
+        let (kind, secret) = match self {
+            GatewayCredential::ApiKey { header, key } => (format!("API key in {}", header), key.trim()),
+            GatewayCredential::Bearer { token } => ("Bearer token".to_string(), token.trim()),
+        };
+        let chars: Vec<char> = secret.chars().collect();
+        let tail: String = if chars.len() > 12 { chars[chars.len() - 4..].iter().collect() } else { String::new() };
+        format!("{} (••••{})", kind, tail)
+    }
+}
+
+/// The keychain entry's username for a profile's credentials.
+pub fn keychain_username(profile: Option<&str>) -> String {
+    match profile {
+        Some(profile) => format!("{}-{}", KEYCHAIN_USERNAME, profile),
+        None => KEYCHAIN_USERNAME.to_string(),
+    }
+}
+
+#[derive(Clone, PartialEq)]
+pub struct AuthHeader {
+    pub name: String,
+    pub value: String,
+}
+
+impl fmt::Debug for AuthHeader {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: [redacted]", self.name)
+    }
+}
@@ -0,0 +1,105 @@
+// Address dashboard: balances for several addresses side by side.
+//
+// The list of extra addresses is saved to `dashboard.json`; the active wallet
+// is always shown first and isn't part of the saved list. Balances come from
+// the response cache and are refreshed concurrently, one API task per address.
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::path::PathBuf;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DashboardSort {
+    #[default]
+    Added,
+    /// Largest total balance first; addresses without a balance go last
+    Balance,
+    Address,
+}
+
+impl DashboardSort {
+    pub fn next(self) -> Self {
+        match self {
+            DashboardSort::Added => DashboardSort::Balance,
+            DashboardSort::Balance => DashboardSort::Address,
+            DashboardSort::Address => DashboardSort::Added,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            DashboardSort::Added => "Sort: Added",
+            DashboardSort::Balance => "Sort: Balance",
+            DashboardSort::Address => "Sort: Address",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct DashboardConfig {
+    /// 0x addresses in the order they were added
+    pub addresses: Vec<String>,
+    pub sort: DashboardSort,
+}
+
+impl DashboardConfig {
+    pub fn load(path: Option<PathBuf>) -> Self {
+        path.and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: Option<PathBuf>) -> std::io::Result<()> {
+        let Some(path) = path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self).unwrap_or_default())
+    }
+
+    /// False if the address is already listed (addresses compare case-insensitively).
+    pub fn add(&mut self, address: String) -> bool {
+        if self.contains(&address) {
+            return false;
+        }
+        self.addresses.push(address);
+        true
+    }
+
+    pub fn remove(&mut self, address: &str) {
+        self.addresses.retain(|listed| !listed.eq_ignore_ascii_case(address));
+    }
+
+    pub fn contains(&self, address: &str) -> bool {
+        self.addresses.iter().any(|listed| listed.eq_ignore_ascii_case(address))
+    }
+}
+
+/// One line of the dashboard.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DashboardRow {
+    pub address: String,
+    /// The active wallet rather than an added address
+    pub own: bool,
+    /// Available and locked, once fetched
+    pub balance: Option<(Decimal, Decimal)>,
+}
+
+/// The active wallet stays on top whatever the sort order.
+pub fn sort_rows(rows: &mut [DashboardRow], sort: DashboardSort) {
+    rows.sort_by(|a, b| {
+        b.own.cmp(&a.own).then_with(|| match sort {
+            DashboardSort::Added => Ordering::Equal,
+            DashboardSort::Balance => {
+                let total = |row: &DashboardRow| row.balance.map(|(available, locked)| available + locked);
+                // None sorts below any balance
+                total(b).cmp(&total(a))
+            }
+            DashboardSort::Address => a.address.to_lowercase().cmp(&b.address.to_lowercase()),
+        })
+    });
+}
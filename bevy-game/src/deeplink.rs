@@ -0,0 +1,159 @@
+// galachain:// payment links, so a dapp in the browser can hand a transfer to
+// the desktop wallet:
+//
+//   galachain://transfer?to=eth|abc...&amount=5&token=GALA
+//
+// `pay` is accepted in place of `transfer`, and `amount` and `token` are
+// optional. The OS starts the wallet with the link as its only argument. The
+// link only fills in the Transfer screen after the user has confirmed it: it
+// never signs or sends anything itself. A link opened while the wallet is
+// already running starts a second copy.
+
+use crate::amounts::parse_amount;
+use rust_decimal::Decimal;
+use std::fmt;
+use std::path::Path;
+use std::process::Command;
+
+pub const SCHEME: &str = "galachain";
+/// Linux desktop entry that registers the scheme handler
+pub const DESKTOP_FILE: &str = "galachain-wallet-links.desktop";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaymentRequest {
+    /// As written in the link; `parse_recipient` checks it when it is shown
+    pub to: String,
+    pub amount: Option<Decimal>,
+    /// Token symbol, when the link names one
+    pub token: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeepLinkError {
+    NotALink,
+    UnknownAction(String),
+    MissingRecipient,
+    InvalidAmount(String),
+    BadEncoding,
+}
+
+impl fmt::Display for DeepLinkError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DeepLinkError::NotALink => write!(f, "Not a {}:// link", SCHEME),
+            DeepLinkError::UnknownAction(action) => write!(f, "Unknown link action {:?} (expected transfer or pay)", action),
+            DeepLinkError::MissingRecipient => write!(f, "The link has no recipient (to=...)"),
+            DeepLinkError::InvalidAmount(amount) => write!(f, "Invalid amount in link: {:?}", amount),
+            DeepLinkError::BadEncoding => write!(f, "The link is not properly URL-encoded"),
+        }
+    }
+}
+
+impl std::error::Error for DeepLinkError {}
+
+/// Whether a launch argument is a link rather than an option.
+pub fn is_deep_link(arg: &str) -> bool {
+    arg.get(..SCHEME.len() + 1).is_some_and(|prefix| prefix.eq_ignore_ascii_case("galachain:"))
+}
+
+pub fn parse_payment_link(link: &str) -> Result<PaymentRequest, DeepLinkError> {
+    if !is_deep_link(link) {
+        return Err(DeepLinkError::NotALink);
+    }
+    let rest = link[SCHEME.len() + 1..].trim_start_matches('/');
+    let (action, query) = rest.split_once('?').unwrap_or((rest, ""));
+    let action = action.trim_end_matches('/');
+    if !action.eq_ignore_ascii_case("transfer") && !action.eq_ignore_ascii_case("pay") {
+        return Err(DeepLinkError::UnknownAction(action.to_string()));
+    }
+
+    let mut request = PaymentRequest { to: String::new(), amount: None, token: None };
+    for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let value = percent_decode(value)?;
+        match key {
+            "to" => request.to = value.trim().to_string(),
+            "amount" => {
+                let amount = parse_amount(&value).filter(|amount| *amount > Decimal::ZERO);
+                request.amount = Some(amount.ok_or(DeepLinkError::InvalidAmount(value))?);
+            }
+            "token" => request.token = Some(value.trim().to_string()).filter(|token| !token.is_empty()),
+            // Ignored, so links with parameters added later still open
+            _ => {}
+        }
+    }
+    if request.to.is_empty() {
+        return Err(DeepLinkError::MissingRecipient);
+    }
+    Ok(request)
+}
+
+// %XX escapes and '+' for a space, as browsers write query strings
+fn percent_decode(value: &str) -> Result<String, DeepLinkError> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' => {
+                let hex = bytes.get(i + 1..i + 3).filter(|hex| hex.iter().all(u8::is_ascii_hexdigit)).ok_or(DeepLinkError::BadEncoding)?;
+                let hex = std::str::from_utf8(hex).map_err(|_| DeepLinkError::BadEncoding)?;
+                decoded.push(u8::from_str_radix(hex, 16).map_err(|_| DeepLinkError::BadEncoding)?);
+                i += 3;
+            }
+            b'+' => {
+                decoded.push(b' ');
+                i += 1;
+            }
+            byte => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8(decoded).map_err(|_| DeepLinkError::BadEncoding)
+}
+
+/// Freedesktop entry that opens galachain:// links with `exe`.
+pub fn desktop_entry(exe: &Path) -> String {
+    // Quoted per the Desktop Entry spec's Exec rules
+    let mut quoted = String::new();
+    for c in exe.display().to_string().chars() {
+        if matches!(c, '"' | '`' | '$' | '\\') {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    format!(
+        "[Desktop Entry]\nType=Application\nName=GalaChain Wallet\nExec=\"{}\" %u\nTerminal=false\nNoDisplay=true\nMimeType=x-scheme-handler/{};\n",
+        quoted, SCHEME
+    )
+}
+
+/// Makes `exe` the handler for galachain:// links for the current user.
+pub fn register_handler(exe: &Path) -> Result<(), String> {
+    if cfg!(target_os = "macos") {
+        return Err(format!("On macOS links reach the app through its bundle: add {} to CFBundleURLTypes in Info.plist", SCHEME));
+    }
+    if cfg!(windows) {
+        let key = format!(r"HKCU\Software\Classes\{}", SCHEME);
+        let command = format!("\"{}\" \"%1\"", exe.display());
+        run("reg", &["add", &key, "/ve", "/d", "URL:GalaChain payment link", "/f"])?;
+        run("reg", &["add", &key, "/v", "URL Protocol", "/d", "", "/f"])?;
+        return run("reg", &["add", &format!(r"{}\shell\open\command", key), "/ve", "/d", &command, "/f"]);
+    }
+
+    let dir = dirs::data_dir().ok_or("No data directory to put the desktop entry in")?.join("applications");
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Can't create {}: {}", dir.display(), e))?;
+    let path = dir.join(DESKTOP_FILE);
+    std::fs::write(&path, desktop_entry(exe)).map_err(|e| format!("Can't write {}: {}", path.display(), e))?;
+    run("xdg-mime", &["default", DESKTOP_FILE, &format!("x-scheme-handler/{}", SCHEME)])
+}
+
+fn run(program: &str, args: &[&str]) -> Result<(), String> {
+    match Command::new(program).args(args).output() {
+        Ok(output) if output.status.success() => Ok(()),
+        Ok(output) => Err(format!("{} failed: {}", program, String::from_utf8_lossy(&output.stderr).trim())),
+        Err(e) => Err(format!("Couldn't run {}: {}", program, e)),
+    }
+}
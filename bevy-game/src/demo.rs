@@ -0,0 +1,150 @@
+// Demo mode: an in-process stand-in for the GalaChain servers.
+//
+// Lets the wallet be explored without an operations or identity server running.
+// Every address is funded the first time it is looked at, registration succeeds
+// at once, and signed transfers and burns move the in-memory balances. Nothing
+// is persisted: the ledger lasts until demo mode is turned off or the app quits.
+
+use crate::chain::ChainApi;
+use crate::signing::SignedDto;
+use crate::tokens::TokenInfo;
+use crate::{CancelToken, GalaChainError};
+use rust_decimal::Decimal;
+use serde_json::{json, Value};
+use sha3::{Digest, Keccak256};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Available balance every address starts with, per token class.
+pub const DEMO_STARTING_BALANCE: Decimal = Decimal::from_parts(1000, 0, 0, false, 0);
+/// GALA also starts with a hold, so the locked column has something to show.
+pub const DEMO_STARTING_LOCKED_GALA: Decimal = Decimal::from_parts(50, 0, 0, false, 0);
+
+const GALA_CLASS: &str = "GALA|Unit|none|none";
+
+#[derive(Default)]
+struct Ledger {
+    /// (GalaChain address, token class key) -> (available, locked)
+    balances: HashMap<(String, String), (Decimal, Decimal)>,
+    /// GalaChain address -> registered public key (hex)
+    registered: HashMap<String, String>,
+}
+
+impl Ledger {
+    fn account(&mut self, owner: &str, class_key: &str) -> &mut (Decimal, Decimal) {
+        self.balances.entry((owner.to_string(), class_key.to_string())).or_insert_with(|| {
+            let locked = if class_key == GALA_CLASS { DEMO_STARTING_LOCKED_GALA } else { Decimal::ZERO };
+            (DEMO_STARTING_BALANCE, locked)
+        })
+    }
+
+    fn debit(&mut self, owner: &str, class_key: &str, quantity: Decimal) -> Result<(), GalaChainError> {
+        let (available, _) = self.account(owner, class_key);
+        if *available < quantity {
+            return Err(GalaChainError::InsufficientBalance(format!("{} has {} available", owner, available)));
+        }
+        *available -= quantity;
+        Ok(())
+    }
+}
+
+/// Shared by every clone of the client, so background tasks see the same ledger.
+#[derive(Clone, Default)]
+pub struct FakeChain(Arc<Mutex<Ledger>>);
+
+// Answers at once, so cancellation never has anything to interrupt
+impl ChainApi for FakeChain {
+    fn fetch_balance(&self, owner: &str, token: &TokenInfo, _cancel: &CancelToken) -> Result<(Decimal, Decimal), GalaChainError> {
+        Ok(*self.0.lock().unwrap().account(owner, &token.class_key()))
+    }
+
+    fn fetch_public_key(&self, owner: &str, _cancel: &CancelToken) -> Result<Option<String>, GalaChainError> {
+        Ok(self.0.lock().unwrap().registered.get(owner).cloned())
+    }
+
+    fn register(&self, public_key: &str, _cancel: &CancelToken) -> Result<(), GalaChainError> {
+        let bytes = hex::decode(public_key.trim_start_matches("0x")).map_err(|e| GalaChainError::Api(format!("Invalid public key: {}", e)))?;
+        if bytes.len() != 65 || bytes[0] != 0x04 {
+            return Err(GalaChainError::Api("Public key must be 65 bytes, uncompressed".to_string()));
+        }
+        let hash = Keccak256::digest(&bytes[1..]);
+        let owner = crate::GalaChainClient::ethereum_to_galachain_address(&hex::encode(&hash[12..]));
+        self.0.lock().unwrap().registered.insert(owner, public_key.to_string());
+        Ok(())
+    }
+
+    fn update_public_key(&self, _signed: &SignedDto, _cancel: &CancelToken) -> Result<Value, GalaChainError> {
+        // Keys are registered under the address they derive, so they can't disagree here
+        Err(GalaChainError::Api("The demo chain has no keys to update".to_string()))
+    }
+
+    fn transfer(&self, signed: &SignedDto, _cancel: &CancelToken) -> Result<Value, GalaChainError> {
+        let dto = &signed.body;
+        let from = field(dto, "from")?;
+        let to = field(dto, "to")?;
+        let class_key = class_key(&dto["tokenInstance"])?;
+        let quantity = quantity(&dto["quantity"])?;
+
+        let mut ledger = self.0.lock().unwrap();
+        ledger.debit(from, &class_key, quantity)?;
+        ledger.account(to, &class_key).0 += quantity;
+        Ok(accepted(signed))
+    }
+
+    fn burn(&self, signed: &SignedDto, _cancel: &CancelToken) -> Result<Value, GalaChainError> {
+        let dto = &signed.body;
+        let owner = field(dto, "owner")?;
+        let burns = dto["tokenInstances"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|instance| Ok((class_key(&instance["tokenInstanceKey"])?, quantity(&instance["quantity"])?)))
+            .collect::<Result<Vec<_>, GalaChainError>>()?;
+
+        // All or nothing, like a chain transaction
+        let mut ledger = self.0.lock().unwrap();
+        let before = ledger.balances.clone();
+        for (class_key, quantity) in burns {
+            if let Err(e) = ledger.debit(owner, &class_key, quantity) {
+                ledger.balances = before;
+                return Err(e);
+            }
+        }
+        Ok(accepted(signed))
+    }
+
+    /// Every class exists here, with the metadata it was asked about.
+    fn fetch_token_classes(&self, classes: &[TokenInfo], _cancel: &CancelToken) -> Result<Vec<TokenInfo>, GalaChainError> {
+        Ok(classes.to_vec())
+    }
+
+    fn ping(&self) -> (Result<Duration, GalaChainError>, Result<Duration, GalaChainError>) {
+        (Ok(Duration::ZERO), Ok(Duration::ZERO))
+    }
+}
+
+fn accepted(signed: &SignedDto) -> Value {
+    json!({ "Status": 1, "Data": signed.unique_key })
+}
+
+fn field<'a>(dto: &'a Value, name: &str) -> Result<&'a str, GalaChainError> {
+    dto[name].as_str().filter(|value| !value.is_empty()).ok_or_else(|| GalaChainError::Api(format!("Missing {}", name)))
+}
+
+fn class_key(token: &Value) -> Result<String, GalaChainError> {
+    let parts = ["collection", "category", "type", "additionalKey"]
+        .iter()
+        .map(|name| field(token, name))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(parts.join("|"))
+}
+
+fn quantity(value: &Value) -> Result<Decimal, GalaChainError> {
+    value
+        .as_str()
+        .and_then(|text| Decimal::from_str(text).ok())
+        .filter(|quantity| *quantity > Decimal::ZERO)
+        .ok_or_else(|| GalaChainError::Api(format!("Invalid quantity {}", value)))
+}
@@ -0,0 +1,142 @@
+// How a seed phrase becomes a key.
+//
+// This wallet has always used the first 32 bytes of the BIP39 seed as the key,
+// called "legacy" here. Most Ethereum wallets derive along BIP44's
+// m/44'/60'/0'/0/i instead, so the same twelve words open a different address
+// in them - which is why an imported phrase can "show the wrong address". The
+// import screen previews both so the user can adopt the one holding their
+// funds. The choice is stored with the wallet; entries without one are legacy.
+// Wallets created here use `MIGRATION_TARGET` (BIP44 account 0, index 0), and
+// Overview offers to migrate the legacy ones made before that.
+
+use crate::address;
+use bip39::{Language, Mnemonic};
+use hmac::{Hmac, Mac};
+use secp256k1::{PublicKey, Scalar, Secp256k1, SecretKey};
+use sha2::Sha512;
+use sha3::{Digest, Keccak256};
+
+/// BIP44 addresses listed by the import preview, from index 0
+pub const PREVIEW_ADDRESSES: u32 = 5;
+
+/// Where the migration assistant moves a legacy wallet: the address other wallets open first
+pub const MIGRATION_TARGET: Derivation = Derivation::Bip44(0);
+
+const HARDENED: u32 = 0x8000_0000;
+const BIP44_PREFIX: &str = "m/44'/60'/0'/0/";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Derivation {
+    /// First 32 bytes of the seed
+    #[default]
+    Legacy,
+    /// m/44'/60'/0'/0/index, as MetaMask and most Ethereum wallets use
+    Bip44(u32),
+}
+
+impl Derivation {
+    /// Everything the import preview offers: legacy, then the first BIP44 addresses
+    pub fn preview_options() -> Vec<Derivation> {
+        std::iter::once(Derivation::Legacy).chain((0..PREVIEW_ADDRESSES).map(Derivation::Bip44)).collect()
+    }
+
+    /// How the choice is written in the keychain entry
+    pub fn path(&self) -> String {
+        match self {
+            Derivation::Legacy => "legacy".to_string(),
+            Derivation::Bip44(index) => format!("{}{}", BIP44_PREFIX, index),
+        }
+    }
+
+    pub fn parse(path: &str) -> Result<Self, String> {
+        if path == "legacy" {
+            return Ok(Derivation::Legacy);
+        }
+        path.strip_prefix(BIP44_PREFIX)
+            .and_then(|index| index.parse::<u32>().ok())
+            .filter(|index| index & HARDENED == 0)
+            .map(Derivation::Bip44)
+            .ok_or_else(|| format!("Unsupported derivation path '{}'", path))
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            Derivation::Legacy => "Legacy (raw seed)".to_string(),
+            Derivation::Bip44(_) => self.path(),
+        }
+    }
+
+    /// For the backup sheet, so the phrase can be recovered with other tools
+    pub fn description(&self) -> String {
+        match self {
+            Derivation::Legacy => "BIP39 seed, empty passphrase, first 32 bytes as secp256k1 key".to_string(),
+            Derivation::Bip44(_) => format!("BIP39 seed, empty passphrase, BIP32 path {}", self.path()),
+        }
+    }
+
+    pub fn secret_key(&self, seed: &[u8]) -> Result<SecretKey, String> {
+        match self {
+            Derivation::Legacy => {
+                let bytes = seed.get(..32).ok_or("Seed is too short")?;
+                SecretKey::from_slice(bytes).map_err(|e| format!("Failed to create private key: {}", e))
+            }
+            Derivation::Bip44(index) => bip32_key(seed, &[44 | HARDENED, 60 | HARDENED, HARDENED, 0, *index]),
+        }
+    }
+}
+
+pub fn mnemonic_seed(mnemonic: &str) -> Result<[u8; 64], String> {
+    let mnemonic = Mnemonic::parse_in_normalized(Language::English, mnemonic).map_err(|e| format!("Invalid mnemonic: {}", e))?;
+    Ok(mnemonic.to_seed(""))
+}
+
+/// The key's 0x address in checksum case
+pub fn ethereum_address(secret_key: &SecretKey) -> String {
+    let public_key = PublicKey::from_secret_key(&Secp256k1::new(), secret_key);
+    // Skip the 0x04 prefix of the uncompressed encoding
+    let hash = Keccak256::digest(&public_key.serialize_uncompressed()[1..]);
+    format!("0x{}", address::checksum(&hex::encode(&hash[12..])))
+}
+
+/// Every preview option with the address it gives for `mnemonic`.
+pub fn preview_addresses(mnemonic: &str) -> Result<Vec<(Derivation, String)>, String> {
+    let seed = mnemonic_seed(mnemonic)?;
+    Derivation::preview_options()
+        .into_iter()
+        .map(|derivation| Ok((derivation, ethereum_address(&derivation.secret_key(&seed)?))))
+        .collect()
+}
+
+// BIP32 private child derivation from the master key down `path`
+fn bip32_key(seed: &[u8], path: &[u32]) -> Result<SecretKey, String> {
+    let secp = Secp256k1::new();
+    let (master, mut chain_code) = hmac_sha512(b"Bitcoin seed", &[seed]);
+    let mut key = SecretKey::from_slice(&master).map_err(|e| format!("Invalid master key: {}", e))?;
+
+    for &child in path {
+        let index = child.to_be_bytes();
+        let (tweak, next_chain_code) = if child & HARDENED != 0 {
+            hmac_sha512(&chain_code, &[&[0], &key.secret_bytes(), &index])
+        } else {
+            hmac_sha512(&chain_code, &[&PublicKey::from_secret_key(&secp, &key).serialize(), &index])
+        };
+        let tweak = Scalar::from_be_bytes(tweak).map_err(|_| "Derived key out of range".to_string())?;
+        key = key.add_tweak(&tweak).map_err(|e| format!("Invalid child key: {}", e))?;
+        chain_code = next_chain_code;
+    }
+    Ok(key)
+}
+
+// Split into the two 32-byte halves BIP32 uses: key material and chain code
+fn hmac_sha512(key: &[u8], data: &[&[u8]]) -> ([u8; 32], [u8; 32]) {
+    let mut mac = Hmac::<Sha512>::new_from_slice(key).expect("HMAC takes keys of any length");
+    for part in data {
+        mac.update(part);
+    }
+    let output = mac.finalize().into_bytes();
+    let mut left = [0u8; 32];
+    let mut right = [0u8; 32];
+    left.copy_from_slice(&output[..32]);
+    right.copy_from_slice(&output[32..]);
+    (left, right)
+}
@@ -0,0 +1,514 @@
+// Wallet operations as events, apart from the screens that start them.
+//
+// A front end asks for an operation with a request event and draws whatever
+// comes back in the matching outcome event; the handlers in between do the
+// work against the keychain, the client and the local ledgers, and never touch
+// the UI. The screens, onboarding and the headless test harness all go through
+// the same requests. Balances arrive as background task results rather than
+// requests, so they're turned into `BalanceFetched` here once they're cached.
+// A generate or import never simply overwrites the stored wallet: the keychain
+// archives the old one first (see wallet_archive.rs), and a restore request
+// brings it back. An import carries the derivation the user picked from the
+// preview, and it's stored with the phrase. Migrating a legacy wallet moves its
+// balances to the phrase's BIP44 address as ordinary transfers, less the GALA
+// their fees need, and only
+// switches the stored derivation once every one of them is signed. Switching to
+// another of the profile's named wallets (wallets.rs) reopens the keychain on
+// that wallet's entries and loads it as at launch.
+//
+// Failures go to the error banner from here, like any other error, and the
+// outcome carries what the screen needs to say about them.
+//
+// Handlers run in `DomainSet` during Update: systems that send requests go
+// before it and systems that read outcomes after it, so a press is answered in
+// the same frame.
+
+use crate::api_tasks::{ApiResult, ApiTaskSet, TaskKind};
+use crate::derivation::{Derivation, MIGRATION_TARGET};
+use crate::errors::WalletError;
+use crate::history::TxKind;
+use crate::keyring_health::KeyringHealth;
+use crate::sweep;
+use crate::tokens::TokenInfo;
+use crate::{
+    balance_cache_key, load_wallet_at_launch, load_wallet_secrets, signed_preview, ApiCache, BalanceState, ErrorBanner, GalaChainClient, GalaChainError,
+    IpcState, KeychainManager, PresenceGate, RegistrationState, SecureWalletData, SendGuards, SignedPreview, TRANSFER_KEY_PREFIX, TokenBalanceFetch, TokenInstanceKey,
+    Tokens, TransferTokenRequest, WalletData, WalletType, Wallets,
+};
+use bevy::prelude::*;
+use bip39::Mnemonic;
+use rust_decimal::Decimal;
+use secp256k1::SecretKey;
+
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DomainSet;
+
+/// Create a new wallet, replacing the one in the keychain.
+#[derive(Event, Debug, Clone)]
+pub struct GenerateWalletRequested;
+
+/// Restore a wallet from its seed phrase, replacing the one in the keychain.
+#[derive(Event, Debug, Clone)]
+pub struct ImportWalletRequested {
+    pub mnemonic: String,
+    pub derivation: Derivation,
+}
+
+/// Swap the newest archived wallet back in; the current one is archived in its place.
+#[derive(Event, Debug, Clone)]
+pub struct RestorePreviousWalletRequested;
+
+/// The new wallet's address; `WalletData` holds its key and seed phrase.
+#[derive(Event, Debug, Clone)]
+pub struct WalletGenerated {
+    pub result: Result<String, String>,
+    /// Address of the wallet it replaced, which was archived first
+    pub replaced: Option<String>,
+}
+
+/// The imported wallet's address.
+#[derive(Event, Debug, Clone)]
+pub struct WalletImported {
+    pub result: Result<String, ImportFailure>,
+    pub replaced: Option<String>,
+}
+
+/// The address of the wallet taken back out of the archive.
+#[derive(Event, Debug, Clone)]
+pub struct WalletRestored {
+    pub result: Result<String, String>,
+}
+
+/// Open another of this profile's named wallets in place of the current one.
+#[derive(Event, Debug, Clone)]
+pub struct SwitchWalletRequested {
+    pub name: String,
+}
+
+/// The opened wallet's label ("savings · testnet"); it may have no address yet.
+#[derive(Event, Debug, Clone)]
+pub struct WalletSwitched {
+    pub result: Result<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImportFailure {
+    InvalidMnemonic(String),
+    /// The phrase was fine but the keychain wouldn't take it
+    Storage(String),
+}
+
+/// Move a legacy wallet's available balances to its BIP44 address, then switch the wallet over.
+#[derive(Event, Debug, Clone)]
+pub struct MigrateWalletRequested {
+    /// Available balances at the legacy address; what moves of each is `sweep::sweep_all`
+    pub available: Vec<(TokenInfo, Decimal)>,
+}
+
+#[derive(Event)]
+pub struct WalletMigrated {
+    pub result: Result<MigrationReport, String>,
+}
+
+pub struct MigrationReport {
+    /// 0x addresses, legacy and BIP44
+    pub from: String,
+    pub to: String,
+    pub transfers: Vec<TransferProcessed>,
+    /// False if any transfer was held or not signed; the wallet then stays on the legacy address
+    pub switched: bool,
+}
+
+/// A transfer that's been through the sender's spending limit check.
+#[derive(Event, Debug, Clone)]
+pub struct TransferSubmitted {
+    /// GalaChain addresses
+    pub from: String,
+    pub to: String,
+    pub token: TokenInfo,
+    pub quantity: Decimal,
+    pub note: String,
+    /// Made when the Transfer form validated; None gets a fresh one when it's signed
+    pub unique_key: Option<String>,
+    /// The NFT instance to send; None for fungible tokens
+    pub instance: Option<String>,
+}
+
+#[derive(Event)]
+pub struct TransferProcessed {
+    pub transfer: TransferSubmitted,
+    pub outcome: TransferOutcome,
+}
+
+pub enum TransferOutcome {
+    /// Over the approval threshold, so queued on the Approvals screen instead of signed
+    Held { approval_id: Option<String> },
+    Signed(SignedPreview),
+}
+
+/// A balance fetch that finished, after it's been cached.
+#[derive(Event, Debug, Clone)]
+pub struct BalanceFetched {
+    pub token: TokenInfo,
+    /// For a row of the all-tokens list rather than the selected token's balance
+    pub all_tokens: bool,
+    /// Available and locked
+    pub result: Result<(Decimal, Decimal), GalaChainError>,
+}
+
+pub struct DomainPlugin;
+
+impl Plugin for DomainPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<GenerateWalletRequested>()
+            .add_event::<ImportWalletRequested>()
+            .add_event::<WalletGenerated>()
+            .add_event::<WalletImported>()
+            .add_event::<RestorePreviousWalletRequested>()
+            .add_event::<WalletRestored>()
+            .add_event::<SwitchWalletRequested>()
+            .add_event::<WalletSwitched>()
+            .add_event::<TransferSubmitted>()
+            .add_event::<TransferProcessed>()
+            .add_event::<BalanceFetched>()
+            .add_event::<MigrateWalletRequested>()
+            .add_event::<WalletMigrated>()
+            .configure_sets(Update, DomainSet.after(ApiTaskSet))
+            .add_systems(
+                Update,
+                (
+                    generate_wallet_handler,
+                    import_wallet_handler,
+                    restore_wallet_handler,
+                    switch_wallet_handler,
+                    transfer_handler,
+                    migrate_wallet_handler,
+                    balance_fetch_handler,
+                )
+                    .in_set(DomainSet),
+            );
+    }
+}
+
+fn new_mnemonic() -> Result<String, WalletError> {
+    let entropy = rand::random::<[u8; 16]>();
+    let mnemonic = Mnemonic::from_entropy(&entropy)
+        .map_err(|e| WalletError::Other(format!("Failed to generate mnemonic: {}", e)))?;
+    Ok(mnemonic.to_string())
+}
+
+struct StoredWallet {
+    secret_key: SecretKey,
+    address: String,
+    /// Address of the wallet it replaced, now archived
+    replaced: Option<String>,
+}
+
+// Derives the wallet from a new or typed seed phrase and stores it over the current one
+fn store_mnemonic_wallet(keychain: &KeychainManager, mnemonic: &str, derivation: Derivation) -> Result<StoredWallet, WalletError> {
+    let (secret_key, address) = keychain.derive_wallet(mnemonic, derivation).map_err(WalletError::InvalidMnemonic)?;
+    let replaced = keychain.replace_wallet(&mnemonic_wallet(mnemonic, derivation))?;
+    Ok(StoredWallet { secret_key, address, replaced })
+}
+
+fn mnemonic_wallet(mnemonic: &str, derivation: Derivation) -> SecureWalletData {
+    SecureWalletData {
+        wallet_type: WalletType::Mnemonic,
+        mnemonic: mnemonic.to_string(),
+        address: None,
+        created_at: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs(),
+        derivation,
+    }
+}
+
+fn unlock(wallet_data: &mut WalletData, secret_key: SecretKey, address: &str, mnemonic: String) {
+    wallet_data.wallet_type = WalletType::Mnemonic;
+    wallet_data.private_key = Some(secret_key);
+    wallet_data.address = Some(address.to_string());
+    wallet_data.mnemonic = Some(mnemonic);
+}
+
+fn generate_wallet_handler(
+    mut requests: EventReader<GenerateWalletRequested>,
+    mut outcomes: EventWriter<WalletGenerated>,
+    keychain: Res<KeychainManager>,
+    mut wallet_data: ResMut<WalletData>,
+    mut error_banner: ResMut<ErrorBanner>,
+) {
+    for _ in requests.read() {
+        let stored = new_mnemonic().and_then(|mnemonic| Ok((store_mnemonic_wallet(&keychain, &mnemonic, MIGRATION_TARGET)?, mnemonic)));
+        let outcome = match stored {
+            Ok((StoredWallet { secret_key, address, replaced }, mnemonic)) => {
+                info!("New wallet generated: {}", address);
+                unlock(&mut wallet_data, secret_key, &address, mnemonic);
+                WalletGenerated { result: Ok(address), replaced }
+            }
+            Err(error) => {
+                let message = error.to_string();
+                error_banner.report(error);
+                WalletGenerated { result: Err(message), replaced: None }
+            }
+        };
+        outcomes.send(outcome);
+    }
+}
+
+fn import_wallet_handler(
+    mut requests: EventReader<ImportWalletRequested>,
+    mut outcomes: EventWriter<WalletImported>,
+    keychain: Res<KeychainManager>,
+    mut wallet_data: ResMut<WalletData>,
+    mut ipc: ResMut<IpcState>,
+    mut error_banner: ResMut<ErrorBanner>,
+) {
+    for ImportWalletRequested { mnemonic, derivation } in requests.read() {
+        let outcome = match store_mnemonic_wallet(&keychain, mnemonic, *derivation) {
+            Ok(StoredWallet { secret_key, address, replaced }) => {
+                info!("Wallet imported successfully: {}", address);
+                unlock(&mut wallet_data, secret_key, &address, mnemonic.clone());
+                // The whole seed phrase is proof enough to give the approval PIN its tries back
+                ipc.clear_pin_lockout();
+                WalletImported { result: Ok(address), replaced }
+            }
+            Err(error) => {
+                let failure = match &error {
+                    WalletError::InvalidMnemonic(reason) => ImportFailure::InvalidMnemonic(reason.clone()),
+                    other => ImportFailure::Storage(other.to_string()),
+                };
+                error_banner.report(error);
+                WalletImported { result: Err(failure), replaced: None }
+            }
+        };
+        outcomes.send(outcome);
+    }
+}
+
+fn restore_wallet_handler(
+    mut requests: EventReader<RestorePreviousWalletRequested>,
+    mut outcomes: EventWriter<WalletRestored>,
+    keychain: Res<KeychainManager>,
+    mut wallet_data: ResMut<WalletData>,
+    mut error_banner: ResMut<ErrorBanner>,
+) {
+    for _ in requests.read() {
+        let restored = keychain.restore_previous_wallet().map_err(WalletError::from).and_then(|_| {
+            // A watch-only wallet has no key or phrase to overwrite the current one's
+            wallet_data.private_key = None;
+            wallet_data.mnemonic = None;
+            load_wallet_secrets(&mut wallet_data, &keychain)?;
+            Ok(wallet_data.address.clone().unwrap_or_default())
+        });
+        let result = match restored {
+            Ok(address) => {
+                info!("Previous wallet restored: {}", address);
+                Ok(address)
+            }
+            Err(error) => {
+                let message = error.to_string();
+                error_banner.report(error);
+                Err(message)
+            }
+        };
+        outcomes.send(WalletRestored { result });
+    }
+}
+
+fn switch_wallet_handler(
+    mut requests: EventReader<SwitchWalletRequested>,
+    mut outcomes: EventWriter<WalletSwitched>,
+    mut wallets: ResMut<Wallets>,
+    keyring: Res<KeyringHealth>,
+    gate: Res<PresenceGate>,
+    mut keychain: ResMut<KeychainManager>,
+    mut wallet_data: ResMut<WalletData>,
+    mut balance_state: ResMut<BalanceState>,
+    mut registration_state: ResMut<RegistrationState>,
+    mut error_banner: ResMut<ErrorBanner>,
+) {
+    for request in requests.read() {
+        if !wallets.open(&request.name) {
+            outcomes.send(WalletSwitched { result: Err(format!("There's no wallet called {}", request.name)) });
+            continue;
+        }
+        let scope = wallets.scope();
+        *keychain = KeychainManager::scoped(&scope, &keyring);
+        // Nothing of the last wallet stays in memory, even if this one doesn't load
+        *wallet_data = WalletData::default();
+        *balance_state = BalanceState::default();
+        *registration_state = RegistrationState::default();
+        let result = match load_wallet_at_launch(&mut wallet_data, &keychain, &gate) {
+            Ok(()) => {
+                info!("Switched to wallet {}", scope.label());
+                Ok(scope.label())
+            }
+            Err(error) => {
+                let message = error.to_string();
+                error_banner.report(error);
+                Err(message)
+            }
+        };
+        outcomes.send(WalletSwitched { result });
+    }
+}
+
+/// Holds the transfer for approval or signs and records it; the local signing server calls this directly.
+pub fn submit_transfer(
+    commands: &mut Commands,
+    client: &GalaChainClient,
+    wallet_data: &WalletData,
+    guards: &mut SendGuards,
+    transfer: &TransferSubmitted,
+) -> TransferOutcome {
+    let TransferSubmitted { from, to, token, quantity, note, unique_key, instance } = transfer;
+
+    if guards.approvals.queue.policy.requires_approval(*quantity) {
+        guards.approvals.hold_transfer(commands, client, transfer);
+        let approval_id = guards.approvals.queue.items.last().map(|item| item.id.clone());
+        return TransferOutcome::Held { approval_id };
+    }
+
+    if let Some(repeat) = SignedPreview::repeat_of(unique_key.as_deref(), &guards.history) {
+        info!("Transfer {} was already submitted; not sending it again", repeat.unique_key.as_deref().unwrap_or_default());
+        return TransferOutcome::Signed(repeat);
+    }
+
+    info!("Transfer requested: {} {} to {}", quantity, token.symbol, to);
+    let request = TransferTokenRequest {
+        from: from.clone(),
+        to: to.clone(),
+        token_instance: TokenInstanceKey::new(token, instance.as_deref()),
+        quantity: *quantity,
+        unique_key: unique_key.clone().unwrap_or_default(),
+    };
+    let signed = signed_preview(
+        client,
+        wallet_data,
+        &request,
+        TRANSFER_KEY_PREFIX,
+        &client.settings.transfer_endpoint,
+        GalaChainClient::transfer_blocking,
+    );
+    guards.record_signed(TxKind::Transfer, token, *quantity, Some(to), &signed, note);
+    TransferOutcome::Signed(signed)
+}
+
+fn transfer_handler(
+    mut commands: Commands,
+    mut requests: EventReader<TransferSubmitted>,
+    mut outcomes: EventWriter<TransferProcessed>,
+    client: Res<GalaChainClient>,
+    wallet_data: Res<WalletData>,
+    mut guards: SendGuards,
+) {
+    for transfer in requests.read() {
+        let outcome = submit_transfer(&mut commands, &client, &wallet_data, &mut guards, transfer);
+        outcomes.send(TransferProcessed { transfer: transfer.clone(), outcome });
+    }
+}
+
+fn migrate_wallet_handler(
+    mut commands: Commands,
+    mut requests: EventReader<MigrateWalletRequested>,
+    mut outcomes: EventWriter<WalletMigrated>,
+    client: Res<GalaChainClient>,
+    mut wallet_data: ResMut<WalletData>,
+    mut guards: SendGuards,
+) {
+    for MigrateWalletRequested { available } in requests.read() {
+        let result = match migrate_wallet(&mut commands, &client, &mut wallet_data, &mut guards, available) {
+            Ok(report) => Ok(report),
+            Err(error) => {
+                let message = error.to_string();
+                guards.error_banner.report(error);
+                Err(message)
+            }
+        };
+        outcomes.send(WalletMigrated { result });
+    }
+}
+
+fn migrate_wallet(
+    commands: &mut Commands,
+    client: &GalaChainClient,
+    wallet_data: &mut WalletData,
+    guards: &mut SendGuards,
+    available: &[(TokenInfo, Decimal)],
+) -> Result<MigrationReport, WalletError> {
+    let secure_data = guards.keychain.load_wallet()?;
+    if secure_data.wallet_type != WalletType::Mnemonic || secure_data.derivation != Derivation::Legacy {
+        return Err(WalletError::Other("Only a wallet on the legacy derivation can be migrated".to_string()));
+    }
+    let (_, from) = guards.keychain.derive_wallet(&secure_data.mnemonic, Derivation::Legacy).map_err(WalletError::InvalidMnemonic)?;
+    let (_, to) = guards.keychain.derive_wallet(&secure_data.mnemonic, MIGRATION_TARGET).map_err(WalletError::InvalidMnemonic)?;
+    // Signing uses the unlocked key, which has to be the legacy one
+    if wallet_data.address.as_deref() != Some(from.as_str()) || wallet_data.private_key.is_none() {
+        return Err(WalletError::Locked);
+    }
+
+    let note = format!("Migration to {}", MIGRATION_TARGET.path());
+    // The GALA balance pays every transfer's fee, so it can't all go
+    let transfers: Vec<TransferProcessed> = sweep::sweep_all(available)
+        .iter()
+        .map(|(token, quantity)| {
+            let transfer = TransferSubmitted {
+                from: GalaChainClient::ethereum_to_galachain_address(&from),
+                to: GalaChainClient::ethereum_to_galachain_address(&to),
+                token: token.clone(),
+                quantity: *quantity,
+                note: note.clone(),
+                unique_key: None,
+                instance: None,
+            };
+            let outcome = submit_transfer(commands, client, wallet_data, guards, &transfer);
+            TransferProcessed { transfer, outcome }
+        })
+        .collect();
+
+    let switched = transfers
+        .iter()
+        .all(|processed| matches!(&processed.outcome, TransferOutcome::Signed(signed) if signed.unique_key.is_some()));
+    if switched {
+        // The legacy entry is archived like any replaced wallet, so this can be undone from Overview
+        guards.keychain.replace_wallet(&SecureWalletData { derivation: MIGRATION_TARGET, ..secure_data })?;
+        wallet_data.private_key = None;
+        wallet_data.mnemonic = None;
+        load_wallet_secrets(wallet_data, &guards.keychain)?;
+        info!("Wallet migrated from {} to {}", from, to);
+    }
+    Ok(MigrationReport { from, to, transfers, switched })
+}
+
+// Caches every balance that comes back, whichever screen is open
+fn balance_fetch_handler(
+    mut results: EventReader<ApiResult<(Decimal, Decimal)>>,
+    mut token_results: EventReader<ApiResult<TokenBalanceFetch>>,
+    mut fetched: EventWriter<BalanceFetched>,
+    mut api_cache: ResMut<ApiCache>,
+    wallet_data: Res<WalletData>,
+    client: Res<GalaChainClient>,
+    tokens: Res<Tokens>,
+) {
+    let gala_address = wallet_data.address.as_deref().map(GalaChainClient::ethereum_to_galachain_address);
+    let mut cache = |token: &TokenInfo, result: &Result<(Decimal, Decimal), GalaChainError>| {
+        if let (Ok(balance), Some(gala_address)) = (result, &gala_address) {
+            api_cache.store_balance(balance_cache_key(&client, gala_address, token), *balance);
+        }
+    };
+
+    for ApiResult { result, .. } in results.read().filter(|r| r.kind == TaskKind::Balance) {
+        let token = tokens.selected().clone();
+        cache(&token, result);
+        fetched.send(BalanceFetched { token, all_tokens: false, result: result.clone() });
+    }
+
+    for ApiResult { result, .. } in token_results.read().filter(|r| r.kind == TaskKind::TokenBalance) {
+        let Ok(TokenBalanceFetch { token, balance }) = result else {
+            continue;
+        };
+        cache(token, balance);
+        fetched.send(BalanceFetched { token: token.clone(), all_tokens: true, result: balance.clone() });
+    }
+}
@@ -0,0 +1,99 @@
+// Canonical serialization of GalaChain DTOs.
+//
+// A signature only verifies if the chain rebuilds exactly the bytes that were
+// signed. `@gala-chain/api` serializes a DTO with its keys sorted (JavaScript
+// string order), no whitespace, null fields left out and BigNumber quantities
+// as plain decimal strings; this module produces the same string from Rust.
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Deserializer, Serializer};
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+/// Top-level fields that are not part of the signed payload: the signature itself and tracing metadata.
+pub const UNSIGNED_FIELDS: [&str; 2] = ["signature", "trace"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DtoError {
+    NotAnObject,
+    Serialize(String),
+}
+
+impl fmt::Display for DtoError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DtoError::NotAnObject => write!(f, "A DTO must serialize to a JSON object"),
+            DtoError::Serialize(e) => write!(f, "Could not serialize the DTO: {}", e),
+        }
+    }
+}
+
+/// Compact JSON with object keys sorted at every level and null fields dropped.
+/// Nulls inside arrays stay, as they do with `JSON.stringify`.
+pub fn canonical_json(value: &Value) -> String {
+    match value {
+        Value::Object(map) => {
+            let mut fields: Vec<(&String, &Value)> = map.iter().filter(|(_, value)| !value.is_null()).collect();
+            fields.sort_by(|(a, _), (b, _)| js_string_cmp(a, b));
+            let fields: Vec<String> = fields
+                .into_iter()
+                .map(|(key, value)| format!("{}:{}", Value::String(key.clone()), canonical_json(value)))
+                .collect();
+            format!("{{{}}}", fields.join(","))
+        }
+        Value::Array(items) => format!("[{}]", items.iter().map(canonical_json).collect::<Vec<_>>().join(",")),
+        Value::Number(number) => js_number(number),
+        other => other.to_string(),
+    }
+}
+
+/// The exact string that gets hashed and signed for a DTO.
+pub fn payload_to_sign(dto: &Value) -> Result<String, DtoError> {
+    let mut plain = dto.as_object().ok_or(DtoError::NotAnObject)?.clone();
+    for field in UNSIGNED_FIELDS {
+        plain.remove(field);
+    }
+    Ok(canonical_json(&Value::Object(plain)))
+}
+
+/// `Array.prototype.sort` compares UTF-16 code units, which differs from Rust's
+/// UTF-8 byte order once keys go beyond the Basic Multilingual Plane.
+fn js_string_cmp(a: &str, b: &str) -> Ordering {
+    a.encode_utf16().cmp(b.encode_utf16())
+}
+
+/// JavaScript has one number type, so a whole-valued float prints without ".0".
+fn js_number(number: &serde_json::Number) -> String {
+    match number.as_f64() {
+        Some(float) if number.is_f64() && float.fract() == 0.0 && float.abs() < 1e21 => format!("{}", float as i128),
+        _ => number.to_string(),
+    }
+}
+
+/// Quantities the way BigNumber prints them: plain decimal notation without trailing zeros.
+pub fn format_quantity(quantity: &Decimal) -> String {
+    quantity.normalize().to_string()
+}
+
+/// Serde adapter for `Decimal` quantities in DTOs: written as BigNumber strings,
+/// read from either a string or a JSON number.
+pub mod bignumber {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(quantity: &Decimal, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format_quantity(quantity))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Decimal, D::Error> {
+        let text = match Value::deserialize(deserializer)? {
+            Value::String(text) => text,
+            Value::Number(number) => number.to_string(),
+            other => return Err(serde::de::Error::custom(format!("expected a quantity, found {}", other))),
+        };
+        Decimal::from_str(&text)
+            .or_else(|_| Decimal::from_scientific(&text))
+            .map_err(serde::de::Error::custom)
+    }
+}
@@ -0,0 +1,235 @@
+// The endpoint templates in Settings and what can go wrong with them.
+//
+// Each chain call's path is a template in `ApiSettings`, with `{channel}` and
+// `{contract}` filled in when the client is built (see `ChainUrls`). A typo
+// there doesn't fail until the call is made, and then only as a 404, so the
+// Settings editor checks templates as they're typed, shows the URL each one
+// resolves to, and can send a test request. The registration path is used as
+// written, so placeholders in it are flagged too.
+//
+// Each endpoint also has a host: the operations server, the identity server, or
+// a base URL of its own. Only registration lives on the identity server by
+// default; everything else, balances included, goes to operations. Routes that
+// match the default aren't stored, so settings files only list the exceptions.
+
+use crate::{ApiSettings, ChainUrls};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+pub const PLACEHOLDERS: [&str; 2] = ["channel", "contract"];
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Endpoint {
+    Registration,
+    RegistrationCheck,
+    Balance,
+    Transfer,
+    Burn,
+    TokenClasses,
+    TokenInstances,
+    UpdatePublicKey,
+}
+
+impl Endpoint {
+    pub const ALL: [Endpoint; 8] = [
+        Endpoint::Registration,
+        Endpoint::RegistrationCheck,
+        Endpoint::Balance,
+        Endpoint::Transfer,
+        Endpoint::Burn,
+        Endpoint::TokenClasses,
+        Endpoint::TokenInstances,
+        Endpoint::UpdatePublicKey,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Endpoint::Registration => "Register",
+            Endpoint::RegistrationCheck => "GetPublicKey",
+            Endpoint::Balance => "FetchBalances",
+            Endpoint::Transfer => "TransferToken",
+            Endpoint::Burn => "BurnTokens",
+            Endpoint::TokenClasses => "FetchTokenClasses",
+            Endpoint::TokenInstances => "FetchTokenInstances",
+            Endpoint::UpdatePublicKey => "UpdatePublicKey",
+        }
+    }
+
+    pub fn default_host(&self) -> Host {
+        match self {
+            Endpoint::Registration => Host::Identity,
+            _ => Host::Operations,
+        }
+    }
+
+    /// The placeholders the template may use
+    pub fn placeholders(&self) -> &'static [&'static str] {
+        match self {
+            Endpoint::Registration => &[],
+            _ => &PLACEHOLDERS,
+        }
+    }
+
+    pub fn template<'a>(&self, settings: &'a ApiSettings) -> &'a str {
+        match self {
+            Endpoint::Registration => &settings.registration_endpoint,
+            Endpoint::RegistrationCheck => &settings.registration_check_endpoint,
+            Endpoint::Balance => &settings.balance_endpoint,
+            Endpoint::Transfer => &settings.transfer_endpoint,
+            Endpoint::Burn => &settings.burn_endpoint,
+            Endpoint::TokenClasses => &settings.token_classes_endpoint,
+            Endpoint::TokenInstances => &settings.token_instances_endpoint,
+            Endpoint::UpdatePublicKey => &settings.update_public_key_endpoint,
+        }
+    }
+
+    pub fn template_mut<'a>(&self, settings: &'a mut ApiSettings) -> &'a mut String {
+        match self {
+            Endpoint::Registration => &mut settings.registration_endpoint,
+            Endpoint::RegistrationCheck => &mut settings.registration_check_endpoint,
+            Endpoint::Balance => &mut settings.balance_endpoint,
+            Endpoint::Transfer => &mut settings.transfer_endpoint,
+            Endpoint::Burn => &mut settings.burn_endpoint,
+            Endpoint::TokenClasses => &mut settings.token_classes_endpoint,
+            Endpoint::TokenInstances => &mut settings.token_instances_endpoint,
+            Endpoint::UpdatePublicKey => &mut settings.update_public_key_endpoint,
+        }
+    }
+
+    /// The resolved URL, host included
+    pub fn url<'a>(&self, urls: &'a ChainUrls) -> &'a str {
+        match self {
+            Endpoint::Registration => &urls.registration,
+            Endpoint::RegistrationCheck => &urls.registration_check,
+            Endpoint::Balance => &urls.balance,
+            Endpoint::Transfer => &urls.transfer,
+            Endpoint::Burn => &urls.burn,
+            Endpoint::TokenClasses => &urls.token_classes,
+            Endpoint::TokenInstances => &urls.token_instances,
+            Endpoint::UpdatePublicKey => &urls.update_public_key,
+        }
+    }
+}
+
+/// Which base URL an endpoint's path goes on
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Host {
+    Operations,
+    Identity,
+    /// A base URL for this endpoint alone, e.g. a gateway in front of one contract
+    Custom(String),
+}
+
+impl Host {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Host::Operations => "Operations",
+            Host::Identity => "Identity",
+            Host::Custom(_) => "Custom",
+        }
+    }
+
+    /// What the Settings button switches to; a new custom host starts from `base_url`
+    pub fn next(&self, base_url: &str) -> Host {
+        match self {
+            Host::Operations => Host::Identity,
+            Host::Identity => Host::Custom(base_url.to_string()),
+            Host::Custom(_) => Host::Operations,
+        }
+    }
+}
+
+/// A custom host has to be an http(s) URL with no spaces or trailing slash.
+pub fn check_base_url(url: &str) -> Result<(), String> {
+    let rest = url.strip_prefix("https://").or_else(|| url.strip_prefix("http://")).ok_or("Base URLs start with http:// or https://")?;
+    if rest.is_empty() || rest.starts_with('/') {
+        return Err("Missing the host name".to_string());
+    }
+    if url.chars().any(char::is_whitespace) {
+        return Err("Base URLs can't contain spaces".to_string());
+    }
+    if url.ends_with('/') {
+        return Err("Leave off the trailing /; endpoint paths start with one".to_string());
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateIssue {
+    Empty,
+    NoLeadingSlash,
+    Whitespace,
+    UnmatchedBrace,
+    UnknownPlaceholder(String),
+    /// A placeholder in a template that isn't filled in at all
+    NotFilled(String),
+}
+
+impl fmt::Display for TemplateIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TemplateIssue::Empty => write!(f, "Enter a path"),
+            TemplateIssue::NoLeadingSlash => write!(f, "Paths start with /"),
+            TemplateIssue::Whitespace => write!(f, "Paths can't contain spaces"),
+            TemplateIssue::UnmatchedBrace => write!(f, "Unmatched {{ or }}"),
+            TemplateIssue::UnknownPlaceholder(name) => write!(f, "Unknown placeholder {{{}}} - use {{channel}} or {{contract}}", name),
+            TemplateIssue::NotFilled(name) => write!(f, "{{{}}} isn't filled in for this endpoint", name),
+        }
+    }
+}
+
+/// Everything wrong with `template` for `endpoint`; empty means it can be saved.
+pub fn check_template(endpoint: Endpoint, template: &str) -> Vec<TemplateIssue> {
+    if template.is_empty() {
+        return vec![TemplateIssue::Empty];
+    }
+    let mut issues = Vec::new();
+    if !template.starts_with('/') {
+        issues.push(TemplateIssue::NoLeadingSlash);
+    }
+    if template.chars().any(char::is_whitespace) {
+        issues.push(TemplateIssue::Whitespace);
+    }
+
+    let mut rest = template;
+    loop {
+        let open = rest.find('{');
+        let close = rest.find('}');
+        match (open, close) {
+            (None, None) => break,
+            (Some(open), Some(close)) if open < close => {
+                let name = &rest[open + 1..close];
+                if name.contains('{') {
+                    issues.push(TemplateIssue::UnmatchedBrace);
+                } else if !endpoint.placeholders().contains(&name) {
+                    issues.push(if PLACEHOLDERS.contains(&name) {
+                        TemplateIssue::NotFilled(name.to_string())
+                    } else {
+                        TemplateIssue::UnknownPlaceholder(name.to_string())
+                    });
+                }
+                rest = &rest[close + 1..];
+            }
+            _ => {
+                issues.push(TemplateIssue::UnmatchedBrace);
+                break;
+            }
+        }
+    }
+    issues.dedup();
+    issues
+}
+
+/// What a test request's HTTP status says about the path. The test posts an
+/// empty body, so an endpoint that exists normally rejects it.
+pub fn describe_probe(status: u16) -> String {
+    match status {
+        404 => "HTTP 404 - nothing answers at this path".to_string(),
+        401 | 403 => format!("HTTP {} - the endpoint exists but refused the credentials", status),
+        400..=499 => format!("HTTP {} - the endpoint exists (it rejected the empty test request)", status),
+        500.. => format!("HTTP {} - the server failed; the path may still be right", status),
+        _ => format!("HTTP {} - the endpoint answered", status),
+    }
+}
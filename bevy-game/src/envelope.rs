@@ -0,0 +1,73 @@
+// The envelope every contract response comes in.
+//
+// GalaChain answers `{"Status": 1, "Data": ...}` on success and, on failure,
+// `{"Status": 0, "Message", "ErrorCode", "ErrorKey"}` with the HTTP status
+// usually matching `ErrorCode`. `parse_response` reads both for any endpoint
+// and turns failures into typed `GalaChainError`s by error key first and code
+// second, so callers can match on "not registered" or "insufficient balance"
+// instead of searching the body for phrases. A failure without an envelope
+// (a proxy's error page, say) still maps by its HTTP status.
+
+use crate::GalaChainError;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct GcResponse<T> {
+    #[serde(rename = "Status")]
+    pub status: i32,
+    #[serde(rename = "Data")]
+    pub data: Option<T>,
+    #[serde(rename = "Message")]
+    pub message: Option<String>,
+    #[serde(rename = "ErrorCode")]
+    pub error_code: Option<u16>,
+    #[serde(rename = "ErrorKey")]
+    pub error_key: Option<String>,
+}
+
+/// Error keys that mean the user has no public key on chain
+const NOT_REGISTERED_KEYS: [&str; 3] = ["USER_NOT_REGISTERED", "PK_NOT_FOUND", "USER_PROFILE_NOT_FOUND"];
+
+impl<T> GcResponse<T> {
+    /// `Data` on success (`None` when the contract returned nothing), the mapped error otherwise.
+    pub fn into_result(self, http_status: u16) -> Result<Option<T>, GalaChainError> {
+        if self.status == 1 {
+            return Ok(self.data);
+        }
+        let code = self.error_code.unwrap_or(http_status);
+        let message = self.message.unwrap_or_else(|| format!("Status {}", self.status));
+        Err(chain_error(code, self.error_key.as_deref(), message))
+    }
+}
+
+/// Maps a failed call to the error the wallet reacts to.
+pub fn chain_error(code: u16, key: Option<&str>, message: String) -> GalaChainError {
+    match key {
+        Some(key) if NOT_REGISTERED_KEYS.contains(&key) => return GalaChainError::NotRegistered,
+        Some("INSUFFICIENT_BALANCE") => return GalaChainError::InsufficientBalance(message),
+        _ => {}
+    }
+    match code {
+        401 | 403 => GalaChainError::Auth(message),
+        404 => GalaChainError::NotFound(message),
+        409 => GalaChainError::Conflict(message),
+        _ => match key {
+            Some(key) => GalaChainError::Api(format!("{} ({}): {}", key, code, message)),
+            None => GalaChainError::Api(format!("HTTP {}: {}", code, message)),
+        },
+    }
+}
+
+/// Reads a response to a contract call. A success status with a body that isn't
+/// an envelope is a parse error; a failure status without one maps by status alone.
+pub fn parse_response<T: DeserializeOwned>(http_status: u16, body: &str) -> Result<Option<T>, GalaChainError> {
+    let success = (200..300).contains(&http_status);
+    match serde_json::from_str::<GcResponse<T>>(body) {
+        // A 2xx that says it failed is still a failure, and an error status wins over Status 1
+        Ok(response) if success || response.status != 1 => response.into_result(http_status),
+        Ok(_) => Err(chain_error(http_status, None, body.to_string())),
+        Err(e) if success => Err(GalaChainError::Parse(e.to_string())),
+        Err(_) => Err(chain_error(http_status, None, body.to_string())),
+    }
+}
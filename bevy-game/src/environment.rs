@@ -0,0 +1,165 @@
+// Environment profiles: a gateway configuration a team can pass around as a file.
+//
+// A profile holds what it takes to talk to one GalaChain deployment - API mode,
+// base URLs, endpoint templates and hosts, channel, contracts (and the other
+// channel/contract pairs to switch between), signature formats and the token list - so a working setup is shared instead of retyped. It never holds
+// credentials: gateway keys live in the keychain, and the HTTP options (extra
+// headers may carry an API key, proxies and CA bundles are per machine) stay out.
+// Importing keeps those, along with demo mode, and replaces the rest.
+//
+// The file is pretty-printed JSON with a `kind` marker so it can be read and
+// reviewed before it's imported. Unknown fields are ignored, so a settings file
+// from Export Config imports too, minus its HTTP options.
+
+use crate::amounts::MAX_DECIMALS;
+use crate::api_mode::ApiMode;
+use crate::chain_targets::{self, ChainTarget};
+use crate::endpoints::{Endpoint, Host};
+use crate::signing::SignatureFormat;
+use crate::tokens::{TokenInfo, TokenRegistry};
+use crate::ApiSettings;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+pub const PROFILE_KIND: &str = "galachain-environment";
+pub const PROFILE_FILE_NAME: &str = "galachain-environment.json";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct EnvironmentProfile {
+    pub kind: String,
+    pub mode: ApiMode,
+    pub operations_base_url: String,
+    pub identity_base_url: String,
+    pub registration_endpoint: String,
+    pub registration_check_endpoint: String,
+    pub balance_endpoint: String,
+    pub transfer_endpoint: String,
+    pub burn_endpoint: String,
+    pub token_classes_endpoint: String,
+    pub token_instances_endpoint: String,
+    pub update_public_key_endpoint: String,
+    pub channel_name: String,
+    pub contract_name: String,
+    pub identity_contract_name: String,
+    /// Channel/contract pairs besides the one above, e.g. custom chaincode
+    pub chain_targets: Vec<ChainTarget>,
+    pub signature_formats: BTreeMap<String, SignatureFormat>,
+    pub routes: BTreeMap<Endpoint, Host>,
+    /// Empty leaves the token list alone, as for settings files without one
+    pub tokens: Vec<TokenInfo>,
+}
+
+impl Default for EnvironmentProfile {
+    fn default() -> Self {
+        Self::from_settings(&ApiSettings::default(), &[])
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProfileError {
+    Json(String),
+    WrongKind(String),
+}
+
+impl fmt::Display for ProfileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ProfileError::Json(reason) => write!(f, "Not a readable profile: {}", reason),
+            ProfileError::WrongKind(kind) => write!(f, "This file is a '{}', not an environment profile", kind),
+        }
+    }
+}
+
+impl std::error::Error for ProfileError {}
+
+impl EnvironmentProfile {
+    pub fn from_settings(settings: &ApiSettings, tokens: &[TokenInfo]) -> Self {
+        Self {
+            kind: PROFILE_KIND.to_string(),
+            mode: settings.mode,
+            operations_base_url: settings.operations_base_url.clone(),
+            identity_base_url: settings.identity_base_url.clone(),
+            registration_endpoint: settings.registration_endpoint.clone(),
+            registration_check_endpoint: settings.registration_check_endpoint.clone(),
+            balance_endpoint: settings.balance_endpoint.clone(),
+            transfer_endpoint: settings.transfer_endpoint.clone(),
+            burn_endpoint: settings.burn_endpoint.clone(),
+            token_classes_endpoint: settings.token_classes_endpoint.clone(),
+            token_instances_endpoint: settings.token_instances_endpoint.clone(),
+            update_public_key_endpoint: settings.update_public_key_endpoint.clone(),
+            channel_name: settings.channel_name.clone(),
+            contract_name: settings.contract_name.clone(),
+            identity_contract_name: settings.identity_contract_name.clone(),
+            chain_targets: settings.chain_targets.clone(),
+            signature_formats: settings.signature_formats.clone(),
+            routes: settings.routes.clone(),
+            tokens: tokens.to_vec(),
+        }
+    }
+
+    /// A file without `kind` is taken to be an older settings export.
+    pub fn from_json(json: &str) -> Result<Self, ProfileError> {
+        let profile: Self = serde_json::from_str(json).map_err(|e| ProfileError::Json(e.to_string()))?;
+        if profile.kind != PROFILE_KIND {
+            return Err(ProfileError::WrongKind(profile.kind));
+        }
+        Ok(profile)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    /// Replaces the endpoints, channel, contracts and tokens. Repeated tokens and
+    /// impossible decimals are dropped, as are blank or repeated channel/contract pairs; the selected token stays selected if the
+    /// profile lists it.
+    pub fn apply(&self, settings: &mut ApiSettings, registry: &mut TokenRegistry) {
+        settings.mode = self.mode;
+        settings.operations_base_url = self.operations_base_url.clone();
+        settings.identity_base_url = self.identity_base_url.clone();
+        settings.registration_endpoint = self.registration_endpoint.clone();
+        settings.registration_check_endpoint = self.registration_check_endpoint.clone();
+        settings.balance_endpoint = self.balance_endpoint.clone();
+        settings.transfer_endpoint = self.transfer_endpoint.clone();
+        settings.burn_endpoint = self.burn_endpoint.clone();
+        settings.token_classes_endpoint = self.token_classes_endpoint.clone();
+        settings.token_instances_endpoint = self.token_instances_endpoint.clone();
+        settings.update_public_key_endpoint = self.update_public_key_endpoint.clone();
+        settings.channel_name = self.channel_name.clone();
+        settings.contract_name = self.contract_name.clone();
+        settings.identity_contract_name = self.identity_contract_name.clone();
+        settings.chain_targets = chain_targets::normalized(&self.chain_targets);
+        settings.signature_formats = self.signature_formats.clone();
+        settings.routes = self.routes.clone();
+
+        let mut tokens: Vec<TokenInfo> = Vec::new();
+        for token in self.tokens.iter().filter(|token| token.decimals <= MAX_DECIMALS) {
+            if !tokens.iter().any(|listed| listed.class_key() == token.class_key()) {
+                tokens.push(token.clone());
+            }
+        }
+        if tokens.is_empty() {
+            return;
+        }
+        let selected = registry.selected().class_key();
+        registry.tokens = tokens;
+        registry.selected = registry.tokens.iter().position(|token| token.class_key() == selected).unwrap_or(0);
+    }
+
+    /// One line for Settings after an import or export
+    pub fn summary(&self) -> String {
+        let tokens = match self.tokens.len() {
+            0 => "token list unchanged".to_string(),
+            1 => "1 token".to_string(),
+            count => format!("{} tokens", count),
+        };
+        let saved = match self.chain_targets.len() {
+            0 => String::new(),
+            1 => " (1 saved pair)".to_string(),
+            count => format!(" ({} saved pairs)", count),
+        };
+        format!("{} ({}) · channel {} · {} / {}{} · {}", self.operations_base_url, self.mode.label(), self.channel_name, self.contract_name, self.identity_contract_name, saved, tokens)
+    }
+}
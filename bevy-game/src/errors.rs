@@ -0,0 +1,146 @@
+// Top-level error type for everything the wallet reports to the user.
+//
+// Lower-level errors (keychain, GalaChain API, backup files) convert into
+// `WalletError`, which adds a stable error code and a remediation hint for the
+// error banner.
+
+use crate::backup::BackupError;
+use crate::backup_sync::SyncError;
+use crate::export::ExportError;
+use crate::wallet_bundle::BundleError;
+use crate::{GalaChainError, KeychainError};
+use std::fmt;
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// Which configured server a request went to, so hints can point at the right setting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApiService {
+    Identity,
+    Operations,
+}
+
+impl fmt::Display for ApiService {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiService::Identity => write!(f, "Identity"),
+            ApiService::Operations => write!(f, "Operations"),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum WalletError {
+    #[error("{0}")]
+    Keychain(#[from] KeychainError),
+    #[error("{service} API: {source}")]
+    Api { service: ApiService, source: GalaChainError },
+    #[error("Invalid seed phrase: {0}")]
+    InvalidMnemonic(String),
+    #[error("Backup failed: {0}")]
+    Backup(#[from] BackupError),
+    #[error("Export failed: {0}")]
+    Export(#[from] ExportError),
+    #[error("Encrypted backup: {0}")]
+    Bundle(#[from] BundleError),
+    #[error("Backup target: {0}")]
+    BackupSync(#[from] SyncError),
+    #[error("Could not access {}: {source}", path.display())]
+    File { path: PathBuf, source: std::io::Error },
+    #[error("Invalid settings file: {0}")]
+    Settings(String),
+    #[error("Invalid recipient list: {0}")]
+    RecipientList(String),
+    #[error("Wallet is locked")]
+    Locked,
+    #[error("{0}")]
+    Other(String),
+}
+
+impl WalletError {
+    pub fn api(service: ApiService, source: GalaChainError) -> Self {
+        WalletError::Api { service, source }
+    }
+
+    pub fn file(path: impl Into<PathBuf>, source: std::io::Error) -> Self {
+        WalletError::File { path: path.into(), source }
+    }
+
+    /// Stable code shown in the banner and logs, handy for support requests.
+    pub fn code(&self) -> &'static str {
+        match self {
+            WalletError::Keychain(KeychainError::NotFound) => "KEY-001",
+            WalletError::Keychain(KeychainError::Access(_)) => "KEY-002",
+            WalletError::Keychain(KeychainError::Serialize(_) | KeychainError::Deserialize(_)) => "KEY-003",
+            WalletError::Api { source: GalaChainError::Network(_), .. } => "NET-001",
+            WalletError::Api { source: GalaChainError::Cancelled, .. } => "NET-002",
+            WalletError::Api { source: GalaChainError::CircuitOpen(_), .. } => "NET-003",
+            WalletError::Api { source: GalaChainError::RateLimited(_), .. } => "NET-004",
+            WalletError::Api { source: GalaChainError::Auth(_), .. } => "API-001",
+            WalletError::Api { source: GalaChainError::Parse(_), .. } => "API-002",
+            WalletError::Api { source: GalaChainError::Api(_), .. } => "API-003",
+            WalletError::Api { source: GalaChainError::NotRegistered, .. } => "API-004",
+            WalletError::Api { source: GalaChainError::DuplicateSubmission(_), .. } => "API-005",
+            WalletError::Api { source: GalaChainError::InsufficientBalance(_), .. } => "API-006",
+            WalletError::Api { source: GalaChainError::NotFound(_), .. } => "API-007",
+            WalletError::Api { source: GalaChainError::Conflict(_), .. } => "API-008",
+            WalletError::Api { source: GalaChainError::Signing(_), .. } => "SIGN-001",
+            WalletError::InvalidMnemonic(_) => "SEED-001",
+            WalletError::Backup(_) => "FILE-001",
+            WalletError::File { .. } => "FILE-002",
+            WalletError::Settings(_) => "FILE-003",
+            WalletError::RecipientList(_) => "FILE-004",
+            WalletError::Export(_) => "FILE-005",
+            WalletError::Bundle(_) => "FILE-006",
+            WalletError::BackupSync(SyncError::Upload(_)) => "NET-005",
+            WalletError::BackupSync(_) => "SYNC-001",
+            WalletError::Locked => "SES-001",
+            WalletError::Other(_) => "GEN-001",
+        }
+    }
+
+    /// What the user can do about it, if there's anything useful to suggest.
+    pub fn hint(&self) -> Option<String> {
+        let hint = match self {
+            WalletError::Keychain(KeychainError::NotFound) => "Create or import a wallet first".to_string(),
+            WalletError::Keychain(KeychainError::Access(_)) => {
+                "Unlock your OS keychain (Keychain Access, Credential Manager or Secret Service) and try again".to_string()
+            }
+            WalletError::Keychain(_) => "The stored wallet entry is damaged - re-import your seed phrase".to_string(),
+            WalletError::Api { service, source } => match source {
+                GalaChainError::Network(_) => format!("{} API unreachable — check Settings → endpoints", service),
+                GalaChainError::CircuitOpen(_) => format!("The {} API kept failing - wait for it to recover or press Retry now", service),
+                GalaChainError::RateLimited(_) => format!("The {} API is limiting how often it's called - wait a minute and try again", service),
+                GalaChainError::Auth(_) => format!("The {} API rejected the request - check the wallet is registered", service),
+                GalaChainError::Parse(_) => format!("Unexpected response from the {} API - check the endpoint paths in Settings", service),
+                GalaChainError::NotRegistered => "Register your identity from Wallet → Registration".to_string(),
+                GalaChainError::DuplicateSubmission(_) | GalaChainError::Conflict(_) => {
+                    "This request was already sent - refresh your balance before trying again".to_string()
+                }
+                GalaChainError::InsufficientBalance(_) => "The chain sees less than this needs - refresh your balance and lower the amount".to_string(),
+                GalaChainError::NotFound(_) => format!("The {} API found nothing there - check the channel, contract and endpoint paths in Settings", service),
+                GalaChainError::Api(_) | GalaChainError::Cancelled | GalaChainError::Signing(_) => return None,
+            },
+            WalletError::InvalidMnemonic(_) => "Check each word against your backup - all 12 must be BIP39 English words".to_string(),
+            WalletError::Backup(BackupError::UnsupportedFormat(_)) => "Save the backup with a .pdf or .png extension".to_string(),
+            WalletError::Export(ExportError::UnsupportedFormat(_)) => "Save the export with a .csv or .json extension".to_string(),
+            WalletError::Bundle(BundleError::WrongPassphrase) => "Type the passphrase chosen when the backup was saved".to_string(),
+            WalletError::Bundle(BundleError::PassphraseTooShort | BundleError::PassphraseTooWeak | BundleError::PassphraseMismatch) => return None,
+            WalletError::Bundle(_) => "Choose a backup saved from Export Seed → Save Encrypted Backup".to_string(),
+            WalletError::BackupSync(SyncError::Upload(_)) => "Check the backup target's URL and credentials in Settings, then upload again".to_string(),
+            WalletError::BackupSync(_) => "Set the backup target's URL and credentials in Settings".to_string(),
+            WalletError::Backup(_) | WalletError::Export(_) | WalletError::File { .. } => "Pick another location and check you have access to it".to_string(),
+            WalletError::Settings(_) => "Choose a settings file exported from this wallet".to_string(),
+            WalletError::RecipientList(_) => "Use one recipient,amount pair per line, e.g. 0x5aAe...BeAed,25".to_string(),
+            WalletError::Locked => "Press Unlock at the top right to reload the wallet".to_string(),
+            WalletError::Other(_) => return None,
+        };
+        Some(hint)
+    }
+}
+
+impl From<String> for WalletError {
+    fn from(message: String) -> Self {
+        WalletError::Other(message)
+    }
+}
@@ -0,0 +1,137 @@
+// Burn-and-claim events, the pattern the dapp-template's burn screen follows.
+//
+// Entering an event is a burn of its required amount whose uniqueKey starts with
+// the event's prefix ("january-2025-event-<ULID>"), which is how the event's
+// backend finds the entries on chain. Once the chain has accepted the burn, the
+// event's claim endpoint is told about it:
+//
+//   POST {claim_endpoint}   {"event", "owner", "uniqueKey", "quantity"}
+//
+// Any 2xx answer means the claim was accepted. Event definitions are edited in
+// `events.json`, which also keeps each entry and whether it has been claimed.
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::PathBuf;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct EventDefinition {
+    pub id: String,
+    pub name: String,
+    /// Start of the burn's uniqueKey; a ULID is added after it
+    pub key_prefix: String,
+    /// Symbol of a token in the wallet's token list
+    pub token: String,
+    #[serde(with = "rust_decimal::serde::str")]
+    pub required_burn: Decimal,
+    /// Where entries are claimed; empty when the event has none yet
+    #[serde(default)]
+    pub claim_endpoint: String,
+}
+
+impl EventDefinition {
+    /// The dapp-template's event, so the list isn't empty on first launch.
+    pub fn january_2025() -> Self {
+        Self {
+            id: "january-2025-event".to_string(),
+            name: "January 2025 Event".to_string(),
+            key_prefix: "january-2025-event".to_string(),
+            token: "GALA".to_string(),
+            required_burn: Decimal::ONE,
+            claim_endpoint: String::new(),
+        }
+    }
+
+    /// Why the definition can't be entered, if it can't.
+    pub fn problem(&self) -> Option<&'static str> {
+        if self.required_burn <= Decimal::ZERO {
+            Some("its required burn isn't a positive amount")
+        } else if self.key_prefix.trim().is_empty() {
+            Some("it has no uniqueKey prefix")
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct EventEntry {
+    pub event_id: String,
+    /// GalaChain address the burn came from
+    pub owner: String,
+    pub unique_key: String,
+    #[serde(with = "rust_decimal::serde::str")]
+    pub quantity: Decimal,
+    pub burned_unix: u64,
+    #[serde(default)]
+    pub claimed_unix: Option<u64>,
+    /// Why the last claim attempt failed
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+impl EventEntry {
+    /// What is posted to the claim endpoint.
+    pub fn claim_record(&self) -> Value {
+        json!({
+            "event": self.event_id,
+            "owner": self.owner,
+            "uniqueKey": self.unique_key,
+            "quantity": self.quantity.to_string(),
+        })
+    }
+
+    pub fn is_claimed(&self) -> bool {
+        self.claimed_unix.is_some()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct EventBook {
+    pub events: Vec<EventDefinition>,
+    /// Oldest first
+    pub entries: Vec<EventEntry>,
+}
+
+impl Default for EventBook {
+    fn default() -> Self {
+        Self { events: vec![EventDefinition::january_2025()], entries: Vec::new() }
+    }
+}
+
+impl EventBook {
+    pub fn load(path: Option<PathBuf>) -> Self {
+        path.and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: Option<PathBuf>) -> std::io::Result<()> {
+        let Some(path) = path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self).unwrap_or_default())
+    }
+
+    pub fn event(&self, id: &str) -> Option<&EventDefinition> {
+        self.events.iter().find(|event| event.id == id)
+    }
+
+    /// `owner`'s entry in the event, if it has entered.
+    pub fn entry(&self, event_id: &str, owner: &str) -> Option<&EventEntry> {
+        self.entries.iter().rev().find(|entry| entry.event_id == event_id && entry.owner == owner)
+    }
+
+    pub fn entry_mut(&mut self, unique_key: &str) -> Option<&mut EventEntry> {
+        self.entries.iter_mut().find(|entry| entry.unique_key == unique_key)
+    }
+
+    pub fn record(&mut self, entry: EventEntry) {
+        self.entries.push(entry);
+    }
+}
@@ -0,0 +1,187 @@
+// Plain-English summaries of the DTOs the wallet signs, for confirmation
+// dialogs: "Burn 25.00 GALA from your wallet", "Grant client|alice an allowance to
+// transfer up to 100.00 TOWN".
+//
+// Known GalaChain methods (the token and public-key contracts) are summarized
+// from the DTO's own fields. An unknown method, or a known one missing a field
+// its summary needs, falls back to "Call <method>" and a list of the fields, so
+// a summary never says more than the DTO does. Token classes are named by the
+// wallet's token list where it has them, by collection otherwise, and the
+// wallet's own address reads "your wallet". The raw JSON always goes along for
+// the dialog's expander.
+
+use crate::address;
+use crate::amounts::{format_amount, parse_amount};
+use crate::tokens::TokenInfo;
+use serde_json::Value;
+use std::time::{Duration, UNIX_EPOCH};
+
+/// Longest field value in a generic summary before it's cut
+const MAX_FIELD_CHARS: usize = 60;
+/// Fields the wallet fills in itself, left out of generic summaries
+const BOOKKEEPING_FIELDS: [&str; 3] = ["uniqueKey", "signature", "trace"];
+/// GalaChain's AllowanceType, by number
+const ALLOWANCE_VERBS: [&str; 7] = ["use", "lock", "spend", "transfer", "mint", "swap", "burn"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Explanation {
+    pub summary: String,
+    /// Anything else to know before signing, one line each
+    pub details: Vec<String>,
+    /// The DTO as pretty-printed JSON
+    pub raw: String,
+    /// False when the method isn't one this module knows and `details` lists the fields
+    pub known: bool,
+}
+
+/// What a summary names things by.
+pub struct Names<'a> {
+    /// The wallet's address, in any form `address` parses
+    pub own_address: Option<&'a str>,
+    pub tokens: &'a [TokenInfo],
+}
+
+impl Names<'_> {
+    fn who(&self, user: &str) -> String {
+        match self.own_address {
+            Some(own) if address::galachain_alias(own) == address::galachain_alias(user) => "your wallet".to_string(),
+            _ => address::display(user),
+        }
+    }
+
+    /// The token class (and instance, for NFTs) a token key names.
+    fn token(&self, key: &Value) -> Option<String> {
+        let field = |name: &str| key.get(name).and_then(Value::as_str);
+        let (collection, category, r#type, additional_key) = (field("collection")?, field("category")?, field("type")?, field("additionalKey")?);
+        let symbol = self
+            .tokens
+            .iter()
+            .find(|token| token.collection == collection && token.category == category && token.r#type == r#type && token.additional_key == additional_key)
+            .map_or(collection, |token| token.symbol.as_str());
+        match key.get("instance").and_then(text).filter(|instance| instance != "0") {
+            Some(instance) => Some(format!("{} #{}", symbol, instance)),
+            None => Some(symbol.to_string()),
+        }
+    }
+}
+
+/// Summarizes `dto`, to be signed for `method` (a contract method name like "TransferToken").
+pub fn explain(method: &str, dto: &Value, names: &Names) -> Explanation {
+    let raw = serde_json::to_string_pretty(dto).unwrap_or_default();
+    match describe(method, dto, names) {
+        Some((summary, details)) => Explanation { summary, details, raw, known: true },
+        None => Explanation { summary: format!("Call {} on GalaChain", method), details: field_lines(dto), raw, known: false },
+    }
+}
+
+fn describe(method: &str, dto: &Value, names: &Names) -> Option<(String, Vec<String>)> {
+    // The caller's own wallet when the DTO leaves the field out
+    let who = |field: &str| dto.get(field).and_then(Value::as_str).map_or_else(|| "your wallet".to_string(), |user| names.who(user));
+    match method {
+        "TransferToken" => {
+            let to = names.who(dto.get("to")?.as_str()?);
+            let summary = format!("Send {} {} from {} to {}", quantity(dto.get("quantity")?)?, names.token(dto.get("tokenInstance")?)?, who("from"), to);
+            Some((summary, Vec::new()))
+        }
+        "BurnTokens" => {
+            let burns = dto
+                .get("tokenInstances")?
+                .as_array()?
+                .iter()
+                .map(|burn| Some(format!("{} {}", quantity(burn.get("quantity")?)?, names.token(burn.get("tokenInstanceKey")?)?)))
+                .collect::<Option<Vec<String>>>()
+                .filter(|burns| !burns.is_empty())?;
+            let summary = format!("Burn {} from {}", burns.join(" and "), who("owner"));
+            Some((summary, vec!["Burned tokens are destroyed for good".to_string()]))
+        }
+        "GrantAllowance" => {
+            let token = names.token(dto.get("tokenInstance")?)?;
+            let verb = ALLOWANCE_VERBS.get(usize::try_from(dto.get("allowanceType")?.as_u64()?).ok()?)?;
+            let grants = dto
+                .get("quantities")?
+                .as_array()?
+                .iter()
+                .map(|grant| Some(format!("Grant {} an allowance to {} up to {} {}", names.who(grant.get("user")?.as_str()?), verb, quantity(grant.get("quantity")?)?, token)))
+                .collect::<Option<Vec<String>>>()
+                .filter(|grants| !grants.is_empty())?;
+            let mut details = Vec::new();
+            if let Some(uses) = dto.get("uses").and_then(text) {
+                details.push(format!("Usable {} times", uses));
+            }
+            match dto.get("expires").and_then(Value::as_u64) {
+                None | Some(0) => details.push("Never expires".to_string()),
+                Some(millis) => details.push(format!("Expires {}", crate::locale::format_time(UNIX_EPOCH + Duration::from_millis(millis)))),
+            }
+            Some((grants.join("; "), details))
+        }
+        "MintToken" => {
+            let summary = format!("Mint {} {} to {}", quantity(dto.get("quantity")?)?, names.token(dto.get("tokenClass")?)?, who("owner"));
+            Some((summary, Vec::new()))
+        }
+        "LockToken" => {
+            let summary = format!("Lock {} {} in {}", quantity(dto.get("quantity")?)?, names.token(dto.get("tokenInstance")?)?, who("owner"));
+            let details = dto.get("lockAuthority").and_then(Value::as_str).map(|authority| format!("Only {} can unlock them", names.who(authority)));
+            Some((summary, details.into_iter().collect()))
+        }
+        "UnlockToken" => {
+            let token = names.token(dto.get("tokenInstance")?)?;
+            let summary = match dto.get("quantity") {
+                Some(amount) => format!("Unlock {} {} in {}", quantity(amount)?, token, who("owner")),
+                None => format!("Unlock all locked {} in {}", token, who("owner")),
+            };
+            Some((summary, Vec::new()))
+        }
+        "RegisterEthUser" | "RegisterUser" => {
+            let summary = format!("Register public key {} as {}'s GalaChain identity", short_public_key(dto.get("publicKey")?.as_str()?), who("user"));
+            Some((summary, vec!["The record is public and can't be removed".to_string()]))
+        }
+        "UpdatePublicKey" => {
+            let summary = format!("Replace your registered public key with {}", short_public_key(dto.get("newPublicKey")?.as_str()?));
+            Some((summary, Vec::new()))
+        }
+        "FetchBalances" => Some((format!("Read the balances of {} - nothing is changed", who("owner")), Vec::new())),
+        _ => None,
+    }
+}
+
+/// The first and last characters of a long public key.
+pub fn short_public_key(public_key: &str) -> String {
+    if public_key.len() > 24 {
+        format!("{}…{}", &public_key[..12], &public_key[public_key.len() - 8..])
+    } else {
+        public_key.to_string()
+    }
+}
+
+// Quantities come as BigNumber strings, or numbers from hand-written DTOs
+fn quantity(value: &Value) -> Option<String> {
+    text(value).as_deref().and_then(parse_amount).map(format_amount)
+}
+
+fn text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => Some(text.clone()),
+        Value::Number(number) => Some(number.to_string()),
+        _ => None,
+    }
+}
+
+// "field: value" for each field a generic summary lists
+fn field_lines(dto: &Value) -> Vec<String> {
+    let Some(fields) = dto.as_object() else {
+        return vec![format!("Not a JSON object: {}", cut(&dto.to_string()))];
+    };
+    fields
+        .iter()
+        .filter(|(name, _)| !BOOKKEEPING_FIELDS.contains(&name.as_str()))
+        .map(|(name, value)| format!("{}: {}", name, cut(&text(value).unwrap_or_else(|| value.to_string()))))
+        .collect()
+}
+
+fn cut(value: &str) -> String {
+    if value.chars().count() > MAX_FIELD_CHARS {
+        format!("{}…", value.chars().take(MAX_FIELD_CHARS).collect::<String>())
+    } else {
+        value.to_string()
+    }
+}
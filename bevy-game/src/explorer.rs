@@ -0,0 +1,195 @@
+// Chain Explorer: a read-only look at raw chain state for developers building
+// chaincode against the local bundler.
+//
+// Every GalaChain contract can answer GetObjectByKey, and some also expose
+// GetObjectsByPartialCompositeKey; both are evaluated, never submitted, so the
+// explorer signs nothing and can't change anything. Keys are typed as the
+// object type and its attributes separated by '|' ("GCTI|GALA|Unit|none|0")
+// and turned into the NUL-delimited composite key the chain stores objects
+// under. The call goes to the token contract, at the Balance endpoint's URL
+// with the method name swapped in, so it follows the channel, contract, host
+// and API mode like every other call.
+//
+// Keys inspected often can be bookmarked; bookmarks are kept in
+// explorer_bookmarks.json, newest first. The contract's other read methods,
+// found through GetContractAPI, are in contract_api.rs.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::PathBuf;
+
+pub const BOOKMARKS_FILE: &str = "explorer_bookmarks.json";
+pub const MAX_BOOKMARKS: usize = 30;
+/// Longer results are cut here; the Developer panel log has the whole response
+pub const MAX_SHOWN_CHARS: usize = 20_000;
+
+// Between the parts of a composite key, and before and after them
+const KEY_DELIMITER: char = '\u{0}';
+// Between the parts of a key as typed
+const TYPED_DELIMITER: char = '|';
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExplorerMethod {
+    GetObjectByKey,
+    GetObjectsByPartialCompositeKey,
+}
+
+impl ExplorerMethod {
+    pub const ALL: [ExplorerMethod; 2] = [ExplorerMethod::GetObjectByKey, ExplorerMethod::GetObjectsByPartialCompositeKey];
+
+    /// The contract method, as it appears in the URL
+    pub fn name(self) -> &'static str {
+        match self {
+            ExplorerMethod::GetObjectByKey => "GetObjectByKey",
+            ExplorerMethod::GetObjectsByPartialCompositeKey => "GetObjectsByPartialCompositeKey",
+        }
+    }
+
+    /// What the key field wants
+    pub fn hint(self) -> &'static str {
+        match self {
+            ExplorerMethod::GetObjectByKey => "Object type and every key attribute, e.g. GCTI|GALA|Unit|none|0",
+            ExplorerMethod::GetObjectsByPartialCompositeKey => "Object type and the leading attributes, e.g. GCTB|eth|0a1b...",
+        }
+    }
+}
+
+/// The chain's composite key: "\0GCTI\0GALA\0Unit\0none\00\0".
+pub fn composite_key(object_type: &str, attributes: &[String]) -> String {
+    let mut key = String::from(KEY_DELIMITER);
+    for part in std::iter::once(object_type).chain(attributes.iter().map(String::as_str)) {
+        key.push_str(part);
+        key.push(KEY_DELIMITER);
+    }
+    key
+}
+
+/// A composite key as it's typed, for showing one the chain returned.
+pub fn display_key(key: &str) -> String {
+    key.trim_matches(KEY_DELIMITER).replace(KEY_DELIMITER, &TYPED_DELIMITER.to_string())
+}
+
+/// `contract_url` with its last path segment - the method it calls - replaced by `method`.
+pub fn method_url(contract_url: &str, method: &str) -> String {
+    match contract_url.rsplit_once('/') {
+        Some((contract, _)) => format!("{}/{}", contract, method),
+        None => format!("{}/{}", contract_url, method),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExplorerQuery {
+    pub method: ExplorerMethod,
+    pub object_type: String,
+    pub attributes: Vec<String>,
+}
+
+impl ExplorerQuery {
+    /// Reads a key typed with '|' between its parts; a trailing '|' is ignored.
+    /// A key pasted with its NUL delimiters reads the same.
+    pub fn parse(method: ExplorerMethod, text: &str) -> Result<Self, String> {
+        let text = display_key(text.trim());
+        let text = text.trim_matches(TYPED_DELIMITER);
+        let mut parts = text.split(TYPED_DELIMITER).map(str::trim);
+        let object_type = parts.next().filter(|object_type| !object_type.is_empty()).ok_or("Enter an object type, like GCTI or GCTB")?;
+        if object_type.chars().any(char::is_whitespace) {
+            return Err(format!("\"{}\" isn't an object type; separate the key's parts with |", object_type));
+        }
+        let attributes: Vec<String> = parts.map(str::to_string).collect();
+        if method == ExplorerMethod::GetObjectByKey && attributes.is_empty() {
+            return Err("GetObjectByKey needs the whole key - add the attributes after the object type".to_string());
+        }
+        Ok(Self { method, object_type: object_type.to_string(), attributes })
+    }
+
+    /// The request body the method takes.
+    pub fn dto(&self) -> Value {
+        match self.method {
+            ExplorerMethod::GetObjectByKey => json!({ "objectId": composite_key(&self.object_type, &self.attributes) }),
+            ExplorerMethod::GetObjectsByPartialCompositeKey => json!({ "objectType": self.object_type, "attributes": self.attributes }),
+        }
+    }
+
+    /// The key as typed, tidied: "GCTI|GALA|Unit|none|0"
+    pub fn key(&self) -> String {
+        std::iter::once(self.object_type.as_str()).chain(self.attributes.iter().map(String::as_str)).collect::<Vec<_>>().join("|")
+    }
+}
+
+/// A result for the explorer to show: indented JSON, with a count for a list of objects.
+pub fn pretty(data: &Value) -> String {
+    let body = serde_json::to_string_pretty(data).unwrap_or_else(|_| data.to_string());
+    let text = match data {
+        Value::Array(objects) if objects.len() == 1 => format!("1 object\n{}", body),
+        Value::Array(objects) => format!("{} objects\n{}", objects.len(), body),
+        Value::Null => "Nothing returned".to_string(),
+        _ => body,
+    };
+    truncate(&text, MAX_SHOWN_CHARS)
+}
+
+fn truncate(text: &str, limit: usize) -> String {
+    match text.char_indices().nth(limit) {
+        Some((cut, _)) => format!("{}\n… {} more characters (the Developer panel has the whole response)", &text[..cut], text[cut..].chars().count()),
+        None => text.to_string(),
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Bookmark {
+    pub method: ExplorerMethod,
+    /// As typed, "GCTI|GALA|Unit|none|0"
+    pub key: String,
+}
+
+impl Bookmark {
+    pub fn of(query: &ExplorerQuery) -> Self {
+        Self { method: query.method, key: query.key() }
+    }
+
+    pub fn label(&self) -> String {
+        format!("{} {}", self.method.name(), self.key)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Bookmarks {
+    /// Newest first
+    pub entries: Vec<Bookmark>,
+}
+
+impl Bookmarks {
+    pub fn load(path: Option<PathBuf>) -> Self {
+        path.and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: Option<PathBuf>) -> std::io::Result<()> {
+        let Some(path) = path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self).unwrap_or_default())
+    }
+
+    pub fn contains(&self, bookmark: &Bookmark) -> bool {
+        self.entries.contains(bookmark)
+    }
+
+    /// Adds it as the newest; the oldest go past `MAX_BOOKMARKS`. False if it was already there.
+    pub fn add(&mut self, bookmark: Bookmark) -> bool {
+        if self.contains(&bookmark) {
+            return false;
+        }
+        self.entries.insert(0, bookmark);
+        self.entries.truncate(MAX_BOOKMARKS);
+        true
+    }
+
+    pub fn remove(&mut self, bookmark: &Bookmark) {
+        self.entries.retain(|entry| entry != bookmark);
+    }
+}
@@ -0,0 +1,112 @@
+// Balance export for bookkeeping and tax tools.
+//
+// Writes the balances the wallet has fetched as CSV or JSON, picked by the file
+// extension. Quantities are exact decimal strings and timestamps are UTC
+// (RFC 3339), so spreadsheets and tax tools read them without rounding.
+
+use crate::backup::format_unix_date;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::error::Error as StdError;
+use std::fmt;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Debug)]
+pub enum ExportError {
+    UnsupportedFormat(String),
+    Io(std::io::Error),
+}
+
+impl fmt::Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ExportError::UnsupportedFormat(ext) => write!(f, "Unsupported export format '{}' (use .csv or .json)", ext),
+            ExportError::Io(e) => write!(f, "File error: {}", e),
+        }
+    }
+}
+
+impl StdError for ExportError {}
+
+impl From<std::io::Error> for ExportError {
+    fn from(e: std::io::Error) -> Self {
+        ExportError::Io(e)
+    }
+}
+
+/// One fetched balance.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BalanceRecord {
+    /// When the API returned this balance
+    #[serde(serialize_with = "serialize_utc")]
+    pub fetched_at: SystemTime,
+    pub address: String,
+    pub galachain_address: String,
+    pub token: String,
+    #[serde(with = "rust_decimal::serde::str")]
+    pub available: Decimal,
+    #[serde(with = "rust_decimal::serde::str")]
+    pub locked: Decimal,
+}
+
+#[derive(Serialize)]
+struct JsonExport<'a> {
+    #[serde(serialize_with = "serialize_utc")]
+    exported_at: SystemTime,
+    balances: &'a [BalanceRecord],
+}
+
+pub fn save(records: &[BalanceRecord], path: &Path) -> Result<(), ExportError> {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+
+    let contents = match extension.as_str() {
+        "csv" => to_csv(records),
+        "json" => to_json(records, SystemTime::now()),
+        other => return Err(ExportError::UnsupportedFormat(other.to_string())),
+    };
+    std::fs::write(path, contents)?;
+    Ok(())
+}
+
+pub fn to_csv(records: &[BalanceRecord]) -> String {
+    let mut csv = String::from("fetched_at,address,galachain_address,token,available,locked,total\n");
+    for record in records {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{}\n",
+            format_utc(record.fetched_at),
+            record.address,
+            record.galachain_address,
+            record.token,
+            record.available,
+            record.locked,
+            record.available + record.locked
+        ));
+    }
+    csv
+}
+
+pub fn to_json(records: &[BalanceRecord], exported_at: SystemTime) -> String {
+    serde_json::to_string_pretty(&JsonExport { exported_at, balances: records }).unwrap_or_default()
+}
+
+/// RFC 3339 UTC timestamp, e.g. "2025-01-31T09:05:00Z".
+pub fn format_utc(time: SystemTime) -> String {
+    let secs = time.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    let seconds_of_day = secs % 86_400;
+    format!(
+        "{}T{:02}:{:02}:{:02}Z",
+        format_unix_date(secs),
+        seconds_of_day / 3_600,
+        seconds_of_day % 3_600 / 60,
+        seconds_of_day % 60
+    )
+}
+
+fn serialize_utc<S: serde::Serializer>(time: &SystemTime, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format_utc(*time))
+}
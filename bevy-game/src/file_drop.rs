@@ -0,0 +1,114 @@
+// Files dropped onto the window.
+//
+// A dropped file is read and sorted into the import flow it belongs to: a
+// seed phrase text file goes to Import Wallet, a wallet entry JSON (the
+// keychain entry format, mnemonic or watch-only) to Import Wallet or the
+// watch-only field, and an environment profile to Settings. Nothing is
+// imported here; main.rs asks for confirmation first and the flow it opens
+// still needs its own button. Encrypted Ethereum keystores and printed backup
+// sheets are recognized only to say why they can't be read.
+
+use crate::environment::{EnvironmentProfile, ProfileError};
+use std::fmt;
+use std::path::Path;
+
+/// Larger files aren't any of the kinds below and aren't read
+pub const MAX_DROP_BYTES: u64 = 1024 * 1024;
+/// Words the Import screen takes
+pub const SEED_PHRASE_WORDS: usize = 12;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DroppedFile {
+    /// Lowercased, in order
+    SeedPhrase(Vec<String>),
+    /// Raw keychain entry JSON, for `SecureWalletData::from_json`
+    WalletEntry(String),
+    EnvironmentProfile(EnvironmentProfile),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DropError {
+    Unsupported(String),
+    BackupSheet,
+    TooLarge(u64),
+    Read(String),
+    WordCount(usize),
+    EncryptedKeystore,
+    Unrecognized,
+    Profile(ProfileError),
+}
+
+impl fmt::Display for DropError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DropError::Unsupported(extension) => {
+                write!(f, "'{}' files can't be imported - drop a seed phrase .txt, a wallet .json or an environment profile", extension)
+            }
+            DropError::BackupSheet => write!(f, "Printed backup sheets can't be read back - type the words in Import Wallet"),
+            DropError::TooLarge(bytes) => write!(f, "The file is {} KB, too large to be a wallet or profile", bytes / 1024),
+            DropError::Read(reason) => write!(f, "Couldn't read the file: {}", reason),
+            DropError::WordCount(count) => write!(f, "The file has {} words, expected a {}-word seed phrase", count, SEED_PHRASE_WORDS),
+            DropError::EncryptedKeystore => {
+                write!(f, "Encrypted Ethereum keystores aren't supported - import the wallet with its seed phrase")
+            }
+            DropError::Unrecognized => write!(f, "This JSON is neither a wallet entry nor an environment profile"),
+            DropError::Profile(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for DropError {}
+
+impl DroppedFile {
+    /// What the confirmation calls it.
+    pub fn description(&self) -> &'static str {
+        match self {
+            DroppedFile::SeedPhrase(_) => "seed phrase",
+            DroppedFile::WalletEntry(_) => "wallet file",
+            DroppedFile::EnvironmentProfile(_) => "environment profile",
+        }
+    }
+}
+
+pub fn read_dropped(path: &Path) -> Result<DroppedFile, DropError> {
+    let extension = path.extension().and_then(|extension| extension.to_str()).unwrap_or_default().to_lowercase();
+    match extension.as_str() {
+        "txt" | "json" => {}
+        "pdf" | "png" => return Err(DropError::BackupSheet),
+        _ => return Err(DropError::Unsupported(extension)),
+    }
+    let size = std::fs::metadata(path).map_err(|e| DropError::Read(e.to_string()))?.len();
+    if size > MAX_DROP_BYTES {
+        return Err(DropError::TooLarge(size));
+    }
+    let contents = std::fs::read_to_string(path).map_err(|e| DropError::Read(e.to_string()))?;
+    classify(&extension, &contents)
+}
+
+/// Sorts the contents of a `.txt` or `.json` file by what it holds.
+pub fn classify(extension: &str, contents: &str) -> Result<DroppedFile, DropError> {
+    if extension != "json" {
+        let words: Vec<String> = contents.split_whitespace().map(|word| word.to_lowercase()).collect();
+        if words.len() != SEED_PHRASE_WORDS {
+            return Err(DropError::WordCount(words.len()));
+        }
+        return Ok(DroppedFile::SeedPhrase(words));
+    }
+
+    let value: serde_json::Value = serde_json::from_str(contents).map_err(|e| DropError::Profile(ProfileError::Json(e.to_string())))?;
+    let Some(object) = value.as_object() else {
+        return Err(DropError::Unrecognized);
+    };
+    // V3 keystores spell it either way
+    if object.contains_key("crypto") || object.contains_key("Crypto") {
+        return Err(DropError::EncryptedKeystore);
+    }
+    if object.contains_key("mnemonic") || object.contains_key("wallet_type") {
+        return Ok(DroppedFile::WalletEntry(contents.to_string()));
+    }
+    // Every field of a profile has a default, so anything parses as one without this
+    if !object.contains_key("kind") && !object.contains_key("operations_base_url") {
+        return Err(DropError::Unrecognized);
+    }
+    EnvironmentProfile::from_json(contents).map(DroppedFile::EnvironmentProfile).map_err(DropError::Profile)
+}
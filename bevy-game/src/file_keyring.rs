@@ -0,0 +1,209 @@
+// Encrypted-file keychain for Linux desktops with neither a Secret Service
+// provider nor a kernel keyring that outlives the session, and for portable
+// mode (portable.rs) on every platform.
+//
+// Every keychain entry the app would store (wallet, archive, credentials) is a
+// field of one file in the config directory, each sealed with ChaCha20-Poly1305
+// under a key stretched from a passphrase, as in wallet_bundle.rs, with the
+// entry's name bound in as associated data so entries can't be swapped. The
+// passphrase is asked for once per session under Settings → Keychain; until
+// then every access fails as a locked keychain would, which keyring_health.rs
+// turns into the usual unlock-and-retry banner. A `check` value sealed when
+// the file is created tells a wrong passphrase apart before anything is read.
+// Creating the file takes the passphrase twice, as a backup bundle does: a
+// typo there would lock every entry behind a passphrase nobody knows.
+
+use crate::wallet_bundle::{check_passphrase, KDF_ITERATIONS, MAX_KDF_ITERATIONS};
+use crate::{KeychainError, SecretStore};
+use base64::Engine;
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+pub const KEYRING_FILE: &str = "keyring.enc.json";
+const FILE_KIND: &str = "galachain-wallet-keyring";
+const FILE_VERSION: u32 = 1;
+const KDF: &str = "pbkdf2-sha256";
+const CHECK: &str = "check";
+
+#[derive(Serialize, Deserialize, Clone)]
+struct Sealed {
+    nonce: String,
+    ciphertext: String,
+}
+
+// The file on disk; binary fields are base64
+#[derive(Serialize, Deserialize)]
+struct KeyringFile {
+    kind: String,
+    version: u32,
+    kdf: String,
+    iterations: u32,
+    salt: String,
+    check: Sealed,
+    #[serde(default)]
+    entries: BTreeMap<String, Sealed>,
+}
+
+/// The unlocked file's key, shared by every entry; None until the passphrase is given.
+#[derive(Clone, Default)]
+pub struct FileKey(Arc<Mutex<Option<ChaCha20Poly1305>>>);
+
+impl FileKey {
+    pub fn is_unlocked(&self) -> bool {
+        self.0.lock().unwrap().is_some()
+    }
+
+    /// Opens the file at `path` with `passphrase`, or creates it when there's none
+    /// yet - then `confirmation` has to repeat the passphrase.
+    pub fn unlock(&self, path: &Path, passphrase: &str, confirmation: &str) -> Result<(), KeychainError> {
+        self.unlock_with_iterations(path, passphrase, confirmation, KDF_ITERATIONS)
+    }
+
+    /// `unlock` creating the file with a chosen PBKDF2 round count; tests use a low one.
+    pub fn unlock_with_iterations(&self, path: &Path, passphrase: &str, confirmation: &str, iterations: u32) -> Result<(), KeychainError> {
+        let cipher = match read_file(path)? {
+            Some(file) => {
+                let cipher = cipher(passphrase, &decode(&file.salt)?, file.iterations);
+                open(&cipher, CHECK, &file.check).map_err(|_| locked("wrong passphrase for the encrypted keyring file"))?;
+                cipher
+            }
+            None => {
+                check_passphrase(passphrase, confirmation).map_err(|e| locked(&e.to_string()))?;
+                let salt: [u8; 16] = rand::random();
+                let cipher = cipher(passphrase, &salt, iterations);
+                let file = KeyringFile {
+                    kind: FILE_KIND.to_string(),
+                    version: FILE_VERSION,
+                    kdf: KDF.to_string(),
+                    iterations,
+                    salt: base64::engine::general_purpose::STANDARD.encode(salt),
+                    check: seal(&cipher, CHECK, FILE_KIND)?,
+                    entries: BTreeMap::new(),
+                };
+                write_file(path, &file)?;
+                cipher
+            }
+        };
+        *self.0.lock().unwrap() = Some(cipher);
+        Ok(())
+    }
+
+    pub fn lock(&self) {
+        *self.0.lock().unwrap() = None;
+    }
+
+    fn cipher(&self) -> Result<ChaCha20Poly1305, KeychainError> {
+        self.0.lock().unwrap().clone().ok_or_else(|| locked("the encrypted keyring file is locked - unlock it under Settings → Keychain"))
+    }
+}
+
+/// One entry of the encrypted file, named like its OS keychain username.
+pub struct FileKeyring {
+    pub path: Option<PathBuf>,
+    pub name: String,
+    pub key: FileKey,
+}
+
+impl FileKeyring {
+    fn path(&self) -> Result<&Path, KeychainError> {
+        self.path.as_deref().ok_or_else(|| locked("there's no config directory for the keyring file"))
+    }
+
+    // Reads the file, lets `change` edit its entries and writes it back
+    fn update(&self, change: impl FnOnce(&mut BTreeMap<String, Sealed>) -> Result<(), KeychainError>) -> Result<(), KeychainError> {
+        let path = self.path()?;
+        let mut file = read_file(path)?.ok_or_else(|| locked("the encrypted keyring file is missing"))?;
+        change(&mut file.entries)?;
+        write_file(path, &file)
+    }
+}
+
+impl SecretStore for FileKeyring {
+    fn get(&self) -> Result<String, KeychainError> {
+        let cipher = self.key.cipher()?;
+        let file = read_file(self.path()?)?.ok_or(KeychainError::NotFound)?;
+        let sealed = file.entries.get(&self.name).ok_or(KeychainError::NotFound)?;
+        open(&cipher, &self.name, sealed)
+    }
+
+    fn set(&self, secret: &str) -> Result<(), KeychainError> {
+        let cipher = self.key.cipher()?;
+        let sealed = seal(&cipher, &self.name, secret)?;
+        self.update(|entries| {
+            entries.insert(self.name.clone(), sealed);
+            Ok(())
+        })
+    }
+
+    fn delete(&self) -> Result<(), KeychainError> {
+        self.key.cipher()?;
+        self.update(|entries| entries.remove(&self.name).map(|_| ()).ok_or(KeychainError::NotFound))
+    }
+}
+
+fn locked(reason: &str) -> KeychainError {
+    KeychainError::Access(reason.to_string())
+}
+
+fn read_file(path: &Path) -> Result<Option<KeyringFile>, KeychainError> {
+    let json = match std::fs::read_to_string(path) {
+        Ok(json) => json,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(locked(&format!("couldn't read the keyring file: {}", e))),
+    };
+    let file: KeyringFile = serde_json::from_str(&json).map_err(|e| KeychainError::Deserialize(e.to_string()))?;
+    // A round count past the bundle limit would hold the UI thread in PBKDF2 for good
+    if file.kind != FILE_KIND || file.version > FILE_VERSION || file.kdf != KDF || file.iterations == 0 || file.iterations > MAX_KDF_ITERATIONS {
+        return Err(KeychainError::Deserialize(format!("{} isn't a keyring file this wallet understands", path.display())));
+    }
+    Ok(Some(file))
+}
+
+// Written to a temporary file and renamed over the old one, so a crash can't leave half a keyring
+fn write_file(path: &Path, file: &KeyringFile) -> Result<(), KeychainError> {
+    let json = serde_json::to_string_pretty(file).map_err(|e| KeychainError::Serialize(e.to_string()))?;
+    let write = || {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let temporary = path.with_extension("tmp");
+        std::fs::write(&temporary, json)?;
+        std::fs::rename(&temporary, path)
+    };
+    write().map_err(|e| locked(&format!("couldn't write the keyring file: {}", e)))
+}
+
+fn cipher(passphrase: &str, salt: &[u8], iterations: u32) -> ChaCha20Poly1305 {
+    let mut key = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, iterations, &mut key);
+    ChaCha20Poly1305::new(Key::from_slice(&key))
+}
+
+fn seal(cipher: &ChaCha20Poly1305, name: &str, secret: &str) -> Result<Sealed, KeychainError> {
+    let nonce: [u8; 12] = rand::random();
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), Payload { msg: secret.as_bytes(), aad: name.as_bytes() })
+        .map_err(|_| KeychainError::Serialize("encryption failed".to_string()))?;
+    let base64 = base64::engine::general_purpose::STANDARD;
+    Ok(Sealed { nonce: base64.encode(nonce), ciphertext: base64.encode(ciphertext) })
+}
+
+fn open(cipher: &ChaCha20Poly1305, name: &str, sealed: &Sealed) -> Result<String, KeychainError> {
+    let (nonce, ciphertext) = (decode(&sealed.nonce)?, decode(&sealed.ciphertext)?);
+    if nonce.len() != 12 {
+        return Err(KeychainError::Deserialize("nonce: wrong length".to_string()));
+    }
+    let plaintext = cipher
+        .decrypt(Nonce::from_slice(&nonce), Payload { msg: &ciphertext, aad: name.as_bytes() })
+        .map_err(|_| KeychainError::Deserialize(format!("the keyring file's '{}' entry doesn't decrypt", name)))?;
+    String::from_utf8(plaintext).map_err(|e| KeychainError::Deserialize(e.to_string()))
+}
+
+fn decode(value: &str) -> Result<Vec<u8>, KeychainError> {
+    base64::engine::general_purpose::STANDARD.decode(value).map_err(|e| KeychainError::Deserialize(e.to_string()))
+}
@@ -0,0 +1,175 @@
+// The UI font and the icons drawn with it.
+//
+// Bevy's built-in font is a Latin subset of Fira Mono: status lines like
+// "✅ Registered" or "🔄 Refresh" came out as boxes. DejaVu Sans is bundled
+// instead (assets/fonts, Bitstream Vera license) and replaces the default font
+// at startup, so every `TextFont` without a font of its own uses it. It has
+// arrows, dingbats and geometric shapes but no colour emoji, so each emoji the
+// UI uses is an `Icon` with a glyph the font does have, and `replace_emoji`
+// swaps them in wherever text is set. The few icons with a drawing of their own
+// (icon_atlas.rs) show it instead where they stand alone, as on buttons.
+
+use bevy::prelude::Component;
+
+/// DejaVu Sans, embedded so the binary needs no asset folder.
+pub const UI_FONT_DATA: &[u8] = include_bytes!("../assets/fonts/DejaVuSans.ttf");
+
+// Asks for the emoji presentation of the character before it; there's none to ask for
+const EMOJI_PRESENTATION: char = '\u{FE0F}';
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Icon {
+    Success,
+    Error,
+    Warning,
+    Pending,
+    Paused,
+    Refresh,
+    Add,
+    Tip,
+    Note,
+    Burn,
+    WatchOnly,
+    Save,
+    Export,
+    Update,
+    Send,
+    Ticket,
+    Reference,
+    Celebrate,
+    Derivation,
+    Wallet,
+    Key,
+    Network,
+    Delete,
+    Search,
+    Identity,
+    Document,
+    Clipboard,
+    Balance,
+    Settings,
+}
+
+impl Icon {
+    pub const ALL: [Icon; 29] = [
+        Icon::Success,
+        Icon::Error,
+        Icon::Warning,
+        Icon::Pending,
+        Icon::Paused,
+        Icon::Refresh,
+        Icon::Add,
+        Icon::Tip,
+        Icon::Note,
+        Icon::Burn,
+        Icon::WatchOnly,
+        Icon::Save,
+        Icon::Export,
+        Icon::Update,
+        Icon::Send,
+        Icon::Ticket,
+        Icon::Reference,
+        Icon::Celebrate,
+        Icon::Derivation,
+        Icon::Wallet,
+        Icon::Key,
+        Icon::Network,
+        Icon::Delete,
+        Icon::Search,
+        Icon::Identity,
+        Icon::Document,
+        Icon::Clipboard,
+        Icon::Balance,
+        Icon::Settings,
+    ];
+
+    /// What the UI font draws for it.
+    pub fn glyph(self) -> char {
+        match self {
+            Icon::Success => '✔',
+            Icon::Error => '✘',
+            Icon::Warning => '⚠',
+            Icon::Pending => '◷',
+            Icon::Paused => '‖',
+            Icon::Refresh => '↻',
+            Icon::Add => '✚',
+            Icon::Tip => 'ℹ',
+            Icon::Note => '✎',
+            Icon::Burn => '♨',
+            Icon::WatchOnly => '◉',
+            Icon::Save => '⬇',
+            Icon::Export => '⇧',
+            Icon::Update => '⬆',
+            Icon::Send => '➜',
+            Icon::Ticket => '✦',
+            Icon::Reference => '⚒',
+            Icon::Celebrate => '★',
+            Icon::Derivation => '⊙',
+            Icon::Wallet => '◈',
+            Icon::Key => '⚷',
+            Icon::Network => '⊕',
+            Icon::Delete => '⌫',
+            Icon::Search => '⚲',
+            Icon::Identity => '▤',
+            Icon::Document => '☰',
+            Icon::Clipboard => '❐',
+            Icon::Balance => '¤',
+            Icon::Settings => '⚙',
+        }
+    }
+
+    /// The emoji it stands in for, as written in the source.
+    pub fn emoji(self) -> char {
+        match self {
+            Icon::Success => '✅',
+            Icon::Error => '❌',
+            Icon::Warning => '⚠',
+            Icon::Pending => '⏳',
+            Icon::Paused => '⏸',
+            Icon::Refresh => '🔄',
+            Icon::Add => '➕',
+            Icon::Tip => '💡',
+            Icon::Note => '📝',
+            Icon::Burn => '🔥',
+            Icon::WatchOnly => '👁',
+            Icon::Save => '💾',
+            Icon::Export => '📤',
+            Icon::Update => '⬆',
+            Icon::Send => '🚀',
+            Icon::Ticket => '🎟',
+            Icon::Reference => '🚧',
+            Icon::Celebrate => '🎉',
+            Icon::Derivation => '🧭',
+            Icon::Wallet => '👛',
+            Icon::Key => '🔑',
+            Icon::Network => '🌐',
+            Icon::Delete => '🗑',
+            Icon::Search => '🔍',
+            Icon::Identity => '🪪',
+            Icon::Document => '📄',
+            Icon::Clipboard => '📋',
+            Icon::Balance => '💰',
+            Icon::Settings => '⚙',
+        }
+    }
+
+    pub fn from_emoji(emoji: char) -> Option<Icon> {
+        Icon::ALL.into_iter().find(|icon| icon.emoji() == emoji)
+    }
+}
+
+/// `text` with each emoji swapped for its icon's glyph, or `None` if there was nothing
+/// to swap, so callers only touch text that needs it.
+pub fn replace_emoji(text: &str) -> Option<String> {
+    // Warning, Update and Settings draw as themselves, so on their own they need no change
+    let swapped = |c: char| c == EMOJI_PRESENTATION || Icon::from_emoji(c).is_some_and(|icon| icon.glyph() != c);
+    if !text.chars().any(swapped) {
+        return None;
+    }
+    Some(
+        text.chars()
+            .filter(|&c| c != EMOJI_PRESENTATION)
+            .map(|c| Icon::from_emoji(c).map_or(c, Icon::glyph))
+            .collect(),
+    )
+}
@@ -0,0 +1,114 @@
+// Local record of the transfers and burns this wallet has signed.
+//
+// Each entry is keyed by the signed DTO's uniqueKey, the ID GalaChain knows the
+// transaction by, and carries the optional note typed when it was sent. Notes
+// never leave the device: they aren't part of the DTO. Saved to `history.json`
+// and exported from the History screen as CSV.
+
+use crate::export::format_utc;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::{Duration, UNIX_EPOCH};
+
+/// Longest note accepted from the note input.
+pub const MAX_NOTE_CHARS: usize = 200;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TxKind {
+    Transfer,
+    Burn,
+}
+
+impl TxKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            TxKind::Transfer => "transfer",
+            TxKind::Burn => "burn",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct HistoryEntry {
+    pub unique_key: String,
+    pub unix_time: u64,
+    pub kind: TxKind,
+    pub symbol: String,
+    /// `TokenInfo::class_key`
+    pub token: String,
+    #[serde(with = "rust_decimal::serde::str")]
+    pub quantity: Decimal,
+    /// Recipient of a transfer
+    #[serde(default)]
+    pub to: Option<String>,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct TransactionHistory {
+    /// Oldest first
+    pub entries: Vec<HistoryEntry>,
+}
+
+impl TransactionHistory {
+    pub fn load(path: Option<PathBuf>) -> Self {
+        path.and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: Option<PathBuf>) -> std::io::Result<()> {
+        let Some(path) = path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self).unwrap_or_default())
+    }
+
+    pub fn record(&mut self, entry: HistoryEntry) {
+        self.entries.push(entry);
+    }
+
+    /// Whether a transaction with this uniqueKey was already signed here.
+    pub fn contains(&self, unique_key: &str) -> bool {
+        self.entries.iter().any(|entry| entry.unique_key == unique_key)
+    }
+}
+
+/// The note as it should be stored: trimmed, cut to `MAX_NOTE_CHARS`, `None` if blank.
+pub fn clean_note(note: &str) -> Option<String> {
+    let note: String = note.trim().chars().take(MAX_NOTE_CHARS).collect();
+    (!note.is_empty()).then_some(note)
+}
+
+pub fn to_csv(entries: &[HistoryEntry]) -> String {
+    let mut csv = String::from("time,kind,token,symbol,quantity,to,unique_key,note\n");
+    for entry in entries {
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{},{}\n",
+            format_utc(UNIX_EPOCH + Duration::from_secs(entry.unix_time)),
+            entry.kind.label(),
+            csv_field(&entry.token),
+            csv_field(&entry.symbol),
+            entry.quantity,
+            csv_field(entry.to.as_deref().unwrap_or_default()),
+            csv_field(&entry.unique_key),
+            csv_field(entry.note.as_deref().unwrap_or_default())
+        ));
+    }
+    csv
+}
+
+// Quoted when it holds a comma, quote or line break, with quotes doubled
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
@@ -0,0 +1,84 @@
+// Token holds: quantities locked on chain until they are released or expire.
+//
+// FetchBalances reports each hold with its name, who created it, the authority
+// that can release it early, and when it was created and expires (milliseconds
+// since the epoch; an expiry of 0 means the hold only ends when it's released).
+// The Balance screen lists them with a countdown to each expiry.
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TokenHold {
+    #[serde(with = "rust_decimal::serde::str")]
+    pub quantity: Decimal,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(rename = "createdBy", default)]
+    pub created_by: Option<String>,
+    #[serde(rename = "lockAuthority", default)]
+    pub lock_authority: Option<String>,
+    #[serde(default)]
+    pub created: u64,
+    #[serde(default)]
+    pub expires: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HoldExpiry {
+    /// Held until released
+    Never,
+    /// Past its expiry; the chain releases it on the next write that touches it
+    Expired,
+    In(Duration),
+}
+
+impl TokenHold {
+    pub fn label(&self) -> &str {
+        self.name.as_deref().filter(|name| !name.is_empty()).unwrap_or("Unnamed hold")
+    }
+
+    pub fn expiry(&self, now_ms: u64) -> HoldExpiry {
+        HoldExpiry::at(self.expires, now_ms)
+    }
+}
+
+impl HoldExpiry {
+    /// Where a hold expiring at `expires` (ms, 0 for never) stands at `now_ms`.
+    pub fn at(expires: u64, now_ms: u64) -> Self {
+        match expires {
+            0 => HoldExpiry::Never,
+            expires if expires <= now_ms => HoldExpiry::Expired,
+            expires => HoldExpiry::In(Duration::from_millis(expires - now_ms)),
+        }
+    }
+
+    pub fn label(self) -> String {
+        match self {
+            HoldExpiry::Never => "until released".to_string(),
+            HoldExpiry::Expired => "expired".to_string(),
+            HoldExpiry::In(remaining) => format!("unlocks in {}", format_countdown(remaining)),
+        }
+    }
+}
+
+/// "2d 03:15:09" past a day, "03:15:09" under it.
+pub fn format_countdown(remaining: Duration) -> String {
+    let secs = remaining.as_secs();
+    let (days, hours, minutes, seconds) = (secs / 86_400, secs / 3_600 % 24, secs / 60 % 60, secs % 60);
+    if days > 0 {
+        format!("{}d {:02}:{:02}:{:02}", days, hours, minutes, seconds)
+    } else {
+        format!("{:02}:{:02}:{:02}", hours, minutes, seconds)
+    }
+}
+
+/// Hold timestamps as a `SystemTime`, for `export::format_utc`.
+pub fn from_millis(ms: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_millis(ms)
+}
+
+pub fn now_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0)
+}
@@ -0,0 +1,220 @@
+// Keyboard shortcuts for the common actions: refresh the balance, lock the
+// session, jump to one of the wallet screens, go back, and the F1 cheat sheet.
+//
+// Bindings are chords like "Ctrl+R" (Ctrl is Cmd on macOS). The defaults can
+// be changed in hotkeys.json, which maps action ids to chords, e.g.
+// `{"lock": "Ctrl+Shift+L", "refresh_balance": ""}` - an empty chord turns the
+// shortcut off. Entries that don't parse are skipped and reported, and a chord
+// taken from another action leaves that action unbound, so one key never does
+// two things.
+//
+// Key names are Bevy's `KeyCode` names with the "Key"/"Digit" prefix dropped
+// ("R", "1", "F1", "Escape"); main.rs turns pressed keys into chords with
+// `key_name` and looks them up with `HotkeyMap::action_for`.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::PathBuf;
+
+pub const HOTKEYS_FILE: &str = "hotkeys.json";
+
+/// Wallet screens reachable with Ctrl+1..8, in that order
+pub const SCREENS: [&str; 8] = ["Overview", "Balance", "Transfer", "Burn", "Dashboard", "Approvals", "History", "Events"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HotkeyAction {
+    RefreshBalance,
+    Lock,
+    /// Index into `SCREENS`
+    Screen(usize),
+    Back,
+    CheatSheet,
+}
+
+impl HotkeyAction {
+    pub fn all() -> Vec<HotkeyAction> {
+        let mut actions = vec![HotkeyAction::RefreshBalance, HotkeyAction::Lock];
+        actions.extend((0..SCREENS.len()).map(HotkeyAction::Screen));
+        actions.extend([HotkeyAction::Back, HotkeyAction::CheatSheet]);
+        actions
+    }
+
+    /// The key used for it in hotkeys.json
+    pub fn id(self) -> String {
+        match self {
+            HotkeyAction::RefreshBalance => "refresh_balance".to_string(),
+            HotkeyAction::Lock => "lock".to_string(),
+            HotkeyAction::Screen(index) => format!("screen_{}", index + 1),
+            HotkeyAction::Back => "back".to_string(),
+            HotkeyAction::CheatSheet => "cheat_sheet".to_string(),
+        }
+    }
+
+    pub fn description(self) -> String {
+        match self {
+            HotkeyAction::RefreshBalance => "Refresh balance".to_string(),
+            HotkeyAction::Lock => "Lock the session".to_string(),
+            HotkeyAction::Screen(index) => format!("Go to {}", SCREENS.get(index).copied().unwrap_or("?")),
+            HotkeyAction::Back => "Back / close this sheet".to_string(),
+            HotkeyAction::CheatSheet => "Show or hide this sheet".to_string(),
+        }
+    }
+
+    fn default_chord(self) -> Chord {
+        match self {
+            HotkeyAction::RefreshBalance => Chord::ctrl("R"),
+            HotkeyAction::Lock => Chord::ctrl("L"),
+            HotkeyAction::Screen(index) => Chord::ctrl(&(index + 1).to_string()),
+            HotkeyAction::Back => Chord::key("Escape"),
+            HotkeyAction::CheatSheet => Chord::key("F1"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chord {
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+    pub key: String,
+}
+
+impl Chord {
+    pub fn key(key: &str) -> Self {
+        Self { ctrl: false, shift: false, alt: false, key: key.to_string() }
+    }
+
+    pub fn ctrl(key: &str) -> Self {
+        Self { ctrl: true, ..Self::key(key) }
+    }
+
+    /// "Ctrl+Shift+R", "F1", "esc"; modifiers and single letters in any case.
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut parts: Vec<&str> = text.split('+').map(str::trim).collect();
+        let key = parts.pop().filter(|key| !key.is_empty())?;
+        let mut chord = Chord::key(&normalize_key(key));
+        for modifier in parts {
+            match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" | "cmd" | "command" => chord.ctrl = true,
+                "shift" => chord.shift = true,
+                "alt" | "option" => chord.alt = true,
+                _ => return None,
+            }
+        }
+        Some(chord)
+    }
+
+    /// A plain character key would also type into a focused text field.
+    pub fn types_text(&self) -> bool {
+        !self.ctrl && !self.alt && self.key.chars().count() == 1
+    }
+}
+
+impl fmt::Display for Chord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.ctrl {
+            write!(f, "Ctrl+")?;
+        }
+        if self.alt {
+            write!(f, "Alt+")?;
+        }
+        if self.shift {
+            write!(f, "Shift+")?;
+        }
+        write!(f, "{}", self.key)
+    }
+}
+
+fn normalize_key(key: &str) -> String {
+    match key.to_ascii_lowercase().as_str() {
+        "esc" | "escape" => "Escape".to_string(),
+        lower if lower.len() > 1 && lower.starts_with('f') && lower[1..].parse::<u8>().is_ok() => lower.to_ascii_uppercase(),
+        _ if key.chars().count() == 1 => key.to_ascii_uppercase(),
+        _ => key.to_string(),
+    }
+}
+
+/// A `KeyCode`'s debug name as used in chords: "KeyR" is "R", "Digit1" is "1".
+pub fn key_name(code: &str) -> String {
+    match code.strip_prefix("Key").or_else(|| code.strip_prefix("Digit")) {
+        Some(rest) if rest.chars().count() == 1 => rest.to_string(),
+        _ => code.to_string(),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HotkeyMap {
+    /// Actions without a chord are left out
+    bindings: Vec<(HotkeyAction, Chord)>,
+    /// hotkeys.json entries that were skipped, and why
+    pub problems: Vec<String>,
+}
+
+impl Default for HotkeyMap {
+    fn default() -> Self {
+        Self {
+            bindings: HotkeyAction::all().into_iter().map(|action| (action, action.default_chord())).collect(),
+            problems: Vec::new(),
+        }
+    }
+}
+
+impl HotkeyMap {
+    /// The defaults with hotkeys.json's changes, or just the defaults when there's no file.
+    pub fn load(path: Option<PathBuf>) -> Self {
+        let Some(json) = path.and_then(|path| std::fs::read_to_string(path).ok()) else {
+            return Self::default();
+        };
+        match serde_json::from_str::<BTreeMap<String, String>>(&json) {
+            Ok(overrides) => Self::with_overrides(&overrides),
+            Err(e) => Self { problems: vec![format!("{} isn't a map of action to chord: {}", HOTKEYS_FILE, e)], ..Self::default() },
+        }
+    }
+
+    pub fn with_overrides(overrides: &BTreeMap<String, String>) -> Self {
+        let mut map = Self::default();
+        let actions = HotkeyAction::all();
+        let mut changed: Vec<HotkeyAction> = Vec::new();
+
+        for (id, text) in overrides {
+            let Some(action) = actions.iter().copied().find(|action| action.id() == *id) else {
+                map.problems.push(format!("Unknown action \"{}\"", id));
+                continue;
+            };
+            if text.trim().is_empty() {
+                map.bindings.retain(|(bound, _)| *bound != action);
+                changed.push(action);
+                continue;
+            }
+            let Some(chord) = Chord::parse(text) else {
+                map.problems.push(format!("Can't read \"{}\" for {}", text, id));
+                continue;
+            };
+            if let Some((other, _)) = map.bindings.iter().find(|(bound, existing)| *existing == chord && *bound != action && changed.contains(bound)) {
+                map.problems.push(format!("{} is already used for {}; {} keeps its default", chord, other.id(), id));
+                continue;
+            }
+            // A default this chord took over goes unbound
+            map.bindings.retain(|(bound, existing)| *bound != action && *existing != chord);
+            map.bindings.push((action, chord));
+            changed.push(action);
+        }
+
+        // Listed in the usual order whatever order the file used
+        map.bindings.sort_by_key(|(action, _)| actions.iter().position(|known| known == action));
+        map
+    }
+
+    pub fn action_for(&self, chord: &Chord) -> Option<HotkeyAction> {
+        self.bindings.iter().find(|(_, bound)| bound == chord).map(|(action, _)| *action)
+    }
+
+    pub fn chord_for(&self, action: HotkeyAction) -> Option<&Chord> {
+        self.bindings.iter().find(|(bound, _)| *bound == action).map(|(_, chord)| chord)
+    }
+
+    /// Chord and description of each bound shortcut, for the cheat sheet.
+    pub fn cheat_sheet(&self) -> Vec<(String, String)> {
+        self.bindings.iter().map(|(action, chord)| (chord.to_string(), action.description())).collect()
+    }
+}
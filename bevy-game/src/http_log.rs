@@ -0,0 +1,121 @@
+// Ring buffer of recent HTTP exchanges for the Developer panel.
+//
+// GalaChainClient records every request it sends here instead of logging
+// bodies. Bodies are redacted and truncated before they are stored, so nothing
+// secret stays in memory or ends up on screen.
+
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+pub const HTTP_LOG_CAPACITY: usize = 50;
+pub const BODY_PREVIEW_LIMIT: usize = 2000;
+const REDACTED: &str = "[redacted]";
+// Compared case-insensitively against JSON object keys
+const SENSITIVE_KEYS: [&str; 10] = [
+    "signature",
+    "privatekey",
+    "private_key",
+    "mnemonic",
+    "seed",
+    "password",
+    "apikey",
+    "api_key",
+    "authorization",
+    // HttpOptions headers, which often carry an API key
+    "extra_headers",
+];
+
+#[derive(Debug, Clone)]
+pub struct HttpExchange {
+    pub method: &'static str,
+    pub url: String,
+    pub duration: Duration,
+    /// None when no response arrived (connection error, timeout)
+    pub status: Option<u16>,
+    pub request_body: String,
+    pub response_body: String,
+    pub error: Option<String>,
+}
+
+/// Shared between the client and its clones, so tasks on other threads record into the same log.
+#[derive(Clone, Default)]
+pub struct HttpLog {
+    entries: Arc<Mutex<VecDeque<HttpExchange>>>,
+    // Bumped on every change so the UI knows when to redraw
+    version: Arc<AtomicU64>,
+}
+
+impl HttpLog {
+    pub fn record(&self, mut exchange: HttpExchange) {
+        exchange.request_body = truncate_body(&redact_body(&exchange.request_body), BODY_PREVIEW_LIMIT);
+        exchange.response_body = truncate_body(&redact_body(&exchange.response_body), BODY_PREVIEW_LIMIT);
+
+        if let Ok(mut entries) = self.entries.lock() {
+            if entries.len() == HTTP_LOG_CAPACITY {
+                entries.pop_front();
+            }
+            entries.push_back(exchange);
+        }
+        self.version.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Newest first.
+    pub fn snapshot(&self) -> Vec<HttpExchange> {
+        self.entries
+            .lock()
+            .map(|entries| entries.iter().rev().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn clear(&self) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.clear();
+        }
+        self.version.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn version(&self) -> u64 {
+        self.version.load(Ordering::Relaxed)
+    }
+}
+
+/// Replaces the values of sensitive JSON keys. Bodies that aren't JSON are returned unchanged.
+pub fn redact_body(body: &str) -> String {
+    match serde_json::from_str::<Value>(body) {
+        Ok(mut value) => {
+            redact_value(&mut value);
+            serde_json::to_string_pretty(&value).unwrap_or_default()
+        }
+        Err(_) => body.to_string(),
+    }
+}
+
+fn redact_value(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                if SENSITIVE_KEYS.contains(&key.to_ascii_lowercase().as_str()) {
+                    *child = Value::String(REDACTED.to_string());
+                } else {
+                    redact_value(child);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_value),
+        _ => {}
+    }
+}
+
+pub fn truncate_body(body: &str, limit: usize) -> String {
+    if body.len() <= limit {
+        return body.to_string();
+    }
+    let mut end = limit;
+    while !body.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}... ({} bytes total)", &body[..end], body.len())
+}
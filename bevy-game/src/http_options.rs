@@ -0,0 +1,205 @@
+// HTTP client options from Settings, part of `ApiSettings`.
+//
+// Proxy, timeout and TLS options apply to every request the wallet makes.
+// Extra headers (an API key for a hosted gateway, say) only go to the chain
+// endpoints, never to icon hosts or approval and claim endpoints. The client is
+// rebuilt whenever the settings change, so nothing here needs a restart.
+// Gateway credentials (credentials.rs) ride along as `auth`, which is never
+// serialized: they live in the keychain, not in config files.
+// Every client keeps connections alive between requests (see connections.rs).
+// The wallet's own clients are `LazyClient`s, built on their first request:
+// building one sets up TLS and loads the system's root certificates, which is
+// slow enough to notice at startup, and Settings only needs `check`.
+
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use crate::connections::{POOL_IDLE_TIMEOUT, POOL_MAX_IDLE_PER_HOST, TCP_KEEPALIVE};
+use crate::credentials::AuthHeader;
+use reqwest::{Certificate, Client, Proxy};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::Deref;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+pub const DEFAULT_TIMEOUT_SECS: u64 = 30;
+/// What the timeout button cycles through
+pub const TIMEOUT_OPTIONS: [u64; 5] = [10, 30, 60, 120, 300];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HttpOptions {
+    /// http:// or https:// URL; empty to connect directly (or use HTTPS_PROXY)
+    pub proxy: String,
+    /// PEM file with extra root certificates, e.g. a local gateway's self-signed one
+    pub ca_bundle: String,
+    /// Skips certificate checks entirely; only for local development gateways
+    pub accept_invalid_certs: bool,
+    /// Whole-request timeout, connecting through reading the body
+    pub timeout_secs: u64,
+    /// Sent with every chain request
+    pub extra_headers: BTreeMap<String, String>,
+    /// The profile's gateway credentials, also sent with every chain request
+    #[serde(skip)]
+    pub auth: Option<AuthHeader>,
+}
+
+impl Default for HttpOptions {
+    fn default() -> Self {
+        Self {
+            proxy: String::new(),
+            ca_bundle: String::new(),
+            accept_invalid_certs: false,
+            timeout_secs: DEFAULT_TIMEOUT_SECS,
+            extra_headers: BTreeMap::new(),
+            auth: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HttpOptionsError {
+    InvalidProxy(String),
+    CaBundle(String),
+    InvalidHeader(String),
+    Build(String),
+}
+
+impl fmt::Display for HttpOptionsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HttpOptionsError::InvalidProxy(reason) => write!(f, "Invalid proxy: {}", reason),
+            HttpOptionsError::CaBundle(reason) => write!(f, "Couldn't load the CA bundle: {}", reason),
+            HttpOptionsError::InvalidHeader(reason) => write!(f, "Invalid header: {}", reason),
+            HttpOptionsError::Build(reason) => write!(f, "Couldn't set up the HTTP client: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for HttpOptionsError {}
+
+impl HttpOptions {
+    /// Client for requests that aren't chain calls: no extra headers.
+    pub fn client(&self) -> Result<Client, HttpOptionsError> {
+        self.builder()?.build().map_err(|e| HttpOptionsError::Build(e.to_string()))
+    }
+
+    /// Client for chain calls, with the extra headers.
+    pub fn chain_client(&self) -> Result<Client, HttpOptionsError> {
+        self.builder()?
+            .default_headers(self.header_map()?)
+            .build()
+            .map_err(|e| HttpOptionsError::Build(e.to_string()))
+    }
+
+    /// Checks the proxy, CA bundle and headers without building a client.
+    pub fn check(&self) -> Result<(), HttpOptionsError> {
+        self.builder().map(|_| ())?;
+        self.header_map().map(|_| ())
+    }
+
+    /// `client`, built on first use.
+    pub fn lazy_client(&self) -> LazyClient {
+        LazyClient { options: self.clone(), chain: false, client: Arc::default() }
+    }
+
+    /// `chain_client`, built on first use.
+    pub fn lazy_chain_client(&self) -> LazyClient {
+        LazyClient { options: self.clone(), chain: true, client: Arc::default() }
+    }
+
+    pub fn next_timeout(&self) -> u64 {
+        let index = TIMEOUT_OPTIONS.iter().position(|secs| *secs == self.timeout_secs);
+        index.map_or(DEFAULT_TIMEOUT_SECS, |index| TIMEOUT_OPTIONS[(index + 1) % TIMEOUT_OPTIONS.len()])
+    }
+
+    fn builder(&self) -> Result<reqwest::ClientBuilder, HttpOptionsError> {
+        let mut builder = Client::builder()
+            .timeout(Duration::from_secs(self.timeout_secs.max(1)))
+            .pool_idle_timeout(POOL_IDLE_TIMEOUT)
+            .pool_max_idle_per_host(POOL_MAX_IDLE_PER_HOST)
+            .tcp_keepalive(TCP_KEEPALIVE)
+            .danger_accept_invalid_certs(self.accept_invalid_certs);
+
+        let proxy = self.proxy.trim();
+        if !proxy.is_empty() {
+            let proxy = Proxy::all(proxy).map_err(|e| HttpOptionsError::InvalidProxy(e.to_string()))?;
+            builder = builder.proxy(proxy);
+        }
+
+        let ca_bundle = self.ca_bundle.trim();
+        if !ca_bundle.is_empty() {
+            let pem = std::fs::read(ca_bundle).map_err(|e| HttpOptionsError::CaBundle(format!("{}: {}", ca_bundle, e)))?;
+            let certificates = Certificate::from_pem_bundle(&pem).map_err(|e| HttpOptionsError::CaBundle(e.to_string()))?;
+            if certificates.is_empty() {
+                return Err(HttpOptionsError::CaBundle(format!("{} holds no PEM certificates", ca_bundle)));
+            }
+            for certificate in certificates {
+                builder = builder.add_root_certificate(certificate);
+            }
+        }
+        Ok(builder)
+    }
+
+    fn header_map(&self) -> Result<HeaderMap, HttpOptionsError> {
+        let mut headers = HeaderMap::new();
+        for (name, value) in &self.extra_headers {
+            let (name, value) = check_header(name, value)?;
+            headers.insert(name, value);
+        }
+        // Credentials win over an extra header of the same name
+        if let Some(auth) = &self.auth {
+            let (name, value) = check_header(&auth.name, &auth.value)?;
+            headers.insert(name, value);
+        }
+        Ok(headers)
+    }
+}
+
+/// A client built the first time it's used; clones share it, and so its connection pool.
+/// Derefs to the `Client`, so requests are made on it directly.
+#[derive(Clone)]
+pub struct LazyClient {
+    options: HttpOptions,
+    chain: bool,
+    client: Arc<OnceLock<Client>>,
+}
+
+impl LazyClient {
+    pub fn is_built(&self) -> bool {
+        self.client.get().is_some()
+    }
+}
+
+impl Deref for LazyClient {
+    type Target = Client;
+
+    fn deref(&self) -> &Client {
+        self.client.get_or_init(|| {
+            let built = if self.chain { self.options.chain_client() } else { self.options.client() };
+            // The options were checked when they were applied, so this is rare
+            built.unwrap_or_else(|e| {
+                bevy::log::warn!("{}; using the default HTTP client options", e);
+                HttpOptions::default().client().expect("Failed to create HTTP client")
+            })
+        })
+    }
+}
+
+/// Splits "Name: value" as typed in Settings, checking both halves.
+pub fn parse_header_line(line: &str) -> Result<(String, String), HttpOptionsError> {
+    let (name, value) = line
+        .split_once(':')
+        .ok_or_else(|| HttpOptionsError::InvalidHeader("expected \"Name: value\"".to_string()))?;
+    let (name, value) = (name.trim(), value.trim());
+    check_header(name, value)?;
+    Ok((name.to_string(), value.to_string()))
+}
+
+pub fn check_header(name: &str, value: &str) -> Result<(HeaderName, HeaderValue), HttpOptionsError> {
+    let header_name = HeaderName::from_bytes(name.as_bytes()).map_err(|_| HttpOptionsError::InvalidHeader(format!("{:?} isn't a header name", name)))?;
+    let mut header_value = HeaderValue::from_str(value).map_err(|_| HttpOptionsError::InvalidHeader(format!("the value for {} has characters headers can't carry", name)))?;
+    // Keeps API keys out of HTTP/2 header compression tables
+    header_value.set_sensitive(true);
+    Ok((header_name, header_value))
+}
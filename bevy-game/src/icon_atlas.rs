@@ -0,0 +1,243 @@
+// Drawn UI icons: the wallet, send, burn, settings and warning icons as one
+// texture atlas.
+//
+// The icons are SVG files in assets/icons, embedded in the binary and
+// rasterized into a single-row atlas at startup, so editing an icon is editing
+// its SVG. Only the subset they use is understood: `circle`, `line`,
+// `polyline`, `polygon` and `rect`, each with its own `fill` (anything but
+// "none" fills) and `stroke`/`stroke-width`, in the root's `viewBox`. There are
+// no transforms, groups or inherited attributes. Shapes come out white so the
+// UI can tint them; icons without a drawing fall back to their font glyph.
+
+use crate::fonts::Icon;
+use image::{Rgba, RgbaImage};
+use std::fmt;
+
+/// Icons drawn from SVG, in atlas order
+pub const ATLAS_ICONS: [(Icon, &str); 5] = [
+    (Icon::Wallet, include_str!("../assets/icons/wallet.svg")),
+    (Icon::Send, include_str!("../assets/icons/send.svg")),
+    (Icon::Burn, include_str!("../assets/icons/burn.svg")),
+    (Icon::Settings, include_str!("../assets/icons/settings.svg")),
+    (Icon::Warning, include_str!("../assets/icons/warning.svg")),
+];
+
+/// Side of one atlas cell in pixels; icons are shown at 16-32 px
+pub const ATLAS_CELL: u32 = 64;
+
+// Samples per pixel along each axis, for antialiased edges
+const SUPERSAMPLE: u32 = 4;
+const DEFAULT_VIEW_BOX: f32 = 24.0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SvgError {
+    MissingAttribute { element: String, attribute: &'static str },
+    BadNumber { element: String, value: String },
+}
+
+impl fmt::Display for SvgError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SvgError::MissingAttribute { element, attribute } => write!(f, "<{}> has no {}", element, attribute),
+            SvgError::BadNumber { element, value } => write!(f, "<{}> has '{}' where a number should be", element, value),
+        }
+    }
+}
+
+impl std::error::Error for SvgError {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Geometry {
+    Circle { center: (f32, f32), radius: f32 },
+    /// Lines, polylines, polygons and rects; `closed` joins the last point to the first
+    Path { points: Vec<(f32, f32)>, closed: bool },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Shape {
+    pub geometry: Geometry,
+    pub fill: bool,
+    /// Stroke width, if stroked
+    pub stroke: Option<f32>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SvgIcon {
+    /// Width of the `viewBox`; icons are square
+    pub view_box: f32,
+    pub shapes: Vec<Shape>,
+}
+
+/// Position of `icon` in the atlas, if it has a drawing.
+pub fn atlas_index(icon: Icon) -> Option<usize> {
+    ATLAS_ICONS.iter().position(|(drawn, _)| *drawn == icon)
+}
+
+pub fn parse_svg(svg: &str) -> Result<SvgIcon, SvgError> {
+    let mut icon = SvgIcon { view_box: DEFAULT_VIEW_BOX, shapes: Vec::new() };
+    for tag in svg.split('<').skip(1) {
+        let tag = tag.split('>').next().unwrap_or_default();
+        let element = tag.split_whitespace().next().unwrap_or_default().trim_end_matches('/');
+        let attributes = Attributes { element, tag };
+        let geometry = match element {
+            "svg" => {
+                if let Some(view_box) = attributes.get("viewBox") {
+                    icon.view_box = attributes.numbers(view_box)?.get(2).copied().unwrap_or(DEFAULT_VIEW_BOX);
+                }
+                continue;
+            }
+            "circle" => Geometry::Circle {
+                center: (attributes.number("cx")?, attributes.number("cy")?),
+                radius: attributes.number("r")?,
+            },
+            "line" => Geometry::Path {
+                points: vec![(attributes.number("x1")?, attributes.number("y1")?), (attributes.number("x2")?, attributes.number("y2")?)],
+                closed: false,
+            },
+            "polyline" | "polygon" => {
+                let points = attributes.get("points").ok_or_else(|| attributes.missing("points"))?;
+                let numbers = attributes.numbers(points)?;
+                Geometry::Path {
+                    points: numbers.chunks_exact(2).map(|pair| (pair[0], pair[1])).collect(),
+                    closed: element == "polygon",
+                }
+            }
+            "rect" => {
+                let (x, y) = (attributes.number("x")?, attributes.number("y")?);
+                let (width, height) = (attributes.number("width")?, attributes.number("height")?);
+                Geometry::Path {
+                    points: vec![(x, y), (x + width, y), (x + width, y + height), (x, y + height)],
+                    closed: true,
+                }
+            }
+            // The root's namespace, comments, titles and anything else draw nothing
+            _ => continue,
+        };
+        let stroked = attributes.get("stroke").is_some_and(|stroke| stroke != "none");
+        icon.shapes.push(Shape {
+            geometry,
+            fill: element != "line" && attributes.get("fill") != Some("none"),
+            stroke: if stroked { Some(attributes.number("stroke-width").unwrap_or(1.0)) } else { None },
+        });
+    }
+    Ok(icon)
+}
+
+struct Attributes<'a> {
+    element: &'a str,
+    tag: &'a str,
+}
+
+impl<'a> Attributes<'a> {
+    // `name="value"` pairs: splitting on quotes leaves each name at the end of the piece before its value
+    fn get(&self, name: &str) -> Option<&'a str> {
+        let pieces: Vec<&str> = self.tag.split('"').collect();
+        pieces
+            .chunks_exact(2)
+            .find(|pair| pair[0].trim_end().strip_suffix('=').and_then(|key| key.split_whitespace().last()) == Some(name))
+            .map(|pair| pair[1])
+    }
+
+    fn number(&self, name: &'static str) -> Result<f32, SvgError> {
+        let value = self.get(name).ok_or_else(|| self.missing(name))?;
+        value.trim().parse().map_err(|_| self.bad_number(value))
+    }
+
+    // Comma- or space-separated, as in `points` and `viewBox`
+    fn numbers(&self, list: &str) -> Result<Vec<f32>, SvgError> {
+        list.split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|number| !number.is_empty())
+            .map(|number| number.parse().map_err(|_| self.bad_number(number)))
+            .collect()
+    }
+
+    fn missing(&self, attribute: &'static str) -> SvgError {
+        SvgError::MissingAttribute { element: self.element.to_string(), attribute }
+    }
+
+    fn bad_number(&self, value: &str) -> SvgError {
+        SvgError::BadNumber { element: self.element.to_string(), value: value.to_string() }
+    }
+}
+
+impl Shape {
+    fn covers(&self, point: (f32, f32)) -> bool {
+        match &self.geometry {
+            Geometry::Circle { center, radius } => {
+                let distance = (point.0 - center.0).hypot(point.1 - center.1);
+                (self.fill && distance <= *radius) || self.stroke.is_some_and(|width| (distance - radius).abs() <= width / 2.0)
+            }
+            Geometry::Path { points, closed } => {
+                (self.fill && inside_polygon(points, point))
+                    || self.stroke.is_some_and(|width| {
+                        segments(points, *closed).any(|(from, to)| distance_to_segment(point, from, to) <= width / 2.0)
+                    })
+            }
+        }
+    }
+}
+
+fn segments(points: &[(f32, f32)], closed: bool) -> impl Iterator<Item = ((f32, f32), (f32, f32))> + '_ {
+    let closing = if closed && points.len() > 2 { points.last().zip(points.first()) } else { None };
+    points.windows(2).map(|pair| (pair[0], pair[1])).chain(closing.map(|(last, first)| (*last, *first)))
+}
+
+// Even-odd rule, as SVG's `fill-rule="evenodd"`; the icons have no self-intersecting outlines
+fn inside_polygon(points: &[(f32, f32)], point: (f32, f32)) -> bool {
+    let mut inside = false;
+    for (from, to) in segments(points, true) {
+        if (from.1 > point.1) != (to.1 > point.1) {
+            let crossing = from.0 + (point.1 - from.1) / (to.1 - from.1) * (to.0 - from.0);
+            if point.0 < crossing {
+                inside = !inside;
+            }
+        }
+    }
+    inside
+}
+
+// Round caps and joins fall out of measuring to the nearest point
+fn distance_to_segment(point: (f32, f32), from: (f32, f32), to: (f32, f32)) -> f32 {
+    let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+    let length_squared = dx * dx + dy * dy;
+    let t = if length_squared == 0.0 {
+        0.0
+    } else {
+        (((point.0 - from.0) * dx + (point.1 - from.1) * dy) / length_squared).clamp(0.0, 1.0)
+    };
+    (point.0 - (from.0 + t * dx)).hypot(point.1 - (from.1 + t * dy))
+}
+
+/// Coverage of each pixel of a `size` x `size` rendering, 0 to 255, row by row.
+pub fn rasterize(icon: &SvgIcon, size: u32) -> Vec<u8> {
+    let scale = icon.view_box / size as f32;
+    let samples = SUPERSAMPLE * SUPERSAMPLE;
+    let mut coverage = Vec::with_capacity((size * size) as usize);
+    for y in 0..size {
+        for x in 0..size {
+            let covered = (0..samples)
+                .filter(|sample| {
+                    let sx = x as f32 + (sample % SUPERSAMPLE) as f32 / SUPERSAMPLE as f32 + 0.5 / SUPERSAMPLE as f32;
+                    let sy = y as f32 + (sample / SUPERSAMPLE) as f32 / SUPERSAMPLE as f32 + 0.5 / SUPERSAMPLE as f32;
+                    icon.shapes.iter().any(|shape| shape.covers((sx * scale, sy * scale)))
+                })
+                .count() as u32;
+            coverage.push((covered * 255 / samples) as u8);
+        }
+    }
+    coverage
+}
+
+/// Every `ATLAS_ICONS` drawing in one row of `cell`-pixel squares, white with the
+/// coverage as alpha.
+pub fn render_atlas(cell: u32) -> Result<RgbaImage, SvgError> {
+    let mut atlas = RgbaImage::new(cell * ATLAS_ICONS.len() as u32, cell);
+    for (index, (_, svg)) in ATLAS_ICONS.iter().enumerate() {
+        let coverage = rasterize(&parse_svg(svg)?, cell);
+        for (offset, alpha) in coverage.into_iter().enumerate() {
+            let (x, y) = (offset as u32 % cell, offset as u32 / cell);
+            atlas.put_pixel(index as u32 * cell + x, y, Rgba([255, 255, 255, alpha]));
+        }
+    }
+    Ok(atlas)
+}
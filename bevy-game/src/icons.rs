@@ -0,0 +1,51 @@
+// Token icons from the registry's icon URLs.
+//
+// Each icon is downloaded once and kept in the user's cache directory under a
+// name derived from its URL, so later launches (and offline ones) load it from
+// disk. Only PNG is decoded; anything else gets the letter placeholder.
+
+use sha3::{Digest, Keccak256};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Icons are shown at 24-32 px; anything bigger than this is not an icon.
+pub const ICON_MAX_BYTES: usize = 512 * 1024;
+
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IconError {
+    TooLarge,
+    UnsupportedFormat,
+}
+
+impl fmt::Display for IconError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IconError::TooLarge => write!(f, "Icon is larger than {} KB", ICON_MAX_BYTES / 1024),
+            IconError::UnsupportedFormat => write!(f, "Icon is not a PNG image"),
+        }
+    }
+}
+
+/// Checks downloaded bytes before they are cached or decoded.
+pub fn check_icon(bytes: &[u8]) -> Result<(), IconError> {
+    if bytes.len() > ICON_MAX_BYTES {
+        return Err(IconError::TooLarge);
+    }
+    if !bytes.starts_with(&PNG_SIGNATURE) {
+        return Err(IconError::UnsupportedFormat);
+    }
+    Ok(())
+}
+
+/// Cache file for an icon URL; the URL itself may not be a valid file name.
+pub fn icon_cache_path(dir: &Path, url: &str) -> PathBuf {
+    let hash = hex::encode(Keccak256::digest(url.as_bytes()));
+    dir.join(format!("{}.png", &hash[..32]))
+}
+
+/// Shown in place of an icon that isn't available: the symbol's first letter.
+pub fn placeholder_letter(symbol: &str) -> String {
+    symbol.chars().find(|c| c.is_alphanumeric()).map(|c| c.to_uppercase().to_string()).unwrap_or_else(|| "?".to_string())
+}
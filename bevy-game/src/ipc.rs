@@ -0,0 +1,372 @@
+// Local JSON-RPC server, so a game or tool on this machine can use the wallet as
+// its signer the way a web dapp uses a browser wallet:
+//
+//   POST http://127.0.0.1:{port}/
+//   {"jsonrpc": "2.0", "id": 1, "method": "signMessage", "params": {"message": "..."}}
+//
+//   getAddress                          -> {"address", "galachainAddress"}
+//   signMessage    {message}            -> {"signature"}  (personal_sign, EIP-191)
+//   submitTransfer {to, quantity, token?} -> {"status", "uniqueKey" | "approvalId"}
+//
+// Off until it's turned on in Settings, and only listening on 127.0.0.1. Every
+// call waits for the user to approve it in the wallet; the HTTP request gives up
+// after `CALL_TIMEOUT`. Requests with an Origin header are refused: a web page can
+// reach localhost too, but browsers always say where its requests come from.
+// A request has `REQUEST_TIMEOUT` to arrive in full and `MAX_HEADER_BYTES` for its
+// request line and headers, and at most `MAX_CONNECTIONS` are open at once, so a
+// program that connects and stalls can't tie up threads.
+// An optional PIN (4 to 8 digits) has to be typed into the prompt before Approve
+// does anything; only its PBKDF2 hash is kept. The settings are saved to `ipc.json`.
+// Wrong PINs are counted across calls and restarts (pin_lockout.rs): past the
+// tries one call gets, each makes the next wait twice as long, and enough of
+// them lock the wallet and refuse the PIN until the fallback passphrase or the
+// seed phrase is entered again.
+
+use crate::biometric::PassphraseHash;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fmt;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_PORT: u16 = 8645;
+/// How long a call waits for the user before the caller gets an error
+pub const CALL_TIMEOUT: Duration = Duration::from_secs(120);
+/// Longest message `signMessage` accepts, so all of it fits on the approval prompt
+pub const MAX_MESSAGE_CHARS: usize = 2_000;
+const MAX_BODY_BYTES: usize = 64 * 1024;
+/// Request line and headers together
+pub const MAX_HEADER_BYTES: usize = 8 * 1024;
+/// How long a request has to arrive in full, however slowly it trickles in
+pub const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+/// Connections open at once, including calls waiting for the user; more are refused
+pub const MAX_CONNECTIONS: usize = 16;
+/// PIN length, in digits
+pub const PIN_DIGITS: std::ops::RangeInclusive<usize> = 4..=8;
+/// Wrong PINs before the call is rejected
+pub const MAX_PIN_ATTEMPTS: u32 = 3;
+// Checked on the UI thread at each Approve, so fewer rounds than the seed phrase's
+const PIN_ITERATIONS: u32 = 100_000;
+
+// JSON-RPC 2.0 codes, plus EIP-1193's for a refusal by the user
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+pub const WALLET_ERROR: i64 = -32000;
+pub const USER_REJECTED: i64 = 4001;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct IpcSettings {
+    pub enabled: bool,
+    pub port: u16,
+    /// Asked for on the approval prompt when set
+    pub pin: Option<PassphraseHash>,
+}
+
+impl Default for IpcSettings {
+    fn default() -> Self {
+        Self { enabled: false, port: DEFAULT_PORT, pin: None }
+    }
+}
+
+impl IpcSettings {
+    pub fn load(path: Option<PathBuf>) -> Self {
+        path.and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: Option<PathBuf>) -> std::io::Result<()> {
+        let Some(path) = path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self).unwrap_or_default())
+    }
+
+    pub fn set_pin(&mut self, pin: &str) -> Result<(), PinError> {
+        self.set_pin_with_iterations(pin, PIN_ITERATIONS)
+    }
+
+    /// `set_pin` with a chosen PBKDF2 round count; tests use a low one.
+    pub fn set_pin_with_iterations(&mut self, pin: &str, iterations: u32) -> Result<(), PinError> {
+        if !PIN_DIGITS.contains(&pin.len()) || !pin.chars().all(|c| c.is_ascii_digit()) {
+            return Err(PinError);
+        }
+        self.pin = Some(PassphraseHash::new(pin, iterations));
+        Ok(())
+    }
+
+    /// Whether `pin` lets a call be approved; anything does when no PIN is set.
+    pub fn pin_accepts(&self, pin: &str) -> bool {
+        self.pin.as_ref().is_none_or(|hash| hash.matches(pin))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PinError;
+
+impl fmt::Display for PinError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "The PIN needs {} to {} digits", PIN_DIGITS.start(), PIN_DIGITS.end())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RpcCall {
+    GetAddress,
+    SignMessage { message: String },
+    /// `to` as the caller wrote it; `token` is a symbol, the selected token when absent
+    SubmitTransfer { to: String, quantity: Decimal, token: Option<String> },
+}
+
+impl RpcCall {
+    /// The JSON-RPC method name
+    pub fn method(&self) -> &'static str {
+        match self {
+            RpcCall::GetAddress => "getAddress",
+            RpcCall::SignMessage { .. } => "signMessage",
+            RpcCall::SubmitTransfer { .. } => "submitTransfer",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+impl RpcError {
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        Self { code, message: message.into() }
+    }
+}
+
+/// Reads a JSON-RPC request; errors come back with whatever id could be read.
+pub fn parse_request(body: &str) -> Result<(Value, RpcCall), (Value, RpcError)> {
+    let request: Value = serde_json::from_str(body).map_err(|e| (Value::Null, RpcError::new(PARSE_ERROR, format!("Invalid JSON: {}", e))))?;
+    let id = request.get("id").cloned().unwrap_or(Value::Null);
+    let fail = |code, message: &str| Err((id.clone(), RpcError::new(code, message)));
+    let Some(method) = request.get("method").and_then(Value::as_str) else {
+        return fail(INVALID_REQUEST, "Missing method");
+    };
+    let params = request.get("params").cloned().unwrap_or(Value::Null);
+    let text = |name: &str| params.get(name).and_then(Value::as_str).map(str::trim).filter(|value| !value.is_empty());
+
+    let call = match method {
+        "getAddress" => RpcCall::GetAddress,
+        "signMessage" => {
+            // The message itself isn't trimmed: what is signed is exactly what was sent
+            let Some(message) = params.get("message").and_then(Value::as_str).filter(|message| !message.is_empty()) else {
+                return fail(INVALID_PARAMS, "signMessage needs a message");
+            };
+            if message.chars().count() > MAX_MESSAGE_CHARS {
+                return fail(INVALID_PARAMS, "Message is too long to show for approval");
+            }
+            RpcCall::SignMessage { message: message.to_string() }
+        }
+        "submitTransfer" => {
+            let Some(to) = text("to") else {
+                return fail(INVALID_PARAMS, "submitTransfer needs a recipient (to)");
+            };
+            // A string, so no precision is lost on the way in
+            let quantity = text("quantity").and_then(crate::amounts::parse_amount).filter(|quantity| *quantity > Decimal::ZERO);
+            let Some(quantity) = quantity else {
+                return fail(INVALID_PARAMS, "submitTransfer needs a positive quantity as a string");
+            };
+            RpcCall::SubmitTransfer { to: to.to_string(), quantity, token: text("token").map(str::to_string) }
+        }
+        _ => return fail(METHOD_NOT_FOUND, &format!("Unknown method {}", method)),
+    };
+    Ok((id, call))
+}
+
+pub fn response_body(id: &Value, result: &Result<Value, RpcError>) -> String {
+    let response = match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(error) => json!({ "jsonrpc": "2.0", "id": id, "error": { "code": error.code, "message": error.message } }),
+    };
+    response.to_string()
+}
+
+/// A call waiting for the user; answering it replies to the caller.
+pub struct PendingCall {
+    pub call: RpcCall,
+    pub received: Instant,
+    reply: mpsc::Sender<Result<Value, RpcError>>,
+}
+
+impl PendingCall {
+    pub fn expired(&self) -> bool {
+        self.received.elapsed() >= CALL_TIMEOUT
+    }
+
+    /// A caller that already gave up is ignored.
+    pub fn answer(self, result: Result<Value, RpcError>) {
+        let _ = self.reply.send(result);
+    }
+}
+
+/// The listening server; dropping it stops it.
+pub struct IpcServer {
+    pub addr: SocketAddr,
+    calls: Mutex<mpsc::Receiver<PendingCall>>,
+    stop: Arc<AtomicBool>,
+}
+
+impl IpcServer {
+    /// Listens on 127.0.0.1:`port` (0 picks a free port).
+    pub fn start(port: u16) -> std::io::Result<Self> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))?;
+        let addr = listener.local_addr()?;
+        let (sender, receiver) = mpsc::channel();
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let stopping = stop.clone();
+        let open = Arc::new(AtomicUsize::new(0));
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                if stopping.load(Ordering::Relaxed) {
+                    break;
+                }
+                let Ok(mut stream) = stream else {
+                    continue;
+                };
+                if open.fetch_add(1, Ordering::SeqCst) >= MAX_CONNECTIONS {
+                    open.fetch_sub(1, Ordering::SeqCst);
+                    write_response(&mut stream, "503 Service Unavailable", "");
+                    continue;
+                }
+                let sender = sender.clone();
+                let open = open.clone();
+                // One thread per connection: a call can wait minutes for the user
+                std::thread::spawn(move || {
+                    handle_connection(stream, sender);
+                    open.fetch_sub(1, Ordering::SeqCst);
+                });
+            }
+        });
+        Ok(Self { addr, calls: Mutex::new(receiver), stop })
+    }
+
+    pub fn try_next(&self) -> Option<PendingCall> {
+        self.calls.lock().ok()?.try_recv().ok()
+    }
+}
+
+impl Drop for IpcServer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        // Wakes the accept loop so it sees the flag
+        let _ = TcpStream::connect_timeout(&self.addr, Duration::from_millis(200));
+    }
+}
+
+fn handle_connection(mut stream: TcpStream, calls: mpsc::Sender<PendingCall>) {
+    let (status, body) = match read_request(&stream) {
+        Err(status) => (status, String::new()),
+        Ok(body) => match parse_request(&body) {
+            Err((id, error)) => ("200 OK", response_body(&id, &Err(error))),
+            Ok((id, call)) => {
+                let (reply, answer) = mpsc::channel();
+                let result = match calls.send(PendingCall { call, received: Instant::now(), reply }) {
+                    Ok(()) => {
+                        // Brings up the signing prompt without waiting for the window's next frame
+                        crate::redraw::wake_event_loop();
+                        answer
+                            .recv_timeout(CALL_TIMEOUT)
+                            .unwrap_or_else(|_| Err(RpcError::new(WALLET_ERROR, "No answer from the wallet in time")))
+                    }
+                    Err(_) => Err(RpcError::new(WALLET_ERROR, "The wallet is shutting down")),
+                };
+                ("200 OK", response_body(&id, &result))
+            }
+        },
+    };
+    write_response(&mut stream, status, &body);
+}
+
+fn write_response(stream: &mut TcpStream, status: &str, body: &str) {
+    let _ = write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+}
+
+// Reads from the stream until `deadline`; each read waits only for what's left of it
+struct DeadlineReader<'a> {
+    stream: &'a TcpStream,
+    deadline: Instant,
+}
+
+impl Read for DeadlineReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let left = self.deadline.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            return Err(std::io::ErrorKind::TimedOut.into());
+        }
+        self.stream.set_read_timeout(Some(left))?;
+        self.stream.read(buf)
+    }
+}
+
+// One line of the request head, counted against what's left of MAX_HEADER_BYTES
+fn read_head_line(reader: &mut impl BufRead, left: &mut usize) -> Result<String, &'static str> {
+    let mut line = String::new();
+    let read = reader.take(*left as u64).read_line(&mut line).map_err(|e| match e.kind() {
+        std::io::ErrorKind::TimedOut | std::io::ErrorKind::WouldBlock => "408 Request Timeout",
+        _ => "400 Bad Request",
+    })?;
+    *left -= read;
+    if !line.is_empty() && !line.ends_with('\n') && *left == 0 {
+        return Err("431 Request Header Fields Too Large");
+    }
+    Ok(line)
+}
+
+// The body of a POST, or the HTTP status to refuse it with
+fn read_request(stream: &TcpStream) -> Result<String, &'static str> {
+    let mut reader = BufReader::new(DeadlineReader { stream, deadline: Instant::now() + REQUEST_TIMEOUT });
+    let mut left = MAX_HEADER_BYTES;
+    let request_line = read_head_line(&mut reader, &mut left)?;
+    if !request_line.starts_with("POST ") {
+        return Err("405 Method Not Allowed");
+    }
+
+    let mut content_length = 0;
+    loop {
+        let header = read_head_line(&mut reader, &mut left)?;
+        if header.is_empty() || header == "\r\n" {
+            break;
+        }
+        let header = header.to_ascii_lowercase();
+        if header.starts_with("origin:") {
+            return Err("403 Forbidden");
+        }
+        if let Some(value) = header.strip_prefix("content-length:") {
+            content_length = value.trim().parse().map_err(|_| "400 Bad Request")?;
+        }
+    }
+    if content_length > MAX_BODY_BYTES {
+        return Err("413 Payload Too Large");
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).map_err(|e| match e.kind() {
+        std::io::ErrorKind::TimedOut | std::io::ErrorKind::WouldBlock => "408 Request Timeout",
+        _ => "400 Bad Request",
+    })?;
+    String::from_utf8(body).map_err(|_| "400 Bad Request")
+}
@@ -0,0 +1,246 @@
+// Which keychain the wallet's entries live in on Linux, and what Settings →
+// Keychain diagnostics finds about it.
+//
+// Linux has no single keychain, which is behind most "the keychain doesn't work
+// on my distro" reports. Secret Service is the default: whatever owns
+// org.freedesktop.secrets (GNOME Keyring, KeePassXC, or KWallet 5.97+ through
+// its Secret Service interface) keeps entries in its default collection.
+// Choosing KWallet puts them in KWallet's own "kdewallet" collection instead.
+// The kernel keyring is what older builds used: it needs no daemon but is
+// cleared at logout. The encrypted file (file_keyring.rs) is for desktops with
+// none of these. The choice is saved in keyring_backend.json and applies after
+// a restart, since entries already loaded came from the old store; nothing is
+// copied between backends. Other platforms have one native keychain.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+pub const BACKEND_FILE: &str = "keyring_backend.json";
+/// Collection KWallet serves over the Secret Service interface
+pub const KWALLET_COLLECTION: &str = "kdewallet";
+#[cfg(target_os = "linux")]
+const SECRET_SERVICE: &str = "org.freedesktop.secrets";
+#[cfg(target_os = "linux")]
+const KWALLET_DAEMONS: [&str; 2] = ["org.kde.kwalletd6", "org.kde.kwalletd5"];
+#[cfg(target_os = "linux")]
+const GNOME_KEYRING: &str = "org.gnome.keyring";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyringBackend {
+    #[default]
+    SecretService,
+    KWallet,
+    KernelKeyring,
+    EncryptedFile,
+}
+
+impl KeyringBackend {
+    pub const ALL: [KeyringBackend; 4] =
+        [KeyringBackend::SecretService, KeyringBackend::KWallet, KeyringBackend::KernelKeyring, KeyringBackend::EncryptedFile];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            KeyringBackend::SecretService => "Secret Service",
+            KeyringBackend::KWallet => "KWallet",
+            KeyringBackend::KernelKeyring => "Kernel keyring",
+            KeyringBackend::EncryptedFile => "Encrypted file",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            KeyringBackend::SecretService => "the default collection of GNOME Keyring, KeePassXC or any Secret Service provider",
+            KeyringBackend::KWallet => "KWallet's kdewallet collection, through its Secret Service interface",
+            KeyringBackend::KernelKeyring => "needs no daemon, but everything in it is cleared when you log out",
+            KeyringBackend::EncryptedFile => "a passphrase-encrypted file in the config directory, unlocked once per session",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|backend| *backend == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    /// Secret Service collection the entries go in; None for the default one
+    pub fn collection(self) -> Option<&'static str> {
+        match self {
+            KeyringBackend::KWallet => Some(KWALLET_COLLECTION),
+            _ => None,
+        }
+    }
+}
+
+/// The native keychain on platforms without a choice.
+pub fn native_keychain() -> Option<&'static str> {
+    if cfg!(target_os = "macos") {
+        Some("macOS Keychain")
+    } else if cfg!(target_os = "windows") {
+        Some("Windows Credential Manager")
+    } else {
+        None
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct BackendConfig {
+    pub backend: KeyringBackend,
+}
+
+impl BackendConfig {
+    pub fn load(path: Option<PathBuf>) -> Self {
+        path.and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    /// The saved choice, or on the first launch with this setting the kernel keyring
+    /// when an older build left the wallet there (so it isn't lost), otherwise the default.
+    pub fn load_or_detect(path: Option<PathBuf>, service: &str, username: &str) -> Self {
+        if path.as_ref().is_some_and(|path| path.exists()) {
+            return Self::load(path);
+        }
+        let backend = if in_kernel_keyring(service, username) { KeyringBackend::KernelKeyring } else { KeyringBackend::default() };
+        let config = Self { backend };
+        if let Err(e) = config.save(path) {
+            bevy::log::warn!("Keychain backend choice not saved: {}", e);
+        }
+        config
+    }
+
+    pub fn save(&self, path: Option<PathBuf>) -> std::io::Result<()> {
+        let Some(path) = path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self).unwrap_or_default())
+    }
+}
+
+/// What the session bus says about the keychains on it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Probe {
+    /// Why the session bus couldn't be reached
+    pub bus_error: Option<String>,
+    /// Something owns org.freedesktop.secrets
+    pub secret_service: bool,
+    pub gnome_keyring: bool,
+    pub kwallet: bool,
+    /// Whether the default collection is locked; None when there isn't one
+    pub default_locked: Option<bool>,
+    /// Whether KWallet's collection is locked; None when there isn't one
+    pub kwallet_locked: Option<bool>,
+}
+
+impl Probe {
+    /// Why `backend` won't work here, if the probe can tell.
+    pub fn problem(&self, backend: KeyringBackend) -> Option<String> {
+        let locked = |collection: &str| format!("the {} collection is locked; unlocking it when asked is enough", collection);
+        match backend {
+            KeyringBackend::SecretService | KeyringBackend::KWallet if self.bus_error.is_some() => {
+                Some(format!("no D-Bus session bus ({})", self.bus_error.as_deref().unwrap_or_default()))
+            }
+            KeyringBackend::SecretService if !self.secret_service => Some("nothing provides the Secret Service on this desktop".to_string()),
+            KeyringBackend::SecretService if self.default_locked == Some(true) => Some(locked("default")),
+            KeyringBackend::KWallet if !self.kwallet => Some("KWallet isn't running".to_string()),
+            KeyringBackend::KWallet if self.kwallet_locked.is_none() => {
+                Some("KWallet's Secret Service interface is off (System Settings → KWallet)".to_string())
+            }
+            KeyringBackend::KWallet if self.kwallet_locked == Some(true) => Some(locked(KWALLET_COLLECTION)),
+            KeyringBackend::KernelKeyring => Some("entries are cleared when you log out".to_string()),
+            _ => None,
+        }
+    }
+
+    /// The backend to suggest: Secret Service when anything provides it (KWallet's
+    /// interface included), otherwise the encrypted file.
+    pub fn recommended(&self) -> KeyringBackend {
+        if self.bus_error.is_none() && self.secret_service {
+            KeyringBackend::SecretService
+        } else {
+            KeyringBackend::EncryptedFile
+        }
+    }
+
+    /// Who provides the Secret Service, as far as the bus names tell.
+    pub fn provider(&self) -> &'static str {
+        match (self.secret_service, self.gnome_keyring, self.kwallet) {
+            (false, _, _) => "none",
+            (true, true, _) => "GNOME Keyring",
+            (true, false, true) => "KWallet",
+            (true, false, false) => "another provider (KeePassXC or similar)",
+        }
+    }
+
+    pub fn lines(&self) -> Vec<String> {
+        if let Some(error) = &self.bus_error {
+            return vec![format!("D-Bus session bus: ❌ {}", error)];
+        }
+        let collection = |locked: Option<bool>| match locked {
+            None => "not found",
+            Some(true) => "locked",
+            Some(false) => "unlocked",
+        };
+        vec![
+            format!("Secret Service provider: {}", self.provider()),
+            format!("Default collection: {}", collection(self.default_locked)),
+            format!("KWallet: {} - {} collection {}", if self.kwallet { "running" } else { "not running" }, KWALLET_COLLECTION, collection(self.kwallet_locked)),
+        ]
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn in_kernel_keyring(service: &str, username: &str) -> bool {
+    use keyring::credential::CredentialApi;
+    keyring::keyutils::KeyutilsCredential::new_with_target(None, service, username)
+        .and_then(|credential| credential.get_password())
+        .is_ok()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn in_kernel_keyring(_service: &str, _username: &str) -> bool {
+    false
+}
+
+/// Asks the session bus who provides the Secret Service and whether its collections
+/// are locked. Blocking (up to a couple of seconds per call); run it off the main thread.
+#[cfg(target_os = "linux")]
+pub fn probe() -> Probe {
+    use dbus::blocking::stdintf::org_freedesktop_dbus::Properties;
+    use dbus::blocking::Connection;
+    use std::time::Duration;
+
+    let connection = match Connection::new_session() {
+        Ok(connection) => connection,
+        Err(e) => return Probe { bus_error: Some(e.to_string()), ..Probe::default() },
+    };
+    let timeout = Duration::from_secs(2);
+    let bus = connection.with_proxy("org.freedesktop.DBus", "/org/freedesktop/DBus", timeout);
+    let has_owner = |name: &str| {
+        bus.method_call("org.freedesktop.DBus", "NameHasOwner", (name,)).map(|(owned,): (bool,)| owned).unwrap_or(false)
+    };
+    let secret_service = has_owner(SECRET_SERVICE);
+    // Reading the property doesn't prompt; unlocking is left to the first real access
+    let locked = |path: String| -> Option<bool> {
+        if !secret_service {
+            return None;
+        }
+        connection.with_proxy(SECRET_SERVICE, path, timeout).get("org.freedesktop.Secret.Collection", "Locked").ok()
+    };
+    Probe {
+        bus_error: None,
+        secret_service,
+        gnome_keyring: has_owner(GNOME_KEYRING),
+        kwallet: KWALLET_DAEMONS.iter().any(|name| has_owner(name)),
+        default_locked: locked("/org/freedesktop/secrets/aliases/default".to_string()),
+        kwallet_locked: locked(format!("/org/freedesktop/secrets/collection/{}", KWALLET_COLLECTION)),
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn probe() -> Probe {
+    Probe::default()
+}
@@ -0,0 +1,172 @@
+// Keeps the wallet usable when the OS keychain stops answering mid-session (the
+// login keyring was locked, the Secret Service restarted over dbus, ...).
+//
+// Every keychain entry the app uses is a `GuardedStore` sharing one
+// `KeyringHealth`. An access error marks the keychain unavailable and the banner
+// asks the user to unlock it and retry. Until it answers again they can switch
+// to memory-only mode: writes are then held in memory instead of failing, reads
+// see them, and the next successful retry writes them to the keychain. Held
+// entries don't survive quitting, which the banner says for as long as it lasts.
+
+use crate::file_keyring::FileKey;
+use crate::keyring_backend::KeyringBackend;
+use crate::{KeychainError, SecretStore};
+use bevy::prelude::Resource;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyringStatus {
+    Available,
+    /// The last keychain call failed, for this reason
+    Unavailable(String),
+    /// Writes are held in memory; `held` entries aren't in the keychain yet
+    MemoryOnly { held: usize },
+}
+
+// No Debug: `held` has wallet entries in it
+#[derive(Default)]
+struct Health {
+    /// Why the last keychain call failed; None once one succeeds
+    failure: Option<String>,
+    memory_only: bool,
+    /// Entries written while in memory-only mode, by keychain username; None is a deletion
+    held: BTreeMap<String, Option<String>>,
+}
+
+/// Keychain availability, shared by every guarded entry and the banner, along with
+/// the backend entries are opened in (keyring_backend.rs).
+#[derive(Resource, Clone, Default)]
+pub struct KeyringHealth {
+    state: Arc<Mutex<Health>>,
+    backend: KeyringBackend,
+    /// Only used by the encrypted-file backend
+    file_key: FileKey,
+}
+
+impl KeyringHealth {
+    pub fn with_backend(backend: KeyringBackend) -> Self {
+        Self { backend, ..Self::default() }
+    }
+
+    pub fn backend(&self) -> KeyringBackend {
+        self.backend
+    }
+
+    pub fn file_key(&self) -> &FileKey {
+        &self.file_key
+    }
+
+    pub fn status(&self) -> KeyringStatus {
+        let health = self.state.lock().unwrap();
+        match (&health.failure, health.memory_only) {
+            (_, true) => KeyringStatus::MemoryOnly { held: health.held.len() },
+            (Some(reason), false) => KeyringStatus::Unavailable(reason.clone()),
+            (None, false) => KeyringStatus::Available,
+        }
+    }
+
+    /// Holds writes in memory from now until a retry reaches the keychain.
+    pub fn use_memory_only(&self) {
+        self.state.lock().unwrap().memory_only = true;
+    }
+
+    fn memory_only(&self) -> bool {
+        self.state.lock().unwrap().memory_only
+    }
+
+    // Records how a keychain call went; only access errors mean it's unavailable
+    fn observe<T>(&self, result: Result<T, KeychainError>) -> Result<T, KeychainError> {
+        let mut health = self.state.lock().unwrap();
+        match &result {
+            Ok(_) | Err(KeychainError::NotFound) => health.failure = None,
+            Err(KeychainError::Access(reason)) => health.failure = Some(reason.clone()),
+            Err(_) => {}
+        }
+        result
+    }
+
+    fn held(&self, name: &str) -> Option<Option<String>> {
+        self.state.lock().unwrap().held.get(name).cloned()
+    }
+
+    fn hold(&self, name: &str, secret: Option<String>) {
+        self.state.lock().unwrap().held.insert(name.to_string(), secret);
+    }
+
+    /// Writes the held entries through `open`, which gives the keychain entry for a
+    /// username, or reads `probe` when nothing is held. Memory-only mode ends once
+    /// everything is written; an entry that fails stays held.
+    pub fn retry<S: SecretStore>(&self, probe: &str, open: impl Fn(&str) -> S) -> Result<(), KeychainError> {
+        let held: Vec<(String, Option<String>)> = self.state.lock().unwrap().held.clone().into_iter().collect();
+        if held.is_empty() {
+            // A missing entry is still an answer
+            match self.observe(open(probe).get()) {
+                Ok(_) | Err(KeychainError::NotFound) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        for (name, secret) in held {
+            let store = open(&name);
+            let written = match &secret {
+                Some(secret) => store.set(secret),
+                None => store.delete().or_else(|e| match e {
+                    KeychainError::NotFound => Ok(()),
+                    e => Err(e),
+                }),
+            };
+            self.observe(written)?;
+            let mut health = self.state.lock().unwrap();
+            // Unless it was written again meanwhile
+            if health.held.get(&name) == Some(&secret) {
+                health.held.remove(&name);
+            }
+        }
+        let mut health = self.state.lock().unwrap();
+        if health.held.is_empty() {
+            health.memory_only = false;
+        }
+        Ok(())
+    }
+}
+
+/// A keychain entry that reports failures to `KeyringHealth` and, in memory-only
+/// mode, holds writes instead of sending them.
+pub struct GuardedStore {
+    /// The entry's keychain username, which `KeyringHealth::retry` opens it by
+    name: String,
+    inner: Box<dyn SecretStore>,
+    health: KeyringHealth,
+}
+
+impl GuardedStore {
+    pub fn new(name: impl Into<String>, inner: impl SecretStore + 'static, health: &KeyringHealth) -> Self {
+        Self { name: name.into(), inner: Box::new(inner), health: health.clone() }
+    }
+}
+
+impl SecretStore for GuardedStore {
+    fn get(&self) -> Result<String, KeychainError> {
+        match self.health.held(&self.name) {
+            Some(held) => held.ok_or(KeychainError::NotFound),
+            None => self.health.observe(self.inner.get()),
+        }
+    }
+
+    fn set(&self, secret: &str) -> Result<(), KeychainError> {
+        // Not tried at all: a keychain that isn't answering can take a dbus timeout to say so
+        if self.health.memory_only() {
+            self.health.hold(&self.name, Some(secret.to_string()));
+            return Ok(());
+        }
+        self.health.observe(self.inner.set(secret))
+    }
+
+    fn delete(&self) -> Result<(), KeychainError> {
+        if self.health.memory_only() {
+            self.health.hold(&self.name, None);
+            return Ok(());
+        }
+        self.health.observe(self.inner.delete())
+    }
+}
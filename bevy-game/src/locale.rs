@@ -0,0 +1,159 @@
+// Number and date formatting for the locale the user reads in.
+//
+// Amounts on screen are built by amounts.rs as "1,234.5" and dates as UTC
+// timestamps; `Locale` swaps in the decimal and grouping separators and the
+// date order of the locale picked in Settings, or the system's (`LC_ALL`,
+// `LC_NUMERIC`, `LANG`) when that's left on System. Times stay in UTC, since
+// that's what the chain records.
+//
+// Only what's displayed is localized. CSV/JSON exports, DTOs, clipboard
+// addresses and amounts typed into inputs keep the "1234.5" form.
+//
+// The locale is process-wide, like the config directory: set once at startup
+// and again when Settings changes it. Until then it's `Locale::STANDARD`, the
+// format the wallet always used.
+
+use serde::{Deserialize, Serialize};
+use std::sync::RwLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The locale choice Settings cycles through.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LocaleSetting {
+    /// Whatever the system's locale variables say
+    #[default]
+    System,
+    /// 1,234.5 and 2025-01-31 14:05:00 UTC
+    Standard,
+    /// 1,234.5 and 01/31/2025
+    EnglishUs,
+    /// 1,234.5 and 31/01/2025
+    EnglishUk,
+    /// 1.234,5 and 31.01.2025
+    German,
+    /// 1 234,5 and 31/01/2025
+    French,
+}
+
+impl LocaleSetting {
+    pub const ALL: [LocaleSetting; 6] = [Self::System, Self::Standard, Self::EnglishUs, Self::EnglishUk, Self::German, Self::French];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::System => "System",
+            Self::Standard => "Standard (ISO dates)",
+            Self::EnglishUs => "English (US)",
+            Self::EnglishUk => "English (UK)",
+            Self::German => "German",
+            Self::French => "French",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|setting| *setting == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    /// The locale this stands for; `System` goes by `system_tag` (see `system_locale_tag`).
+    pub fn resolve(self, system_tag: Option<&str>) -> Locale {
+        match self {
+            Self::System => system_tag.map_or(Locale::STANDARD, Locale::from_tag),
+            Self::Standard => Locale::STANDARD,
+            Self::EnglishUs => Locale { decimal: '.', group: ',', dates: DateOrder::MonthDayYear },
+            Self::EnglishUk => Locale { decimal: '.', group: ',', dates: DateOrder::DayMonthYear('/') },
+            Self::German => Locale { decimal: ',', group: '.', dates: DateOrder::DayMonthYear('.') },
+            Self::French => Locale { decimal: ',', group: ' ', dates: DateOrder::DayMonthYear('/') },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateOrder {
+    /// 2025-01-31
+    Iso,
+    /// 01/31/2025
+    MonthDayYear,
+    /// 31/01/2025 or 31.01.2025
+    DayMonthYear(char),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Locale {
+    pub decimal: char,
+    pub group: char,
+    pub dates: DateOrder,
+}
+
+static CURRENT: RwLock<Locale> = RwLock::new(Locale::STANDARD);
+
+impl Locale {
+    pub const STANDARD: Locale = Locale { decimal: '.', group: ',', dates: DateOrder::Iso };
+
+    /// A POSIX locale name ("de_DE.UTF-8") or language tag ("en-GB"); unknown languages get `STANDARD`.
+    pub fn from_tag(tag: &str) -> Locale {
+        let tag = tag.split(['.', '@']).next().unwrap_or_default().replace('-', "_");
+        let (language, region) = tag.split_once('_').unwrap_or((&tag, ""));
+        let region = region.to_ascii_uppercase();
+        let setting = match (language.to_ascii_lowercase().as_str(), region.as_str()) {
+            ("en", "US" | "PH") => LocaleSetting::EnglishUs,
+            ("en", "") => LocaleSetting::Standard,
+            ("en", _) => LocaleSetting::EnglishUk,
+            ("de" | "nl" | "da" | "id" | "tr" | "it" | "es" | "pt" | "el" | "ro" | "sl" | "hr" | "sr", _) => LocaleSetting::German,
+            ("fr" | "ru" | "pl" | "cs" | "sk" | "sv" | "fi" | "nb" | "nn" | "no" | "uk" | "hu" | "bg" | "lt" | "lv" | "et", _) => LocaleSetting::French,
+            _ => LocaleSetting::Standard,
+        };
+        // Swiss German groups with apostrophes
+        match (setting, region.as_str()) {
+            (LocaleSetting::German, "CH") => Locale { decimal: '.', group: '\'', dates: DateOrder::DayMonthYear('.') },
+            _ => setting.resolve(None),
+        }
+    }
+
+    /// "1,234.5" (as amounts.rs builds it) with this locale's separators.
+    pub fn number(&self, standard: &str) -> String {
+        standard
+            .chars()
+            .map(|c| match c {
+                ',' => self.group,
+                '.' => self.decimal,
+                other => other,
+            })
+            .collect()
+    }
+
+    /// A date and UTC time of day, in this locale's order.
+    pub fn date_time(&self, time: SystemTime) -> String {
+        let secs = time.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+        let seconds_of_day = secs % 86_400;
+        let clock = format!("{:02}:{:02}:{:02}", seconds_of_day / 3_600, seconds_of_day % 3_600 / 60, seconds_of_day % 60);
+        let iso = crate::backup::format_unix_date(secs);
+        let mut parts = iso.splitn(3, '-');
+        let (year, month, day) = (parts.next().unwrap_or_default(), parts.next().unwrap_or_default(), parts.next().unwrap_or_default());
+        match self.dates {
+            DateOrder::Iso => format!("{}T{}Z", iso, clock),
+            DateOrder::MonthDayYear => format!("{}/{}/{} {} UTC", month, day, year, clock),
+            DateOrder::DayMonthYear(separator) => format!("{}{}{}{}{} {} UTC", day, separator, month, separator, year, clock),
+        }
+    }
+}
+
+/// The system's locale name from the environment, skipping the "C"/"POSIX" placeholders.
+pub fn system_locale_tag() -> Option<String> {
+    ["LC_ALL", "LC_NUMERIC", "LANG"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.is_empty() && !matches!(value.split('.').next(), Some("C" | "POSIX")))
+}
+
+pub fn current() -> Locale {
+    *CURRENT.read().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+pub fn set(locale: Locale) {
+    *CURRENT.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = locale;
+}
+
+/// A timestamp for display, in the current locale.
+pub fn format_time(time: SystemTime) -> String {
+    current().date_time(time)
+}
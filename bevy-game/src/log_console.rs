@@ -0,0 +1,183 @@
+// Recent log output for the in-app log console.
+//
+// A tracing layer installed through `LogPlugin::custom_layer` copies every event
+// that passes the log filter into a ring buffer, so the console (backtick) can
+// show it without the app having been started from a terminal. Users copy the
+// filtered lines from there into bug reports.
+
+use bevy::log::tracing_subscriber::layer::{Context, Layer};
+use bevy::log::BoxedLayer;
+use bevy::prelude::{App, Resource};
+use bevy::utils::tracing::field::{Field, Visit};
+use bevy::utils::tracing::{Event, Level, Subscriber};
+use std::collections::VecDeque;
+use std::fmt::{self, Write as _};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+pub const LOG_CAPACITY: usize = 500;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogLine {
+    pub time: SystemTime,
+    pub level: Level,
+    pub target: String,
+    pub message: String,
+}
+
+impl fmt::Display for LogLine {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let secs = self.time.duration_since(SystemTime::UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+        write!(
+            f,
+            "{:02}:{:02}:{:02} {:>5} {}: {}",
+            secs / 3600 % 24,
+            secs / 60 % 60,
+            secs % 60,
+            self.level,
+            self.target,
+            self.message
+        )
+    }
+}
+
+/// Shared by the tracing layer (any thread) and the console UI.
+#[derive(Resource, Clone, Default)]
+pub struct LogBuffer {
+    lines: Arc<Mutex<VecDeque<LogLine>>>,
+    // Bumped on every new line so the console knows when to redraw
+    version: Arc<AtomicU64>,
+}
+
+impl LogBuffer {
+    pub fn push(&self, line: LogLine) {
+        if let Ok(mut lines) = self.lines.lock() {
+            if lines.len() == LOG_CAPACITY {
+                lines.pop_front();
+            }
+            lines.push_back(line);
+        }
+        self.version.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Oldest first.
+    pub fn snapshot(&self) -> Vec<LogLine> {
+        self.lines.lock().map(|lines| lines.iter().cloned().collect()).unwrap_or_default()
+    }
+
+    pub fn version(&self) -> u64 {
+        self.version.load(Ordering::Relaxed)
+    }
+}
+
+/// Copies events into a `LogBuffer`.
+pub struct CaptureLayer(pub LogBuffer);
+
+impl<S: Subscriber> Layer<S> for CaptureLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+        let metadata = event.metadata();
+        self.0.push(LogLine {
+            time: SystemTime::now(),
+            level: *metadata.level(),
+            target: metadata.target().to_string(),
+            message: visitor.message,
+        });
+    }
+}
+
+// The `message` field, followed by any other fields as key=value
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+}
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            let fields = std::mem::take(&mut self.message);
+            let _ = write!(self.message, "{:?}{}", value, fields);
+        } else {
+            let _ = write!(self.message, " {}={:?}", field.name(), value);
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.insert_str(0, value);
+        } else {
+            let _ = write!(self.message, " {}={}", field.name(), value);
+        }
+    }
+}
+
+/// For `LogPlugin::custom_layer`: makes the buffer a resource and captures into it.
+pub fn capture_layer(app: &mut App) -> Option<BoxedLayer> {
+    let buffer = LogBuffer::default();
+    app.insert_resource(buffer.clone());
+    Some(Box::new(CaptureLayer(buffer)))
+}
+
+/// What the console shows: lines at `min_level` or more severe, from `target` (a prefix) if set.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogFilter {
+    pub min_level: Level,
+    pub target: Option<String>,
+}
+
+impl Default for LogFilter {
+    fn default() -> Self {
+        Self { min_level: Level::INFO, target: None }
+    }
+}
+
+impl LogFilter {
+    pub fn matches(&self, line: &LogLine) -> bool {
+        // tracing orders levels by verbosity: ERROR < WARN < INFO
+        line.level <= self.min_level && self.target.as_ref().is_none_or(|target| line.target.starts_with(target.as_str()))
+    }
+}
+
+/// Level filters the console cycles through, most verbose first.
+pub const LEVEL_OPTIONS: [Level; 5] = [Level::TRACE, Level::DEBUG, Level::INFO, Level::WARN, Level::ERROR];
+
+/// Crate names seen in `lines` (the part of the target before "::"), sorted, for the target filter.
+pub fn crate_targets(lines: &[LogLine]) -> Vec<String> {
+    let mut targets: Vec<String> = lines.iter().map(|line| line.target.split("::").next().unwrap_or_default().to_string()).collect();
+    targets.sort();
+    targets.dedup();
+    targets
+}
+
+/// Lines as plain text, one per line, for the clipboard.
+pub fn format_lines<'a>(lines: impl IntoIterator<Item = &'a LogLine>) -> String {
+    lines.into_iter().map(|line| format!("{}\n", line)).collect()
+}
+
+/// Puts `text` on the system clipboard using the platform's clipboard command.
+pub fn copy_to_clipboard(text: &str) -> Result<(), String> {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    let candidates: &[(&str, &[&str])] = if cfg!(target_os = "macos") {
+        &[("pbcopy", &[])]
+    } else if cfg!(windows) {
+        &[("clip", &[])]
+    } else {
+        &[("wl-copy", &[]), ("xclip", &["-selection", "clipboard"]), ("xsel", &["--clipboard", "--input"])]
+    };
+
+    for (program, args) in candidates {
+        let Ok(mut child) = Command::new(program).args(*args).stdin(Stdio::piped()).spawn() else {
+            continue;
+        };
+        let written = child.stdin.take().map(|mut stdin| stdin.write_all(text.as_bytes()));
+        if matches!(written, Some(Ok(()))) && child.wait().is_ok_and(|status| status.success()) {
+            return Ok(());
+        }
+    }
+    let names: Vec<&str> = candidates.iter().map(|(program, _)| *program).collect();
+    Err(format!("No clipboard tool worked (tried {})", names.join(", ")))
+}
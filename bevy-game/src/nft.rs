@@ -0,0 +1,117 @@
+// NFT instances behind a class balance, for the Balance screen's drill-down.
+//
+// GalaChain only makes non-fungible classes with 0 decimals, so those get an
+// Instances button. FetchBalances lists the instance ids the owner holds
+// (`instanceIds`), FetchTokenInstances returns each one, and the class's
+// `metadataAddress` says where its metadata lives. An address with `{id}` in
+// it is filled in per instance (the ERC-1155 convention); one ending in `/`
+// gets the instance appended; anything else is the same for every instance.
+//
+// Metadata is the usual NFT JSON: name, description, image and attributes,
+// either as `[{"trait_type", "value"}]` or as a plain object. `ipfs://` links
+// go through a public gateway. The image is loaded like a token icon, so only
+// PNGs are drawn.
+
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use serde_json::Value;
+
+/// Instances asked for in one FetchTokenInstances call; an owner with more sees the first ones
+pub const MAX_INSTANCES: usize = 100;
+/// Where `ipfs://` links are fetched from
+pub const IPFS_GATEWAY: &str = "https://ipfs.io/ipfs/";
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct NftInstance {
+    #[serde(deserialize_with = "instance_id")]
+    pub instance: String,
+    #[serde(with = "crate::dto::bignumber")]
+    pub quantity: Decimal,
+    #[serde(default)]
+    pub owner: Option<String>,
+}
+
+/// The instances of one class an owner holds, and where the class keeps its metadata.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InstanceList {
+    pub instances: Vec<NftInstance>,
+    pub metadata_address: Option<String>,
+    /// Ids past `MAX_INSTANCES`, not fetched
+    pub more: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InstanceMetadata {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    /// Resolved to http(s)
+    pub image: Option<String>,
+    /// Trait and value, in the order the metadata lists them
+    pub attributes: Vec<(String, String)>,
+}
+
+// Instance ids are BigNumbers: strings from the chain, numbers from hand-written fixtures
+fn instance_id<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    match Value::deserialize(deserializer)? {
+        Value::String(text) => Ok(text),
+        Value::Number(number) => Ok(number.to_string()),
+        other => Err(serde::de::Error::custom(format!("expected an instance id, found {}", other))),
+    }
+}
+
+/// Where `instance`'s metadata is, given the class's `metadataAddress`.
+pub fn metadata_url(address: &str, instance: &str) -> Option<String> {
+    let address = address.trim();
+    if address.is_empty() {
+        return None;
+    }
+    let url = if address.contains("{id}") {
+        address.replace("{id}", instance)
+    } else if address.ends_with('/') {
+        format!("{}{}", address, instance)
+    } else {
+        address.to_string()
+    };
+    Some(resolve_uri(&url))
+}
+
+/// `ipfs://` links through `IPFS_GATEWAY`; anything else as written.
+pub fn resolve_uri(uri: &str) -> String {
+    match uri.strip_prefix("ipfs://") {
+        Some(path) => format!("{}{}", IPFS_GATEWAY, path.trim_start_matches("ipfs/")),
+        None => uri.to_string(),
+    }
+}
+
+pub fn parse_metadata(json: &str) -> Result<InstanceMetadata, String> {
+    let value: Value = serde_json::from_str(json).map_err(|e| format!("Metadata isn't JSON: {}", e))?;
+    let Some(fields) = value.as_object() else {
+        return Err("Metadata isn't a JSON object".to_string());
+    };
+    let text = |name: &str| fields.get(name).and_then(Value::as_str).map(str::trim).filter(|text| !text.is_empty()).map(str::to_string);
+    let attributes = match fields.get("attributes").or_else(|| fields.get("properties")) {
+        Some(Value::Array(traits)) => traits
+            .iter()
+            .filter_map(|item| {
+                let value = item.get("value").map(display_value)?;
+                let name = item.get("trait_type").and_then(Value::as_str).unwrap_or("Trait");
+                Some((name.to_string(), value))
+            })
+            .collect(),
+        Some(Value::Object(traits)) => traits.iter().map(|(name, value)| (name.clone(), display_value(value))).collect(),
+        _ => Vec::new(),
+    };
+    Ok(InstanceMetadata {
+        name: text("name"),
+        description: text("description"),
+        image: text("image").or_else(|| text("image_url")).map(|image| resolve_uri(&image)),
+        attributes,
+    })
+}
+
+fn display_value(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
@@ -0,0 +1,205 @@
+// Passphrase strength, estimated the way zxcvbn does it.
+//
+// A passphrase is worth as many guesses as an attacker working through the
+// usual patterns would need before reaching it, not its length times its
+// alphabet: "Password1234" is twelve characters and falls at once. It's split
+// left to right into the cheapest parts it can find - one of the most common
+// passwords (also with l33t digits), a run of the same character, an
+// alphabetical, numeric or keyboard-row sequence, a word-like run of letters -
+// and anything left over is guessed a character at a time. The parts' guesses
+// multiply, so their bits add up.
+//
+// There's no English dictionary here, only the common-password list, so a word
+// is priced by its letters at a rate nearer English text than random letters.
+// New passphrases below `MIN_BITS` are refused wherever one is set, and the
+// meter under each passphrase field shows where the typed one stands.
+
+/// Weakest estimate a new passphrase is accepted with
+pub const MIN_BITS: f64 = 50.0;
+
+// Bits a letter of a word-like run costs; English text is well under the 4.7 of a random letter
+const WORD_LETTER_BITS: f64 = 2.5;
+// Letters in a row before they're priced as a word
+const WORD_MIN_LETTERS: usize = 4;
+
+// Most common first; a match costs about log2 of its rank
+const COMMON: [&str; 48] = [
+    "password", "qwerty", "letmein", "welcome", "admin", "iloveyou", "monkey", "dragon", "football", "baseball", "sunshine", "princess",
+    "master", "shadow", "trustno", "superman", "batman", "michael", "hello", "freedom", "whatever", "starwars", "login", "secret",
+    "passphrase", "passwort", "bitcoin", "ethereum", "crypto", "wallet", "galachain", "gala", "mnemonic", "money", "summer", "winter",
+    "love", "charlie", "jordan", "hunter", "ranger", "soccer", "killer", "pepper", "ginger", "cookie", "flower", "access",
+];
+
+const KEYBOARD_ROWS: [&str; 4] = ["1234567890", "qwertyuiop", "asdfghjkl", "zxcvbnm"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Strength {
+    Weak,
+    Fair,
+    Strong,
+    VeryStrong,
+}
+
+impl Strength {
+    fn of(bits: f64) -> Self {
+        if bits < MIN_BITS {
+            Strength::Weak
+        } else if bits < 65.0 {
+            Strength::Fair
+        } else if bits < 80.0 {
+            Strength::Strong
+        } else {
+            Strength::VeryStrong
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Strength::Weak => "Weak",
+            Strength::Fair => "Fair",
+            Strength::Strong => "Strong",
+            Strength::VeryStrong => "Very strong",
+        }
+    }
+
+    /// Filled segments of the four-segment meter
+    pub fn segments(self) -> usize {
+        self as usize + 1
+    }
+}
+
+/// Why a passphrase scored what it did, zxcvbn's "feedback".
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Weakness {
+    CommonPassword,
+    Repeated,
+    Sequence,
+}
+
+impl Weakness {
+    pub fn hint(self) -> &'static str {
+        match self {
+            Weakness::CommonPassword => "contains one of the most common passwords",
+            Weakness::Repeated => "repeats a character",
+            Weakness::Sequence => "contains a sequence like abc, 123 or qwerty",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Estimate {
+    pub bits: f64,
+    pub strength: Strength,
+    /// The first guessable pattern found, if any
+    pub weakness: Option<Weakness>,
+}
+
+impl Estimate {
+    pub fn acceptable(&self) -> bool {
+        self.bits >= MIN_BITS
+    }
+
+    /// "Strength: ■■□□ Fair (~58 bits)", with the pattern that weakened it
+    pub fn meter(&self) -> String {
+        let bar: String = (0..4).map(|segment| if segment < self.strength.segments() { '■' } else { '□' }).collect();
+        let mut line = format!("Strength: {} {} (~{:.0} bits)", bar, self.strength.label(), self.bits);
+        if let Some(weakness) = self.weakness.filter(|_| self.strength < Strength::Strong) {
+            line.push_str(&format!(" - {}", weakness.hint()));
+        }
+        line
+    }
+}
+
+pub fn estimate(passphrase: &str) -> Estimate {
+    let chars: Vec<char> = passphrase.chars().collect();
+    let mut bits = 0.0;
+    let mut weakness = None;
+    let mut index = 0;
+    while index < chars.len() {
+        let (len, cost, found) = common_password(&chars[index..])
+            .or_else(|| repeat(&chars[index..]))
+            .or_else(|| sequence(&chars[index..]))
+            .or_else(|| word(&chars[index..]))
+            .unwrap_or_else(|| (1, pool_bits(chars[index]), None));
+        bits += cost;
+        weakness = weakness.or(found);
+        index += len;
+    }
+    Estimate { bits, strength: Strength::of(bits), weakness }
+}
+
+// Bits to guess one character of its kind at random
+fn pool_bits(c: char) -> f64 {
+    let pool: f64 = match c {
+        'a'..='z' | 'A'..='Z' => 26.0,
+        '0'..='9' => 10.0,
+        ' '..='~' => 33.0,
+        _ => 100.0,
+    };
+    pool.log2()
+}
+
+// What a l33t spelling stands for
+fn unleet(c: char) -> char {
+    match c.to_ascii_lowercase() {
+        '0' => 'o',
+        '1' | '!' => 'i',
+        '3' => 'e',
+        '4' | '@' => 'a',
+        '5' | '$' => 's',
+        '7' => 't',
+        c => c,
+    }
+}
+
+// The longest common password the text starts with; capitals and l33t add a bit each
+fn common_password(chars: &[char]) -> Option<(usize, f64, Option<Weakness>)> {
+    let (rank, word) = COMMON
+        .iter()
+        .enumerate()
+        .filter(|(_, word)| word.len() <= chars.len() && word.chars().zip(chars).all(|(w, c)| unleet(*c) == w))
+        .max_by_key(|(_, word)| word.len())?;
+    let part = &chars[..word.len()];
+    let mut bits = (rank as f64 + 2.0).log2();
+    if part.iter().any(|c| c.is_ascii_uppercase()) {
+        bits += 1.0;
+    }
+    if part.iter().any(|c| !c.is_ascii_alphabetic()) {
+        bits += 1.0;
+    }
+    Some((word.len(), bits, Some(Weakness::CommonPassword)))
+}
+
+// Three or more of the same character: the character, then how many
+fn repeat(chars: &[char]) -> Option<(usize, f64, Option<Weakness>)> {
+    let len = chars.iter().take_while(|c| **c == chars[0]).count();
+    (len >= 3).then(|| (len, pool_bits(chars[0]) + (len as f64).log2(), Some(Weakness::Repeated)))
+}
+
+// abc, 987 or a keyboard row, three or more long, either way: where it starts, its direction and length
+fn sequence(chars: &[char]) -> Option<(usize, f64, Option<Weakness>)> {
+    let step = |a: char, b: char| {
+        let (a, b) = (a.to_ascii_lowercase(), b.to_ascii_lowercase());
+        if a.is_ascii_alphanumeric() && b.is_ascii_alphanumeric() && (b as i32 - a as i32).abs() == 1 {
+            return Some(b as i32 - a as i32);
+        }
+        KEYBOARD_ROWS.iter().find_map(|row| {
+            let (i, j) = (row.find(a)? as i32, row.find(b)? as i32);
+            ((j - i).abs() == 1).then_some(j - i + 10)
+        })
+    };
+    let direction = step(*chars.first()?, *chars.get(1)?)?;
+    let len = 2 + chars.windows(2).skip(1).take_while(|pair| step(pair[0], pair[1]) == Some(direction)).count();
+    (len >= 3).then(|| (len, pool_bits(chars[0]) + 1.0 + (len as f64).log2(), Some(Weakness::Sequence)))
+}
+
+// A run of letters long enough to be a word
+fn word(chars: &[char]) -> Option<(usize, f64, Option<Weakness>)> {
+    let len = chars.iter().take_while(|c| c.is_alphabetic()).count();
+    if len < WORD_MIN_LETTERS {
+        return None;
+    }
+    // Only a capital first letter is a word's usual variation
+    let capitalized = chars[0].is_uppercase() && chars[1..len].iter().all(|c| c.is_lowercase());
+    Some((len, len as f64 * WORD_LETTER_BITS + if capitalized { 1.0 } else { 0.0 }, None))
+}
@@ -0,0 +1,75 @@
+// Brute-force protection for PIN entry.
+//
+// A PIN has few enough combinations to be guessed, so wrong ones are counted
+// in a row - across prompts and restarts, in their own file - until a right
+// one clears the count. The first few are free; after that each wrong PIN
+// makes the next wait twice as long, and enough of them refuse the PIN
+// altogether until the fallback passphrase (at Unlock) or the seed phrase is
+// entered again. Unlocking from the keychain alone doesn't count.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Wrong PINs in a row before the waits start
+pub const FREE_FAILURES: u32 = 3;
+/// Wrong PINs in a row before the PIN is refused until the owner proves who they are
+pub const LOCKOUT_FAILURES: u32 = 10;
+// The wait after the last free try; it doubles with each wrong PIN after that
+const FIRST_DELAY: Duration = Duration::from_secs(30);
+const MAX_DELAY: Duration = Duration::from_secs(60 * 60);
+
+/// Wrong PINs since the last right one.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct PinLockout {
+    pub failures: u32,
+    /// Unix seconds of the last wrong PIN
+    pub last_failure: u64,
+}
+
+impl PinLockout {
+    pub fn load(path: Option<PathBuf>) -> Self {
+        path.and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: Option<PathBuf>) -> std::io::Result<()> {
+        let Some(path) = path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self).unwrap_or_default())
+    }
+
+    pub fn fail(&mut self, now: SystemTime) {
+        self.failures += 1;
+        self.last_failure = now.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    }
+
+    /// How long after the last wrong PIN the next one is checked: nothing for
+    /// the first `FREE_FAILURES`, then 30 s doubling up to an hour.
+    pub fn delay(&self) -> Duration {
+        match self.failures.checked_sub(FREE_FAILURES) {
+            Some(over) => FIRST_DELAY.saturating_mul(2u32.saturating_pow(over)).min(MAX_DELAY),
+            None => Duration::ZERO,
+        }
+    }
+
+    pub fn locked_out(&self) -> bool {
+        self.failures >= LOCKOUT_FAILURES
+    }
+
+    /// Why a PIN typed at `now` isn't checked, if it isn't.
+    pub fn refusal(&self, now: SystemTime) -> Option<String> {
+        if self.locked_out() {
+            return Some(format!("{} wrong PINs in a row - unlock with the fallback passphrase or import the seed phrase again before the PIN is accepted", self.failures));
+        }
+        let since = now.duration_since(UNIX_EPOCH + Duration::from_secs(self.last_failure)).unwrap_or_default();
+        let wait = self.delay().checked_sub(since).filter(|wait| !wait.is_zero())?;
+        Some(format!("Too many wrong PINs - try again in {} s", wait.as_secs_f64().ceil()))
+    }
+}
@@ -0,0 +1,52 @@
+// Portable mode (`--portable`): everything the wallet keeps lives next to the
+// executable, so it can run from a USB stick and leave nothing on the machine.
+//
+// The config files, caches and the keychain entries all go under `DATA_DIR`
+// beside the executable (beside the .app bundle on macOS). The OS keychain is
+// never used: the entries are kept in the passphrase-encrypted keyring file
+// (file_keyring.rs) instead, which is weaker - anyone holding the stick can
+// copy the file and try passphrases offline - so the wallet says so wherever
+// it shows where the wallet is stored. The mode is process-wide, like the
+// config directory it sets.
+
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+/// Folder next to the executable that holds the portable wallet's files
+pub const DATA_DIR: &str = "galachain-wallet-data";
+
+/// The always-visible badge
+pub const BADGE: &str = "⚠ Portable mode";
+
+/// Why portable mode is less safe, for the badge's details and Settings → Keychain.
+pub const WARNING: &str = "The wallet is kept in a passphrase-encrypted file beside the app instead of the OS keychain. \
+Anyone with the drive can copy that file and try to guess the passphrase, so use a long one and keep the drive safe.";
+
+static DATA: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// `DATA_DIR` for the executable at `exe`. Inside a macOS app bundle
+/// (Wallet.app/Contents/MacOS/wallet) it goes next to the bundle.
+pub fn data_dir_for(exe: &Path) -> Option<PathBuf> {
+    let mut dir = exe.parent()?;
+    if dir.ends_with("Contents/MacOS") {
+        let bundle = dir.parent().and_then(Path::parent).filter(|bundle| bundle.extension().is_some_and(|extension| extension == "app"));
+        if let Some(bundle) = bundle {
+            dir = bundle.parent()?;
+        }
+    }
+    Some(dir.join(DATA_DIR))
+}
+
+/// Turns portable mode on for this run, keeping everything in `dir`.
+pub fn enable(dir: PathBuf) {
+    *DATA.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(dir);
+}
+
+/// Where the portable wallet's files are; None when not in portable mode.
+pub fn data_dir() -> Option<PathBuf> {
+    DATA.read().unwrap_or_else(|poisoned| poisoned.into_inner()).clone()
+}
+
+pub fn is_enabled() -> bool {
+    data_dir().is_some()
+}
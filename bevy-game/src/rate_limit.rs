@@ -0,0 +1,88 @@
+// Rate limits a gateway announces with 429 Too Many Requests.
+//
+// A 429 pauses its request class - every request to that endpoint URL - for as
+// long as the Retry-After header says, or `DEFAULT_PAUSE` without one. Requests
+// of that class wait the pause out before they're sent, other endpoints carry
+// on, and the banner counts down. Only a pause longer than `MAX_WAIT` ends in
+// `GalaChainError::RateLimited`; anything shorter is just a slower request.
+// Like the circuit breaker, the pauses are shared by every clone of the client.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Used when a 429 comes without a usable Retry-After
+pub const DEFAULT_PAUSE: Duration = Duration::from_secs(5);
+/// Longer pauses fail the request rather than leave a screen waiting
+pub const MAX_WAIT: Duration = Duration::from_secs(60);
+
+#[derive(Clone, Default)]
+pub struct RateLimits {
+    paused_until: Arc<Mutex<HashMap<String, Instant>>>,
+}
+
+impl RateLimits {
+    pub fn pause(&self, url: &str, duration: Duration) {
+        let until = Instant::now() + duration;
+        let mut paused = self.paused_until.lock().unwrap();
+        let entry = paused.entry(url.to_string()).or_insert(until);
+        *entry = (*entry).max(until);
+    }
+
+    /// How long requests to `url` still have to wait, if they're paused
+    pub fn remaining(&self, url: &str) -> Option<Duration> {
+        let until = *self.paused_until.lock().unwrap().get(url)?;
+        until.checked_duration_since(Instant::now()).filter(|remaining| !remaining.is_zero())
+    }
+
+    /// Every class still paused with its remaining time, longest first; expired pauses are dropped.
+    pub fn paused(&self) -> Vec<(String, Duration)> {
+        let now = Instant::now();
+        let mut paused = self.paused_until.lock().unwrap();
+        paused.retain(|_, until| *until > now);
+        let mut classes: Vec<(String, Duration)> = paused.iter().map(|(url, until)| (url.clone(), *until - now)).collect();
+        classes.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        classes
+    }
+}
+
+/// The method name at the end of an endpoint URL, for the banner.
+pub fn class_label(url: &str) -> &str {
+    url.trim_end_matches('/').rsplit('/').next().unwrap_or(url)
+}
+
+/// Retry-After as delta-seconds or an IMF-fixdate ("Sun, 06 Nov 1994 08:49:37 GMT").
+/// A date already past means no wait.
+pub fn parse_retry_after(value: &str, now: SystemTime) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let at = UNIX_EPOCH + Duration::from_secs(parse_http_date(value)?);
+    Some(at.duration_since(now).unwrap_or(Duration::ZERO))
+}
+
+// Seconds since the epoch for an IMF-fixdate
+fn parse_http_date(value: &str) -> Option<u64> {
+    const MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+    let parts: Vec<&str> = value.split_whitespace().collect();
+    let [_weekday, day, month, year, time, "GMT"] = parts.as_slice() else {
+        return None;
+    };
+    let day: u64 = day.parse().ok().filter(|day| (1..=31).contains(day))?;
+    let month = MONTHS.iter().position(|name| name == month)? as u64 + 1;
+    let year: u64 = year.parse().ok().filter(|year| *year >= 1970)?;
+    let clock: Vec<u64> = time.split(':').map(|part| part.parse().ok()).collect::<Option<_>>()?;
+    let [hours, minutes, seconds] = clock.as_slice() else {
+        return None;
+    };
+
+    // Days from the civil date (Howard Hinnant's algorithm), with March as the first month
+    let (y, m) = if month <= 2 { (year - 1, month + 9) } else { (year, month - 3) };
+    let era = y / 400;
+    let year_of_era = y % 400;
+    let day_of_year = (153 * m + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+    Some(days * 86_400 + hours * 3600 + minutes * 60 + seconds)
+}
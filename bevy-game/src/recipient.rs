@@ -0,0 +1,83 @@
+// Transfer recipients.
+//
+// A recipient can be typed as a 0x Ethereum address or as a GalaChain alias
+// (`eth|<address>` or `client|<id>`). address.rs parses it, and the alias the
+// GalaChain APIs expect is what a transfer is sent to.
+//
+// Tokens sent to an alias nobody has registered can't be used, so Transfer
+// refuses unregistered recipients. An `eth|` recipient can be registered by the
+// sender instead, given their public key: `sponsor_public_key` checks it is the
+// key behind the address before it goes to the identity API.
+
+use crate::address::{parse_address, AddressError};
+use secp256k1::PublicKey;
+use sha3::{Digest, Keccak256};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recipient {
+    /// Alias sent to the API, e.g. "eth|5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed".
+    pub gala_address: String,
+    /// Mixed-case hex that doesn't match its EIP-55 checksum, which usually means a typo.
+    pub checksum_mismatch: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecipientError {
+    Empty,
+    InvalidHex,
+    InvalidClientId,
+    UnknownFormat,
+    /// Only `eth|` recipients can be registered from a public key
+    NotSponsorable,
+    InvalidPublicKey,
+    KeyMismatch,
+}
+
+impl fmt::Display for RecipientError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RecipientError::Empty => write!(f, "Enter a recipient address"),
+            RecipientError::InvalidHex => write!(f, "Ethereum addresses are 40 hex characters after 0x or eth|"),
+            RecipientError::InvalidClientId => write!(f, "client| aliases need an id without spaces or |"),
+            RecipientError::UnknownFormat => write!(f, "Use a 0x address, eth|<address> or client|<id>"),
+            RecipientError::NotSponsorable => write!(f, "client| aliases can't be registered from a public key"),
+            RecipientError::InvalidPublicKey => write!(f, "Public keys are 33 or 65 bytes of hex"),
+            RecipientError::KeyMismatch => write!(f, "That public key belongs to a different address"),
+        }
+    }
+}
+
+impl From<AddressError> for RecipientError {
+    fn from(e: AddressError) -> Self {
+        match e {
+            AddressError::Empty => RecipientError::Empty,
+            AddressError::InvalidHex => RecipientError::InvalidHex,
+            AddressError::InvalidClientId => RecipientError::InvalidClientId,
+            AddressError::UnknownFormat => RecipientError::UnknownFormat,
+        }
+    }
+}
+
+pub fn parse_recipient(input: &str) -> Result<Recipient, RecipientError> {
+    let parsed = parse_address(input)?;
+    Ok(Recipient { gala_address: parsed.address.galachain(), checksum_mismatch: parsed.checksum_mismatch })
+}
+
+/// The recipient's public key as the identity API takes it (uncompressed hex),
+/// if `public_key` is the key behind `gala_address`. Compressed keys and a 0x
+/// prefix are accepted.
+pub fn sponsor_public_key(gala_address: &str, public_key: &str) -> Result<String, RecipientError> {
+    let Some(address) = gala_address.strip_prefix("eth|") else {
+        return Err(RecipientError::NotSponsorable);
+    };
+    let public_key = public_key.trim();
+    let bytes = hex::decode(public_key.strip_prefix("0x").unwrap_or(public_key)).map_err(|_| RecipientError::InvalidPublicKey)?;
+    let key = PublicKey::from_slice(&bytes).map_err(|_| RecipientError::InvalidPublicKey)?;
+    let uncompressed = key.serialize_uncompressed();
+    let derived = hex::encode(&Keccak256::digest(&uncompressed[1..])[12..]);
+    if !derived.eq_ignore_ascii_case(address) {
+        return Err(RecipientError::KeyMismatch);
+    }
+    Ok(hex::encode(uncompressed))
+}
@@ -0,0 +1,74 @@
+// Reactive updates: the window only runs frames when something happens.
+//
+// A wallet spends most of its life idle, so instead of redrawing at the display
+// rate the app waits for input, a wake-up, or the timeout below. Work that
+// finishes on another thread (API tasks, the connectivity and update checks,
+// local signing calls, tray menu clicks) calls `wake_event_loop` so its result
+// shows up right away. The focused timeout is short enough that the seconds in
+// countdowns (holds, the circuit banner, the seed auto-hide) keep ticking.
+// Gamepads aren't window events, so frames run continuously while one is
+// connected.
+
+use crate::{AppState, WalletState};
+use bevy::prelude::*;
+use bevy::window::RequestRedraw;
+use bevy::winit::{EventLoopProxy, EventLoopProxyWrapper, UpdateMode, WakeUp, WinitSettings};
+use std::sync::Mutex;
+use std::time::Duration;
+
+const FOCUSED_WAIT: Duration = Duration::from_secs(1);
+const UNFOCUSED_WAIT: Duration = Duration::from_secs(60);
+
+// None in headless runs and tests, where there's no event loop to wake
+static EVENT_LOOP: Mutex<Option<EventLoopProxy<WakeUp>>> = Mutex::new(None);
+
+pub struct RedrawPlugin;
+
+impl Plugin for RedrawPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(reactive_settings())
+            .add_systems(Startup, install_waker)
+            .add_systems(Update, gamepad_update_mode_system)
+            .add_systems(Last, pending_state_redraw_system);
+    }
+}
+
+fn reactive_settings() -> WinitSettings {
+    WinitSettings {
+        focused_mode: UpdateMode::reactive(FOCUSED_WAIT),
+        unfocused_mode: UpdateMode::reactive_low_power(UNFOCUSED_WAIT),
+    }
+}
+
+/// Runs a frame soon, from any thread. Does nothing without a window.
+pub fn wake_event_loop() {
+    if let Some(proxy) = EVENT_LOOP.lock().ok().and_then(|proxy| proxy.clone()) {
+        let _ = proxy.send_event(WakeUp);
+    }
+}
+
+fn install_waker(proxy: Res<EventLoopProxyWrapper<WakeUp>>) {
+    if let Ok(mut installed) = EVENT_LOOP.lock() {
+        *installed = Some((*proxy).clone());
+    }
+}
+
+fn gamepad_update_mode_system(gamepads: Query<(), With<Gamepad>>, mut settings: ResMut<WinitSettings>) {
+    let wanted = if gamepads.is_empty() { reactive_settings() } else { WinitSettings::game() };
+    if settings.focused_mode != wanted.focused_mode || settings.unfocused_mode != wanted.unfocused_mode {
+        info!("Update mode: {:?}", wanted.focused_mode);
+        *settings = wanted;
+    }
+}
+
+// A screen change set this frame is applied at the start of the next one, which
+// would otherwise wait for the next input
+fn pending_state_redraw_system(
+    next_app_state: Res<NextState<AppState>>,
+    next_wallet_state: Res<NextState<WalletState>>,
+    mut redraw: EventWriter<RequestRedraw>,
+) {
+    if matches!(*next_app_state, NextState::Pending(_)) || matches!(*next_wallet_state, NextState::Pending(_)) {
+        redraw.send(RequestRedraw);
+    }
+}
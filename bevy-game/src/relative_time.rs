@@ -0,0 +1,51 @@
+// Timestamps shown relative to now ("Last updated: 12 seconds ago").
+//
+// A screen is built once, so a label computed then goes stale while it's
+// open. Text carrying a `RelativeTime` is rewritten by main.rs once a second
+// instead; the reactive update mode (redraw.rs) already runs a frame at least
+// that often while the window is focused. Past an hour "N minutes ago" says
+// less than the time itself, so the label switches to the timestamp, in the
+// date format of the current locale.
+
+use crate::locale::format_time;
+use bevy::prelude::Component;
+use std::time::{Duration, SystemTime};
+
+/// Older than this, the absolute timestamp is shown
+pub const ABSOLUTE_AFTER: Duration = Duration::from_secs(3_600);
+
+/// Text that reads `prefix`, the time relative to now, then `suffix`.
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct RelativeTime {
+    pub at: SystemTime,
+    pub prefix: String,
+    pub suffix: String,
+}
+
+impl RelativeTime {
+    pub fn new(prefix: impl Into<String>, at: SystemTime) -> Self {
+        Self { at, prefix: prefix.into(), suffix: String::new() }
+    }
+
+    pub fn with_suffix(mut self, suffix: impl Into<String>) -> Self {
+        self.suffix = suffix.into();
+        self
+    }
+
+    pub fn label(&self, now: SystemTime) -> String {
+        format!("{}{}{}", self.prefix, format_relative(self.at, now), self.suffix)
+    }
+}
+
+/// "just now", "N seconds ago", "N minutes ago", then the UTC timestamp past an hour.
+pub fn format_relative(at: SystemTime, now: SystemTime) -> String {
+    // A time ahead of the clock (it was changed, or the cache came from elsewhere) is "just now"
+    let age = now.duration_since(at).unwrap_or_default();
+    let plural = |count: u64, unit: &str| format!("{} {}{} ago", count, unit, if count == 1 { "" } else { "s" });
+    match age.as_secs() {
+        0 => "just now".to_string(),
+        secs if secs < 60 => plural(secs, "second"),
+        _ if age < ABSOLUTE_AFTER => plural(age.as_secs() / 60, "minute"),
+        _ => format_time(at),
+    }
+}
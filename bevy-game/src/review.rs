@@ -0,0 +1,85 @@
+// The Transfer screen's review step.
+//
+// Review Transfer doesn't sign anything: it freezes what the form describes into
+// a `TransferReview` - the recipient as typed and the alias it resolves to, the
+// token, amount, fee, estimated total and memo - and the screen shows its
+// `lines` with Confirm & Sign and Edit. Editing the form afterwards drops the
+// review (`matches` no longer holds), so what gets signed is always what was
+// last reviewed.
+
+use crate::amounts::{format_amount, format_units, NETWORK_FEE};
+use crate::recipient::Recipient;
+use crate::tokens::TokenInfo;
+use rust_decimal::Decimal;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransferReview {
+    /// The recipient field as typed
+    pub typed: String,
+    /// GalaChain alias the transfer is sent to
+    pub to: String,
+    /// From the recipient lookup; None when it couldn't be checked
+    pub registered: Option<bool>,
+    pub checksum_mismatch: bool,
+    pub token: TokenInfo,
+    /// The NFT instance being sent; None for fungible tokens
+    pub instance: Option<String>,
+    pub quantity: Decimal,
+    /// Kept in the local history only, never sent
+    pub memo: String,
+}
+
+impl TransferReview {
+    pub fn new(typed: &str, recipient: &Recipient, registered: Option<bool>, token: &TokenInfo, instance: Option<&str>, quantity: Decimal, memo: &str) -> Self {
+        Self {
+            typed: typed.trim().to_string(),
+            to: recipient.gala_address.clone(),
+            registered,
+            checksum_mismatch: recipient.checksum_mismatch,
+            token: token.clone(),
+            instance: instance.map(str::to_string),
+            quantity,
+            memo: memo.trim().to_string(),
+        }
+    }
+
+    /// Still describes the form: same recipient, token, instance, amount and memo.
+    pub fn matches(&self, to: &str, token: &TokenInfo, instance: Option<&str>, quantity: Decimal, memo: &str) -> bool {
+        self.to == to && self.token == *token && self.instance.as_deref() == instance && self.quantity == quantity && self.memo == memo.trim()
+    }
+
+    /// One line per item of the review, in the order it's read.
+    pub fn lines(&self) -> Vec<String> {
+        let token = &self.token;
+        let units = |quantity: Decimal| format!("{} {}", format_units(quantity, token.decimals), token.symbol);
+        let mut lines = vec![format!("Recipient: {}", self.typed)];
+        let status = match self.registered {
+            Some(true) => "registered",
+            Some(false) => "not registered",
+            None => "registration couldn't be checked",
+        };
+        lines.push(format!("Sends to: {} ({})", self.to, status));
+        if self.checksum_mismatch {
+            lines.push("⚠️ The address's capitalization doesn't match its checksum - check it for typos".to_string());
+        }
+        lines.push(match &self.instance {
+            Some(instance) => format!("Token: {} #{}", token.symbol, instance),
+            None => format!("Token: {}", token.symbol),
+        });
+        lines.push(format!("Amount: {}", units(self.quantity)));
+        // GALA pays its own fee; any other token's comes from the GALA balance
+        if token.fee().is_zero() {
+            lines.push(format!("Network fee: {} GALA, paid from your GALA balance", format_amount(NETWORK_FEE)));
+            lines.push(format!("Estimated total: {} + {} GALA", units(self.quantity), format_amount(NETWORK_FEE)));
+        } else {
+            lines.push(format!("Network fee: {} GALA", format_amount(token.fee())));
+            lines.push(format!("Estimated total: {}", units(self.quantity + token.fee())));
+        }
+        lines.push(if self.memo.is_empty() {
+            "Memo: none".to_string()
+        } else {
+            format!("Memo: {} (kept in your history, not sent)", self.memo)
+        });
+        lines
+    }
+}
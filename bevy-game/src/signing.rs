@@ -0,0 +1,193 @@
+// Signing for DTOs submitted to GalaChain.
+//
+// Every submitted DTO carries a `uniqueKey` the chain uses to reject replays, and
+// a signature over its canonical JSON (see dto.rs). Keys are "<prefix>-<ULID>", so
+// they sort by creation time and two keys made in the same millisecond still differ.
+// Endpoints differ in the signature encoding they accept, so the format is chosen
+// per endpoint in `ApiSettings`; every format carries a low-s signature.
+
+use crate::dto::{payload_to_sign, DtoError};
+use secp256k1::ecdsa::RecoverableSignature;
+use secp256k1::{Message, PublicKey, Secp256k1, SecretKey};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha3::{Digest, Keccak256};
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Crockford base32, as used by ULIDs (no I, L, O or U).
+const ULID_ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// 26-character ULID: 48 bits of milliseconds then 80 random bits (extra high bits are dropped).
+pub fn ulid(timestamp_ms: u64, random: u128) -> String {
+    let value = (u128::from(timestamp_ms & 0xFFFF_FFFF_FFFF) << 80) | (random & ((1 << 80) - 1));
+    (0..26)
+        .map(|i| ULID_ALPHABET[((value >> (125 - 5 * i)) & 0x1F) as usize] as char)
+        .collect()
+}
+
+pub fn new_unique_key(prefix: &str) -> String {
+    use rand::RngCore;
+    let now_ms = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0);
+    let mut random = [0u8; 16];
+    rand::rngs::OsRng.fill_bytes(&mut random);
+    format!("{}-{}", prefix, ulid(now_ms, u128::from_be_bytes(random)))
+}
+
+/// uniqueKey for a send form, made when its contents validate and kept while they
+/// stay the same, so pressing Send again submits the same key and the chain (or
+/// `UsedKeys`) refuses the repeat instead of sending twice.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FormKey {
+    form: String,
+    unique_key: Option<String>,
+}
+
+impl FormKey {
+    /// The key for `form`, anything that changes when the form's contents do; a new one
+    /// if they changed since the last call.
+    pub fn validated(&mut self, form: &str, key_prefix: &str) -> &str {
+        if self.form != form {
+            self.form = form.to_string();
+            self.unique_key = None;
+        }
+        self.unique_key.get_or_insert_with(|| new_unique_key(key_prefix))
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SignatureFormat {
+    /// ASN.1 DER, variable length (70-72 bytes)
+    Der,
+    /// r || s, 64 bytes
+    Compact,
+    /// r || s || v with v = 27 + recovery id, 65 bytes; what GalaChain expects by default
+    #[default]
+    Recoverable,
+}
+
+impl SignatureFormat {
+    pub fn label(self) -> &'static str {
+        match self {
+            SignatureFormat::Der => "DER",
+            SignatureFormat::Compact => "64-byte compact",
+            SignatureFormat::Recoverable => "65-byte r||s||v",
+        }
+    }
+}
+
+/// Signs the keccak256 of `payload` and returns the signature as hex bytes in `format`.
+pub fn sign_payload(payload: &str, key: &SecretKey, format: SignatureFormat) -> String {
+    let hash = Keccak256::digest(payload.as_bytes());
+    let message = Message::from_slice(&hash).expect("keccak256 digest is 32 bytes");
+    let recoverable = Secp256k1::new().sign_ecdsa_recoverable(&message, key);
+    hex::encode(encode_signature(&recoverable, format))
+}
+
+/// Signs `message` as Ethereum's personal_sign does (EIP-191), so any Ethereum
+/// library can recover the wallet's address from the 0x-prefixed 65-byte result.
+pub fn sign_personal_message(message: &str, key: &SecretKey) -> String {
+    let prefixed = format!("\x19Ethereum Signed Message:\n{}{}", message.len(), message);
+    format!("0x{}", sign_payload(&prefixed, key, SignatureFormat::Recoverable))
+}
+
+/// Checks a personal_sign signature over `message` against `key`. Takes the hex form
+/// `sign_personal_message` returns; the recovery byte is optional and ignored.
+pub fn verify_personal_message(message: &str, signature: &str, key: &PublicKey) -> bool {
+    let prefixed = format!("\x19Ethereum Signed Message:\n{}{}", message.len(), message);
+    let hash = Keccak256::digest(prefixed.as_bytes());
+    let message = Message::from_slice(&hash).expect("keccak256 digest is 32 bytes");
+    let signature = signature.trim();
+    let Ok(bytes) = hex::decode(signature.strip_prefix("0x").unwrap_or(signature)) else {
+        return false;
+    };
+    if bytes.len() != 64 && bytes.len() != 65 {
+        return false;
+    }
+    let Ok(mut signature) = secp256k1::ecdsa::Signature::from_compact(&bytes[..64]) else {
+        return false;
+    };
+    signature.normalize_s();
+    Secp256k1::verification_only().verify_ecdsa(&message, &signature, key).is_ok()
+}
+
+/// Encodes a signature with s in the lower half of the curve order, which the chain
+/// requires. Flipping s to -s also flips which of the two candidate keys v recovers.
+pub fn encode_signature(signature: &RecoverableSignature, format: SignatureFormat) -> Vec<u8> {
+    let (recovery_id, raw) = signature.serialize_compact();
+    let mut standard = signature.to_standard();
+    standard.normalize_s();
+    match format {
+        SignatureFormat::Der => standard.serialize_der().to_vec(),
+        SignatureFormat::Compact => standard.serialize_compact().to_vec(),
+        SignatureFormat::Recoverable => {
+            let compact = standard.serialize_compact();
+            let flipped = compact != raw;
+            let mut bytes = compact.to_vec();
+            bytes.push(27 + (recovery_id.to_i32() as u8 ^ u8::from(flipped)));
+            bytes
+        }
+    }
+}
+
+/// Reads a secp256k1 public key as hex (with or without 0x) or base64, compressed or not.
+/// GetPublicKey answers in base64 while the wallet derives hex, so keys are compared parsed.
+pub fn decode_public_key(text: &str) -> Option<PublicKey> {
+    use base64::Engine;
+    let text = text.trim();
+    let parse = |bytes: Vec<u8>| PublicKey::from_slice(&bytes).ok();
+    hex::decode(text.strip_prefix("0x").unwrap_or(text))
+        .ok()
+        .and_then(parse)
+        .or_else(|| base64::engine::general_purpose::STANDARD.decode(text).ok().and_then(parse))
+}
+
+/// Whether two encoded keys are the same key. Anything that doesn't parse never matches.
+pub fn same_public_key(a: &str, b: &str) -> bool {
+    matches!((decode_public_key(a), decode_public_key(b)), (Some(a), Some(b)) if a == b)
+}
+
+/// A DTO ready to submit, with its `uniqueKey` and `signature` filled in.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignedDto {
+    pub body: Value,
+    pub unique_key: String,
+    pub signature: String,
+}
+
+/// Fills in `uniqueKey` unless the DTO already has one, then signs the canonical payload.
+/// Any signature already on the DTO is replaced.
+pub fn sign_dto(dto: &impl Serialize, key: &SecretKey, key_prefix: &str, format: SignatureFormat) -> Result<SignedDto, DtoError> {
+    let mut body = serde_json::to_value(dto).map_err(|e| DtoError::Serialize(e.to_string()))?;
+    let fields = body.as_object_mut().ok_or(DtoError::NotAnObject)?;
+    let unique_key = match fields.get("uniqueKey").and_then(Value::as_str).filter(|key| !key.is_empty()) {
+        Some(existing) => existing.to_string(),
+        None => new_unique_key(key_prefix),
+    };
+    fields.insert("uniqueKey".to_string(), Value::String(unique_key.clone()));
+
+    let signature = sign_payload(&payload_to_sign(&body)?, key, format);
+    body["signature"] = Value::String(signature.clone());
+    Ok(SignedDto { body, unique_key, signature })
+}
+
+/// uniqueKeys this session has submitted, shared by every clone of the client.
+/// Retries resend the same body, but a second submission of the same key is refused.
+#[derive(Clone, Default, Debug)]
+pub struct UsedKeys(Arc<Mutex<HashSet<String>>>);
+
+impl UsedKeys {
+    /// Records the key; false if it was already used.
+    pub fn claim(&self, unique_key: &str) -> bool {
+        self.0.lock().unwrap().insert(unique_key.to_string())
+    }
+
+    pub fn contains(&self, unique_key: &str) -> bool {
+        self.0.lock().unwrap().contains(unique_key)
+    }
+}
@@ -0,0 +1,146 @@
+// Local spending limits for Transfer and Burn.
+//
+// Every transfer or burn the wallet signs is recorded with its token and time,
+// and the daily and weekly limits are checked against the last 24 hours and
+// 7 days of those records. The limits count each token separately, in its own
+// units. Nothing here is enforced by the chain: it stops mistakes and someone
+// at an unlocked session, not someone who has the seed phrase. The limits and
+// the records are saved together to `spending.json`.
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Limits Settings cycles through, in whole tokens; `None` is no limit.
+pub const LIMIT_OPTIONS: [Option<u32>; 6] = [None, Some(100), Some(1_000), Some(10_000), Some(100_000), Some(1_000_000)];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+    Day,
+    Week,
+}
+
+impl Period {
+    pub fn secs(self) -> u64 {
+        match self {
+            Period::Day => 24 * 60 * 60,
+            Period::Week => 7 * 24 * 60 * 60,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Period::Day => "daily",
+            Period::Week => "weekly",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct SpendingLimits {
+    #[serde(with = "rust_decimal::serde::str_option")]
+    pub daily: Option<Decimal>,
+    #[serde(with = "rust_decimal::serde::str_option")]
+    pub weekly: Option<Decimal>,
+    /// Going over a limit re-reads the wallet from the keychain first
+    pub unlock_to_override: bool,
+}
+
+impl Default for SpendingLimits {
+    fn default() -> Self {
+        Self { daily: None, weekly: None, unlock_to_override: true }
+    }
+}
+
+impl SpendingLimits {
+    pub fn limit(&self, period: Period) -> Option<Decimal> {
+        match period {
+            Period::Day => self.daily,
+            Period::Week => self.weekly,
+        }
+    }
+
+    pub fn any(&self) -> bool {
+        self.daily.is_some() || self.weekly.is_some()
+    }
+}
+
+/// The next entry of `LIMIT_OPTIONS` after `current`, wrapping; a hand-edited value goes back to no limit.
+pub fn next_limit(current: Option<Decimal>) -> Option<Decimal> {
+    let index = LIMIT_OPTIONS.iter().position(|option| option.map(Decimal::from) == current);
+    LIMIT_OPTIONS[index.map_or(0, |index| (index + 1) % LIMIT_OPTIONS.len())].map(Decimal::from)
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SpendEntry {
+    pub unix_time: u64,
+    /// `TokenInfo::class_key`
+    pub token: String,
+    #[serde(with = "rust_decimal::serde::str")]
+    pub quantity: Decimal,
+}
+
+/// A limit the next transfer or burn would go over.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LimitBreach {
+    pub period: Period,
+    pub limit: Decimal,
+    /// What was left before this one, never negative
+    pub remaining: Decimal,
+    pub over_by: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct SpendingLedger {
+    pub limits: SpendingLimits,
+    /// Oldest first; anything older than a week is dropped when a new one is recorded
+    pub entries: Vec<SpendEntry>,
+}
+
+impl SpendingLedger {
+    pub fn load(path: Option<PathBuf>) -> Self {
+        path.and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: Option<PathBuf>) -> std::io::Result<()> {
+        let Some(path) = path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self).unwrap_or_default())
+    }
+
+    pub fn record(&mut self, token: &str, quantity: Decimal, now: u64) {
+        self.entries.retain(|entry| now.saturating_sub(entry.unix_time) < Period::Week.secs());
+        self.entries.push(SpendEntry { unix_time: now, token: token.to_string(), quantity });
+    }
+
+    pub fn spent(&self, token: &str, period: Period, now: u64) -> Decimal {
+        self.entries
+            .iter()
+            .filter(|entry| entry.token == token && now.saturating_sub(entry.unix_time) < period.secs())
+            .map(|entry| entry.quantity)
+            .sum()
+    }
+
+    /// What's left under the period's limit, if it has one.
+    pub fn remaining(&self, token: &str, period: Period, now: u64) -> Option<Decimal> {
+        let limit = self.limits.limit(period)?;
+        Some((limit - self.spent(token, period, now)).max(Decimal::ZERO))
+    }
+
+    /// The limit `quantity` would go over, the daily one first.
+    pub fn breach(&self, token: &str, quantity: Decimal, now: u64) -> Option<LimitBreach> {
+        [Period::Day, Period::Week].into_iter().find_map(|period| {
+            let limit = self.limits.limit(period)?;
+            let remaining = self.remaining(token, period, now)?;
+            (quantity > remaining).then(|| LimitBreach { period, limit, remaining, over_by: quantity - remaining })
+        })
+    }
+}
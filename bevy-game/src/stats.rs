@@ -0,0 +1,179 @@
+// Local usage statistics for the Stats screen.
+//
+// Counts what the wallet has done - transfers sent, tokens burned, and for each
+// endpoint the requests made, how many failed and how long they took - so a
+// developer can see how their backend is holding up. Nothing is sent anywhere:
+// the numbers are saved to `stats.json` in the config directory and can be
+// reset from the Stats screen. Like the HTTP log, the counts are shared by
+// every clone of the client, so background tasks record into the same place.
+
+use crate::history::TxKind;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Days the Stats screen charts
+pub const CHART_DAYS: u64 = 14;
+const DAY_SECS: u64 = 24 * 60 * 60;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct EndpointStats {
+    pub requests: u64,
+    /// No response, or a response that wasn't 2xx
+    pub errors: u64,
+    pub total_latency_ms: u64,
+}
+
+impl EndpointStats {
+    pub fn error_rate(&self) -> f32 {
+        if self.requests == 0 {
+            return 0.0;
+        }
+        self.errors as f32 / self.requests as f32
+    }
+
+    pub fn average_latency(&self) -> Duration {
+        Duration::from_millis(self.total_latency_ms.checked_div(self.requests).unwrap_or(0))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct DayCounts {
+    pub transfers: u32,
+    pub burns: u32,
+}
+
+impl DayCounts {
+    pub fn total(&self) -> u32 {
+        self.transfers + self.burns
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct UsageData {
+    pub transfers_sent: u64,
+    pub burns: u64,
+    /// Total burned per token symbol
+    pub burned: BTreeMap<String, Decimal>,
+    /// Keyed by endpoint URL
+    pub endpoints: BTreeMap<String, EndpointStats>,
+    /// Keyed by UTC day number (days since the epoch)
+    pub days: BTreeMap<u64, DayCounts>,
+}
+
+impl UsageData {
+    pub fn load(path: Option<PathBuf>) -> Self {
+        path.and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: Option<PathBuf>) -> std::io::Result<()> {
+        let Some(path) = path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self).unwrap_or_default())
+    }
+
+    pub fn record_request(&mut self, url: &str, duration: Duration, succeeded: bool) {
+        let endpoint = self.endpoints.entry(url.to_string()).or_default();
+        endpoint.requests += 1;
+        endpoint.errors += u64::from(!succeeded);
+        endpoint.total_latency_ms += duration.as_millis() as u64;
+    }
+
+    pub fn record_operation(&mut self, kind: TxKind, symbol: &str, quantity: Decimal, unix_time: u64) {
+        let day = self.days.entry(unix_time / DAY_SECS).or_default();
+        match kind {
+            TxKind::Transfer => {
+                self.transfers_sent += 1;
+                day.transfers += 1;
+            }
+            TxKind::Burn => {
+                self.burns += 1;
+                day.burns += 1;
+                *self.burned.entry(symbol.to_string()).or_default() += quantity;
+            }
+        }
+    }
+
+    /// Requests and errors over every endpoint
+    pub fn totals(&self) -> EndpointStats {
+        self.endpoints.values().fold(EndpointStats::default(), |total, endpoint| EndpointStats {
+            requests: total.requests + endpoint.requests,
+            errors: total.errors + endpoint.errors,
+            total_latency_ms: total.total_latency_ms + endpoint.total_latency_ms,
+        })
+    }
+
+    /// The last `count` days up to the one holding `now`, oldest first, with days that saw nothing as zeros.
+    pub fn recent_days(&self, now: u64, count: u64) -> Vec<(u64, DayCounts)> {
+        let today = now / DAY_SECS;
+        (0..count)
+            .rev()
+            .filter_map(|back| today.checked_sub(back))
+            .map(|day| (day, self.days.get(&day).copied().unwrap_or_default()))
+            .collect()
+    }
+}
+
+/// Shared between the client and its clones, so tasks on other threads record into the same counts.
+#[derive(Clone, Default)]
+pub struct UsageStats {
+    data: Arc<Mutex<UsageData>>,
+    // Bumped on every change so the UI and the saver know when to look again
+    version: Arc<AtomicU64>,
+}
+
+impl UsageStats {
+    pub fn record_request(&self, url: &str, duration: Duration, succeeded: bool) {
+        self.update(|data| data.record_request(url, duration, succeeded));
+    }
+
+    pub fn record_operation(&self, kind: TxKind, symbol: &str, quantity: Decimal, unix_time: u64) {
+        self.update(|data| data.record_operation(kind, symbol, quantity, unix_time));
+    }
+
+    /// Replaces the counts, e.g. with the ones saved by the last run.
+    pub fn restore(&self, data: UsageData) {
+        self.update(|current| *current = data);
+    }
+
+    pub fn reset(&self) {
+        self.restore(UsageData::default());
+    }
+
+    pub fn snapshot(&self) -> UsageData {
+        self.data.lock().map(|data| data.clone()).unwrap_or_default()
+    }
+
+    pub fn version(&self) -> u64 {
+        self.version.load(Ordering::Relaxed)
+    }
+
+    fn update(&self, change: impl FnOnce(&mut UsageData)) {
+        if let Ok(mut data) = self.data.lock() {
+            change(&mut data);
+        }
+        self.version.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Average latency as "85 ms" or "1.2 s".
+pub fn format_latency(latency: Duration) -> String {
+    if latency < Duration::from_secs(1) {
+        format!("{} ms", latency.as_millis())
+    } else {
+        format!("{:.1} s", latency.as_secs_f32())
+    }
+}
@@ -0,0 +1,77 @@
+// Sweeping a wallet: one transfer of everything available to another address,
+// for moving out after a key rotation or a migration without leaving dust behind.
+//
+// The amount is the whole available balance, less the network fee when the token
+// is GALA itself (other tokens pay it from the GALA balance), cut to the token's
+// decimals. Locked tokens can't move until their holds expire, so they stay. The
+// Transfer screen shows `summary` and sends nothing until the sweep is confirmed.
+// A migration sweeps every token at once (`sweep_all`), so the GALA balance holds
+// back the fee for each of those transfers, not just its own.
+
+use crate::address;
+use crate::amounts::{format_units, max_amount, NETWORK_FEE};
+use crate::tokens::TokenInfo;
+use rust_decimal::Decimal;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SweepPlan {
+    /// GalaChain address everything goes to
+    pub to: String,
+    pub token: TokenInfo,
+    pub available: Decimal,
+    /// What the transfer sends
+    pub amount: Decimal,
+    /// Stays behind until its holds expire
+    pub locked: Decimal,
+}
+
+impl SweepPlan {
+    pub fn new(to: &str, token: &TokenInfo, available: Decimal, locked: Decimal) -> Result<Self, String> {
+        let amount = max_amount(available, token.fee(), token.decimals);
+        if amount.is_zero() {
+            return Err(if token.fee().is_zero() {
+                format!("Nothing to sweep - no available {}", token.symbol)
+            } else {
+                format!("Nothing to sweep - {} {} doesn't cover the network fee", format_units(available, token.decimals), token.symbol)
+            });
+        }
+        Ok(Self { to: to.to_string(), token: token.clone(), available, amount, locked })
+    }
+
+    /// Available balance the transfer can't take: sub-unit dust past the token's decimals.
+    pub fn left(&self) -> Decimal {
+        (self.available - self.amount - self.token.fee()).max(Decimal::ZERO)
+    }
+
+    /// Exactly what moves and what doesn't, one line each.
+    pub fn summary(&self) -> Vec<String> {
+        let token = &self.token;
+        let units = |quantity: Decimal| format!("{} {}", format_units(quantity, token.decimals), token.symbol);
+        let mut lines = vec![format!("Send {} to {}", units(self.amount), address::display(&self.to))];
+        if token.fee().is_zero() {
+            lines.push(format!("Network fee: {} GALA, paid from your GALA balance", NETWORK_FEE));
+        } else {
+            lines.push(format!("Network fee: {} GALA, taken from this balance", token.fee()));
+        }
+        lines.push(format!("Left available afterwards: {}", units(self.left())));
+        if self.locked > Decimal::ZERO {
+            lines.push(format!("Locked {} stays behind until its holds expire", units(self.locked)));
+        }
+        lines
+    }
+}
+
+/// What sweeping every one of `available` sends of each token, zero amounts left
+/// out. Each transfer's fee comes from the GALA balance, so GALA keeps back one
+/// for its own transfer and one for every other token's.
+pub fn sweep_all(available: &[(TokenInfo, Decimal)]) -> Vec<(TokenInfo, Decimal)> {
+    let others = available.iter().filter(|(token, amount)| token.fee().is_zero() && *amount > Decimal::ZERO).count();
+    available
+        .iter()
+        .map(|(token, amount)| {
+            let fee = token.fee() * Decimal::from(others + 1);
+            (token.clone(), max_amount(*amount, fee, token.decimals))
+        })
+        .filter(|(_, amount)| *amount > Decimal::ZERO)
+        .collect()
+}
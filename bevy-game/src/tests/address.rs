@@ -0,0 +1,59 @@
+//! Address parsing tests
+//!
+//! Covers the one parser behind every address field:
+//! - 0x, eth| and client| input in any prefix case
+//! - Both representations of an Ethereum address from any form
+//! - The alias for addresses the wallet already holds
+//! - How screens display an address, in full and truncated
+
+use crate::address::{self, parse_address, Address, AddressError};
+use crate::GalaChainClient;
+
+#[cfg(test)]
+mod address_tests {
+    use super::*;
+
+    const CHECKSUMMED: &str = "5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+
+    #[test]
+    fn test_prefixes_in_any_case() {
+        for input in [format!("0X{}", CHECKSUMMED), format!("ETH|{}", CHECKSUMMED), format!("Eth|0x{}", CHECKSUMMED.to_lowercase())] {
+            assert_eq!(parse_address(&input).unwrap().address, Address::Ethereum(CHECKSUMMED.to_string()), "{:?}", input);
+        }
+        assert_eq!(parse_address("CLIENT|server").unwrap().address, Address::Client("server".to_string()));
+        assert_eq!(parse_address(CHECKSUMMED), Err(AddressError::UnknownFormat));
+    }
+
+    #[test]
+    fn test_both_representations() {
+        let address = parse_address(&format!("eth|{}", CHECKSUMMED.to_lowercase())).unwrap().address;
+        assert_eq!(address.ethereum(), Some(format!("0x{}", CHECKSUMMED)));
+        assert_eq!(address.galachain(), format!("eth|{}", CHECKSUMMED));
+
+        let client = Address::Client("server".to_string());
+        assert_eq!(client.ethereum(), None);
+        assert_eq!(client.galachain(), "client|server");
+    }
+
+    #[test]
+    fn test_alias_accepts_every_form() {
+        let expected = format!("eth|{}", CHECKSUMMED);
+        assert_eq!(GalaChainClient::ethereum_to_galachain_address(&format!("0x{}", CHECKSUMMED.to_lowercase())), expected);
+        assert_eq!(GalaChainClient::ethereum_to_galachain_address(&expected), expected);
+        assert_eq!(GalaChainClient::ethereum_to_galachain_address("client|server"), "client|server");
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(address::display(&format!("0x{}", CHECKSUMMED.to_lowercase())), format!("0x{0} · eth|{0}", CHECKSUMMED));
+        assert_eq!(address::display("client|server"), "client|server");
+        assert_eq!(address::display("unknown"), "unknown");
+    }
+
+    #[test]
+    fn test_truncated() {
+        assert_eq!(address::truncated(&format!("eth|{}", CHECKSUMMED.to_lowercase())), "0x5aAe…eAed");
+        assert_eq!(address::truncated("client|server"), "client|server");
+        assert_eq!(address::truncated("client|a-much-longer-name"), "client…name");
+    }
+}
@@ -0,0 +1,120 @@
+//! Token quantity tests
+//!
+//! Covers exact decimal handling of GALA amounts:
+//! - Parsing user input and API quantity strings without float rounding
+//! - Display formatting with thousands separators
+//! - Transfer/Burn validation against the balance and the Max amount
+//! - A GALA balance below the network fee, for GALA and for other tokens
+//! - Tokens with other decimals and the chain's smallest-unit counts
+
+use crate::amounts::{
+    check_fee, format_amount, format_units, from_base_units, max_amount, parse_amount, to_base_units, validate_amount, AmountError, GALA_DECIMALS, NETWORK_FEE,
+};
+use crate::envelope::parse_response;
+use crate::TokenBalance;
+use rust_decimal::Decimal;
+
+#[cfg(test)]
+mod amount_tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_keeps_full_precision() {
+        assert_eq!(parse_amount("0.00000001"), Some(Decimal::new(1, 8)));
+        assert_eq!(parse_amount(" 12.5 "), Some(Decimal::new(125, 1)));
+        // Too large for f64 to hold exactly
+        let large = parse_amount("12345678901234567.12345678").unwrap();
+        assert_eq!(large.to_string(), "12345678901234567.12345678");
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_input() {
+        for input in ["", "   ", "-1", "+1", "abc", "1e5", "1.2.3"] {
+            assert_eq!(parse_amount(input), None, "{:?} should be rejected", input);
+        }
+    }
+
+    #[test]
+    fn test_format_groups_thousands() {
+        assert_eq!(format_amount(Decimal::ZERO), "0.00");
+        assert_eq!(format_amount(Decimal::new(1000, 0)), "1,000.00");
+        assert_eq!(format_amount(Decimal::new(123456712345678, 8)), "1,234,567.12345678");
+        assert_eq!(format_amount(Decimal::new(-9999950, 1)), "-999,995.00");
+        assert_eq!(format_amount(Decimal::new(100_000, 5)), "1.00");
+    }
+
+    #[test]
+    fn test_balance_response_quantities_are_exact() {
+        let json = r#"{"Status":1,"Data":[{"collection":"GALA","category":"Unit","type":"none","additionalKey":"none","instance":"0",
+            "quantity":"1000000000.00000001","lockedHolds":[{"quantity":"0.1"},{"quantity":"0.2"}]}]}"#;
+        let balances = parse_response::<Vec<TokenBalance>>(200, json).unwrap().unwrap();
+        let balance = &balances[0];
+        let locked: Decimal = balance.locked_holds.iter().map(|hold| hold.quantity).sum();
+
+        assert_eq!(locked, Decimal::new(3, 1));
+        assert_eq!((balance.quantity - locked).to_string(), "999999999.70000001");
+    }
+
+    #[test]
+    fn test_validate_amount_against_balance() {
+        let available = Some(Decimal::new(10, 0));
+        assert_eq!(validate_amount("9", available, NETWORK_FEE, GALA_DECIMALS), Ok(Decimal::new(9, 0)));
+        assert_eq!(
+            validate_amount("9.00000001", available, NETWORK_FEE, GALA_DECIMALS),
+            Err(AmountError::ExceedsBalance { max: Decimal::new(9, 0) })
+        );
+        // Unknown balance only checks the amount itself
+        assert_eq!(validate_amount("1000", None, NETWORK_FEE, GALA_DECIMALS), Ok(Decimal::new(1000, 0)));
+    }
+
+    #[test]
+    fn test_insufficient_gala_for_fee() {
+        let short = Some(Decimal::new(5, 1));
+        let error = AmountError::InsufficientFee { fee: NETWORK_FEE };
+        assert_eq!(validate_amount("0.1", short, NETWORK_FEE, GALA_DECIMALS), Err(error));
+        assert_eq!(error.to_string(), "Insufficient GALA for network fee (1.00 GALA needed)");
+        // Exactly the fee can't send anything, but it's the amount that's wrong
+        assert_eq!(validate_amount("0.1", Some(NETWORK_FEE), NETWORK_FEE, GALA_DECIMALS), Err(AmountError::ExceedsBalance { max: Decimal::ZERO }));
+
+        // Other tokens pay it from the GALA balance
+        assert_eq!(check_fee(short, NETWORK_FEE), Err(error));
+        assert_eq!(check_fee(Some(NETWORK_FEE), NETWORK_FEE), Ok(()));
+        assert_eq!(check_fee(None, NETWORK_FEE), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_amount_rejects_bad_input() {
+        assert_eq!(validate_amount("", None, NETWORK_FEE, GALA_DECIMALS), Err(AmountError::Invalid));
+        assert_eq!(validate_amount("0.000", None, NETWORK_FEE, GALA_DECIMALS), Err(AmountError::Zero));
+        assert_eq!(validate_amount("0.000000001", None, NETWORK_FEE, GALA_DECIMALS), Err(AmountError::TooManyDecimals { decimals: GALA_DECIMALS }));
+        // Trailing zeros past 8 places don't add precision
+        assert_eq!(validate_amount("1.0000000000", None, NETWORK_FEE, GALA_DECIMALS), Ok(Decimal::ONE));
+    }
+
+    #[test]
+    fn test_max_amount_leaves_the_fee() {
+        assert_eq!(max_amount(Decimal::new(1005, 1), NETWORK_FEE, GALA_DECIMALS), Decimal::new(995, 1));
+        assert_eq!(max_amount(Decimal::new(5, 1), NETWORK_FEE, GALA_DECIMALS), Decimal::ZERO);
+        assert_eq!(max_amount(Decimal::new(2_123456789, 9), NETWORK_FEE, GALA_DECIMALS), Decimal::new(1_12345678, 8));
+    }
+
+    #[test]
+    fn test_amounts_follow_token_decimals() {
+        assert_eq!(format_units(Decimal::new(5, 0), 0), "5");
+        assert_eq!(format_units(Decimal::new(12345, 1), 1), "1,234.5");
+        assert_eq!(format_units(Decimal::new(1_999, 3), 2), "1.99");
+        assert_eq!(validate_amount("2.5", None, Decimal::ZERO, 0), Err(AmountError::TooManyDecimals { decimals: 0 }));
+        assert_eq!(validate_amount("3", None, Decimal::ZERO, 0), Ok(Decimal::new(3, 0)));
+        assert_eq!(max_amount(Decimal::new(99, 1), Decimal::ZERO, 0), Decimal::new(9, 0));
+    }
+
+    #[test]
+    fn test_base_units_round_trip() {
+        assert_eq!(to_base_units(Decimal::new(15, 1), GALA_DECIMALS), Some(150_000_000));
+        assert_eq!(to_base_units(Decimal::new(1, 9), GALA_DECIMALS), None);
+        assert_eq!(to_base_units(Decimal::new(-1, 0), GALA_DECIMALS), None);
+        assert_eq!(to_base_units(Decimal::ONE, 18), Some(1_000_000_000_000_000_000));
+        assert_eq!(from_base_units(150_000_000, GALA_DECIMALS), Some(Decimal::new(15, 1)));
+        assert_eq!(from_base_units(1, 18).unwrap().to_string(), "0.000000000000000001");
+    }
+}
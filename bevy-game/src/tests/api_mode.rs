@@ -0,0 +1,65 @@
+//! API mode tests
+//!
+//! Covers switching between the local dev server and the public gateway:
+//! - Presets replace only the settings nobody edited
+//! - Gateway envelopes unwrapped into the contract response shape
+//! - Errors a gateway reports inside a 200
+
+use crate::api_mode::{ApiMode, GATEWAY_BASE_URL};
+use crate::ApiSettings;
+
+#[cfg(test)]
+mod api_mode_tests {
+    use super::*;
+
+    #[test]
+    fn test_switch_keeps_edited_settings() {
+        let mut settings = ApiSettings::default();
+        settings.identity_base_url = "http://identity.internal:4000".to_string();
+        ApiMode::switch(&mut settings, ApiMode::Gateway);
+        assert_eq!(settings.mode, ApiMode::Gateway);
+        assert_eq!(settings.operations_base_url, GATEWAY_BASE_URL);
+        assert_eq!(settings.identity_base_url, "http://identity.internal:4000");
+        assert_eq!(settings.channel_name, "asset");
+        assert_eq!(settings.contract_name, "token-contract");
+
+        // Back again lands on the defaults, apart from the edit
+        ApiMode::switch(&mut settings, ApiMode::LocalDev);
+        let defaults = ApiSettings::default();
+        assert_eq!(settings.operations_base_url, defaults.operations_base_url);
+        assert_eq!(settings.registration_endpoint, defaults.registration_endpoint);
+        assert_eq!(settings.channel_name, defaults.channel_name);
+        assert_eq!(settings.identity_contract_name, defaults.identity_contract_name);
+        assert_eq!(settings.identity_base_url, "http://identity.internal:4000");
+    }
+
+    #[test]
+    fn test_gateway_envelopes() {
+        let gateway = ApiMode::Gateway;
+        let contract = r#"{"Status":1,"Data":[]}"#.to_string();
+        assert_eq!(gateway.unwrap_response(contract.clone()), Ok(contract.clone()));
+        let unwrapped = gateway.unwrap_response(format!(r#"{{"data":{}}}"#, contract)).unwrap();
+        assert_eq!(serde_json::from_str::<serde_json::Value>(&unwrapped).unwrap(), serde_json::json!({ "Status": 1, "Data": [] }));
+
+        let wrapped = gateway.unwrap_response(r#"{"data":{"publicKey":"abc"}}"#.to_string()).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&wrapped).unwrap();
+        assert_eq!(value["Status"], 1);
+        assert_eq!(value["Data"]["publicKey"], "abc");
+
+        // Local dev bodies are never touched
+        let envelope = r#"{"data":[1]}"#.to_string();
+        assert_eq!(ApiMode::LocalDev.unwrap_response(envelope.clone()), Ok(envelope));
+    }
+
+    #[test]
+    fn test_gateway_errors() {
+        let gateway = ApiMode::Gateway;
+        assert_eq!(
+            gateway.unwrap_response(r#"{"error":{"message":"Insufficient balance","code":400}}"#.to_string()),
+            Err("Insufficient balance".to_string())
+        );
+        assert_eq!(gateway.unwrap_response(r#"{"error":"Unauthorized"}"#.to_string()), Err("Unauthorized".to_string()));
+        // Not JSON: left for the caller's parser to reject
+        assert_eq!(gateway.unwrap_response("Bad Gateway".to_string()), Ok("Bad Gateway".to_string()));
+    }
+}
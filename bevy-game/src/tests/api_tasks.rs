@@ -0,0 +1,123 @@
+//! Background API task tests
+//!
+//! Covers the task queue behind the balance and registration screens:
+//! - Finished tasks arrive as typed ApiResult events and their entity is removed
+//! - Cancelled tasks never deliver a result
+//! - InFlight follows tasks as they start and end, and picks what the busy overlay shows
+
+use crate::api_tasks::{cancel_api_tasks, format_elapsed, spawn_api_task, ApiResult, ApiTaskAppExt, ApiTaskHandle, InFlight, TaskKind};
+use crate::{ApiSettings, GalaChainClient};
+use bevy::ecs::system::RunSystemOnce;
+use bevy::prelude::*;
+use bevy::tasks::{IoTaskPool, TaskPool};
+use std::time::{Duration, Instant};
+
+#[cfg(test)]
+mod api_task_tests {
+    use super::*;
+
+    fn test_app() -> App {
+        IoTaskPool::get_or_init(TaskPool::new);
+        let mut app = App::new();
+        app.insert_resource(GalaChainClient::new(&ApiSettings::default()))
+            .add_api_task::<u32>();
+        app
+    }
+
+    fn spawn(app: &mut App, kind: TaskKind, value: u32) {
+        app.world_mut()
+            .run_system_once(move |mut commands: Commands, client: Res<GalaChainClient>| {
+                spawn_api_task(&mut commands, &client, kind, move |_| Ok(value));
+            })
+            .unwrap();
+    }
+
+    fn collect_results(app: &mut App) -> Vec<(TaskKind, u32)> {
+        let mut received = Vec::new();
+        for _ in 0..200 {
+            app.update();
+            let events = app.world().resource::<Events<ApiResult<u32>>>();
+            received.extend(events.iter_current_update_events().map(|event| (event.kind, *event.result.as_ref().unwrap())));
+            if app.world_mut().query::<&ApiTaskHandle>().iter(app.world()).next().is_none() {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+        received
+    }
+
+    #[test]
+    fn test_concurrent_tasks_deliver_typed_results() {
+        let mut app = test_app();
+        spawn(&mut app, TaskKind::Balance, 1);
+        spawn(&mut app, TaskKind::RegistrationCheck, 2);
+
+        let mut received = collect_results(&mut app);
+        received.sort_by_key(|(_, value)| *value);
+        assert_eq!(received, vec![(TaskKind::Balance, 1), (TaskKind::RegistrationCheck, 2)]);
+        assert_eq!(app.world_mut().query::<&ApiTaskHandle>().iter(app.world()).count(), 0);
+    }
+
+    #[test]
+    fn test_cancelled_task_sends_no_result() {
+        let mut app = test_app();
+        spawn(&mut app, TaskKind::Balance, 1);
+        spawn(&mut app, TaskKind::Registration, 2);
+
+        let cancelled = app
+            .world_mut()
+            .run_system_once(|mut commands: Commands, handles: Query<(Entity, &ApiTaskHandle)>| {
+                cancel_api_tasks(&mut commands, &handles, TaskKind::Balance)
+            })
+            .unwrap();
+        assert!(cancelled);
+
+        assert_eq!(collect_results(&mut app), vec![(TaskKind::Registration, 2)]);
+    }
+
+    #[test]
+    fn test_in_flight_follows_tasks() {
+        let mut app = test_app();
+        // Held until the check below has seen it running
+        let (release, released) = std::sync::mpsc::channel::<()>();
+        let released = std::sync::Arc::new(std::sync::Mutex::new(released));
+        app.world_mut()
+            .run_system_once(move |mut commands: Commands, client: Res<GalaChainClient>| {
+                let released = released.clone();
+                spawn_api_task(&mut commands, &client, TaskKind::Balance, move |_| {
+                    let _ = released.lock().map(|released| released.recv());
+                    Ok(1u32)
+                });
+            })
+            .unwrap();
+        app.update();
+        assert!(app.world().resource::<InFlight>().tasks.iter().any(|(kind, _)| *kind == TaskKind::Balance));
+
+        release.send(()).unwrap();
+        assert_eq!(collect_results(&mut app), vec![(TaskKind::Balance, 1)]);
+        app.update();
+        assert_eq!(*app.world().resource::<InFlight>(), InFlight::default());
+    }
+
+    #[test]
+    fn test_busy_picks_oldest_foreground_task() {
+        let start = Instant::now();
+        let at = |secs: u64| start + Duration::from_secs(secs);
+        let in_flight = InFlight::from_tasks([
+            (TaskKind::TokenIcon, at(0)),
+            (TaskKind::RegistrationCheck, at(3)),
+            (TaskKind::Balance, at(2)),
+            (TaskKind::Balance, at(1)),
+        ]);
+        assert_eq!(in_flight.tasks, vec![(TaskKind::TokenIcon, at(0)), (TaskKind::Balance, at(1)), (TaskKind::RegistrationCheck, at(3))]);
+        // Icons download in the background and never cover the screen
+        assert_eq!(in_flight.busy(), Some((TaskKind::Balance, at(1), 1)));
+        assert_eq!(InFlight::from_tasks([(TaskKind::TokenIcon, at(0))]).busy(), None);
+    }
+
+    #[test]
+    fn test_format_elapsed() {
+        assert_eq!(format_elapsed(Duration::from_millis(4_900)), "4s");
+        assert_eq!(format_elapsed(Duration::from_secs(65)), "1m 05s");
+    }
+}
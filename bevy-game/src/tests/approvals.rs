@@ -0,0 +1,111 @@
+//! Approval policy tests
+//!
+//! Covers the large-transfer policy and its queue:
+//! - Only transfers strictly above the threshold need approval, and only when on
+//! - The threshold cycles through its options and recovers from a hand-edited value
+//! - The policy and held transfers survive a save/load round trip
+//! - Status answers from the approval endpoint
+//! - An approval only counts when the paired device signed the record id
+
+use crate::approvals::{parse_remote_status, record_url, ApprovalMethod, ApprovalQueue, ApprovalStatus, THRESHOLD_OPTIONS};
+use crate::signing::sign_personal_message;
+use crate::tokens::TokenInfo;
+use rust_decimal::Decimal;
+use secp256k1::{PublicKey, Secp256k1, SecretKey};
+
+fn device(byte: u8) -> (SecretKey, String) {
+    let key = SecretKey::from_slice(&[byte; 32]).unwrap();
+    let public = PublicKey::from_secret_key(&Secp256k1::new(), &key);
+    (key, format!("0x{}", hex::encode(public.serialize())))
+}
+
+fn approved(signature: &str) -> String {
+    serde_json::json!({ "status": "approved", "signature": signature }).to_string()
+}
+
+#[cfg(test)]
+mod approval_tests {
+    use super::*;
+
+    #[test]
+    fn test_threshold_is_exclusive() {
+        let mut queue = ApprovalQueue::default();
+        queue.policy.threshold = Decimal::from(1000);
+        assert!(!queue.policy.requires_approval(Decimal::from(5000)));
+
+        queue.policy.enabled = true;
+        assert!(!queue.policy.requires_approval(Decimal::from(1000)));
+        assert!(queue.policy.requires_approval("1000.00000001".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_threshold_options_cycle() {
+        let mut queue = ApprovalQueue::default();
+        for _ in 0..THRESHOLD_OPTIONS.len() {
+            queue.policy.threshold = queue.policy.next_threshold();
+        }
+        assert_eq!(queue.policy.threshold, Decimal::from(THRESHOLD_OPTIONS[1]));
+
+        queue.policy.threshold = Decimal::from(42);
+        assert_eq!(queue.policy.next_threshold(), Decimal::from(THRESHOLD_OPTIONS[0]));
+    }
+
+    #[test]
+    fn test_queue_round_trip() {
+        let path = std::env::temp_dir().join(format!("galachain-approvals-{}.json", std::process::id()));
+        let mut queue = ApprovalQueue::default();
+        queue.policy.enabled = true;
+        queue.policy.method = ApprovalMethod::SecondDevice;
+        queue.policy.endpoint = "https://approvals.example/pending".to_string();
+        queue.add("approval-1".to_string(), "eth|a".to_string(), "eth|b".to_string(), TokenInfo::gala(), "2500.5".parse().unwrap(), Some("rent".to_string()));
+        queue.save(Some(path.clone())).unwrap();
+
+        let loaded = ApprovalQueue::load(Some(path.clone()));
+        assert_eq!(loaded, queue);
+        assert_eq!(loaded.items[0].method, ApprovalMethod::SecondDevice);
+        assert_eq!(loaded.items[0].record()["quantity"], "2500.5");
+        // The memo is never shown to the second device
+        assert!(loaded.items[0].record().get("memo").is_none());
+
+        queue.remove("approval-1");
+        assert!(queue.items.is_empty());
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(ApprovalQueue::load(Some(path)), ApprovalQueue::default());
+    }
+
+    #[test]
+    fn test_remote_status() {
+        let (key, device_key) = device(7);
+        assert_eq!(parse_remote_status(r#"{"status":"pending"}"#, "approval-1", &device_key), Ok(ApprovalStatus::Pending));
+        let body = serde_json::json!({ "status": "Approved", "by": "phone", "signature": sign_personal_message("approval-1", &key) });
+        assert_eq!(parse_remote_status(&body.to_string(), "approval-1", &device_key), Ok(ApprovalStatus::Approved));
+        assert_eq!(parse_remote_status(r#"{"status":"rejected"}"#, "approval-1", &device_key), Ok(ApprovalStatus::Rejected));
+        assert!(parse_remote_status(r#"{"status":"sent"}"#, "approval-1", &device_key).is_err());
+        assert!(parse_remote_status(r#"{"id":"x"}"#, "approval-1", &device_key).is_err());
+        assert!(parse_remote_status("<html>", "approval-1", &device_key).is_err());
+
+        assert_eq!(record_url("https://approvals.example/pending/", "approval-1"), "https://approvals.example/pending/approval-1");
+    }
+
+    #[test]
+    fn test_unsigned_or_forged_approval_is_refused() {
+        let (key, device_key) = device(7);
+        let (other, _) = device(9);
+
+        // A plain "approved" from whoever runs the endpoint
+        assert!(parse_remote_status(r#"{"status":"approved"}"#, "approval-1", &device_key).unwrap_err().contains("isn't signed"));
+        // Signed by a key that isn't the paired device
+        let forged = approved(&sign_personal_message("approval-1", &other));
+        assert!(parse_remote_status(&forged, "approval-1", &device_key).unwrap_err().contains("doesn't match"));
+        // The paired device's signature for a different record
+        let replayed = approved(&sign_personal_message("approval-2", &key));
+        assert!(parse_remote_status(&replayed, "approval-1", &device_key).is_err());
+        assert!(parse_remote_status(&approved("0xdeadbeef"), "approval-1", &device_key).is_err());
+
+        // Nothing can be approved before a device is paired
+        let signed = approved(&sign_personal_message("approval-1", &key));
+        assert!(parse_remote_status(&signed, "approval-1", "").unwrap_err().contains("No second device"));
+        assert!(parse_remote_status(&signed, "approval-1", "0x1234").unwrap_err().contains("isn't a valid public key"));
+        assert_eq!(parse_remote_status(&signed, "approval-1", &device_key), Ok(ApprovalStatus::Approved));
+    }
+}
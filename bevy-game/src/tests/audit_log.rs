@@ -0,0 +1,61 @@
+//! Audit log tests
+//!
+//! Covers the decisions file in audit_log.rs:
+//! - Entries read back newest first, with what the prompt showed
+//! - The file is trimmed to the newest `MAX_ENTRIES`
+//! - Lines that don't parse are skipped, not fatal
+
+use crate::audit_log::{recent, record, AuditEntry, Decision, MAX_ENTRIES};
+
+#[cfg(test)]
+mod audit_log_tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn temp_log(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("galachain-audit-{}-{}.jsonl", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    fn entry(method: &str, decision: Decision) -> AuditEntry {
+        let details = vec![("Method".to_string(), method.to_string()), ("Amount".to_string(), "1.5".to_string())];
+        AuditEntry::new("local app", method, details, decision, "done")
+    }
+
+    #[test]
+    fn test_decisions_read_back_newest_first() {
+        let path = temp_log("order");
+        assert!(recent(Some(path.clone()), 5).is_empty());
+        record(Some(path.clone()), &entry("getAddress", Decision::Approved)).unwrap();
+        record(Some(path.clone()), &entry("submitTransfer", Decision::Rejected)).unwrap();
+        std::fs::write(&path, std::fs::read_to_string(&path).unwrap() + "not json\n").unwrap();
+        record(Some(path.clone()), &entry("signMessage", Decision::WrongPin)).unwrap();
+
+        let entries = recent(Some(path.clone()), 5);
+        let methods: Vec<&str> = entries.iter().map(|entry| entry.method.as_str()).collect();
+        assert_eq!(methods, ["signMessage", "submitTransfer", "getAddress"]);
+        assert_eq!(entries[1].decision, Decision::Rejected);
+        assert_eq!(entries[1].details[1], ("Amount".to_string(), "1.5".to_string()));
+        assert_eq!(recent(Some(path.clone()), 1).len(), 1);
+
+        let line = entries[0].line(entries[0].time());
+        assert!(line.contains("Wrong PIN signMessage from a local app - Amount: 1.5 (done)"), "{}", line);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_log_keeps_the_newest_entries() {
+        let path = temp_log("trim");
+        for index in 0..MAX_ENTRIES + 3 {
+            record(Some(path.clone()), &entry(&format!("call{}", index), Decision::Expired)).unwrap();
+        }
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(contents.lines().count(), MAX_ENTRIES);
+        let newest = recent(Some(path.clone()), 1);
+        assert_eq!(newest[0].method, format!("call{}", MAX_ENTRIES + 2));
+        assert!(!contents.contains("\"call2\""));
+        assert!(contents.contains("\"call3\""));
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,51 @@
+//! Printable backup sheet tests
+//!
+//! Covers the pure helpers behind the Export screen's backup sheet:
+//! - Creation date formatting
+//! - Output format selection by file extension
+//! - The on-screen address QR code
+
+use crate::backup::{BackupError, BackupSheet, format_unix_date, qr_image};
+use crate::derivation::Derivation;
+use super::test_utils::*;
+use std::path::Path;
+
+#[cfg(test)]
+mod backup_sheet_tests {
+    use super::*;
+
+    #[test]
+    fn test_format_unix_date() {
+        assert_eq!(format_unix_date(0), "1970-01-01");
+        assert_eq!(format_unix_date(951_782_400), "2000-02-29"); // Leap day
+        assert_eq!(format_unix_date(1_735_689_599), "2024-12-31"); // Last second of the year
+        assert_eq!(format_unix_date(1_735_689_600), "2025-01-01");
+    }
+
+    #[test]
+    fn test_unsupported_extension_is_rejected() {
+        let sheet = BackupSheet {
+            words: TestVectors::TEST_MNEMONIC_12.split_whitespace().map(str::to_string).collect(),
+            address: TestVectors::EXPECTED_ETH_ADDRESS.to_string(),
+            galachain_address: String::new(),
+            created_at: 0,
+            derivation: Derivation::Legacy,
+            include_seed_qr: false,
+        };
+
+        match sheet.save(Path::new("backup.txt")) {
+            Err(BackupError::UnsupportedFormat(ext)) => assert_eq!(ext, "txt"),
+            other => panic!("Expected UnsupportedFormat, got {:?}", other.err()),
+        }
+    }
+
+    #[test]
+    fn test_qr_image() {
+        let image = qr_image(TestVectors::EXPECTED_ETH_ADDRESS, 3).unwrap();
+        // A 42-character address takes a version 4 code: 33 modules plus the quiet zone
+        assert_eq!(image.dimensions(), ((33 + 8) * 3, (33 + 8) * 3));
+        // Quiet zone is white, the finder pattern's corner is black
+        assert_eq!(image.get_pixel(0, 0).0, [255, 255, 255, 255]);
+        assert_eq!(image.get_pixel(4 * 3, 4 * 3).0, [0, 0, 0, 255]);
+    }
+}
@@ -0,0 +1,75 @@
+//! Balance history tests
+//!
+//! Covers the snapshots behind the Balance screen chart:
+//! - Unchanged balances are kept once per interval, changes straight away
+//! - Snapshots past the longest range are dropped
+//! - Chart slices carry the last balance forward and scale between low and high
+//! - Snapshots survive a save/load round trip
+
+use crate::balance_history::{
+    chart_heights, chart_values, BalanceHistory, BalanceSnapshot, ChartRange, SNAPSHOT_INTERVAL_SECS,
+};
+use rust_decimal::Decimal;
+
+#[cfg(test)]
+mod balance_history_tests {
+    use super::*;
+
+    const ADDRESS: &str = "0x0000000000000000000000000000000000000001";
+    const GALA: &str = "GALA|Unit|none|0";
+    const NOW: u64 = 1_700_000_000;
+
+    fn snapshot(unix_time: u64, token: &str, available: &str) -> BalanceSnapshot {
+        BalanceSnapshot {
+            unix_time,
+            address: ADDRESS.to_string(),
+            token: token.to_string(),
+            symbol: "GALA".to_string(),
+            available: available.parse().unwrap(),
+            locked: Decimal::ZERO,
+        }
+    }
+
+    #[test]
+    fn test_snapshots_are_throttled() {
+        let mut history = BalanceHistory::default();
+        assert!(history.record(snapshot(NOW, GALA, "10")));
+        assert!(!history.record(snapshot(NOW + 60, GALA, "10")));
+        assert!(history.record(snapshot(NOW + 120, GALA, "12")));
+        assert!(history.record(snapshot(NOW + 120, "SILK|Unit|none|0", "1")));
+        assert!(history.record(snapshot(NOW + 120 + SNAPSHOT_INTERVAL_SECS, GALA, "12")));
+        assert_eq!(history.snapshots.len(), 4);
+
+        // A month later everything before is gone
+        assert!(history.record(snapshot(NOW + ChartRange::Month.secs() + 121, GALA, "12")));
+        assert_eq!(history.snapshots.len(), 2);
+        let later = NOW + ChartRange::Month.secs() + 121;
+        assert_eq!(history.series(ADDRESS, GALA, ChartRange::Month, later).len(), 2);
+        assert_eq!(history.series(ADDRESS, GALA, ChartRange::Day, later).len(), 1);
+    }
+
+    #[test]
+    fn test_chart_values_and_heights() {
+        let older = snapshot(NOW - 17 * 60 * 60, GALA, "10");
+        let newer = snapshot(NOW - 60, GALA, "30");
+        let series = [&older, &newer];
+
+        let values = chart_values(&series, ChartRange::Day, NOW, 4);
+        let ten: Decimal = "10".parse().unwrap();
+        assert_eq!(values, vec![None, Some(ten), Some(ten), Some("30".parse().unwrap())]);
+        assert_eq!(chart_heights(&values), vec![None, Some(0.0), Some(0.0), Some(1.0)]);
+        assert_eq!(chart_heights(&[Some(ten), Some(ten)]), vec![Some(0.5), Some(0.5)]);
+        assert_eq!(chart_heights(&[None, None]), vec![None, None]);
+    }
+
+    #[test]
+    fn test_balance_history_round_trip() {
+        let path = std::env::temp_dir().join(format!("galachain-balance-history-{}.json", std::process::id()));
+        let mut history = BalanceHistory::default();
+        history.record(snapshot(NOW, GALA, "10.5"));
+        history.save(Some(path.clone())).unwrap();
+
+        assert_eq!(BalanceHistory::load(Some(path.clone())), history);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,68 @@
+//! All-tokens balance queue tests
+//!
+//! Covers how the Balance screen's token list is fetched:
+//! - No more than the concurrency limit is in flight at once
+//! - Each token keeps its own result, and failures are counted in the summary
+//! - Saved token lists without a limit get the default one
+
+use crate::balance_queue::{BalanceQueue, TokenBalanceStatus};
+use crate::tokens::{parse_token_spec, TokenInfo, TokenRegistry, CONCURRENCY_OPTIONS, DEFAULT_CONCURRENCY};
+use rust_decimal::Decimal;
+
+#[cfg(test)]
+mod balance_queue_tests {
+    use super::*;
+
+    fn tokens(collections: &[&str]) -> Vec<TokenInfo> {
+        collections.iter().map(|collection| parse_token_spec(&format!("{}|Unit|none|none", collection)).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_concurrency_limit() {
+        let tokens = tokens(&["GALA", "TOWN", "SILK", "MATERIUM"]);
+        let mut queue = BalanceQueue::new(&tokens, 2);
+
+        assert_eq!(queue.next_to_start(), Some(tokens[0].clone()));
+        assert_eq!(queue.next_to_start(), Some(tokens[1].clone()));
+        assert!(!queue.can_start());
+        assert_eq!(queue.next_to_start(), None);
+        assert_eq!(queue.in_flight(), 2);
+
+        queue.finish(&tokens[1], Ok((Decimal::ONE, Decimal::ZERO)));
+        assert!(queue.can_start());
+        assert_eq!(queue.next_to_start(), Some(tokens[2].clone()));
+        assert_eq!(queue.next_to_start(), None);
+
+        // A limit of 0 would never start anything
+        assert!(BalanceQueue::new(&tokens, 0).can_start());
+    }
+
+    #[test]
+    fn test_partial_failures() {
+        let tokens = tokens(&["GALA", "TOWN", "SILK"]);
+        let mut queue = BalanceQueue::new(&tokens, 4);
+        while queue.next_to_start().is_some() {}
+
+        queue.finish(&tokens[0], Ok((Decimal::new(125, 1), Decimal::ONE)));
+        queue.finish(&tokens[2], Err("Identity API: Network error: timeout".to_string()));
+        assert_eq!(queue.summary(), "1 of 3 tokens loaded, 1 failed");
+        assert_eq!(queue.rows()[0].1, TokenBalanceStatus::Loaded { available: Decimal::new(125, 1), locked: Decimal::ONE });
+        assert_eq!(queue.rows()[1].1, TokenBalanceStatus::Loading);
+        assert!(matches!(&queue.rows()[2].1, TokenBalanceStatus::Failed(error) if error.contains("timeout")));
+
+        // Results for tokens removed since the fetch started are dropped
+        queue.finish(&parse_token_spec("OLD|Unit|none|none").unwrap(), Ok((Decimal::ONE, Decimal::ZERO)));
+        assert_eq!(queue.rows().len(), 3);
+    }
+
+    #[test]
+    fn test_registry_concurrency() {
+        let saved: TokenRegistry = serde_json::from_str(r#"{"tokens":[],"selected":0}"#).unwrap();
+        assert_eq!(saved.concurrency, DEFAULT_CONCURRENCY);
+
+        let last = TokenRegistry { concurrency: CONCURRENCY_OPTIONS[CONCURRENCY_OPTIONS.len() - 1], ..TokenRegistry::default() };
+        assert_eq!(last.next_concurrency(), CONCURRENCY_OPTIONS[0]);
+        let odd = TokenRegistry { concurrency: 3, ..TokenRegistry::default() };
+        assert_eq!(odd.next_concurrency(), DEFAULT_CONCURRENCY);
+    }
+}
@@ -0,0 +1,53 @@
+//! Batch transfer tests
+//!
+//! Covers the recipient lists used for airdrop-style batches:
+//! - CSV parsing with headers, comments and quoted fields
+//! - Per-row checks against the balance left by earlier rows
+
+use crate::amounts::NETWORK_FEE;
+use crate::batch::{batch_total, check_row, parse_batch_csv, BatchRow};
+use crate::tokens::TokenInfo;
+use rust_decimal::Decimal;
+
+#[cfg(test)]
+mod batch_tests {
+    use super::*;
+
+    const ALICE: &str = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+
+    #[test]
+    fn test_csv_skips_header_comments_and_blank_lines() {
+        let csv = "Recipient, Amount\n# team payouts\n\n0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed, 10\n\"client|bob\",\"2.5\"\n";
+        let rows = parse_batch_csv(csv).unwrap();
+        assert_eq!(rows, vec![BatchRow::new(ALICE, "10"), BatchRow::new("client|bob", "2.5")]);
+    }
+
+    #[test]
+    fn test_csv_reports_the_bad_line() {
+        let error = parse_batch_csv("client|a,1\nclient|b\n").unwrap_err();
+        assert_eq!(error.line, 2);
+        assert!(error.to_string().starts_with("line 2:"));
+    }
+
+    #[test]
+    fn test_bad_values_only_fail_their_row() {
+        // Values are checked on send, not on import
+        let rows = parse_batch_csv("not-an-address,abc").unwrap();
+        assert!(check_row(&rows[0], None, &TokenInfo::gala()).is_err());
+    }
+
+    #[test]
+    fn test_rows_are_checked_against_the_remaining_balance() {
+        let row = BatchRow::new(ALICE, "9");
+        let (gala_address, amount) = check_row(&row, Some(Decimal::new(10, 0)), &TokenInfo::gala()).unwrap();
+        assert_eq!(gala_address, "eth|5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed");
+        assert_eq!(amount, Decimal::new(9, 0));
+        assert!(check_row(&row, Some(Decimal::new(9, 0)), &TokenInfo::gala()).is_err());
+    }
+
+    #[test]
+    fn test_total_includes_one_fee_per_valid_row() {
+        let rows = [BatchRow::new(ALICE, "10"), BatchRow::new(ALICE, "0.5"), BatchRow::new(ALICE, "oops")];
+        assert_eq!(batch_total(&rows, &TokenInfo::gala()), Decimal::new(105, 1) + NETWORK_FEE * Decimal::TWO);
+    }
+}
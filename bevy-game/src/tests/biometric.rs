@@ -0,0 +1,54 @@
+//! Presence check settings tests
+//!
+//! Covers what Settings keeps for the Touch ID / Windows Hello check:
+//! - Checks can't be turned on without a fallback passphrase, and a weak one is refused
+//! - The fallback passphrase is checked against its hash, never stored
+//! - Approvals are checked whenever there's a fallback passphrase, whatever the Unlock setting
+
+use crate::biometric::{GateError, GatePurpose, GateSettings};
+
+#[cfg(test)]
+mod biometric_tests {
+    use super::*;
+
+    const PASSPHRASE: &str = "correct horse battery";
+    // Tests don't need a real key stretch
+    const ITERATIONS: u32 = 10;
+
+    #[test]
+    fn test_checks_need_a_fallback_passphrase() {
+        let mut settings = GateSettings::default();
+        assert_eq!(settings.set_gate(GatePurpose::Sign, true), Err(GateError::NoFallback));
+        assert!(!settings.gates(GatePurpose::Sign));
+        assert!(!settings.gates(GatePurpose::Approve));
+        assert_eq!(settings.set_fallback_with_iterations("too short", ITERATIONS), Err(GateError::PassphraseTooShort));
+        assert_eq!(settings.set_fallback_with_iterations("password12345", ITERATIONS), Err(GateError::PassphraseTooWeak));
+
+        settings.set_fallback_with_iterations(PASSPHRASE, ITERATIONS).unwrap();
+        settings.set_gate(GatePurpose::Unlock, true).unwrap();
+        assert!(settings.gates(GatePurpose::Unlock));
+        assert!(settings.gates(GatePurpose::Approve));
+        assert!(!settings.gates(GatePurpose::Sign));
+        settings.set_gate(GatePurpose::Unlock, false).unwrap();
+        assert!(!settings.gates(GatePurpose::Unlock));
+        assert!(settings.gates(GatePurpose::Approve));
+    }
+
+    #[test]
+    fn test_fallback_passphrase_is_only_kept_hashed() {
+        let mut settings = GateSettings::default();
+        assert!(!settings.check_fallback(PASSPHRASE));
+        settings.set_fallback_with_iterations(PASSPHRASE, ITERATIONS).unwrap();
+        settings.set_gate(GatePurpose::Sign, true).unwrap();
+        assert!(settings.check_fallback(PASSPHRASE));
+        assert!(!settings.check_fallback("incorrect horse battery"));
+
+        let path = std::env::temp_dir().join(format!("galachain-biometric-{}.json", std::process::id()));
+        settings.save(Some(path.clone())).unwrap();
+        assert!(!std::fs::read_to_string(&path).unwrap().contains(PASSPHRASE));
+        let loaded = GateSettings::load(Some(path.clone()));
+        assert_eq!(loaded, settings);
+        assert!(loaded.check_fallback(PASSPHRASE));
+        let _ = std::fs::remove_file(&path);
+    }
+}
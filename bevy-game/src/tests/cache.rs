@@ -0,0 +1,84 @@
+//! Response cache tests
+//!
+//! Covers when cached balance and registration lookups are served:
+//! - Fresh vs stale entries against the TTL
+//! - Caching turned off
+//! - Entries kept per endpoint
+//! - Entries only written to disk when persistence is on
+
+use crate::cache::{cache_key, ResponseCache};
+use rust_decimal::Decimal;
+use std::time::{Duration, SystemTime};
+
+#[cfg(test)]
+mod response_cache_tests {
+    use super::*;
+
+    const BALANCE_URL: &str = "http://localhost:4000/api/product/GalaChainToken/FetchBalances";
+
+    #[test]
+    fn test_entries_go_stale_after_ttl() {
+        let mut cache = ResponseCache::default();
+        cache.ttl_secs = Some(60);
+        let fetched = SystemTime::now();
+        let key = cache_key(BALANCE_URL, "eth|abc");
+        cache.store_balance(key.clone(), (Decimal::from(10), Decimal::new(25, 1)), fetched);
+
+        let fresh = cache.balance(&key, fetched + Duration::from_secs(30)).unwrap();
+        assert_eq!(fresh.value, (Decimal::from(10), Decimal::new(25, 1)));
+        assert!(!fresh.stale);
+
+        let stale = cache.balance(&key, fetched + Duration::from_secs(61)).unwrap();
+        assert_eq!(stale.value, (Decimal::from(10), Decimal::new(25, 1)));
+        assert!(stale.stale);
+    }
+
+    #[test]
+    fn test_disabled_cache_stores_and_serves_nothing() {
+        let mut cache = ResponseCache::default();
+        cache.ttl_secs = None;
+        let now = SystemTime::now();
+        cache.store_registration("key".to_string(), true, now);
+        assert!(cache.registration("key", now).is_none());
+        assert!(!cache.is_stale(now - Duration::from_secs(3600), now));
+    }
+
+    #[test]
+    fn test_entries_are_per_endpoint() {
+        let mut cache = ResponseCache::default();
+        let now = SystemTime::now();
+        cache.store_balance(cache_key(BALANCE_URL, "eth|abc"), (Decimal::ONE, Decimal::ZERO), now);
+
+        assert!(cache.balance(&cache_key(BALANCE_URL, "eth|abc"), now).is_some());
+        assert!(cache.balance(&cache_key("http://other:4000/FetchBalances", "eth|abc"), now).is_none());
+        assert!(cache.balance(&cache_key(BALANCE_URL, "eth|def"), now).is_none());
+    }
+
+    #[test]
+    fn test_save_keeps_entries_only_when_persisting() {
+        let path = std::env::temp_dir().join(format!("galachain-api-cache-{}.json", std::process::id()));
+        let now = SystemTime::now();
+        let key = cache_key(BALANCE_URL, "eth|abc");
+
+        let mut cache = ResponseCache::default();
+        cache.ttl_secs = Some(300);
+        cache.store_balance(key.clone(), (Decimal::new(512345678, 8), Decimal::ZERO), now);
+        cache.save(Some(path.clone())).unwrap();
+        let reloaded = ResponseCache::load(Some(path.clone()));
+        assert_eq!(reloaded.ttl_secs, Some(300));
+        assert!(reloaded.balance(&key, now).is_none());
+        assert!(!reloaded.auto_refresh_balance && !reloaded.auto_refresh_registration);
+
+        cache.auto_refresh_registration = true;
+        cache.save(Some(path.clone())).unwrap();
+        let reloaded = ResponseCache::load(Some(path.clone()));
+        assert!(!reloaded.auto_refresh_balance && reloaded.auto_refresh_registration);
+
+        cache.persist = true;
+        cache.save(Some(path.clone())).unwrap();
+        let reloaded = ResponseCache::load(Some(path.clone()));
+        assert_eq!(reloaded.balance(&key, now).unwrap().value, (Decimal::new(512345678, 8), Decimal::ZERO));
+
+        let _ = std::fs::remove_file(path);
+    }
+}
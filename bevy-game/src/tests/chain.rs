@@ -0,0 +1,132 @@
+//! Chain backend tests
+//!
+//! Covers `GalaChainClient` forwarding to a `ChainApi` backend:
+//! - Calls reach a test double passed to `with_backend`
+//! - uniqueKeys are claimed by the client, whatever the backend
+//! - Rebuilding for new settings keeps the demo ledger only while demo mode stays on
+
+use crate::chain::ChainApi;
+use crate::signing::SignedDto;
+use crate::tokens::TokenInfo;
+use crate::{ApiSettings, CancelToken, GalaChainClient, GalaChainError};
+use rust_decimal::Decimal;
+use secp256k1::SecretKey;
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[cfg(test)]
+mod chain_tests {
+    use super::*;
+
+    /// Records each call and answers with fixed values.
+    #[derive(Default)]
+    struct RecordingChain {
+        calls: Mutex<Vec<String>>,
+    }
+
+    impl RecordingChain {
+        fn record(&self, call: String) {
+            self.calls.lock().unwrap().push(call);
+        }
+    }
+
+    impl ChainApi for RecordingChain {
+        fn fetch_balance(&self, owner: &str, token: &TokenInfo, _cancel: &CancelToken) -> Result<(Decimal, Decimal), GalaChainError> {
+            self.record(format!("balance {} {}", owner, token.symbol));
+            Ok((Decimal::from(42), Decimal::ONE))
+        }
+
+        fn fetch_public_key(&self, owner: &str, _cancel: &CancelToken) -> Result<Option<String>, GalaChainError> {
+            self.record(format!("public key? {}", owner));
+            Ok(Some("04ff".to_string()))
+        }
+
+        fn is_registered(&self, owner: &str, _cancel: &CancelToken) -> Result<bool, GalaChainError> {
+            self.record(format!("registered? {}", owner));
+            Ok(true)
+        }
+
+        fn register(&self, public_key: &str, _cancel: &CancelToken) -> Result<(), GalaChainError> {
+            self.record(format!("register {}", public_key));
+            Ok(())
+        }
+
+        fn transfer(&self, signed: &SignedDto, _cancel: &CancelToken) -> Result<Value, GalaChainError> {
+            self.record(format!("transfer {}", signed.unique_key));
+            Ok(json!({ "Status": 1 }))
+        }
+
+        fn update_public_key(&self, signed: &SignedDto, _cancel: &CancelToken) -> Result<Value, GalaChainError> {
+            self.record(format!("update key {}", signed.unique_key));
+            Ok(json!({ "Status": 1 }))
+        }
+
+        fn burn(&self, signed: &SignedDto, _cancel: &CancelToken) -> Result<Value, GalaChainError> {
+            self.record(format!("burn {}", signed.unique_key));
+            Ok(json!({ "Status": 1 }))
+        }
+
+        fn fetch_token_classes(&self, classes: &[TokenInfo], _cancel: &CancelToken) -> Result<Vec<TokenInfo>, GalaChainError> {
+            self.record(format!("classes {}", classes.len()));
+            Ok(Vec::new())
+        }
+
+        fn ping(&self) -> (Result<Duration, GalaChainError>, Result<Duration, GalaChainError>) {
+            (Ok(Duration::from_millis(1)), Err(GalaChainError::Network("identity down".to_string())))
+        }
+    }
+
+    fn signed(unique_key: &str) -> SignedDto {
+        SignedDto { body: json!({ "uniqueKey": unique_key }), unique_key: unique_key.to_string(), signature: String::new() }
+    }
+
+    #[test]
+    fn test_calls_reach_the_backend() {
+        let backend = Arc::new(RecordingChain::default());
+        let client = GalaChainClient::with_backend(&ApiSettings::default(), backend.clone());
+
+        assert_eq!(client.get_balance_blocking("eth|abc", &TokenInfo::gala()).unwrap(), (Decimal::from(42), Decimal::ONE));
+        assert!(client.check_registration_blocking("eth|abc").unwrap());
+        client.register_user_blocking("04ff").unwrap();
+        client.transfer_blocking(signed("t-1")).unwrap();
+        client.fetch_token_classes_blocking(&[TokenInfo::gala()]).unwrap();
+        assert!(client.ping_endpoints_blocking().1.is_err());
+
+        assert_eq!(
+            *backend.calls.lock().unwrap(),
+            ["balance eth|abc GALA", "registered? eth|abc", "register 04ff", "transfer t-1", "classes 1"]
+        );
+    }
+
+    #[test]
+    fn test_client_claims_unique_keys() {
+        let backend = Arc::new(RecordingChain::default());
+        let client = GalaChainClient::with_backend(&ApiSettings::default(), backend.clone());
+
+        client.burn_blocking(signed("k-1")).unwrap();
+        // Refused before it reaches the backend, even as a different operation
+        assert!(matches!(client.transfer_blocking(signed("k-1")), Err(GalaChainError::DuplicateSubmission(_))));
+        // Clones share the used keys
+        assert!(matches!(client.clone().burn_blocking(signed("k-1")), Err(GalaChainError::DuplicateSubmission(_))));
+        assert_eq!(*backend.calls.lock().unwrap(), ["burn k-1"]);
+    }
+
+    #[test]
+    fn test_rebuilt_keeps_demo_ledger_while_demo_mode_stays_on() {
+        let demo = ApiSettings { demo_mode: true, ..ApiSettings::default() };
+        let owner = "eth|f39Fd6e51aad88F6F4ce6aB8827279cffFb92266";
+        let client = GalaChainClient::new(&demo);
+        // Hardhat account #0
+        let key = SecretKey::from_slice(&hex::decode("ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80").unwrap()).unwrap();
+        client.register_user_blocking(&GalaChainClient::get_public_key_from_private(&key)).unwrap();
+
+        let renamed = client.rebuilt(&ApiSettings { channel_name: "asset".to_string(), ..demo.clone() });
+        assert!(renamed.check_registration_blocking(owner).unwrap());
+
+        let real = renamed.rebuilt(&ApiSettings::default());
+        assert!(!real.is_demo());
+        let demo_again = real.rebuilt(&demo);
+        assert!(!demo_again.check_registration_blocking(owner).unwrap());
+    }
+}
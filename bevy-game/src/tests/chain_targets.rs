@@ -0,0 +1,87 @@
+//! Channel/contract pair tests
+//!
+//! Covers switching the token endpoints between saved pairs in chain_targets.rs:
+//! - "channel/contract" parsing, and pairs that would break a URL template
+//! - The picker cycles through the saved pairs, starting from the one in use
+//! - Token endpoints follow the pair; identity endpoints stay put, and the pair left is kept
+//! - Environment profiles carry the pairs, without blanks or repeats
+
+use crate::chain_targets::{choices, next, ChainTarget};
+use crate::environment::EnvironmentProfile;
+use crate::tokens::TokenRegistry;
+use crate::{ApiSettings, ChainUrls};
+
+#[cfg(test)]
+mod chain_targets_tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pairs() {
+        assert_eq!(ChainTarget::parse(" asset / MyGame "), Some(ChainTarget::new("asset", "MyGame")));
+        assert_eq!(ChainTarget::new("product", "GalaChainToken").to_string(), "product/GalaChainToken");
+        for text in ["product", "/GalaChainToken", "product/", "a/b/c", "product/{contract}", "my channel/Token"] {
+            assert_eq!(ChainTarget::parse(text), None, "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_picker_cycles_saved_pairs() {
+        let token = ChainTarget::new("product", "GalaChainToken");
+        let game = ChainTarget::new("product", "MyGame");
+        let asset = ChainTarget::new("asset", "MyGame");
+
+        // The pair in use leads the list when it isn't saved
+        let saved = [game.clone(), asset.clone(), game.clone()];
+        assert_eq!(choices(&saved, &token), vec![token.clone(), game.clone(), asset.clone()]);
+        assert_eq!(next(&saved, &token), game);
+        assert_eq!(next(&saved, &game), asset);
+        assert_eq!(next(&[game.clone(), asset.clone()], &asset), game);
+        assert_eq!(next(&[], &token), token);
+    }
+
+    #[test]
+    fn test_token_endpoints_follow_the_pair() {
+        let mut settings = ApiSettings::default();
+        settings.chain_targets = vec![ChainTarget::new("product", "GalaChainToken"), ChainTarget::new("asset", "MyGame")];
+        settings.select_next_chain_target();
+        assert_eq!(settings.chain_target(), ChainTarget::new("asset", "MyGame"));
+
+        let urls = ChainUrls::new(&settings);
+        assert_eq!(urls.balance, "http://localhost:3000/api/asset/MyGame/FetchBalances");
+        assert_eq!(urls.transfer, "http://localhost:3000/api/asset/MyGame/TransferToken");
+        assert_eq!(urls.registration_check, "http://localhost:3000/api/asset/PublicKeyContract/GetPublicKey");
+
+        settings.select_next_chain_target();
+        assert_eq!(ChainUrls::new(&settings).balance, "http://localhost:3000/api/product/GalaChainToken/FetchBalances");
+
+        // The pair left behind is kept, so the picker can switch back to it
+        let mut settings = ApiSettings::default();
+        settings.chain_targets = vec![ChainTarget::new("asset", "MyGame")];
+        settings.select_next_chain_target();
+        assert_eq!(settings.chain_targets, vec![ChainTarget::new("product", "GalaChainToken"), ChainTarget::new("asset", "MyGame")]);
+        settings.select_next_chain_target();
+        assert_eq!(settings.chain_target(), ChainTarget::new("product", "GalaChainToken"));
+    }
+
+    #[test]
+    fn test_profiles_carry_the_pairs() {
+        let mut settings = ApiSettings::default();
+        settings.chain_targets = vec![ChainTarget::new("product", "MyGame")];
+        let profile = EnvironmentProfile::from_settings(&settings, &[]);
+        assert!(profile.to_json().contains("\"contract\": \"MyGame\""));
+        assert!(profile.summary().contains("(1 saved pair)"));
+
+        let json = r#"{"kind": "galachain-environment", "chain_targets": [
+            {"channel": "product", "contract": "MyGame"},
+            {"channel": "product", "contract": "MyGame"},
+            {"channel": "", "contract": "Blank"}
+        ]}"#;
+        let mut imported = ApiSettings::default();
+        EnvironmentProfile::from_json(json).unwrap().apply(&mut imported, &mut TokenRegistry::default());
+        assert_eq!(imported.chain_targets, vec![ChainTarget::new("product", "MyGame")]);
+
+        // Profiles from before pairs existed clear them
+        EnvironmentProfile::from_json(r#"{"kind": "galachain-environment"}"#).unwrap().apply(&mut imported, &mut TokenRegistry::default());
+        assert!(imported.chain_targets.is_empty());
+    }
+}
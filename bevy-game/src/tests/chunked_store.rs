@@ -0,0 +1,152 @@
+//! Chunked keychain entry tests
+//!
+//! Covers secrets bigger than one credential, as on Windows:
+//! - Big secrets are split into chunks within the limit and read back whole
+//! - Secrets under the limit are stored as they are
+//! - Shrinking or deleting an entry removes its old chunks
+//! - A missing or mismatched chunk fails instead of returning a partial secret
+//! - A write that fails partway leaves the previous secret readable
+//! - Entries chunked before generations still read
+
+use crate::chunked_store::{chunk_name, ChunkedStore};
+use crate::{KeychainError, SecretStore};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+#[cfg(test)]
+mod chunked_store_tests {
+    use super::*;
+
+    const LIMIT: usize = 100;
+
+    /// A keychain that refuses entries over `LIMIT` UTF-16 units, like Credential Manager.
+    #[derive(Clone, Default)]
+    struct Keychain {
+        entries: Arc<Mutex<HashMap<String, String>>>,
+        /// Writes to this entry fail, like a keychain that went away mid-write
+        failing: Arc<Mutex<Option<String>>>,
+    }
+
+    struct Entry {
+        name: String,
+        keychain: Keychain,
+    }
+
+    impl Keychain {
+        fn store(&self, name: &str) -> ChunkedStore {
+            let keychain = self.clone();
+            ChunkedStore::new(name, Some(LIMIT), move |name| Entry { name: name.to_string(), keychain: keychain.clone() })
+        }
+
+        fn names(&self) -> Vec<String> {
+            let mut names: Vec<String> = self.entries.lock().unwrap().keys().cloned().collect();
+            names.sort();
+            names
+        }
+    }
+
+    impl SecretStore for Entry {
+        fn get(&self) -> Result<String, KeychainError> {
+            self.keychain.entries.lock().unwrap().get(&self.name).cloned().ok_or(KeychainError::NotFound)
+        }
+
+        fn set(&self, secret: &str) -> Result<(), KeychainError> {
+            if self.keychain.failing.lock().unwrap().as_deref() == Some(self.name.as_str()) {
+                return Err(KeychainError::Access("the keychain is locked".to_string()));
+            }
+            if secret.encode_utf16().count() > LIMIT {
+                return Err(KeychainError::Access("the credential is too long".to_string()));
+            }
+            self.keychain.entries.lock().unwrap().insert(self.name.clone(), secret.to_string());
+            Ok(())
+        }
+
+        fn delete(&self) -> Result<(), KeychainError> {
+            self.keychain.entries.lock().unwrap().remove(&self.name).map(|_| ()).ok_or(KeychainError::NotFound)
+        }
+    }
+
+    #[test]
+    fn test_big_secrets_are_chunked_and_reassembled() {
+        let keychain = Keychain::default();
+        let wallet = keychain.store("default-wallet");
+        // Characters outside the BMP take two UTF-16 units and mustn't be split
+        let secret = format!("{{\"accounts\":\"{}{}\"}}", "a".repeat(150), "🔑".repeat(40));
+        wallet.set(&secret).unwrap();
+        assert_eq!(keychain.names(), vec!["default-wallet", "default-wallet#g1:1", "default-wallet#g1:2", "default-wallet#g1:3"]);
+        assert!(keychain.entries.lock().unwrap().values().all(|stored| stored.encode_utf16().count() <= LIMIT));
+        assert_eq!(wallet.get().unwrap(), secret);
+
+        wallet.set("small").unwrap();
+        assert_eq!(keychain.names(), vec!["default-wallet"]);
+        assert_eq!(keychain.entries.lock().unwrap()["default-wallet"], "small");
+        assert_eq!(wallet.get().unwrap(), "small");
+
+        wallet.set(&secret).unwrap();
+        // A rewrite goes to the next generation and drops the previous one
+        wallet.set(&secret.replace('a', "b")).unwrap();
+        assert_eq!(keychain.names(), vec!["default-wallet", "default-wallet#g2:1", "default-wallet#g2:2", "default-wallet#g2:3"]);
+        wallet.delete().unwrap();
+        assert!(keychain.names().is_empty());
+        assert!(matches!(wallet.get(), Err(KeychainError::NotFound)));
+    }
+
+    #[test]
+    fn test_missing_or_mismatched_chunks_fail() {
+        let keychain = Keychain::default();
+        let wallet = keychain.store("default-wallet");
+        wallet.set(&"a".repeat(250)).unwrap();
+
+        // A write that stopped after the chunks: the old manifest no longer matches
+        keychain.entries.lock().unwrap().insert(chunk_name("default-wallet", Some(1), 1), "b".repeat(LIMIT));
+        assert!(matches!(wallet.get(), Err(KeychainError::Deserialize(_))));
+
+        keychain.entries.lock().unwrap().remove(&chunk_name("default-wallet", Some(1), 3));
+        assert!(matches!(wallet.get(), Err(KeychainError::Deserialize(message)) if message.contains("chunk 3 of 3")));
+    }
+
+    #[test]
+    fn test_failed_write_keeps_the_previous_secret() {
+        let keychain = Keychain::default();
+        let wallet = keychain.store("default-wallet");
+        let previous = "a".repeat(250);
+        wallet.set(&previous).unwrap();
+        let before = keychain.names();
+
+        // The second chunk of the new generation won't save
+        *keychain.failing.lock().unwrap() = Some(chunk_name("default-wallet", Some(2), 2));
+        assert!(matches!(wallet.set(&"b".repeat(250)), Err(KeychainError::Access(_))));
+        assert_eq!(wallet.get().unwrap(), previous);
+        // The chunk that did save is cleaned up
+        assert_eq!(keychain.names(), before);
+
+        // Every chunk saved but the manifest didn't
+        *keychain.failing.lock().unwrap() = Some("default-wallet".to_string());
+        assert!(wallet.set(&"c".repeat(250)).is_err());
+        assert_eq!(wallet.get().unwrap(), previous);
+        assert_eq!(keychain.names(), before);
+
+        *keychain.failing.lock().unwrap() = None;
+        wallet.set(&"d".repeat(250)).unwrap();
+        assert_eq!(wallet.get().unwrap(), "d".repeat(250));
+    }
+
+    #[test]
+    fn test_chunks_from_before_generations_still_read() {
+        let keychain = Keychain::default();
+        let wallet = keychain.store("default-wallet");
+        let secret = "a".repeat(150);
+        {
+            let mut entries = keychain.entries.lock().unwrap();
+            entries.insert("default-wallet".to_string(), format!("galachain-chunks:v1:2:{}", hex::encode(Sha256::digest(secret.as_bytes()))));
+            entries.insert(chunk_name("default-wallet", None, 1), "a".repeat(100));
+            entries.insert(chunk_name("default-wallet", None, 2), "a".repeat(50));
+        }
+        assert_eq!(wallet.get().unwrap(), secret);
+
+        wallet.set(&"b".repeat(150)).unwrap();
+        assert_eq!(keychain.names(), vec!["default-wallet", "default-wallet#g1:1", "default-wallet#g1:2"]);
+        assert_eq!(wallet.get().unwrap(), "b".repeat(150));
+    }
+}
@@ -0,0 +1,113 @@
+//! Circuit breaker tests
+//!
+//! Covers how failing endpoints are paused:
+//! - The circuit opens after the threshold and a success closes it again
+//! - A cooled-down circuit lets a probe through and reopens if it fails
+//! - Every path on a server shares one circuit
+//! - HttpChain stops retrying once the circuit opens and fails fast after
+
+use crate::circuit::{origin, CircuitBreaker, CircuitState};
+use crate::chain::ChainApi;
+use crate::tokens::TokenInfo;
+use crate::rate_limit::RateLimits;
+use crate::stats::UsageStats;
+use crate::{ApiSettings, CancelToken, ChainUrls, GalaChainError, HttpChain};
+use crate::http_log::HttpLog;
+use crate::http_options::HttpOptions;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[cfg(test)]
+mod circuit_tests {
+    use super::*;
+
+    const URL: &str = "http://127.0.0.1:9/api/asset/token-contract/FetchBalances";
+
+    #[test]
+    fn test_opens_after_threshold() {
+        let breaker = CircuitBreaker::new(3, Duration::from_secs(60));
+        breaker.record(URL, false);
+        breaker.record(URL, false);
+        assert_eq!(breaker.state(URL), CircuitState::Closed);
+        breaker.record(URL, true);
+        breaker.record(URL, false);
+        breaker.record(URL, false);
+        assert!(breaker.check(URL).is_ok(), "a success in between starts the count again");
+
+        breaker.record(URL, false);
+        assert!(matches!(breaker.state(URL), CircuitState::Open(remaining) if remaining <= Duration::from_secs(60)));
+        assert!(breaker.check(URL).is_err());
+
+        breaker.reset();
+        assert_eq!(breaker.state(URL), CircuitState::Closed);
+    }
+
+    #[test]
+    fn test_half_open_probe() {
+        let breaker = CircuitBreaker::new(2, Duration::from_millis(20));
+        breaker.record(URL, false);
+        breaker.record(URL, false);
+        assert!(breaker.check(URL).is_err());
+
+        std::thread::sleep(Duration::from_millis(40));
+        assert_eq!(breaker.state(URL), CircuitState::HalfOpen);
+        assert!(breaker.check(URL).is_ok());
+        breaker.record(URL, false);
+        assert!(breaker.check(URL).is_err(), "one failed probe reopens it");
+
+        std::thread::sleep(Duration::from_millis(40));
+        breaker.record(URL, true);
+        assert_eq!(breaker.state(URL), CircuitState::Closed);
+    }
+
+    #[test]
+    fn test_circuits_are_per_server() {
+        assert_eq!(origin(URL), "http://127.0.0.1:9");
+        assert_eq!(origin("https://gateway-mainnet.galachain.com/api/identities/RegisterEthUser"), "https://gateway-mainnet.galachain.com");
+
+        let breaker = CircuitBreaker::new(1, Duration::from_secs(60));
+        breaker.record(URL, false);
+        assert!(breaker.check("http://127.0.0.1:9/api/identities/GetPublicKey").is_err());
+        assert!(breaker.check("http://127.0.0.1:10/api/identities/GetPublicKey").is_ok());
+    }
+
+    #[test]
+    fn test_http_chain_fails_fast_once_open() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let hits = Arc::new(AtomicUsize::new(0));
+        let counted = hits.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let mut reader = BufReader::new(stream);
+                loop {
+                    let mut line = String::new();
+                    if reader.read_line(&mut line).unwrap_or(0) == 0 || line == "\r\n" {
+                        break;
+                    }
+                }
+                counted.fetch_add(1, Ordering::SeqCst);
+                let _ = write!(reader.get_mut(), "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 2\r\nConnection: close\r\n\r\n{{}}");
+            }
+        });
+
+        let settings = ApiSettings {
+            operations_base_url: base_url.clone(),
+            identity_base_url: base_url,
+            ..ApiSettings::default()
+        };
+        let breaker = CircuitBreaker::new(2, Duration::from_secs(60));
+        let chain = HttpChain::new(HttpOptions::default().lazy_chain_client(), ChainUrls::new(&settings), HttpLog::default(), breaker.clone(), RateLimits::default(), UsageStats::default(), settings.mode);
+
+        let first = chain.fetch_balance("eth|0000000000000000000000000000000000000001", &TokenInfo::gala(), &CancelToken::default());
+        assert!(matches!(first, Err(GalaChainError::Api(_))), "{:?}", first);
+        assert_eq!(hits.load(Ordering::SeqCst), 2, "retries stop when the circuit opens");
+
+        let second = ChainApi::fetch_public_key(&chain, "eth|0000000000000000000000000000000000000001", &CancelToken::default());
+        assert!(matches!(second, Err(GalaChainError::CircuitOpen(_))), "{:?}", second);
+        assert_eq!(hits.load(Ordering::SeqCst), 2);
+    }
+}
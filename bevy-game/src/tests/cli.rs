@@ -0,0 +1,80 @@
+//! Launch option tests
+//!
+//! Covers command-line flags and GALA_* environment variables:
+//! - Flags in both `--flag value` and `--flag=value` form, and `--portable`
+//! - Flags taking precedence over the environment
+//! - Rejected input: unknown flags, missing values, bad booleans and profile names
+//! - A galachain:// link among the arguments
+
+use crate::cli::{parse_launch_options, CliError, LaunchOptions};
+use crate::ApiSettings;
+use std::collections::HashMap;
+
+#[cfg(test)]
+mod cli_tests {
+    use super::*;
+
+    fn parse(args: &[&str], env: &[(&str, &str)]) -> Result<LaunchOptions, CliError> {
+        let env: HashMap<String, String> = env.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        parse_launch_options(args.iter().map(|arg| arg.to_string()), |name| env.get(name).cloned())
+    }
+
+    #[test]
+    fn test_flags() {
+        let options = parse(&["--operations-url", "http://ops:3000", "--identity-url=http://id:4000", "--channel", "asset", "--demo", "--headless", "--portable", "--profile", "ci-1"], &[]).unwrap();
+        assert_eq!(
+            options,
+            LaunchOptions {
+                operations_url: Some("http://ops:3000".to_string()),
+                identity_url: Some("http://id:4000".to_string()),
+                channel: Some("asset".to_string()),
+                profile: Some("ci-1".to_string()),
+                demo: true,
+                headless: true,
+                portable: true,
+                deep_link: None,
+            }
+        );
+        assert_eq!(parse(&[], &[]).unwrap(), LaunchOptions::default());
+    }
+
+    #[test]
+    fn test_environment_and_precedence() {
+        let env = [("GALA_CHANNEL", "env-channel"), ("GALA_OPERATIONS_URL", "http://env"), ("GALA_DEMO", "true"), ("GALA_IDENTITY_URL", " "), ("GALA_PORTABLE", "1")];
+        let options = parse(&["--channel", "flag-channel"], &env).unwrap();
+        assert!(options.portable);
+        assert_eq!(options.channel.as_deref(), Some("flag-channel"));
+        assert_eq!(options.operations_url.as_deref(), Some("http://env"));
+        assert_eq!(options.identity_url, None);
+        assert!(options.demo);
+
+        let mut settings = ApiSettings::default();
+        options.apply(&mut settings);
+        assert_eq!(settings.channel_name, "flag-channel");
+        assert_eq!(settings.operations_base_url, "http://env");
+        assert_eq!(settings.identity_base_url, ApiSettings::default().identity_base_url);
+        assert!(settings.demo_mode);
+    }
+
+    #[test]
+    fn test_rejected_input() {
+        assert_eq!(parse(&["--help"], &[]), Err(CliError::Help));
+        assert_eq!(parse(&["--verbose"], &[]), Err(CliError::UnknownFlag("--verbose".to_string())));
+        assert_eq!(parse(&["--channel"], &[]), Err(CliError::MissingValue("--channel".to_string())));
+        assert_eq!(parse(&["--channel", "--demo"], &[]), Err(CliError::MissingValue("--channel".to_string())));
+        assert!(matches!(parse(&["--demo=false"], &[]), Err(CliError::InvalidValue { .. })));
+        assert!(matches!(parse(&[], &[("GALA_HEADLESS", "maybe")]), Err(CliError::InvalidValue { .. })));
+        assert!(matches!(parse(&["--profile", "../work"], &[]), Err(CliError::InvalidValue { .. })));
+        assert!(!parse(&[], &[("GALA_DEMO", "0")]).unwrap().demo);
+    }
+
+    #[test]
+    fn test_deep_link_argument() {
+        let link = "galachain://transfer?to=eth|0000000000000000000000000000000000000001&amount=5";
+        let options = parse(&["--demo", link], &[]).unwrap();
+        assert_eq!(options.deep_link.as_deref(), Some(link));
+        assert!(options.demo);
+        // Anything else that isn't a flag is still refused
+        assert_eq!(parse(&["https://example.com/?a=b"], &[]), Err(CliError::UnknownFlag("https://example.com/?a=b".to_string())));
+    }
+}
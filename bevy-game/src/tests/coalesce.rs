@@ -0,0 +1,105 @@
+//! Request coalescing tests
+//!
+//! Covers how duplicate API requests are collapsed:
+//! - Concurrent identical requests share one call
+//! - Different payloads stay separate
+//! - Results are reused only within the minimum interval
+//! - A cancelled request hands over to the next caller
+
+use crate::coalesce::{RequestCoalescer, RequestKey};
+use crate::{CancelToken, GalaChainError};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+#[cfg(test)]
+mod coalesce_tests {
+    use super::*;
+
+    fn key(owner: &str) -> RequestKey {
+        RequestKey::new("http://localhost/api/FetchBalances", &serde_json::json!({ "owner": owner }))
+    }
+
+    async fn counted_call(calls: &AtomicUsize, delay: Duration, value: u32) -> Result<u32, GalaChainError> {
+        calls.fetch_add(1, Ordering::SeqCst);
+        tokio::time::sleep(delay).await;
+        Ok(value)
+    }
+
+    #[test]
+    fn test_key_depends_on_endpoint_and_payload() {
+        assert_eq!(key("eth|a"), key("eth|a"));
+        assert_ne!(key("eth|a"), key("eth|b"));
+        assert_ne!(key("eth|a"), RequestKey::new("http://localhost/other", &serde_json::json!({ "owner": "eth|a" })));
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_duplicates_share_one_call() {
+        let coalescer = RequestCoalescer::new(Duration::from_secs(5));
+        let calls = AtomicUsize::new(0);
+        let cancel = CancelToken::default();
+
+        let (first, second) = tokio::join!(
+            coalescer.run(key("eth|a"), &cancel, || counted_call(&calls, Duration::from_millis(100), 7)),
+            coalescer.run(key("eth|a"), &cancel, || counted_call(&calls, Duration::from_millis(100), 8)),
+        );
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(first.unwrap(), second.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_different_payloads_are_not_coalesced() {
+        let coalescer = RequestCoalescer::new(Duration::from_secs(5));
+        let calls = AtomicUsize::new(0);
+        let cancel = CancelToken::default();
+
+        let (a, b) = tokio::join!(
+            coalescer.run(key("eth|a"), &cancel, || counted_call(&calls, Duration::from_millis(20), 1)),
+            coalescer.run(key("eth|b"), &cancel, || counted_call(&calls, Duration::from_millis(20), 2)),
+        );
+
+        assert_eq!((a.unwrap(), b.unwrap()), (1, 2));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_results_reused_only_within_min_interval() {
+        let coalescer = RequestCoalescer::new(Duration::from_millis(200));
+        let calls = AtomicUsize::new(0);
+        let cancel = CancelToken::default();
+
+        let first = coalescer.run(key("eth|a"), &cancel, || counted_call(&calls, Duration::ZERO, 1)).await;
+        let rapid = coalescer.run(key("eth|a"), &cancel, || counted_call(&calls, Duration::ZERO, 2)).await;
+        assert_eq!((first.unwrap(), rapid.unwrap()), (1, 1));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        tokio::time::sleep(Duration::from_millis(250)).await;
+        let later = coalescer.run(key("eth|a"), &cancel, || counted_call(&calls, Duration::ZERO, 3)).await;
+        assert_eq!(later.unwrap(), 3);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_leader_hands_over_to_waiter() {
+        let coalescer = RequestCoalescer::new(Duration::from_secs(5));
+        let calls = AtomicUsize::new(0);
+        let leader_cancel = CancelToken::default();
+        let waiter_cancel = CancelToken::default();
+
+        let leader = coalescer.run(key("eth|a"), &leader_cancel, || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            leader_cancel.cancel();
+            Err::<u32, _>(GalaChainError::Cancelled)
+        });
+        let waiter = async {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            coalescer.run(key("eth|a"), &waiter_cancel, || counted_call(&calls, Duration::ZERO, 9)).await
+        };
+
+        let (leader, waiter) = tokio::join!(leader, waiter);
+        assert!(matches!(leader, Err(GalaChainError::Cancelled)));
+        assert_eq!(waiter.unwrap(), 9);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,168 @@
+//! Connection reuse tests
+//!
+//! Covers the pool estimate and host pre-resolution in connections.rs:
+//! - Requests after the first reuse an idle connection; concurrent ones open more
+//! - Idle connections expire after the keep-alive timeout and failed requests drop theirs
+//! - A rebuilt client starts with an empty pool but keeps the counts
+//! - Configured URLs reduce to unique host/port pairs, and localhost resolves
+//! - The Developer panel marks the reuse figures as estimates
+
+use crate::connections::{hosts, resolve, ConnectionData, ConnectionStats, Lookup, OriginPool, POOL_IDLE_TIMEOUT, POOL_MAX_IDLE_PER_HOST};
+use std::net::{IpAddr, Ipv4Addr};
+use std::time::{Duration, Instant};
+
+#[cfg(test)]
+mod connections_tests {
+    use super::*;
+
+    #[test]
+    fn test_sequential_requests_reuse_one_connection() {
+        let start = Instant::now();
+        let mut pool = OriginPool::default();
+        for second in 0..3 {
+            let now = start + Duration::from_secs(second);
+            pool.begin(now);
+            pool.finish(now, true);
+        }
+        assert_eq!((pool.requests, pool.opened, pool.reused), (3, 1, 2));
+        assert_eq!(pool.idle(start + Duration::from_secs(3)), 1);
+        assert!((pool.reuse_rate() - 2.0 / 3.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_concurrent_requests_open_more_connections() {
+        let now = Instant::now();
+        let mut pool = OriginPool::default();
+        pool.begin(now);
+        pool.begin(now);
+        assert_eq!((pool.opened, pool.reused, pool.in_flight), (2, 0, 2));
+        assert_eq!(pool.idle(now), 0);
+
+        pool.finish(now, true);
+        pool.finish(now, true);
+        assert_eq!(pool.idle(now), 2);
+        pool.begin(now);
+        assert_eq!((pool.opened, pool.reused), (2, 1));
+    }
+
+    #[test]
+    fn test_idle_connections_are_capped() {
+        let now = Instant::now();
+        let mut pool = OriginPool::default();
+        for _ in 0..POOL_MAX_IDLE_PER_HOST + 3 {
+            pool.begin(now);
+        }
+        for _ in 0..POOL_MAX_IDLE_PER_HOST + 3 {
+            pool.finish(now, true);
+        }
+        assert_eq!(pool.idle(now), POOL_MAX_IDLE_PER_HOST);
+    }
+
+    #[test]
+    fn test_idle_connections_expire() {
+        let start = Instant::now();
+        let mut pool = OriginPool::default();
+        pool.begin(start);
+        pool.finish(start, true);
+
+        let later = start + POOL_IDLE_TIMEOUT;
+        assert_eq!(pool.idle(later), 0);
+        pool.begin(later);
+        assert_eq!((pool.opened, pool.reused), (2, 0));
+    }
+
+    #[test]
+    fn test_failed_request_keeps_no_connection() {
+        let now = Instant::now();
+        let mut pool = OriginPool::default();
+        pool.begin(now);
+        pool.finish(now, false);
+        assert_eq!(pool.idle(now), 0);
+        pool.begin(now);
+        assert_eq!(pool.opened, 2);
+    }
+
+    #[test]
+    fn test_stats_count_by_origin_and_guard_finishes_on_drop() {
+        let stats = ConnectionStats::default();
+        let version = stats.version();
+        stats.begin("https://gateway.example.com/api/product/GalaChainToken/FetchBalances").finish(true);
+        {
+            // Dropped without finishing, like a cancelled request
+            let _cancelled = stats.begin("https://gateway.example.com/api/product/GalaChainToken/TransferToken");
+        }
+        stats.begin("http://localhost:4000/api/identities/register").finish(true);
+
+        let data = stats.snapshot();
+        assert!(stats.version() > version);
+        assert_eq!(data.origins.len(), 2);
+        let gateway = &data.origins["https://gateway.example.com"];
+        assert_eq!((gateway.requests, gateway.reused, gateway.in_flight), (2, 1, 0));
+        // The cancelled request's connection isn't kept
+        assert_eq!(gateway.idle(Instant::now()), 0);
+        assert_eq!(data.origins["http://localhost:4000"].requests, 1);
+    }
+
+    #[test]
+    fn test_rebuilt_client_starts_cold() {
+        let stats = ConnectionStats::default();
+        stats.begin("http://localhost:3000/a").finish(true);
+        stats.client_rebuilt();
+        stats.begin("http://localhost:3000/b").finish(true);
+
+        let pool = &stats.snapshot().origins["http://localhost:3000"];
+        assert_eq!((pool.requests, pool.opened, pool.reused), (2, 2, 0));
+    }
+
+    #[test]
+    fn test_lookups_replace_earlier_ones_for_the_same_host() {
+        let stats = ConnectionStats::default();
+        let lookup = |result: Result<Vec<IpAddr>, String>| Lookup { host: "gateway.example.com".to_string(), result, took: Duration::from_millis(12) };
+        stats.record_lookup(lookup(Err("no such host".to_string())));
+        stats.record_lookup(lookup(Ok(vec![IpAddr::V4(Ipv4Addr::new(93, 184, 216, 34))])));
+
+        let data = stats.snapshot();
+        assert_eq!(data.lookups.len(), 1);
+        assert!(data.lines(Instant::now()).contains(&"gateway.example.com → 93.184.216.34 (12 ms)".to_string()));
+    }
+
+    #[test]
+    fn test_lines() {
+        let now = Instant::now();
+        assert_eq!(ConnectionData::default().lines(now).last().unwrap(), "No connections yet");
+
+        let stats = ConnectionStats::default();
+        stats.begin("http://localhost:3000/a").finish(true);
+        stats.begin("http://localhost:3000/b").finish(true);
+        let lines = stats.snapshot().lines(Instant::now());
+        assert!(lines[0].starts_with("Keep-alive: idle connections kept 90 s"));
+        assert!(lines[0].ends_with("(reuse estimated from request timing)"));
+        assert_eq!(lines[1], "http://localhost:3000: 2 requests, ~1 reused (est. 50%), ~1 new connections, ~1 idle");
+    }
+
+    #[test]
+    fn test_hosts_are_unique_with_default_ports() {
+        let found = hosts(&[
+            "https://gateway.example.com/api/product/GalaChainToken/FetchBalances",
+            "https://gateway.example.com/api/product/GalaChainToken/TransferToken",
+            "https://gateway.example.com:8443/identity",
+            "http://[::1]:4000/api",
+            "not a url",
+        ]);
+        assert_eq!(
+            found,
+            vec![
+                ("gateway.example.com".to_string(), 443),
+                ("gateway.example.com".to_string(), 8443),
+                ("::1".to_string(), 4000),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_resolve_localhost() {
+        let lookup = resolve("127.0.0.1", 3000);
+        assert_eq!(lookup.host, "127.0.0.1");
+        assert_eq!(lookup.result, Ok(vec![IpAddr::V4(Ipv4Addr::LOCALHOST)]));
+    }
+}
@@ -0,0 +1,134 @@
+//! Contract method discovery tests
+//!
+//! Covers reading a GetContractAPI answer in contract_api.rs:
+//! - Methods are sorted, writes are counted but not offered
+//! - Schema properties become form fields, required first, without the signature fields
+//! - Typed values become the DTO by each property's type, with a reason when one doesn't fit
+
+use crate::contract_api::{ContractApi, FieldKind};
+use serde_json::{json, Value};
+
+#[cfg(test)]
+mod contract_api_tests {
+    use super::*;
+
+    fn sample() -> Value {
+        json!({
+            "contractName": "GalaChainToken",
+            "contractVersion": "1.4.2",
+            "methods": [
+                {
+                    "methodName": "TransferToken",
+                    "isWrite": true,
+                    "dtoSchema": { "properties": { "to": { "type": "string" } } }
+                },
+                {
+                    "methodName": "FetchBalances",
+                    "isWrite": false,
+                    "description": "Balances of an owner",
+                    "dtoSchema": {
+                        "type": "object",
+                        "properties": {
+                            "owner": { "type": "string", "description": "User alias" },
+                            "collection": { "type": "string" },
+                            "signature": { "type": "string" },
+                            "signerPublicKey": { "type": "string" },
+                            "uniqueKey": { "type": "string" }
+                        },
+                        "required": ["owner"]
+                    }
+                },
+                {
+                    "methodName": "FetchAllowances",
+                    "dtoSchema": {
+                        "properties": {
+                            "grantedTo": { "type": "string" },
+                            "allowanceType": { "type": "number", "enum": [0, 1, 2] },
+                            "limit": { "type": ["integer", "null"] },
+                            "active": { "type": "boolean" },
+                            "instances": { "type": "array", "items": { "type": "string" } },
+                            "bookmark": {}
+                        },
+                        "required": ["grantedTo"]
+                    }
+                },
+                { "isWrite": false }
+            ]
+        })
+    }
+
+    #[test]
+    fn test_methods_sorted_and_writes_not_offered() {
+        let api = ContractApi::parse(&sample()).unwrap();
+        let names: Vec<&str> = api.methods.iter().map(|method| method.name.as_str()).collect();
+        // The one without a name is dropped
+        assert_eq!(names, vec!["FetchAllowances", "FetchBalances", "TransferToken"]);
+        let reads: Vec<&str> = api.reads().map(|method| method.name.as_str()).collect();
+        assert_eq!(reads, vec!["FetchAllowances", "FetchBalances"]);
+        assert!(api.read("TransferToken").is_none());
+        assert_eq!(api.summary(), "GalaChainToken 1.4.2: 2 read methods (1 that write aren't offered)");
+    }
+
+    #[test]
+    fn test_not_a_contract_api() {
+        assert!(ContractApi::parse(&json!({ "message": "Not found" })).is_err());
+        assert!(ContractApi::parse(&Value::Null).is_err());
+    }
+
+    #[test]
+    fn test_fields_required_first_without_signature_fields() {
+        let api = ContractApi::parse(&sample()).unwrap();
+        let balances = api.read("FetchBalances").unwrap();
+        let names: Vec<&str> = balances.fields.iter().map(|field| field.name.as_str()).collect();
+        assert_eq!(names, vec!["owner", "collection"]);
+        assert_eq!(balances.fields[0].label(), "owner (text, required)");
+        assert_eq!(balances.fields[0].description.as_deref(), Some("User alias"));
+        assert_eq!(balances.description.as_deref(), Some("Balances of an owner"));
+
+        let allowances = api.read("FetchAllowances").unwrap();
+        let kinds: Vec<(&str, FieldKind)> = allowances.fields.iter().map(|field| (field.name.as_str(), field.kind)).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("grantedTo", FieldKind::Text),
+                ("active", FieldKind::Boolean),
+                ("allowanceType", FieldKind::Number),
+                ("bookmark", FieldKind::Json),
+                ("instances", FieldKind::List),
+                ("limit", FieldKind::Integer),
+            ]
+        );
+        assert_eq!(allowances.fields[2].label(), "allowanceType (0 / 1 / 2)");
+    }
+
+    #[test]
+    fn test_dto_from_typed_values() {
+        let api = ContractApi::parse(&sample()).unwrap();
+        let allowances = api.read("FetchAllowances").unwrap();
+        let inputs: Vec<String> = ["eth|0a1b", "yes", "1", "", "GALA, 0", "25"].iter().map(|input| input.to_string()).collect();
+        assert_eq!(
+            allowances.dto(&inputs).unwrap(),
+            json!({ "grantedTo": "eth|0a1b", "active": true, "allowanceType": 1.0, "instances": ["GALA", "0"], "limit": 25 })
+        );
+
+        let inputs: Vec<String> = ["eth|0a1b", "", "", "{\"page\": 2}", "[\"1\", \"2\"]", ""].iter().map(|input| input.to_string()).collect();
+        assert_eq!(allowances.dto(&inputs).unwrap(), json!({ "grantedTo": "eth|0a1b", "bookmark": { "page": 2 }, "instances": ["1", "2"] }));
+    }
+
+    #[test]
+    fn test_dto_problems() {
+        let api = ContractApi::parse(&sample()).unwrap();
+        let allowances = api.read("FetchAllowances").unwrap();
+        let with = |index: usize, text: &str| {
+            let mut inputs = vec!["eth|0a1b".to_string(), String::new(), String::new(), String::new(), String::new(), String::new()];
+            inputs[index] = text.to_string();
+            allowances.dto(&inputs)
+        };
+        assert_eq!(with(0, " "), Err("grantedTo is required".to_string()));
+        assert_eq!(with(1, "maybe"), Err("active must be true or false".to_string()));
+        assert_eq!(with(2, "7"), Err("allowanceType must be one of 0, 1, 2".to_string()));
+        assert_eq!(with(5, "2.5"), Err("limit must be a whole number".to_string()));
+        assert_eq!(with(4, "[1,"), Err("instances isn't a JSON list".to_string()));
+        assert!(with(3, "{page").unwrap_err().starts_with("bookmark isn't valid JSON"));
+    }
+}
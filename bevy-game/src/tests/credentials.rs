@@ -0,0 +1,63 @@
+//! Gateway credential tests
+//!
+//! Covers the API key / bearer token kept per profile in the keychain:
+//! - Credentials become the header they're sent as, and empty ones are refused
+//! - Saved credentials round-trip through the keychain entry's JSON
+//! - Debug output and the Settings summary don't reveal the secret
+//! - The credential is never serialized with `ApiSettings`
+
+use crate::credentials::{keychain_username, AuthHeader, GatewayCredential, DEFAULT_API_KEY_HEADER};
+use crate::ApiSettings;
+
+#[cfg(test)]
+mod credentials_tests {
+    use super::*;
+
+    fn api_key(key: &str) -> GatewayCredential {
+        GatewayCredential::ApiKey { header: DEFAULT_API_KEY_HEADER.to_string(), key: key.to_string() }
+    }
+
+    #[test]
+    fn test_credential_headers() {
+        let header = api_key(" sk-123 ").header().unwrap();
+        assert_eq!((header.name.as_str(), header.value.as_str()), ("X-API-Key", "sk-123"));
+
+        let bearer = GatewayCredential::Bearer { token: "eyJhbGciOi".to_string() }.header().unwrap();
+        assert_eq!((bearer.name.as_str(), bearer.value.as_str()), ("Authorization", "Bearer eyJhbGciOi"));
+
+        assert!(api_key("  ").header().is_err());
+        assert!(GatewayCredential::Bearer { token: String::new() }.header().is_err());
+        assert!(GatewayCredential::ApiKey { header: "Bad Header".to_string(), key: "sk".to_string() }.header().is_err());
+    }
+
+    #[test]
+    fn test_keychain_entry_round_trip() {
+        for credential in [api_key("sk-123"), GatewayCredential::Bearer { token: "tok".to_string() }] {
+            let json = serde_json::to_string(&credential).unwrap();
+            assert_eq!(serde_json::from_str::<GatewayCredential>(&json).unwrap(), credential);
+        }
+        assert_eq!(keychain_username(None), "gateway-credentials");
+        assert_eq!(keychain_username(Some("staging")), "gateway-credentials-staging");
+    }
+
+    #[test]
+    fn test_secret_not_revealed() {
+        let credential = api_key("sk-live-0123456789abcd");
+        assert!(!format!("{:?}", credential).contains("sk-live"));
+        assert_eq!(credential.summary(), "API key in X-API-Key (••••abcd)");
+        // Too short to show any of it
+        assert_eq!(api_key("sk-1234").summary(), "API key in X-API-Key (••••)");
+
+        let header = AuthHeader { name: "Authorization".to_string(), value: "Bearer tok".to_string() };
+        assert!(!format!("{:?}", header).contains("tok"));
+    }
+
+    #[test]
+    fn test_credential_not_in_config_file() {
+        let mut settings = ApiSettings::default();
+        settings.http.auth = Some(api_key("sk-123").header().unwrap());
+        let json = serde_json::to_string(&settings).unwrap();
+        assert!(!json.contains("sk-123"));
+        assert!(serde_json::from_str::<ApiSettings>(&json).unwrap().http.auth.is_none());
+    }
+}
@@ -0,0 +1,75 @@
+//! Address dashboard tests
+//!
+//! Covers the saved address list and row ordering:
+//! - Duplicate addresses are refused regardless of case
+//! - The list and sort order survive a save/load round trip
+//! - The active wallet stays first under every sort
+
+use crate::dashboard::{sort_rows, DashboardConfig, DashboardRow, DashboardSort};
+use rust_decimal::Decimal;
+
+#[cfg(test)]
+mod dashboard_tests {
+    use super::*;
+
+    const ALICE: &str = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+    const BOB: &str = "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359";
+
+    fn row(address: &str, own: bool, total: Option<i64>) -> DashboardRow {
+        DashboardRow {
+            address: address.to_string(),
+            own,
+            balance: total.map(|total| (Decimal::from(total), Decimal::ZERO)),
+        }
+    }
+
+    #[test]
+    fn test_add_refuses_duplicates() {
+        let mut config = DashboardConfig::default();
+        assert!(config.add(ALICE.to_string()));
+        assert!(!config.add(ALICE.to_lowercase()));
+        assert!(config.add(BOB.to_string()));
+        assert_eq!(config.addresses, vec![ALICE, BOB]);
+
+        config.remove(&BOB.to_uppercase().replace("0X", "0x"));
+        assert_eq!(config.addresses, vec![ALICE]);
+    }
+
+    #[test]
+    fn test_config_round_trip() {
+        let path = std::env::temp_dir().join(format!("galachain-dashboard-{}.json", std::process::id()));
+        let mut config = DashboardConfig::default();
+        config.add(BOB.to_string());
+        config.sort = DashboardSort::Balance;
+        config.save(Some(path.clone())).unwrap();
+
+        assert_eq!(DashboardConfig::load(Some(path.clone())), config);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(DashboardConfig::load(Some(path)), DashboardConfig::default());
+    }
+
+    #[test]
+    fn test_sort_keeps_own_wallet_first() {
+        let rows = vec![
+            row(BOB, false, Some(5)),
+            row("0x0000000000000000000000000000000000000001", false, None),
+            row(ALICE, true, Some(1)),
+            row("0xAb00000000000000000000000000000000000000", false, Some(50)),
+        ];
+
+        let mut added = rows.clone();
+        sort_rows(&mut added, DashboardSort::Added);
+        let order: Vec<_> = added.iter().map(|row| &row.address[..4]).collect();
+        assert_eq!(order, ["0x5a", "0xfB", "0x00", "0xAb"]);
+
+        let mut by_balance = rows.clone();
+        sort_rows(&mut by_balance, DashboardSort::Balance);
+        let order: Vec<_> = by_balance.iter().map(|row| &row.address[..4]).collect();
+        assert_eq!(order, ["0x5a", "0xAb", "0xfB", "0x00"]);
+
+        let mut by_address = rows;
+        sort_rows(&mut by_address, DashboardSort::Address);
+        let order: Vec<_> = by_address.iter().map(|row| &row.address[..4]).collect();
+        assert_eq!(order, ["0x5a", "0x00", "0xAb", "0xfB"]);
+    }
+}
@@ -0,0 +1,51 @@
+//! Payment link tests
+//!
+//! Covers galachain:// links:
+//! - `transfer` and `pay` links with percent-encoded recipients
+//! - Optional amount and token, unknown parameters ignored
+//! - Rejected links: other schemes, actions, bad amounts and encodings
+//! - The Linux desktop entry quoting the executable path
+
+use crate::deeplink::{desktop_entry, is_deep_link, parse_payment_link, DeepLinkError, PaymentRequest};
+use rust_decimal::Decimal;
+use std::path::Path;
+
+#[cfg(test)]
+mod deeplink_tests {
+    use super::*;
+
+    const TO: &str = "eth|0000000000000000000000000000000000000001";
+
+    #[test]
+    fn test_payment_links() {
+        assert_eq!(
+            parse_payment_link("galachain://transfer?to=eth%7C0000000000000000000000000000000000000001&amount=5.25&token=GALA"),
+            Ok(PaymentRequest { to: TO.to_string(), amount: Some("5.25".parse().unwrap()), token: Some("GALA".to_string()) })
+        );
+        assert_eq!(
+            parse_payment_link(&format!("GalaChain:pay/?to={}&ref=shop+42", TO)),
+            Ok(PaymentRequest { to: TO.to_string(), amount: None, token: None })
+        );
+        assert_eq!(parse_payment_link(&format!("galachain://pay?amount=1&to={}", TO)).unwrap().amount, Some(Decimal::ONE));
+        assert!(is_deep_link("GALACHAIN://transfer"));
+        assert!(!is_deep_link("galachain"));
+    }
+
+    #[test]
+    fn test_rejected_links() {
+        assert_eq!(parse_payment_link("https://example.com/?to=x"), Err(DeepLinkError::NotALink));
+        assert_eq!(parse_payment_link(&format!("galachain://burn?to={}", TO)), Err(DeepLinkError::UnknownAction("burn".to_string())));
+        assert_eq!(parse_payment_link("galachain://transfer?amount=5"), Err(DeepLinkError::MissingRecipient));
+        assert_eq!(parse_payment_link(&format!("galachain://transfer?to={}&amount=-5", TO)), Err(DeepLinkError::InvalidAmount("-5".to_string())));
+        assert_eq!(parse_payment_link(&format!("galachain://transfer?to={}&amount=0", TO)), Err(DeepLinkError::InvalidAmount("0".to_string())));
+        assert_eq!(parse_payment_link("galachain://transfer?to=eth%7"), Err(DeepLinkError::BadEncoding));
+        assert_eq!(parse_payment_link("galachain://transfer?to=%FF"), Err(DeepLinkError::BadEncoding));
+    }
+
+    #[test]
+    fn test_desktop_entry() {
+        let entry = desktop_entry(Path::new("/opt/my \"wallet\"/bevy-game"));
+        assert!(entry.contains("Exec=\"/opt/my \\\"wallet\\\"/bevy-game\" %u\n"));
+        assert!(entry.contains("MimeType=x-scheme-handler/galachain;\n"));
+    }
+}
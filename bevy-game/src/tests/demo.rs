@@ -0,0 +1,105 @@
+//! Demo mode tests
+//!
+//! Covers the in-memory chain used when demo mode is on:
+//! - Addresses funded on first look, separate from real cache keys
+//! - Registration from a public key
+//! - Signed transfers and burns moving balances, and what gets rejected
+
+use crate::demo::{DEMO_STARTING_BALANCE, DEMO_STARTING_LOCKED_GALA};
+use crate::tokens::TokenInfo;
+use crate::{ApiSettings, BurnRequest, GalaChainClient, GalaChainError, TokenInstance, TokenInstanceKey, TransferTokenRequest};
+use rust_decimal::Decimal;
+use secp256k1::{PublicKey, Secp256k1, SecretKey};
+
+#[cfg(test)]
+mod demo_tests {
+    use super::*;
+
+    // Account #0 of the Hardhat/Anvil test mnemonic, as in the DTO fixtures
+    const KEY: &str = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+    const OWNER: &str = "eth|f39Fd6e51aad88F6F4ce6aB8827279cffFb92266";
+    const RECIPIENT: &str = "eth|70997970C51812dc3A010C7d01b50e0d17dc79C8";
+
+    fn demo_client() -> GalaChainClient {
+        GalaChainClient::new(&ApiSettings { demo_mode: true, ..ApiSettings::default() })
+    }
+
+    fn key() -> SecretKey {
+        SecretKey::from_slice(&hex::decode(KEY).unwrap()).unwrap()
+    }
+
+    fn transfer(quantity: i64) -> TransferTokenRequest {
+        TransferTokenRequest {
+            from: OWNER.to_string(),
+            to: RECIPIENT.to_string(),
+            token_instance: TokenInstanceKey::fungible(&TokenInfo::gala()),
+            quantity: Decimal::from(quantity),
+            unique_key: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_addresses_start_funded() {
+        let client = demo_client();
+        assert!(client.is_demo());
+        assert!(client.get_balance_url().starts_with("demo://"));
+
+        let gala = client.get_balance_blocking(OWNER, &TokenInfo::gala()).unwrap();
+        assert_eq!(gala, (DEMO_STARTING_BALANCE, DEMO_STARTING_LOCKED_GALA));
+
+        let other = TokenInfo { collection: "TOWN".to_string(), symbol: "TOWN".to_string(), ..TokenInfo::gala() };
+        assert_eq!(client.get_balance_blocking(OWNER, &other).unwrap(), (DEMO_STARTING_BALANCE, Decimal::ZERO));
+        assert!(!GalaChainClient::new(&ApiSettings::default()).is_demo());
+    }
+
+    #[test]
+    fn test_registration() {
+        let client = demo_client();
+        assert!(!client.check_registration_blocking(OWNER).unwrap());
+
+        let public_key = PublicKey::from_secret_key(&Secp256k1::new(), &key());
+        client.register_user_blocking(&hex::encode(public_key.serialize_uncompressed())).unwrap();
+        assert!(client.check_registration_blocking(OWNER).unwrap());
+
+        assert!(client.register_user_blocking("04abcd").is_err());
+    }
+
+    #[test]
+    fn test_transfer_and_burn_move_balances() {
+        let client = demo_client();
+        let gala = TokenInfo::gala();
+
+        let signed = client.sign_dto(&transfer(250), &key(), "test", &client.settings.transfer_endpoint).unwrap();
+        client.transfer_blocking(signed).unwrap();
+        assert_eq!(client.get_balance_blocking(OWNER, &gala).unwrap().0, Decimal::from(750));
+        assert_eq!(client.get_balance_blocking(RECIPIENT, &gala).unwrap().0, Decimal::from(1250));
+
+        let burn = BurnRequest {
+            owner: OWNER.to_string(),
+            token_instances: vec![TokenInstance { quantity: Decimal::new(5, 1), token_instance_key: TokenInstanceKey::fungible(&gala) }],
+            unique_key: String::new(),
+        };
+        let signed = client.sign_dto(&burn, &key(), "test", &client.settings.burn_endpoint).unwrap();
+        client.burn_blocking(signed).unwrap();
+        assert_eq!(client.get_balance_blocking(OWNER, &gala).unwrap(), (Decimal::new(7495, 1), DEMO_STARTING_LOCKED_GALA));
+    }
+
+    #[test]
+    fn test_rejected_submissions_leave_balances() {
+        let client = demo_client();
+        let gala = TokenInfo::gala();
+
+        let too_much = client.sign_dto(&transfer(1001), &key(), "test", &client.settings.transfer_endpoint).unwrap();
+        assert!(matches!(client.transfer_blocking(too_much), Err(GalaChainError::InsufficientBalance(_))));
+        assert_eq!(client.get_balance_blocking(OWNER, &gala).unwrap().0, DEMO_STARTING_BALANCE);
+
+        // The same signed DTO is applied once
+        let signed = client.sign_dto(&transfer(1), &key(), "test", &client.settings.transfer_endpoint).unwrap();
+        client.transfer_blocking(signed.clone()).unwrap();
+        assert!(matches!(
+            client.transfer_blocking(signed),
+            Err(GalaChainError::DuplicateSubmission(_))
+        ));
+        assert_eq!(client.get_balance_blocking(OWNER, &gala).unwrap().0, Decimal::from(999));
+    }
+}
@@ -0,0 +1,62 @@
+//! Seed phrase derivation tests
+//!
+//! Covers the derivations offered by the import preview:
+//! - BIP44 m/44'/60'/0'/0/0 matches the standard test vector
+//! - Legacy is the raw-seed key the wallet has always used
+//! - Derivation paths round-trip and unsupported ones are refused
+//! - Keychain entries keep a BIP44 choice and read old entries as legacy
+
+use super::test_utils::{create_test_wallet_data, TestVectors};
+use crate::derivation::{self, Derivation, PREVIEW_ADDRESSES};
+use crate::{KeychainManager, SecureWalletData};
+
+#[cfg(test)]
+mod derivation_tests {
+    use super::*;
+
+    #[test]
+    fn test_bip44_matches_test_vector() {
+        let seed = derivation::mnemonic_seed(TestVectors::TEST_MNEMONIC_12).unwrap();
+        let key = Derivation::Bip44(0).secret_key(&seed).unwrap();
+
+        assert_eq!(hex::encode(key.secret_bytes()), "1ab42cc412b618bdea3a599e3c9bae199ebf030895b039e9db1e30dafb12b727");
+        assert_eq!(derivation::ethereum_address(&key), TestVectors::EXPECTED_ETH_ADDRESS);
+    }
+
+    #[test]
+    fn test_preview_lists_legacy_then_bip44() {
+        let preview = derivation::preview_addresses(TestVectors::TEST_MNEMONIC_12).unwrap();
+        let (_, legacy_address) = KeychainManager::new().generate_wallet_from_mnemonic(TestVectors::TEST_MNEMONIC_12).unwrap();
+
+        assert_eq!(preview.len(), PREVIEW_ADDRESSES as usize + 1);
+        assert_eq!(preview[0], (Derivation::Legacy, legacy_address));
+        assert_eq!(preview[1].1, TestVectors::EXPECTED_ETH_ADDRESS);
+        let distinct: std::collections::HashSet<&String> = preview.iter().map(|(_, address)| address).collect();
+        assert_eq!(distinct.len(), preview.len());
+        assert!(derivation::preview_addresses("not a seed phrase").is_err());
+    }
+
+    #[test]
+    fn test_paths_round_trip() {
+        for derivation in Derivation::preview_options() {
+            assert_eq!(Derivation::parse(&derivation.path()), Ok(derivation));
+        }
+        assert_eq!(Derivation::Bip44(3).path(), "m/44'/60'/0'/0/3");
+        assert!(Derivation::parse("m/44'/60'/0'/0/2147483648").is_err()); // Hardened index
+        assert!(Derivation::parse("m/44'/501'/0'/0'").is_err());
+    }
+
+    #[test]
+    fn test_entry_keeps_derivation() {
+        let legacy = create_test_wallet_data();
+        assert!(!legacy.to_json().unwrap().contains("derivation"));
+
+        let bip44 = SecureWalletData { derivation: Derivation::Bip44(2), ..create_test_wallet_data() };
+        let json = bip44.to_json().unwrap();
+        assert_eq!(SecureWalletData::from_json(&json).unwrap().derivation, Derivation::Bip44(2));
+
+        let older = r#"{"mnemonic":"abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about","created_at":1}"#;
+        assert_eq!(SecureWalletData::from_json(older).unwrap().derivation, Derivation::Legacy);
+        assert!(SecureWalletData::from_json(&json.replace("/0/2", "/x")).is_err());
+    }
+}
@@ -0,0 +1,133 @@
+//! Wallet domain event tests
+//!
+//! Sends requests straight to the domain handlers, with no screen open:
+//! - Generating a wallet stores it on BIP44 account 0 and reports its address
+//! - Importing a seed phrase restores the expected address
+//! - A bad seed phrase is rejected and nothing is stored
+//! - A transfer over the approval threshold is held, not signed
+//! - A transfer under it is signed and goes into the history with its note
+//! - Sending the same form's uniqueKey again is caught and not recorded twice
+
+use super::harness::HeadlessApp;
+use super::test_utils::{create_test_wallet_data, TestVectors};
+use crate::derivation::Derivation;
+use crate::domain::{GenerateWalletRequested, ImportFailure, ImportWalletRequested, TransferOutcome, TransferProcessed, TransferSubmitted, WalletGenerated, WalletImported};
+use crate::tokens::TokenInfo;
+use crate::{ApprovalState, GalaChainClient, HistoryState, KeychainManager, WalletData};
+use bevy::prelude::*;
+use rust_decimal::Decimal;
+
+#[cfg(test)]
+mod domain_tests {
+    use super::*;
+
+    // Sends `request` and reads back the outcomes the handlers sent that frame
+    fn answer<R: Event, O: Event, T>(harness: &mut HeadlessApp, request: R, read: impl Fn(&O) -> T) -> Vec<T> {
+        // Events aren't swapped out every frame, so an earlier request's outcome can still be current
+        harness.app.world_mut().resource_mut::<Events<O>>().clear();
+        harness.app.world_mut().send_event(request);
+        harness.update();
+        let events = harness.app.world().resource::<Events<O>>();
+        events.iter_current_update_events().map(read).collect()
+    }
+
+    fn transfer(harness: &HeadlessApp, quantity: u32) -> TransferSubmitted {
+        let address = harness.resource::<WalletData>().address.clone().unwrap();
+        TransferSubmitted {
+            from: GalaChainClient::ethereum_to_galachain_address(&address),
+            to: "eth|0000000000000000000000000000000000000001".to_string(),
+            token: TokenInfo::gala(),
+            quantity: Decimal::from(quantity),
+            note: "rent".to_string(),
+            unique_key: None,
+            instance: None,
+        }
+    }
+
+    #[test]
+    fn test_generate_request_stores_wallet() {
+        let mut harness = HeadlessApp::new();
+        let outcomes = answer(&mut harness, GenerateWalletRequested, WalletGenerated::clone);
+
+        assert_eq!(outcomes.len(), 1);
+        let address = outcomes[0].result.clone().unwrap();
+        assert_eq!(harness.resource::<WalletData>().address.as_deref(), Some(address.as_str()));
+        assert!(harness.resource::<WalletData>().private_key.is_some());
+        assert!(harness.secrets.contents().unwrap().contains("\"mnemonic\""));
+        // New wallets open at the address other wallets show for the phrase
+        assert!(harness.secrets.contents().unwrap().contains(r#""derivation":"m/44'/60'/0'/0/0""#));
+    }
+
+    #[test]
+    fn test_import_request_restores_address() {
+        let mut harness = HeadlessApp::new();
+        let request = ImportWalletRequested { mnemonic: TestVectors::TEST_MNEMONIC_12.to_string(), derivation: Derivation::Legacy };
+        let outcomes = answer(&mut harness, request, WalletImported::clone);
+
+        let (_, expected_address) = KeychainManager::new().generate_wallet_from_mnemonic(TestVectors::TEST_MNEMONIC_12).unwrap();
+        assert_eq!(outcomes.len(), 1);
+        assert_eq!(outcomes[0].result, Ok(expected_address.clone()));
+        assert_eq!(harness.resource::<WalletData>().address, Some(expected_address));
+        assert!(harness.secrets.contents().is_some());
+    }
+
+    #[test]
+    fn test_import_request_rejects_bad_phrase() {
+        let mut harness = HeadlessApp::new();
+        let request = ImportWalletRequested { mnemonic: "not a seed phrase".to_string(), derivation: Derivation::Legacy };
+        let outcomes = answer(&mut harness, request, WalletImported::clone);
+
+        assert!(matches!(outcomes[0].result, Err(ImportFailure::InvalidMnemonic(_))));
+        assert!(harness.resource::<WalletData>().address.is_none());
+        assert!(harness.secrets.contents().is_none());
+    }
+
+    #[test]
+    fn test_transfer_over_threshold_is_held() {
+        let entry = create_test_wallet_data().to_json().unwrap();
+        let mut harness = HeadlessApp::with_wallet_entry(&entry);
+        harness.app.world_mut().resource_mut::<ApprovalState>().queue.policy.enabled = true;
+        let request = transfer(&harness, 5000);
+        let held = answer(&mut harness, request, |processed: &TransferProcessed| match &processed.outcome {
+            TransferOutcome::Held { approval_id } => approval_id.clone(),
+            TransferOutcome::Signed(_) => None,
+        });
+
+        let approvals = harness.resource::<ApprovalState>();
+        assert_eq!(approvals.queue.items.len(), 1);
+        assert_eq!(held, vec![Some(approvals.queue.items[0].id.clone())]);
+        assert!(harness.resource::<HistoryState>().history.entries.is_empty());
+    }
+
+    #[test]
+    fn test_transfer_is_signed_and_recorded() {
+        let entry = create_test_wallet_data().to_json().unwrap();
+        let mut harness = HeadlessApp::with_wallet_entry(&entry);
+        let request = transfer(&harness, 5);
+        let unique_keys = answer(&mut harness, request, |processed: &TransferProcessed| match &processed.outcome {
+            TransferOutcome::Signed(signed) => signed.unique_key.clone(),
+            TransferOutcome::Held { .. } => None,
+        });
+
+        let history = &harness.resource::<HistoryState>().history;
+        assert_eq!(history.entries.len(), 1);
+        assert_eq!(unique_keys, vec![Some(history.entries[0].unique_key.clone())]);
+        assert_eq!(history.entries[0].note.as_deref(), Some("rent"));
+        assert!(harness.resource::<ApprovalState>().queue.items.is_empty());
+    }
+
+    #[test]
+    fn test_repeated_unique_key_is_not_sent_again() {
+        let entry = create_test_wallet_data().to_json().unwrap();
+        let mut harness = HeadlessApp::with_wallet_entry(&entry);
+        let request = TransferSubmitted { unique_key: Some("galachain-wallet-transfer-repeat".to_string()), ..transfer(&harness, 5) };
+        let repeat = TransferSubmitted { unique_key: request.unique_key.clone(), ..transfer(&harness, 5) };
+        let duplicate = |processed: &TransferProcessed| matches!(&processed.outcome, TransferOutcome::Signed(signed) if signed.duplicate);
+
+        assert_eq!(answer(&mut harness, request, duplicate), vec![false]);
+        assert_eq!(answer(&mut harness, repeat, duplicate), vec![true]);
+        let history = &harness.resource::<HistoryState>().history;
+        assert_eq!(history.entries.len(), 1);
+        assert_eq!(history.entries[0].unique_key, "galachain-wallet-transfer-repeat");
+    }
+}
@@ -0,0 +1,134 @@
+//! DTO serialization tests
+//!
+//! Covers the canonical payload the chain re-serializes to check signatures:
+//! - Key order (nested objects, JavaScript string order) and compact output
+//! - Null fields, unsigned fields and whole-valued numbers
+//! - BigNumber quantity strings in both directions
+//! - Transfer and burn fixtures with the payload and signature GalaChain's own signing gives
+
+use crate::dto::{canonical_json, format_quantity, payload_to_sign, DtoError};
+use crate::signing::{sign_payload, SignatureFormat};
+use crate::tokens::TokenInfo;
+use crate::{BurnRequest, TokenInstance, TokenInstanceKey, TransferTokenRequest};
+use rust_decimal::Decimal;
+use secp256k1::SecretKey;
+use serde_json::json;
+use std::str::FromStr;
+
+#[cfg(test)]
+mod dto_tests {
+    use super::*;
+
+    // The fixtures' payloads and signatures are what @gala-chain/api 2.x gives for the same
+    // DTOs, not this crate's output pinned after the fact:
+    //
+    //     const key = Buffer.from(FIXTURE_KEY, "hex");
+    //     signatures.getPayloadToSign(dto)   // serialize() without signature and trace
+    //     signatures.getSignature(dto, key)  // keccak256, elliptic sign, low s, r || s || 27 + recovery
+    //
+    // They were worked out with a standalone JavaScript port of those two functions (the
+    // json-stringify-deterministic key order, js-sha3's keccak256 and elliptic's RFC 6979
+    // nonce), which shares no code with this crate and reproduces the published keccak256
+    // and secp256k1 RFC 6979 test vectors. Re-running the lines above against the package
+    // itself must give the same strings; if it doesn't, the chain disagrees with this crate.
+    //
+    // Account #0 of the Hardhat/Anvil test mnemonic - public, never holds real funds
+    const FIXTURE_KEY: &str = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+    const FIXTURE_FROM: &str = "eth|f39Fd6e51aad88F6F4ce6aB8827279cffFb92266";
+    const FIXTURE_TO: &str = "eth|70997970C51812dc3A010C7d01b50e0d17dc79C8";
+
+    fn fixture_key() -> SecretKey {
+        SecretKey::from_slice(&hex::decode(FIXTURE_KEY).unwrap()).unwrap()
+    }
+
+    fn transfer_fixture() -> TransferTokenRequest {
+        TransferTokenRequest {
+            from: FIXTURE_FROM.to_string(),
+            to: FIXTURE_TO.to_string(),
+            token_instance: TokenInstanceKey::fungible(&TokenInfo::gala()),
+            quantity: Decimal::from_str("12.50").unwrap(),
+            unique_key: "galachain-wallet-transfer-01JHR5Z3Q8N4V6W2X9Y7K0M1P3".to_string(),
+        }
+    }
+
+    fn burn_fixture() -> BurnRequest {
+        BurnRequest {
+            owner: FIXTURE_FROM.to_string(),
+            token_instances: vec![TokenInstance {
+                quantity: Decimal::from_str("1").unwrap(),
+                token_instance_key: TokenInstanceKey::fungible(&TokenInfo::gala()),
+            }],
+            unique_key: "january-2025-event-01JHR5Z3Q8N4V6W2X9Y7K0M1P3".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_keys_sorted_at_every_level() {
+        let value = json!({"to": "eth|b", "from": "eth|a", "tokenInstance": {"instance": "0", "collection": "GALA"}, "list": [{"b": 1, "a": 2}]});
+        assert_eq!(
+            canonical_json(&value),
+            r#"{"from":"eth|a","list":[{"a":2,"b":1}],"to":"eth|b","tokenInstance":{"collection":"GALA","instance":"0"}}"#
+        );
+    }
+
+    #[test]
+    fn test_key_order_follows_javascript() {
+        // Uppercase before lowercase, and UTF-16 order: U+FF21 (one code unit) sorts after U+1F600 (a surrogate pair)
+        let value = json!({"b": 1, "B": 2, "\u{FF21}": 3, "\u{1F600}": 4});
+        assert_eq!(canonical_json(&value), "{\"B\":2,\"b\":1,\"\u{1F600}\":4,\"\u{FF21}\":3}");
+    }
+
+    #[test]
+    fn test_nulls_omitted_from_objects_only() {
+        let value = json!({"owner": "eth|a", "memo": null, "nested": {"x": null}, "list": [null, 1]});
+        assert_eq!(canonical_json(&value), r#"{"list":[null,1],"nested":{},"owner":"eth|a"}"#);
+    }
+
+    #[test]
+    fn test_whole_floats_print_like_javascript() {
+        assert_eq!(canonical_json(&json!({"a": 1.0, "b": 0.5, "c": -3.0, "d": 7})), r#"{"a":1,"b":0.5,"c":-3,"d":7}"#);
+    }
+
+    #[test]
+    fn test_payload_drops_signature_and_trace() {
+        let dto = json!({"uniqueKey": "k", "signature": "abc", "trace": {"id": 1}, "owner": "eth|a"});
+        assert_eq!(payload_to_sign(&dto).unwrap(), r#"{"owner":"eth|a","uniqueKey":"k"}"#);
+        assert_eq!(payload_to_sign(&json!(["not", "an", "object"])), Err(DtoError::NotAnObject));
+    }
+
+    #[test]
+    fn test_quantities_format_like_bignumber() {
+        for (input, expected) in [("12.50", "12.5"), ("1.00000000", "1"), ("0.00000001", "0.00000001"), ("100", "100"), ("-0.10", "-0.1")] {
+            assert_eq!(format_quantity(&Decimal::from_str(input).unwrap()), expected);
+        }
+    }
+
+    #[test]
+    fn test_quantities_read_from_strings_and_numbers() {
+        let from_string: TokenInstance = serde_json::from_value(json!({"quantity": "1.5", "tokenInstanceKey": TokenInstanceKey::fungible(&TokenInfo::gala())})).unwrap();
+        let from_number: TokenInstance = serde_json::from_value(json!({"quantity": 1.5, "tokenInstanceKey": TokenInstanceKey::fungible(&TokenInfo::gala())})).unwrap();
+        assert_eq!(from_string.quantity, Decimal::from_str("1.5").unwrap());
+        assert_eq!(from_number.quantity, from_string.quantity);
+    }
+
+    #[test]
+    fn test_transfer_fixture() {
+        let payload = payload_to_sign(&serde_json::to_value(transfer_fixture()).unwrap()).unwrap();
+        assert_eq!(
+            payload,
+            r#"{"from":"eth|f39Fd6e51aad88F6F4ce6aB8827279cffFb92266","quantity":"12.5","to":"eth|70997970C51812dc3A010C7d01b50e0d17dc79C8","tokenInstance":{"additionalKey":"none","category":"Unit","collection":"GALA","instance":"0","type":"none"},"uniqueKey":"galachain-wallet-transfer-01JHR5Z3Q8N4V6W2X9Y7K0M1P3"}"#
+        );
+        // RFC 6979 nonces make the signature deterministic, so it matches byte for byte
+        assert_eq!(sign_payload(&payload, &fixture_key(), SignatureFormat::Recoverable), "73dc478ffc89a6afb9efab859b32e00ee4287e5893f49d46a5911ec6e27087e2759a55182178b59a4b586d284cb9ee170d9f4e2a9459bc8df7a0db984e7e0d8b1b");
+    }
+
+    #[test]
+    fn test_burn_fixture() {
+        let payload = payload_to_sign(&serde_json::to_value(burn_fixture()).unwrap()).unwrap();
+        assert_eq!(
+            payload,
+            r#"{"owner":"eth|f39Fd6e51aad88F6F4ce6aB8827279cffFb92266","tokenInstances":[{"quantity":"1","tokenInstanceKey":{"additionalKey":"none","category":"Unit","collection":"GALA","instance":"0","type":"none"}}],"uniqueKey":"january-2025-event-01JHR5Z3Q8N4V6W2X9Y7K0M1P3"}"#
+        );
+        assert_eq!(sign_payload(&payload, &fixture_key(), SignatureFormat::Recoverable), "70caff8b0bf199755b2a50968c8f428b6dc5931e049018fc9ae06c383510bd562b9d88d0a7f975acfe534d6e9eb45b820bab49cf5027f19518182524f063cbfc1c");
+    }
+}
@@ -0,0 +1,85 @@
+//! Endpoint template tests
+//!
+//! Covers the checks behind the Settings template editor:
+//! - Placeholders each endpoint fills in, and ones it doesn't
+//! - Paths that can't be valid
+//! - What a test request's status says about the path
+//! - Which host each endpoint goes to, by default and when rerouted
+
+use crate::endpoints::{check_base_url, check_template, describe_probe, Endpoint, Host, TemplateIssue};
+use crate::errors::ApiService;
+use crate::{ApiSettings, ChainUrls};
+
+#[cfg(test)]
+mod endpoint_tests {
+    use super::*;
+
+    #[test]
+    fn test_default_templates_are_valid() {
+        let settings = ApiSettings::default();
+        for endpoint in Endpoint::ALL {
+            assert_eq!(check_template(endpoint, endpoint.template(&settings)), vec![], "{:?}", endpoint);
+        }
+        let urls = ChainUrls::new(&settings);
+        assert_eq!(Endpoint::Transfer.url(&urls), "http://localhost:3000/api/product/GalaChainToken/TransferToken");
+    }
+
+    #[test]
+    fn test_placeholder_issues() {
+        assert_eq!(
+            check_template(Endpoint::Balance, "/api/{chanel}/{contract}/FetchBalances"),
+            vec![TemplateIssue::UnknownPlaceholder("chanel".to_string())]
+        );
+        assert_eq!(check_template(Endpoint::Registration, "/api/{channel}/register"), vec![TemplateIssue::NotFilled("channel".to_string())]);
+        assert_eq!(check_template(Endpoint::Burn, "/api/{channel/BurnTokens"), vec![TemplateIssue::UnmatchedBrace]);
+        assert_eq!(check_template(Endpoint::Burn, "/api/channel}/BurnTokens"), vec![TemplateIssue::UnmatchedBrace]);
+    }
+
+    #[test]
+    fn test_path_issues() {
+        assert_eq!(check_template(Endpoint::Transfer, ""), vec![TemplateIssue::Empty]);
+        assert_eq!(
+            check_template(Endpoint::Transfer, "api/{channel}/Transfer Token"),
+            vec![TemplateIssue::NoLeadingSlash, TemplateIssue::Whitespace]
+        );
+    }
+
+    #[test]
+    fn test_describe_probe() {
+        assert!(describe_probe(404).contains("nothing answers"));
+        assert!(describe_probe(400).contains("exists"));
+        assert!(describe_probe(401).contains("credentials"));
+        assert!(describe_probe(200).contains("answered"));
+    }
+
+    #[test]
+    fn test_default_routes() {
+        let settings = ApiSettings::default();
+        let urls = ChainUrls::new(&settings);
+        assert_eq!(Endpoint::Registration.url(&urls), "http://localhost:4000/api/identities/register");
+        // Balances used to be fetched from the identity server
+        assert_eq!(Endpoint::Balance.url(&urls), "http://localhost:3000/api/product/GalaChainToken/FetchBalances");
+        assert_eq!(settings.service(Endpoint::Balance), ApiService::Operations);
+        assert_eq!(settings.service(Endpoint::Registration), ApiService::Identity);
+    }
+
+    #[test]
+    fn test_rerouted_endpoints() {
+        let mut settings = ApiSettings::default();
+        settings.set_host(Endpoint::Balance, Host::Identity);
+        settings.set_host(Endpoint::Transfer, Host::Custom("https://gateway.example".to_string()));
+        let urls = ChainUrls::new(&settings);
+        assert_eq!(Endpoint::Balance.url(&urls), "http://localhost:4000/api/product/GalaChainToken/FetchBalances");
+        assert_eq!(Endpoint::Transfer.url(&urls), "https://gateway.example/api/product/GalaChainToken/TransferToken");
+        assert_eq!(settings.service(Endpoint::Balance), ApiService::Identity);
+
+        // Setting the default back leaves nothing stored
+        settings.set_host(Endpoint::Balance, Host::Operations);
+        settings.set_host(Endpoint::Transfer, Host::Operations);
+        assert!(settings.routes.is_empty());
+
+        assert!(check_base_url("https://gateway.example").is_ok());
+        assert!(check_base_url("gateway.example").is_err());
+        assert!(check_base_url("https://gateway.example/").is_err());
+    }
+}
@@ -0,0 +1,46 @@
+//! Response envelope tests
+//!
+//! Covers how contract responses are read:
+//! - Data on success, including an empty one
+//! - Error keys and codes mapped to typed errors
+//! - Failures with no envelope, and successes that say they failed
+
+use crate::envelope::parse_response;
+use crate::GalaChainError;
+use serde_json::Value;
+
+#[cfg(test)]
+mod envelope_tests {
+    use super::*;
+
+    #[test]
+    fn test_success_data() {
+        assert_eq!(parse_response::<Vec<u32>>(200, r#"{"Status":1,"Data":[1,2]}"#).unwrap(), Some(vec![1, 2]));
+        assert_eq!(parse_response::<Vec<u32>>(201, r#"{"Status":1}"#).unwrap(), None);
+        assert!(matches!(parse_response::<Vec<u32>>(200, r#"{"Data":[1]}"#), Err(GalaChainError::Parse(_))));
+    }
+
+    #[test]
+    fn test_error_keys_and_codes() {
+        let failure = |code: u16, key: &str| {
+            let body = format!(r#"{{"Status":0,"Message":"refused","ErrorCode":{},"ErrorKey":"{}"}}"#, code, key);
+            parse_response::<Value>(code, &body).unwrap_err()
+        };
+        assert!(matches!(failure(404, "PK_NOT_FOUND"), GalaChainError::NotRegistered));
+        assert!(matches!(failure(400, "INSUFFICIENT_BALANCE"), GalaChainError::InsufficientBalance(message) if message == "refused"));
+        assert!(matches!(failure(409, "UNIQUE_TRANSACTION_CONFLICT"), GalaChainError::Conflict(_)));
+        assert!(matches!(failure(403, "FORBIDDEN"), GalaChainError::Auth(_)));
+        assert!(matches!(failure(404, "OBJECT_NOT_FOUND"), GalaChainError::NotFound(_)));
+        assert!(matches!(failure(400, "VALIDATION_FAILED"), GalaChainError::Api(message) if message.starts_with("VALIDATION_FAILED (400)")));
+    }
+
+    #[test]
+    fn test_failures_without_an_envelope() {
+        // A 200 whose envelope says it failed takes its code from ErrorCode
+        let body = r#"{"Status":0,"Message":"no such user","ErrorCode":404}"#;
+        assert!(matches!(parse_response::<Value>(200, body), Err(GalaChainError::NotFound(_))));
+
+        assert!(matches!(parse_response::<Value>(401, "Unauthorized"), Err(GalaChainError::Auth(_))));
+        assert!(matches!(parse_response::<Value>(502, "<html>Bad Gateway</html>"), Err(GalaChainError::Api(message)) if message.starts_with("HTTP 502")));
+    }
+}
@@ -0,0 +1,73 @@
+//! Environment profile tests
+//!
+//! Covers the profile files Settings imports and exports:
+//! - Nothing secret or machine-specific is written
+//! - Importing replaces endpoints and tokens but keeps HTTP options
+//! - Older settings exports and files of other kinds
+
+use crate::api_mode::ApiMode;
+use crate::environment::{EnvironmentProfile, ProfileError};
+use crate::tokens::{parse_token_spec, TokenInfo, TokenRegistry};
+use crate::ApiSettings;
+
+#[cfg(test)]
+mod environment_tests {
+    use super::*;
+
+    fn team_settings() -> ApiSettings {
+        let mut settings = ApiSettings::default();
+        settings.operations_base_url = "https://gateway.example.com".to_string();
+        settings.channel_name = "asset".to_string();
+        settings.mode = ApiMode::Gateway;
+        settings.http.extra_headers.insert("x-api-key".to_string(), "secret-key".to_string());
+        settings.http.proxy = "http://proxy.internal:8080".to_string();
+        settings
+    }
+
+    #[test]
+    fn test_export_leaves_out_http_options() {
+        let json = EnvironmentProfile::from_settings(&team_settings(), &[TokenInfo::gala()]).to_json();
+        assert!(json.contains("\"kind\": \"galachain-environment\""));
+        assert!(json.contains("https://gateway.example.com"));
+        assert!(!json.contains("secret-key"));
+        assert!(!json.contains("proxy.internal"));
+    }
+
+    #[test]
+    fn test_import_keeps_local_options() {
+        let town = parse_token_spec("TOWN|Unit|none|none,TOWN,0").unwrap();
+        let json = EnvironmentProfile::from_settings(&team_settings(), &[town.clone(), town.clone(), TokenInfo::gala()]).to_json();
+
+        let mut settings = ApiSettings::default();
+        settings.demo_mode = true;
+        settings.http.timeout_secs = 120;
+        let mut registry = TokenRegistry::default();
+        EnvironmentProfile::from_json(&json).unwrap().apply(&mut settings, &mut registry);
+
+        assert_eq!(settings.operations_base_url, "https://gateway.example.com");
+        assert_eq!(settings.channel_name, "asset");
+        assert_eq!(settings.mode, ApiMode::Gateway);
+        assert!(settings.demo_mode);
+        assert_eq!(settings.http.timeout_secs, 120);
+        assert!(settings.http.extra_headers.is_empty());
+        // The duplicate is dropped and GALA stays selected
+        assert_eq!(registry.tokens, vec![town, TokenInfo::gala()]);
+        assert_eq!(registry.selected().symbol, "GALA");
+    }
+
+    #[test]
+    fn test_old_settings_export_and_other_files() {
+        let old_export = serde_json::to_string_pretty(&team_settings()).unwrap();
+        let profile = EnvironmentProfile::from_json(&old_export).unwrap();
+        assert_eq!(profile.channel_name, "asset");
+        assert!(profile.tokens.is_empty());
+
+        let mut registry = TokenRegistry::default();
+        registry.add(parse_token_spec("TOWN|Unit|none|none").unwrap()).unwrap();
+        profile.apply(&mut ApiSettings::default(), &mut registry);
+        assert_eq!(registry.tokens.len(), 2, "a profile without tokens leaves the list alone");
+
+        assert_eq!(EnvironmentProfile::from_json(r#"{"kind":"wallet-backup"}"#), Err(ProfileError::WrongKind("wallet-backup".to_string())));
+        assert!(matches!(EnvironmentProfile::from_json("[1, 2]"), Err(ProfileError::Json(_))));
+    }
+}
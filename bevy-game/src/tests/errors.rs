@@ -0,0 +1,61 @@
+//! Error mapping tests
+//!
+//! Covers how lower-level failures surface in the error banner:
+//! - Stable error codes per failure kind
+//! - Remediation hints that name the right API and setting
+
+use crate::errors::{ApiService, WalletError};
+use crate::backup_sync::SyncError;
+use crate::wallet_bundle::BundleError;
+use crate::{GalaChainError, KeychainError};
+
+#[cfg(test)]
+mod wallet_error_tests {
+    use super::*;
+
+    #[test]
+    fn test_error_codes_are_distinct_per_kind() {
+        let errors = [
+            WalletError::from(KeychainError::NotFound),
+            WalletError::from(KeychainError::Access("denied".to_string())),
+            WalletError::api(ApiService::Identity, GalaChainError::Network("refused".to_string())),
+            WalletError::api(ApiService::Identity, GalaChainError::NotRegistered),
+            WalletError::api(ApiService::Operations, GalaChainError::Cancelled),
+            WalletError::InvalidMnemonic("bad word".to_string()),
+            WalletError::from(BundleError::WrongPassphrase),
+            WalletError::from(SyncError::Upload("503".to_string())),
+            WalletError::from(SyncError::MissingCredentials),
+            WalletError::Locked,
+            WalletError::from("something else".to_string()),
+        ];
+        let mut codes: Vec<&str> = errors.iter().map(WalletError::code).collect();
+        codes.sort_unstable();
+        codes.dedup();
+        assert_eq!(codes.len(), errors.len());
+    }
+
+    #[test]
+    fn test_network_hint_names_the_service() {
+        let identity = WalletError::api(ApiService::Identity, GalaChainError::Network("refused".to_string()));
+        assert_eq!(identity.code(), "NET-001");
+        assert_eq!(identity.hint().unwrap(), "Identity API unreachable — check Settings → endpoints");
+
+        let operations = WalletError::api(ApiService::Operations, GalaChainError::Network("timeout".to_string()));
+        assert!(operations.hint().unwrap().starts_with("Operations API unreachable"));
+        assert_eq!(operations.to_string(), "Operations API: Network error: timeout");
+    }
+
+    #[test]
+    fn test_keychain_errors_keep_their_message() {
+        let error = WalletError::from(KeychainError::NotFound);
+        assert_eq!(error.to_string(), "Wallet not found in keychain");
+        assert!(error.hint().is_some());
+    }
+
+    #[test]
+    fn test_string_errors_have_no_hint() {
+        let error = WalletError::from("Failed to store wallet".to_string());
+        assert_eq!(error.code(), "GEN-001");
+        assert!(error.hint().is_none());
+    }
+}
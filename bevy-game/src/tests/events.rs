@@ -0,0 +1,83 @@
+//! Burn-and-claim event tests
+//!
+//! Covers the event book behind the Events screen:
+//! - The dapp-template's event is there on first launch
+//! - Definitions that can't be entered say why
+//! - An owner's entry is found, and the claim record carries its uniqueKey
+//! - Entries and claims survive a save/load round trip
+
+use crate::events::{EventBook, EventDefinition, EventEntry};
+use rust_decimal::Decimal;
+use serde_json::json;
+
+#[cfg(test)]
+mod events_tests {
+    use super::*;
+
+    const OWNER: &str = "eth|0000000000000000000000000000000000000001";
+
+    fn entry(unique_key: &str, owner: &str) -> EventEntry {
+        EventEntry {
+            event_id: "january-2025-event".to_string(),
+            owner: owner.to_string(),
+            unique_key: unique_key.to_string(),
+            quantity: Decimal::ONE,
+            burned_unix: 1_700_000_000,
+            claimed_unix: None,
+            note: None,
+        }
+    }
+
+    #[test]
+    fn test_default_event() {
+        let book = EventBook::default();
+        let event = book.event("january-2025-event").unwrap();
+        assert_eq!(event.key_prefix, "january-2025-event");
+        assert_eq!(event.token, "GALA");
+        assert!(event.claim_endpoint.is_empty());
+        assert_eq!(event.problem(), None);
+    }
+
+    #[test]
+    fn test_definition_problems() {
+        let mut event = EventDefinition::january_2025();
+        event.required_burn = Decimal::ZERO;
+        assert!(event.problem().is_some());
+        let mut event = EventDefinition::january_2025();
+        event.key_prefix = " ".to_string();
+        assert!(event.problem().is_some());
+    }
+
+    #[test]
+    fn test_entry_lookup_and_claim_record() {
+        let mut book = EventBook::default();
+        book.record(entry("january-2025-event-A", "eth|0000000000000000000000000000000000000002"));
+        book.record(entry("january-2025-event-B", OWNER));
+
+        assert!(book.entry("january-2025-event", OWNER).is_some_and(|entry| entry.unique_key == "january-2025-event-B"));
+        assert!(book.entry("another-event", OWNER).is_none());
+        assert_eq!(
+            book.entry("january-2025-event", OWNER).unwrap().claim_record(),
+            json!({ "event": "january-2025-event", "owner": OWNER, "uniqueKey": "january-2025-event-B", "quantity": "1" })
+        );
+
+        book.entry_mut("january-2025-event-B").unwrap().claimed_unix = Some(1_700_000_100);
+        assert!(book.entry("january-2025-event", OWNER).unwrap().is_claimed());
+    }
+
+    #[test]
+    fn test_event_book_round_trip() {
+        let path = std::env::temp_dir().join(format!("galachain-events-{}.json", std::process::id()));
+        let mut book = EventBook::default();
+        book.events[0].claim_endpoint = "https://example.com/claim".to_string();
+        book.record(entry("january-2025-event-A", OWNER));
+        book.save(Some(path.clone())).unwrap();
+
+        assert_eq!(EventBook::load(Some(path.clone())), book);
+        std::fs::remove_file(&path).unwrap();
+        // A file with only entries keeps the default event
+        std::fs::write(&path, r#"{"entries": []}"#).unwrap();
+        assert_eq!(EventBook::load(Some(path.clone())).events, vec![EventDefinition::january_2025()]);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,73 @@
+//! DTO explanation tests
+//!
+//! Covers the plain-English summaries in explain.rs:
+//! - Transfers, burns and allowances named by the token list and "your wallet"
+//! - Unknown methods, and known ones missing a field, fall back to a field list
+//! - The raw JSON always comes along
+
+use crate::explain::{explain, Names};
+use super::test_utils::town;
+use crate::tokens::TokenInfo;
+use serde_json::json;
+
+#[cfg(test)]
+mod explain_tests {
+    use super::*;
+
+    const OWN: &str = "0x1234567890123456789012345678901234567890";
+
+    fn key(collection: &str) -> serde_json::Value {
+        json!({ "collection": collection, "category": "Unit", "type": "none", "additionalKey": "none", "instance": "0" })
+    }
+
+    #[test]
+    fn test_known_methods_in_plain_english() {
+        let tokens = [TokenInfo::gala(), town(8)];
+        let names = Names { own_address: Some(OWN), tokens: &tokens };
+        let own_alias = crate::address::galachain_alias(OWN);
+
+        let transfer = json!({ "from": own_alias, "to": "client|alice", "tokenInstance": key("GALA"), "quantity": "1500.5", "uniqueKey": "k" });
+        assert_eq!(explain("TransferToken", &transfer, &names).summary, "Send 1,500.50 GALA from your wallet to client|alice");
+
+        let burn = json!({ "owner": own_alias, "tokenInstances": [{ "tokenInstanceKey": key("GALA"), "quantity": "25" }] });
+        let explanation = explain("BurnTokens", &burn, &names);
+        assert_eq!(explanation.summary, "Burn 25.00 GALA from your wallet");
+        assert!(explanation.known);
+
+        let grant = json!({
+            "tokenInstance": key("TOWN"),
+            "allowanceType": 3,
+            "quantities": [{ "user": "client|alice", "quantity": "100" }],
+            "uses": "5",
+            "expires": 0
+        });
+        let explanation = explain("GrantAllowance", &grant, &names);
+        assert_eq!(explanation.summary, "Grant client|alice an allowance to transfer up to 100.00 TOWN");
+        assert_eq!(explanation.details, ["Usable 5 times", "Never expires"]);
+
+        // Not in the token list: named by collection, with the NFT instance
+        let mut nft = key("DRAGONS");
+        nft["instance"] = json!("42");
+        let lock = json!({ "tokenInstance": nft, "quantity": 1 });
+        assert_eq!(explain("LockToken", &lock, &names).summary, "Lock 1.00 DRAGONS #42 in your wallet");
+    }
+
+    #[test]
+    fn test_unknown_or_incomplete_dtos_list_their_fields() {
+        let names = Names { own_address: None, tokens: &[] };
+        let dto = json!({ "poolId": "p1", "amount": 7, "uniqueKey": "k", "note": "x".repeat(100) });
+        let explanation = explain("AddLiquidity", &dto, &names);
+        assert!(!explanation.known);
+        assert_eq!(explanation.summary, "Call AddLiquidity on GalaChain");
+        assert_eq!(explanation.details.len(), 3);
+        assert!(explanation.details.contains(&"poolId: p1".to_string()));
+        assert!(explanation.details.iter().any(|line| line.starts_with("note: xxx") && line.ends_with('…')));
+        assert_eq!(explanation.raw, serde_json::to_string_pretty(&dto).unwrap());
+
+        // A transfer without a recipient isn't guessed at
+        let transfer = json!({ "tokenInstance": key("GALA"), "quantity": "1" });
+        let explanation = explain("TransferToken", &transfer, &names);
+        assert!(!explanation.known);
+        assert_eq!(explanation.summary, "Call TransferToken on GalaChain");
+    }
+}
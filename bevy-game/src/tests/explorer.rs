@@ -0,0 +1,113 @@
+//! Chain Explorer tests
+//!
+//! Covers the queries and bookmarks in explorer.rs:
+//! - Typed keys become composite keys and the DTO each method takes
+//! - Keys that can't be sent are refused with a reason
+//! - The method's URL follows the contract URL it's given
+//! - Results are pretty-printed, counted and cut when long
+//! - Bookmarks are kept newest first without duplicates
+
+use crate::explorer::{
+    composite_key, display_key, method_url, pretty, Bookmark, Bookmarks, ExplorerMethod, ExplorerQuery, MAX_BOOKMARKS, MAX_SHOWN_CHARS,
+};
+use serde_json::json;
+
+#[cfg(test)]
+mod explorer_tests {
+    use super::*;
+
+    #[test]
+    fn test_composite_key() {
+        let attributes = vec!["GALA".to_string(), "Unit".to_string(), "none".to_string(), "0".to_string()];
+        let key = composite_key("GCTI", &attributes);
+        assert_eq!(key, "\u{0}GCTI\u{0}GALA\u{0}Unit\u{0}none\u{0}0\u{0}");
+        assert_eq!(display_key(&key), "GCTI|GALA|Unit|none|0");
+    }
+
+    #[test]
+    fn test_get_object_by_key_dto() {
+        let query = ExplorerQuery::parse(ExplorerMethod::GetObjectByKey, " GCTI | GALA|Unit|none|0| ").unwrap();
+        assert_eq!(query.key(), "GCTI|GALA|Unit|none|0");
+        assert_eq!(query.dto(), json!({ "objectId": "\u{0}GCTI\u{0}GALA\u{0}Unit\u{0}none\u{0}0\u{0}" }));
+
+        // A key pasted from chain output reads the same
+        let pasted = ExplorerQuery::parse(ExplorerMethod::GetObjectByKey, "\u{0}GCTI\u{0}GALA\u{0}Unit\u{0}none\u{0}0\u{0}").unwrap();
+        assert_eq!(pasted, query);
+    }
+
+    #[test]
+    fn test_partial_key_dto() {
+        let query = ExplorerQuery::parse(ExplorerMethod::GetObjectsByPartialCompositeKey, "GCTB|eth|abc").unwrap();
+        assert_eq!(query.dto(), json!({ "objectType": "GCTB", "attributes": ["eth", "abc"] }));
+
+        // Every object of a type
+        let all = ExplorerQuery::parse(ExplorerMethod::GetObjectsByPartialCompositeKey, "GCTB").unwrap();
+        assert_eq!(all.dto(), json!({ "objectType": "GCTB", "attributes": [] }));
+    }
+
+    #[test]
+    fn test_keys_that_cant_be_sent() {
+        assert!(ExplorerQuery::parse(ExplorerMethod::GetObjectByKey, "").is_err());
+        assert!(ExplorerQuery::parse(ExplorerMethod::GetObjectByKey, "|GALA").is_err());
+        assert!(ExplorerQuery::parse(ExplorerMethod::GetObjectByKey, "GCTI").unwrap_err().contains("whole key"));
+        assert!(ExplorerQuery::parse(ExplorerMethod::GetObjectsByPartialCompositeKey, "GCTI GALA").is_err());
+    }
+
+    #[test]
+    fn test_method_url() {
+        assert_eq!(
+            method_url("http://localhost:3000/api/product/GalaChainToken/FetchBalances", "GetObjectByKey"),
+            "http://localhost:3000/api/product/GalaChainToken/GetObjectByKey"
+        );
+        assert_eq!(
+            method_url("https://gateway-mainnet.galachain.com/api/asset/token-contract/FetchBalances", ExplorerMethod::GetObjectsByPartialCompositeKey.name()),
+            "https://gateway-mainnet.galachain.com/api/asset/token-contract/GetObjectsByPartialCompositeKey"
+        );
+    }
+
+    #[test]
+    fn test_pretty() {
+        assert_eq!(pretty(&json!({ "symbol": "GALA" })), "{\n  \"symbol\": \"GALA\"\n}");
+        assert!(pretty(&json!([{ "a": 1 }, { "a": 2 }])).starts_with("2 objects\n["));
+        assert!(pretty(&json!([{ "a": 1 }])).starts_with("1 object\n"));
+        assert_eq!(pretty(&json!(null)), "Nothing returned");
+
+        let long = pretty(&json!("x".repeat(MAX_SHOWN_CHARS * 2)));
+        assert!(long.contains("more characters"));
+        assert!(long.chars().count() < MAX_SHOWN_CHARS + 100);
+    }
+
+    #[test]
+    fn test_bookmarks() {
+        let mut bookmarks = Bookmarks::default();
+        let query = ExplorerQuery::parse(ExplorerMethod::GetObjectByKey, "GCTI|GALA|Unit|none|0").unwrap();
+        let bookmark = Bookmark::of(&query);
+        assert_eq!(bookmark.label(), "GetObjectByKey GCTI|GALA|Unit|none|0");
+        assert!(bookmarks.add(bookmark.clone()));
+        assert!(!bookmarks.add(bookmark.clone()));
+
+        // The same key under the other method is a bookmark of its own
+        let partial = Bookmark { method: ExplorerMethod::GetObjectsByPartialCompositeKey, key: bookmark.key.clone() };
+        assert!(bookmarks.add(partial.clone()));
+        assert_eq!(bookmarks.entries, vec![partial.clone(), bookmark.clone()]);
+
+        bookmarks.remove(&partial);
+        assert_eq!(bookmarks.entries, vec![bookmark]);
+
+        for index in 0..MAX_BOOKMARKS + 5 {
+            bookmarks.add(Bookmark { method: ExplorerMethod::GetObjectByKey, key: format!("GCTI|T{}", index) });
+        }
+        assert_eq!(bookmarks.entries.len(), MAX_BOOKMARKS);
+        assert_eq!(bookmarks.entries[0].key, format!("GCTI|T{}", MAX_BOOKMARKS + 4));
+    }
+
+    #[test]
+    fn test_bookmarks_round_trip() {
+        let path = std::env::temp_dir().join(format!("galachain-explorer-test-{}.json", std::process::id()));
+        let mut bookmarks = Bookmarks::default();
+        bookmarks.add(Bookmark { method: ExplorerMethod::GetObjectsByPartialCompositeKey, key: "GCTB|eth".to_string() });
+        bookmarks.save(Some(path.clone())).unwrap();
+        assert_eq!(Bookmarks::load(Some(path.clone())), bookmarks);
+        let _ = std::fs::remove_file(path);
+    }
+}
@@ -0,0 +1,57 @@
+//! Balance export tests
+//!
+//! Covers the CSV/JSON files written by Export Data:
+//! - UTC timestamps and exact decimal quantities
+//! - Format chosen by file extension
+
+use crate::export::{format_utc, save, to_csv, to_json, BalanceRecord, ExportError};
+use rust_decimal::Decimal;
+use std::time::{Duration, UNIX_EPOCH};
+
+#[cfg(test)]
+mod export_tests {
+    use super::*;
+
+    fn record() -> BalanceRecord {
+        BalanceRecord {
+            fetched_at: UNIX_EPOCH + Duration::from_secs(1_738_314_300),
+            address: "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed".to_string(),
+            galachain_address: "eth|5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed".to_string(),
+            token: "GALA".to_string(),
+            available: Decimal::new(123456789, 8),
+            locked: Decimal::new(5, 1),
+        }
+    }
+
+    #[test]
+    fn test_format_utc() {
+        assert_eq!(format_utc(UNIX_EPOCH), "1970-01-01T00:00:00Z");
+        assert_eq!(format_utc(UNIX_EPOCH + Duration::from_secs(1_738_314_300)), "2025-01-31T09:05:00Z");
+    }
+
+    #[test]
+    fn test_csv_has_header_and_exact_quantities() {
+        let csv = to_csv(&[record()]);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "fetched_at,address,galachain_address,token,available,locked,total");
+        assert_eq!(
+            lines[1],
+            "2025-01-31T09:05:00Z,0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed,eth|5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed,GALA,1.23456789,0.5,1.73456789"
+        );
+    }
+
+    #[test]
+    fn test_json_uses_strings_for_quantities() {
+        let json: serde_json::Value = serde_json::from_str(&to_json(&[record()], UNIX_EPOCH)).unwrap();
+        assert_eq!(json["exported_at"], "1970-01-01T00:00:00Z");
+        assert_eq!(json["balances"][0]["available"], "1.23456789");
+        assert_eq!(json["balances"][0]["fetched_at"], "2025-01-31T09:05:00Z");
+    }
+
+    #[test]
+    fn test_unknown_extension_is_rejected() {
+        let path = std::env::temp_dir().join("galachain-export-test.xlsx");
+        assert!(matches!(save(&[record()], &path), Err(ExportError::UnsupportedFormat(ext)) if ext == "xlsx"));
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,56 @@
+//! Dropped file tests
+//!
+//! Covers how files dropped onto the window are sorted:
+//! - Seed phrase text files, lowercased, with exactly 12 words
+//! - Wallet entry JSON versus environment profiles versus anything else
+//! - Encrypted keystores, backup sheets and unknown extensions are refused with a reason
+
+use crate::environment::{EnvironmentProfile, ProfileError};
+use crate::file_drop::{classify, read_dropped, DropError, DroppedFile};
+use std::path::Path;
+
+#[cfg(test)]
+mod file_drop_tests {
+    use super::*;
+
+    const PHRASE: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    #[test]
+    fn test_seed_phrase_files() {
+        let dropped = classify("txt", &format!("  {}\n", PHRASE.to_uppercase())).unwrap();
+        let words: Vec<String> = PHRASE.split(' ').map(str::to_string).collect();
+        assert_eq!(dropped, DroppedFile::SeedPhrase(words));
+        assert_eq!(classify("txt", "abandon about"), Err(DropError::WordCount(2)));
+    }
+
+    #[test]
+    fn test_json_files() {
+        let entry = r#"{"wallet_type":"watch_only","mnemonic":"","address":"0x0000000000000000000000000000000000000001","created_at":0}"#;
+        assert_eq!(classify("json", entry), Ok(DroppedFile::WalletEntry(entry.to_string())));
+
+        let profile = EnvironmentProfile::default();
+        assert_eq!(classify("json", &profile.to_json()), Ok(DroppedFile::EnvironmentProfile(profile)));
+        assert_eq!(
+            classify("json", r#"{"kind":"something-else"}"#),
+            Err(DropError::Profile(ProfileError::WrongKind("something-else".to_string())))
+        );
+
+        // Every profile field has a default, so unrelated JSON mustn't pass as one
+        assert_eq!(classify("json", r#"{"name":"not a profile"}"#), Err(DropError::Unrecognized));
+        assert_eq!(classify("json", "[1, 2]"), Err(DropError::Unrecognized));
+        assert_eq!(classify("json", r#"{"version":3,"crypto":{"cipher":"aes-128-ctr"}}"#), Err(DropError::EncryptedKeystore));
+        assert!(matches!(classify("json", "{"), Err(DropError::Profile(ProfileError::Json(_)))));
+    }
+
+    #[test]
+    fn test_read_dropped() {
+        assert_eq!(read_dropped(Path::new("backup.PDF")), Err(DropError::BackupSheet));
+        assert_eq!(read_dropped(Path::new("wallet.dat")), Err(DropError::Unsupported("dat".to_string())));
+        assert!(matches!(read_dropped(Path::new("/nonexistent/seed.txt")), Err(DropError::Read(_))));
+
+        let path = std::env::temp_dir().join(format!("galachain-drop-{}.txt", std::process::id()));
+        std::fs::write(&path, PHRASE).unwrap();
+        assert!(matches!(read_dropped(&path), Ok(DroppedFile::SeedPhrase(words)) if words.len() == 12));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,111 @@
+//! Encrypted keyring file tests
+//!
+//! Covers the keychain used on Linux desktops without a Secret Service:
+//! - Entries round-trip once the file is unlocked, and survive locking again
+//! - Creating the file needs the passphrase confirmed; opening it doesn't
+//! - A file asking for more key-stretching rounds than a bundle may is refused
+//! - A wrong passphrase doesn't unlock an existing file
+//! - A locked file fails like a locked keychain
+//! - Each entry only opens under its own name
+
+use crate::file_keyring::{FileKey, FileKeyring};
+use crate::wallet_bundle::MAX_KDF_ITERATIONS;
+use crate::{KeychainError, SecretStore};
+use std::path::{Path, PathBuf};
+
+#[cfg(test)]
+mod file_keyring_tests {
+    use super::*;
+
+    const PASSPHRASE: &str = "correct horse battery";
+    // Tests don't need a real key stretch
+    const ITERATIONS: u32 = 10;
+
+    fn keyring_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("galachain-file-keyring-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir.join("keyring.enc.json")
+    }
+
+    fn entry(path: &Path, name: &str, key: &FileKey) -> FileKeyring {
+        FileKeyring { path: Some(path.to_path_buf()), name: name.to_string(), key: key.clone() }
+    }
+
+    #[test]
+    fn test_entries_round_trip_once_unlocked() {
+        let path = keyring_path("round-trip");
+        let key = FileKey::default();
+        assert!(key.unlock_with_iterations(&path, "too short", "too short", ITERATIONS).is_err());
+        assert!(matches!(key.unlock_with_iterations(&path, PASSPHRASE, "correct horse battery!", ITERATIONS), Err(KeychainError::Access(_))));
+        assert!(!path.exists());
+        key.unlock_with_iterations(&path, PASSPHRASE, PASSPHRASE, ITERATIONS).unwrap();
+        let wallet = entry(&path, "default-wallet", &key);
+        assert!(matches!(wallet.get(), Err(KeychainError::NotFound)));
+        wallet.set("wallet entry").unwrap();
+        assert_eq!(wallet.get().unwrap(), "wallet entry");
+        assert!(!std::fs::read_to_string(&path).unwrap().contains("wallet entry"));
+
+        // Another session unlocks the same file, with no confirmation to type
+        let key = FileKey::default();
+        key.unlock_with_iterations(&path, PASSPHRASE, "", ITERATIONS).unwrap();
+        let wallet = entry(&path, "default-wallet", &key);
+        assert_eq!(wallet.get().unwrap(), "wallet entry");
+        wallet.delete().unwrap();
+        assert!(matches!(wallet.get(), Err(KeychainError::NotFound)));
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_wrong_passphrase_and_locked_file_are_access_errors() {
+        let path = keyring_path("locked");
+        FileKey::default().unlock_with_iterations(&path, PASSPHRASE, PASSPHRASE, ITERATIONS).unwrap();
+
+        let key = FileKey::default();
+        assert!(matches!(key.unlock_with_iterations(&path, "incorrect horse battery", "", ITERATIONS), Err(KeychainError::Access(_))));
+        assert!(!key.is_unlocked());
+        let wallet = entry(&path, "default-wallet", &key);
+        assert!(matches!(wallet.get(), Err(KeychainError::Access(_))));
+        assert!(matches!(wallet.set("wallet entry"), Err(KeychainError::Access(_))));
+
+        key.unlock_with_iterations(&path, PASSPHRASE, PASSPHRASE, ITERATIONS).unwrap();
+        wallet.set("wallet entry").unwrap();
+        key.lock();
+        assert!(matches!(wallet.get(), Err(KeychainError::Access(_))));
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_entries_are_bound_to_their_names() {
+        let path = keyring_path("names");
+        let key = FileKey::default();
+        key.unlock_with_iterations(&path, PASSPHRASE, PASSPHRASE, ITERATIONS).unwrap();
+        entry(&path, "default-wallet", &key).set("wallet entry").unwrap();
+        entry(&path, "previous-wallets", &key).set("archive").unwrap();
+
+        // Swap the two sealed entries in the file
+        let mut file: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        let entries = file["entries"].as_object_mut().unwrap();
+        let wallet = entries["default-wallet"].clone();
+        entries.insert("default-wallet".to_string(), entries["previous-wallets"].clone());
+        entries.insert("previous-wallets".to_string(), wallet);
+        std::fs::write(&path, serde_json::to_string(&file).unwrap()).unwrap();
+
+        assert!(matches!(entry(&path, "default-wallet", &key).get(), Err(KeychainError::Deserialize(_))));
+        assert!(matches!(entry(&path, "previous-wallets", &key).get(), Err(KeychainError::Deserialize(_))));
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_too_many_iterations_refused() {
+        let path = keyring_path("iterations");
+        FileKey::default().unlock_with_iterations(&path, PASSPHRASE, PASSPHRASE, ITERATIONS).unwrap();
+        let mut file: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        file["iterations"] = serde_json::json!(MAX_KDF_ITERATIONS + 1);
+        std::fs::write(&path, serde_json::to_string(&file).unwrap()).unwrap();
+
+        let key = FileKey::default();
+        assert!(key.unlock_with_iterations(&path, PASSPHRASE, "", ITERATIONS).is_err());
+        assert!(!key.is_unlocked());
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+}
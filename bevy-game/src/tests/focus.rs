@@ -7,7 +7,7 @@
 //! - Tab navigation
 
 use super::test_utils::*;
-use crate::{FocusedInput, FocusedInputType};
+use crate::{FocusedInput, FocusedInputType, TextEdit, TextEntry};
 
 // Note: These are placeholder tests for the focus system.
 // Full integration testing with Bevy UI would require setting up a test app
@@ -114,7 +114,29 @@ mod focus_logic_tests {
                 FocusedInputType::TransferRecipient => true,
                 FocusedInputType::TransferAmount => true,
                 FocusedInputType::BurnAmount => true,
-                FocusedInputType::SettingsUrl => true,
+                FocusedInputType::SettingsOperationsUrl | FocusedInputType::SettingsIdentityUrl => true,
+                FocusedInputType::DeleteConfirmation => true,
+                FocusedInputType::OnboardingVerifyWord(_) => true,
+                FocusedInputType::WatchAddress => true,
+                FocusedInputType::DashboardAddress => true,
+                FocusedInputType::SettingsTokenSpec => true,
+                FocusedInputType::SettingsApprovalEndpoint | FocusedInputType::SettingsApprovalDeviceKey => true,
+                FocusedInputType::TransactionNote => true,
+                FocusedInputType::SponsorPublicKey => true,
+                FocusedInputType::SettingsProxy | FocusedInputType::SettingsHeader => true,
+                FocusedInputType::SettingsEndpoint(_) | FocusedInputType::SettingsEndpointHost(_) => true,
+                FocusedInputType::GatewayHeader | FocusedInputType::GatewaySecret => true,
+                FocusedInputType::BackupTarget(_) => true,
+                FocusedInputType::BundlePassphrase | FocusedInputType::BundleConfirmation => true,
+                FocusedInputType::RestorePassphrase => true,
+                FocusedInputType::KeychainPassphrase | FocusedInputType::KeychainConfirmation => true,
+                FocusedInputType::PresencePassphrase => true,
+                FocusedInputType::PresenceFallback => true,
+                FocusedInputType::IpcPin => true,
+                FocusedInputType::IpcNewPin => true,
+                FocusedInputType::NewWalletName => true,
+                FocusedInputType::ExplorerKey => true,
+                FocusedInputType::ExplorerField(_) => true,
             }
         }
         
@@ -218,6 +240,48 @@ mod keyboard_input_tests {
         assert!(!is_valid_amount_char('-', ""));
         assert!(!is_valid_amount_char(' ', "123"));
     }
+
+    #[test]
+    fn test_text_entry_filters() {
+        let entry = TextEntry {
+            edits: "Ab1.2.".chars().map(TextEdit::Insert).collect(),
+            tab: false,
+        };
+
+        let mut word = String::new();
+        assert!(entry.apply(&mut word, crate::seed_word_char));
+        assert_eq!(word, "ab");
+
+        let mut amount = String::new();
+        assert!(entry.apply(&mut amount, |c, text| crate::amount_char(c, text, 8)));
+        assert_eq!(amount, "1.2");
+
+        // A whole-unit token takes no decimal point
+        let mut whole = String::new();
+        assert!(entry.apply(&mut whole, |c, text| crate::amount_char(c, text, 0)));
+        assert_eq!(whole, "12");
+
+        let mut address = String::from("eth|");
+        assert!(entry.apply(&mut address, crate::address_char));
+        assert_eq!(address, "eth|Ab1.2.");
+    }
+
+    #[test]
+    fn test_text_entry_backspace() {
+        let entry = TextEntry {
+            edits: vec![TextEdit::Backspace, TextEdit::Backspace, TextEdit::Insert('x')],
+            tab: false,
+        };
+
+        let mut text = String::from("abc");
+        assert!(entry.apply(&mut text, crate::address_char));
+        assert_eq!(text, "ax");
+
+        // Backspace on an empty field with nothing accepted is not a change
+        let mut empty = String::new();
+        let only_backspace = TextEntry { edits: vec![TextEdit::Backspace], tab: false };
+        assert!(!only_backspace.apply(&mut empty, |c, text| crate::amount_char(c, text, 8)));
+    }
 }
 
 // Note: Full integration tests with Bevy UI would require:
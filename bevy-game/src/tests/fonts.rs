@@ -0,0 +1,44 @@
+//! UI font and icon tests
+//!
+//! Covers the bundled font and the glyphs standing in for emoji:
+//! - Every icon's glyph is in the bundled font
+//! - Emoji in UI text are swapped for glyphs, and text without them is left alone
+//! - Icons map back from the emoji they replace
+
+use crate::fonts::{replace_emoji, Icon, UI_FONT_DATA};
+use ab_glyph::{Font, FontRef};
+
+#[cfg(test)]
+mod font_tests {
+    use super::*;
+
+    #[test]
+    fn test_icon_glyphs_are_in_the_font() {
+        let font = FontRef::try_from_slice(UI_FONT_DATA).unwrap();
+        for icon in Icon::ALL {
+            assert_ne!(font.glyph_id(icon.glyph()).0, 0, "{:?} glyph missing", icon);
+        }
+        // Symbols the UI uses as they are
+        for symbol in "·—•…←→↩▸▾○●✓✕✗".chars() {
+            assert_ne!(font.glyph_id(symbol).0, 0, "{} missing", symbol);
+        }
+    }
+
+    #[test]
+    fn test_replace_emoji() {
+        assert_eq!(replace_emoji("✅ Registered"), Some("✔ Registered".to_string()));
+        assert_eq!(replace_emoji("📤 3 transfers sent · 🔥 2 burns"), Some("⇧ 3 transfers sent · ♨ 2 burns".to_string()));
+        // The emoji presentation selector goes, the warning sign stays
+        assert_eq!(replace_emoji("⚠️ Degraded"), Some("⚠ Degraded".to_string()));
+        assert_eq!(replace_emoji("⚠ Degraded"), None);
+        assert_eq!(replace_emoji("● Operations → ok"), None);
+    }
+
+    #[test]
+    fn test_icons_from_emoji() {
+        for icon in Icon::ALL {
+            assert_eq!(Icon::from_emoji(icon.emoji()), Some(icon));
+        }
+        assert_eq!(Icon::from_emoji('a'), None);
+    }
+}
@@ -0,0 +1,289 @@
+//! Headless app harness for UI flow tests
+//!
+//! Builds the wallet `App` on `MinimalPlugins` with the real `MenuPlugin`, but with:
+//! - in-memory `SecretStore`s instead of the OS keychain, for the wallet, its archive and gateway credentials
+//! - a throwaway config directory instead of the user's settings
+//! - a local mock GalaChain server that answers balance and registration calls
+//!
+//! There is no renderer or UI focus pass, so tests press buttons by setting
+//! their `Interaction` directly and read screens back from `Text` components.
+
+use crate::{add_headless_plugins, set_config_dir, AppState, BackupSync, GatewayAuth, KeychainError, KeychainManager, MenuPlugin, SecretStore, WalletState};
+use crate::locale::LocaleSetting;
+use crate::{ApiSettings, GalaChainClient, UiStateStore};
+use bevy::input::keyboard::{Key, KeyboardInput, NativeKey};
+use bevy::input::ButtonState;
+use bevy::prelude::*;
+use bevy::state::state::FreelyMutableState;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+/// Keeps one harness at a time: the config directory override is process-wide.
+static HARNESS_LOCK: Mutex<()> = Mutex::new(());
+static HARNESS_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+const TOKEN_CLASSES: &str = r#"{"Status":1,"Data":[{"collection":"GALA","category":"Unit","type":"none","additionalKey":"none","symbol":"GALA","decimals":8},{"collection":"TOWN","category":"Unit","type":"none","additionalKey":"none","symbol":"TOWN","decimals":0,"metadataAddress":"{base}/metadata/{id}"}]}"#;
+const TOKEN_INSTANCES: &str = r#"{"Status":1,"Data":[{"instance":"1","quantity":"1","owner":"client|mock"},{"instance":"2","quantity":"1","owner":"client|mock"}]}"#;
+const INSTANCE_METADATA: &str = r#"{"name":"Town Hall","description":"The first building","attributes":[{"trait_type":"Level","value":3}]}"#;
+
+/// Wallet entry held in memory, shared with the app's `KeychainManager`.
+#[derive(Clone, Default)]
+pub struct MemorySecretStore(Arc<Mutex<Option<String>>>);
+
+impl MemorySecretStore {
+    pub fn contents(&self) -> Option<String> {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+impl SecretStore for MemorySecretStore {
+    fn get(&self) -> Result<String, KeychainError> {
+        self.0.lock().unwrap().clone().ok_or(KeychainError::NotFound)
+    }
+
+    fn set(&self, secret: &str) -> Result<(), KeychainError> {
+        *self.0.lock().unwrap() = Some(secret.to_string());
+        Ok(())
+    }
+
+    fn delete(&self) -> Result<(), KeychainError> {
+        self.0.lock().unwrap().take().map(|_| ()).ok_or(KeychainError::NotFound)
+    }
+}
+
+/// A GalaChain stand-in on a local port. Every address holds `balance` GALA
+/// (plus `locked` in one hold that never expires) and is registered under a placeholder key that no
+/// wallet matches; registrations, key updates, burns and event claims (`/claim`)
+/// are accepted and request paths are recorded. Token classes are GALA and a
+/// whole-unit TOWN, whose instances 1 and 2 every address holds, with metadata
+/// served from `/metadata/<instance>`.
+pub struct MockChainServer {
+    pub base_url: String,
+    pub requests: Arc<Mutex<Vec<String>>>,
+}
+
+impl MockChainServer {
+    pub fn start(balance: &str, locked: &str) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind mock server");
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let balances = format!(
+            r#"{{"Status":1,"Data":[{{"collection":"GALA","category":"Unit","type":"none","additionalKey":"none","instance":"0","quantity":"{}","instanceIds":["1","2"],"lockedHolds":[{{"quantity":"{}","name":"mock-stake","lockAuthority":"client|mock","created":1700000000000,"expires":0}}]}}]}}"#,
+            balance, locked
+        );
+        let token_classes = TOKEN_CLASSES.replace("{base}", &base_url);
+        let recorded = requests.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let mut reader = BufReader::new(stream);
+                let mut request_line = String::new();
+                if reader.read_line(&mut request_line).is_err() {
+                    continue;
+                }
+                let path = request_line.split_whitespace().nth(1).unwrap_or_default().to_string();
+                let mut content_length = 0;
+                loop {
+                    let mut header = String::new();
+                    if reader.read_line(&mut header).unwrap_or(0) == 0 || header == "\r\n" {
+                        break;
+                    }
+                    if let Some(value) = header.to_ascii_lowercase().strip_prefix("content-length:") {
+                        content_length = value.trim().parse().unwrap_or(0);
+                    }
+                }
+                let mut body = vec![0; content_length];
+                let _ = reader.read_exact(&mut body);
+                recorded.lock().unwrap().push(path.clone());
+
+                let (status, response) = if path.ends_with("/FetchBalances") {
+                    ("200 OK", balances.clone())
+                } else if path.ends_with("/FetchTokenClasses") {
+                    ("200 OK", token_classes.clone())
+                } else if path.ends_with("/FetchTokenInstances") {
+                    ("200 OK", TOKEN_INSTANCES.to_string())
+                } else if path.starts_with("/metadata/") {
+                    ("200 OK", INSTANCE_METADATA.to_string())
+                } else if path.ends_with("/GetPublicKey") {
+                    ("200 OK", r#"{"Status":1,"Data":{"publicKey":"mock","signing":"ETH"}}"#.to_string())
+                } else if path.ends_with("/register") || path.ends_with("/UpdatePublicKey") {
+                    ("201 Created", r#"{"Status":1}"#.to_string())
+                } else if path.ends_with("/BurnTokens") {
+                    ("200 OK", r#"{"Status":1,"Data":[]}"#.to_string())
+                } else if path.ends_with("/claim") {
+                    ("200 OK", "{}".to_string())
+                } else {
+                    ("404 Not Found", "{}".to_string())
+                };
+                let _ = write!(
+                    reader.get_mut(),
+                    "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    response.len(),
+                    response
+                );
+            }
+        });
+
+        Self { base_url, requests }
+    }
+
+    pub fn request_count(&self, path_suffix: &str) -> usize {
+        self.requests.lock().unwrap().iter().filter(|path| path.ends_with(path_suffix)).count()
+    }
+}
+
+/// POSTs `body` to a local server with any extra header lines and returns the raw HTTP response.
+pub fn post_json(addr: std::net::SocketAddr, body: &str, extra_headers: &str) -> String {
+    let mut stream = std::net::TcpStream::connect(addr).unwrap();
+    write!(stream, "POST / HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\n{}Content-Length: {}\r\n\r\n{}", addr, extra_headers, body.len(), body).unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    response
+}
+
+pub struct HeadlessApp {
+    pub app: App,
+    pub secrets: MemorySecretStore,
+    pub server: MockChainServer,
+    config_dir: PathBuf,
+    _lock: MutexGuard<'static, ()>,
+}
+
+impl HeadlessApp {
+    /// A first launch: no wallet stored yet.
+    pub fn new() -> Self {
+        Self::build(MemorySecretStore::default())
+    }
+
+    /// A launch with `entry` (a keychain wallet entry) already stored.
+    pub fn with_wallet_entry(entry: &str) -> Self {
+        let secrets = MemorySecretStore::default();
+        secrets.set(entry).unwrap();
+        Self::build(secrets)
+    }
+
+    fn build(secrets: MemorySecretStore) -> Self {
+        let lock = HARNESS_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let config_dir = std::env::temp_dir().join(format!(
+            "galachain-wallet-harness-{}-{}",
+            std::process::id(),
+            HARNESS_COUNT.fetch_add(1, Ordering::Relaxed)
+        ));
+        set_config_dir(Some(config_dir.clone()));
+
+        let server = MockChainServer::start("1234.5", "10");
+        let settings = ApiSettings {
+            operations_base_url: server.base_url.clone(),
+            identity_base_url: server.base_url.clone(),
+            ..ApiSettings::default()
+        };
+
+        let mut app = App::new();
+        add_headless_plugins(&mut app);
+        app.init_state::<AppState>()
+        .init_state::<WalletState>()
+        .add_plugins(MenuPlugin)
+        .insert_resource(KeychainManager::with_store(secrets.clone()).with_archive(MemorySecretStore::default()))
+        .insert_resource(GatewayAuth::with_store(None, MemorySecretStore::default()))
+        .insert_resource(BackupSync::with_store(MemorySecretStore::default()))
+        .insert_resource(GalaChainClient::new(&settings))
+        .insert_resource(settings);
+
+        let mut harness = Self { app, secrets, server, config_dir, _lock: lock };
+        // Expected texts are in the standard format whatever the machine's LANG is
+        harness.app.world_mut().resource_mut::<UiStateStore>().state.locale = LocaleSetting::Standard;
+        // Startup, then the state transition it queues
+        harness.update();
+        harness.update();
+        harness
+    }
+
+    pub fn update(&mut self) {
+        self.app.update();
+    }
+
+    /// Runs frames until `done` holds, for results that arrive from background tasks.
+    pub fn update_until(&mut self, mut done: impl FnMut(&mut Self) -> bool) {
+        for _ in 0..500 {
+            self.update();
+            if done(self) {
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        panic!("condition not reached; screen shows {:?}", self.texts());
+    }
+
+    pub fn state<S: States>(&self) -> S {
+        self.app.world().resource::<State<S>>().get().clone()
+    }
+
+    pub fn set_state<S: FreelyMutableState>(&mut self, state: S) {
+        self.app.world_mut().resource_mut::<NextState<S>>().set(state);
+        self.update();
+        self.update();
+    }
+
+    pub fn resource<R: Resource>(&self) -> &R {
+        self.app.world().resource::<R>()
+    }
+
+    /// Presses the first button with a `C` that matches, as a click would:
+    /// one frame pressed, then one released.
+    pub fn press<C: Component>(&mut self, matches: impl Fn(&C) -> bool) {
+        let world = self.app.world_mut();
+        let button = world
+            .query_filtered::<(Entity, &C), With<Interaction>>()
+            .iter(world)
+            .find(|(_, component)| matches(component))
+            .map(|(entity, _)| entity)
+            .unwrap_or_else(|| panic!("no {} button to press", std::any::type_name::<C>()));
+
+        *world.get_mut::<Interaction>(button).unwrap() = Interaction::Pressed;
+        self.update();
+        if let Some(mut interaction) = self.app.world_mut().get_mut::<Interaction>(button) {
+            *interaction = Interaction::None;
+        }
+        self.update();
+    }
+
+    /// Holds `keys` down for a frame, then lets go, as a chord typed on the keyboard.
+    pub fn press_keys(&mut self, keys: &[KeyCode]) {
+        for state in [ButtonState::Pressed, ButtonState::Released] {
+            for key in keys {
+                self.app.world_mut().send_event(KeyboardInput {
+                    key_code: *key,
+                    logical_key: Key::Unidentified(NativeKey::Unidentified),
+                    state,
+                    repeat: false,
+                    window: Entity::PLACEHOLDER,
+                });
+            }
+            self.update();
+        }
+        // Screen changes are applied the frame after they're requested
+        self.update();
+    }
+
+    /// Every piece of text currently spawned.
+    pub fn texts(&mut self) -> Vec<String> {
+        let world = self.app.world_mut();
+        world.query::<&Text>().iter(world).map(|text| text.0.clone()).collect()
+    }
+
+    pub fn shows(&mut self, needle: &str) -> bool {
+        self.texts().iter().any(|text| text.contains(needle))
+    }
+}
+
+impl Drop for HeadlessApp {
+    fn drop(&mut self) {
+        set_config_dir(None);
+        let _ = std::fs::remove_dir_all(&self.config_dir);
+    }
+}
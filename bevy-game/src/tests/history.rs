@@ -0,0 +1,67 @@
+//! Transaction history tests
+//!
+//! Covers the local history behind the History screen:
+//! - Notes are trimmed and capped, and blank ones aren't stored
+//! - CSV export quotes fields holding commas, quotes or line breaks
+//! - Entries and notes survive a save/load round trip
+
+use crate::history::{clean_note, to_csv, HistoryEntry, TransactionHistory, TxKind, MAX_NOTE_CHARS};
+use rust_decimal::Decimal;
+
+#[cfg(test)]
+mod history_tests {
+    use super::*;
+
+    fn entry(unique_key: &str, note: Option<&str>) -> HistoryEntry {
+        HistoryEntry {
+            unique_key: unique_key.to_string(),
+            unix_time: 1_700_000_000,
+            kind: TxKind::Transfer,
+            symbol: "GALA".to_string(),
+            token: "GALA|Unit|none|0".to_string(),
+            quantity: "12.5".parse().unwrap(),
+            to: Some("eth|0000000000000000000000000000000000000001".to_string()),
+            note: note.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_clean_note() {
+        assert_eq!(clean_note("  rent  "), Some("rent".to_string()));
+        assert_eq!(clean_note(" \t "), None);
+        assert_eq!(clean_note(&"é".repeat(MAX_NOTE_CHARS + 5)).unwrap().chars().count(), MAX_NOTE_CHARS);
+    }
+
+    #[test]
+    fn test_csv_escapes_notes() {
+        let mut burn = entry("k-2", None);
+        burn.kind = TxKind::Burn;
+        burn.to = None;
+        burn.quantity = Decimal::ONE;
+        let csv = to_csv(&[entry("k-1", Some("rent, \"March\"\nsplit")), burn]);
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(lines[0], "time,kind,token,symbol,quantity,to,unique_key,note");
+        assert_eq!(
+            lines[1],
+            "2023-11-14T22:13:20Z,transfer,GALA|Unit|none|0,GALA,12.5,eth|0000000000000000000000000000000000000001,k-1,\"rent, \"\"March\"\""
+        );
+        assert_eq!(lines[2], "split\"");
+        assert_eq!(lines[3], "2023-11-14T22:13:20Z,burn,GALA|Unit|none|0,GALA,1,,k-2,");
+    }
+
+    #[test]
+    fn test_history_round_trip() {
+        let path = std::env::temp_dir().join(format!("galachain-history-{}.json", std::process::id()));
+        let mut history = TransactionHistory::default();
+        history.record(entry("k-1", Some("rent")));
+        history.record(entry("k-2", None));
+        history.save(Some(path.clone())).unwrap();
+
+        let loaded = TransactionHistory::load(Some(path.clone()));
+        assert_eq!(loaded, history);
+        assert_eq!(loaded.entries[0].note.as_deref(), Some("rent"));
+        assert_eq!(loaded.entries[1].note, None);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,45 @@
+//! Token hold tests
+//!
+//! Covers the holds table on the Balance screen:
+//! - FetchBalances holds parse with their name, authority and timestamps
+//! - Quantity-only holds still parse, and never expire
+//! - Countdowns to expiry and their formatting
+
+use crate::holds::{format_countdown, HoldExpiry, TokenHold};
+use rust_decimal::Decimal;
+use std::time::Duration;
+
+#[cfg(test)]
+mod holds_tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_full_hold() {
+        let hold: TokenHold = serde_json::from_str(
+            r#"{"quantity":"250.5","name":"staking","createdBy":"client|staker","lockAuthority":"client|pool","created":1700000000000,"expires":1700086400000,"instanceId":"0"}"#,
+        )
+        .unwrap();
+        assert_eq!(hold.quantity, Decimal::new(2505, 1));
+        assert_eq!(hold.label(), "staking");
+        assert_eq!(hold.created_by.as_deref(), Some("client|staker"));
+        assert_eq!(hold.lock_authority.as_deref(), Some("client|pool"));
+        assert_eq!((hold.created, hold.expires), (1_700_000_000_000, 1_700_086_400_000));
+    }
+
+    #[test]
+    fn test_quantity_only_hold_never_expires() {
+        let hold: TokenHold = serde_json::from_str(r#"{"quantity":"10"}"#).unwrap();
+        assert_eq!(hold.label(), "Unnamed hold");
+        assert_eq!(hold.expiry(u64::MAX), HoldExpiry::Never);
+        assert_eq!(hold.expiry(0).label(), "until released");
+    }
+
+    #[test]
+    fn test_expiry_countdown() {
+        assert_eq!(HoldExpiry::at(10_000, 4_500), HoldExpiry::In(Duration::from_millis(5_500)));
+        assert_eq!(HoldExpiry::at(10_000, 10_000), HoldExpiry::Expired);
+        assert_eq!(HoldExpiry::at(10_000, 4_500).label(), "unlocks in 00:00:05");
+        assert_eq!(format_countdown(Duration::from_secs(3_600 + 61)), "01:01:01");
+        assert_eq!(format_countdown(Duration::from_secs(2 * 86_400 + 3 * 3_600 + 15 * 60 + 9)), "2d 03:15:09");
+    }
+}
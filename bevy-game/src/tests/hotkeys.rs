@@ -0,0 +1,81 @@
+//! Hotkey tests
+//!
+//! Covers the shortcut map in hotkeys.rs:
+//! - Chords parse in any case and print back in one form
+//! - Bevy key names become chord keys
+//! - The defaults: Ctrl+R, Ctrl+L, Ctrl+1..8, Escape and F1
+//! - hotkeys.json can rebind or turn off a shortcut, and bad entries are reported
+
+use crate::hotkeys::{key_name, Chord, HotkeyAction, HotkeyMap};
+use std::collections::BTreeMap;
+
+#[cfg(test)]
+mod hotkeys_tests {
+    use super::*;
+
+    fn overrides(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs.iter().map(|(id, chord)| (id.to_string(), chord.to_string())).collect()
+    }
+
+    #[test]
+    fn test_chord_parsing() {
+        assert_eq!(Chord::parse("Ctrl+R"), Some(Chord::ctrl("R")));
+        assert_eq!(Chord::parse("cmd + shift + r").unwrap().to_string(), "Ctrl+Shift+R");
+        assert_eq!(Chord::parse("esc"), Some(Chord::key("Escape")));
+        assert_eq!(Chord::parse("f12"), Some(Chord::key("F12")));
+        assert_eq!(Chord::parse("Hyper+R"), None);
+        assert_eq!(Chord::parse("Ctrl+"), None);
+
+        assert!(Chord::key("R").types_text());
+        assert!(!Chord::ctrl("R").types_text());
+        assert!(!Chord::key("F1").types_text());
+    }
+
+    #[test]
+    fn test_key_names() {
+        assert_eq!(key_name("KeyR"), "R");
+        assert_eq!(key_name("Digit1"), "1");
+        assert_eq!(key_name("F1"), "F1");
+        assert_eq!(key_name("Escape"), "Escape");
+        assert_eq!(key_name("Keyboard"), "Keyboard");
+    }
+
+    #[test]
+    fn test_default_bindings() {
+        let map = HotkeyMap::default();
+        assert_eq!(map.action_for(&Chord::ctrl("R")), Some(HotkeyAction::RefreshBalance));
+        assert_eq!(map.action_for(&Chord::ctrl("L")), Some(HotkeyAction::Lock));
+        assert_eq!(map.action_for(&Chord::ctrl("1")), Some(HotkeyAction::Screen(0)));
+        assert_eq!(map.action_for(&Chord::ctrl("8")), Some(HotkeyAction::Screen(7)));
+        assert_eq!(map.action_for(&Chord::ctrl("9")), None);
+        assert_eq!(map.action_for(&Chord::key("Escape")), Some(HotkeyAction::Back));
+        assert_eq!(map.action_for(&Chord::key("R")), None);
+
+        let sheet = map.cheat_sheet();
+        assert_eq!(sheet.len(), 12);
+        assert_eq!(sheet[2], ("Ctrl+1".to_string(), "Go to Overview".to_string()));
+        assert_eq!(sheet[11], ("F1".to_string(), "Show or hide this sheet".to_string()));
+    }
+
+    #[test]
+    fn test_overrides() {
+        let map = HotkeyMap::with_overrides(&overrides(&[("lock", "Ctrl+Shift+L"), ("refresh_balance", "")]));
+        assert!(map.problems.is_empty());
+        assert_eq!(map.action_for(&Chord::parse("Ctrl+Shift+L").unwrap()), Some(HotkeyAction::Lock));
+        assert_eq!(map.action_for(&Chord::ctrl("L")), None);
+        assert_eq!(map.chord_for(HotkeyAction::RefreshBalance), None);
+
+        // Taking another action's default leaves that one unbound
+        let map = HotkeyMap::with_overrides(&overrides(&[("back", "Ctrl+1")]));
+        assert_eq!(map.action_for(&Chord::ctrl("1")), Some(HotkeyAction::Back));
+        assert_eq!(map.chord_for(HotkeyAction::Screen(0)), None);
+        // The sheet keeps its usual order
+        assert_eq!(map.cheat_sheet().last().unwrap().0, "F1");
+
+        let map = HotkeyMap::with_overrides(&overrides(&[("launch", "Ctrl+X"), ("lock", "Hyper+L"), ("back", "F2"), ("cheat_sheet", "F2")]));
+        assert_eq!(map.problems.len(), 3);
+        assert_eq!(map.action_for(&Chord::key("F2")), Some(HotkeyAction::Back));
+        assert_eq!(map.chord_for(HotkeyAction::CheatSheet), Some(&Chord::key("F1")));
+        assert_eq!(map.chord_for(HotkeyAction::Lock), Some(&Chord::ctrl("L")));
+    }
+}
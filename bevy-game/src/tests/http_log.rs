@@ -0,0 +1,67 @@
+//! Request inspector tests
+//!
+//! Covers what the Developer panel is allowed to keep:
+//! - Redaction of secrets in JSON bodies
+//! - Truncation of long bodies
+//! - Ring buffer capacity and ordering
+
+use crate::http_log::{HTTP_LOG_CAPACITY, HttpExchange, HttpLog, redact_body, truncate_body};
+use std::time::Duration;
+
+#[cfg(test)]
+mod http_log_tests {
+    use super::*;
+
+    fn exchange(url: &str, request_body: &str) -> HttpExchange {
+        HttpExchange {
+            method: "POST",
+            url: url.to_string(),
+            duration: Duration::from_millis(42),
+            status: Some(200),
+            request_body: request_body.to_string(),
+            response_body: String::new(),
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_redacts_sensitive_json_keys() {
+        let body = r#"{"owner":"eth|abc","signature":"0xdeadbeef","nested":{"PrivateKey":"secret"},"items":[{"mnemonic":"word word"}]}"#;
+        let redacted = redact_body(body);
+        assert!(redacted.contains("eth|abc"));
+        assert!(!redacted.contains("0xdeadbeef"));
+        assert!(!redacted.contains("secret\""));
+        assert!(!redacted.contains("word word"));
+        assert_eq!(redacted.matches("[redacted]").count(), 3);
+    }
+
+    #[test]
+    fn test_non_json_bodies_pass_through() {
+        assert_eq!(redact_body("Bad Gateway"), "Bad Gateway");
+        assert_eq!(redact_body(""), "");
+    }
+
+    #[test]
+    fn test_truncate_body_respects_char_boundaries() {
+        assert_eq!(truncate_body("short", 10), "short");
+        let truncated = truncate_body("ééééé", 3);
+        assert!(truncated.starts_with("é..."));
+        assert!(truncated.ends_with("(10 bytes total)"));
+    }
+
+    #[test]
+    fn test_ring_buffer_keeps_newest_first() {
+        let log = HttpLog::default();
+        for i in 0..HTTP_LOG_CAPACITY + 5 {
+            log.record(exchange(&format!("http://localhost/{}", i), "{}"));
+        }
+        let snapshot = log.snapshot();
+        assert_eq!(snapshot.len(), HTTP_LOG_CAPACITY);
+        assert_eq!(snapshot[0].url, format!("http://localhost/{}", HTTP_LOG_CAPACITY + 4));
+
+        let version = log.version();
+        log.clear();
+        assert!(log.snapshot().is_empty());
+        assert!(log.version() > version);
+    }
+}
@@ -0,0 +1,94 @@
+//! Icon atlas tests
+//!
+//! Covers the SVG icons behind the UI's texture atlas:
+//! - Every bundled SVG parses, and only drawn icons have an atlas slot
+//! - The supported subset: circles, lines, polylines, polygons and rects, fill and stroke
+//! - Missing and malformed attributes are reported with their element
+//! - Rasterized coverage and the atlas layout
+
+use crate::fonts::Icon;
+use crate::icon_atlas::{atlas_index, parse_svg, rasterize, render_atlas, Geometry, Shape, SvgError, ATLAS_ICONS};
+
+#[cfg(test)]
+mod icon_atlas_tests {
+    use super::*;
+
+    #[test]
+    fn test_bundled_svgs_parse() {
+        for (icon, svg) in ATLAS_ICONS {
+            let parsed = parse_svg(svg).unwrap();
+            assert_eq!(parsed.view_box, 24.0, "{:?}", icon);
+            assert!(!parsed.shapes.is_empty(), "{:?} draws nothing", icon);
+        }
+        assert_eq!(atlas_index(Icon::Wallet), Some(0));
+        assert_eq!(atlas_index(Icon::Warning), Some(4));
+        assert_eq!(atlas_index(Icon::Success), None);
+    }
+
+    #[test]
+    fn test_svg_subset() {
+        let svg = r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 12 12">
+            <!-- a comment -->
+            <circle cx="6" cy="6" r="2" fill="white"/>
+            <line x1="1" y1="1" x2="11" y2="1" stroke="white" stroke-width="2"/>
+            <rect x="1" y="2" width="3" height="4" fill="none" stroke="white"/>
+        </svg>"#;
+        let parsed = parse_svg(svg).unwrap();
+        assert_eq!(parsed.view_box, 12.0);
+        assert_eq!(
+            parsed.shapes,
+            vec![
+                Shape { geometry: Geometry::Circle { center: (6.0, 6.0), radius: 2.0 }, fill: true, stroke: None },
+                Shape { geometry: Geometry::Path { points: vec![(1.0, 1.0), (11.0, 1.0)], closed: false }, fill: false, stroke: Some(2.0) },
+                Shape {
+                    geometry: Geometry::Path { points: vec![(1.0, 2.0), (4.0, 2.0), (4.0, 6.0), (1.0, 6.0)], closed: true },
+                    fill: false,
+                    stroke: Some(1.0),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_svg_errors() {
+        assert_eq!(
+            parse_svg(r#"<circle cx="1" cy="x" r="2"/>"#),
+            Err(SvgError::BadNumber { element: "circle".to_string(), value: "x".to_string() })
+        );
+        assert_eq!(
+            parse_svg(r#"<line x1="1"/>"#),
+            Err(SvgError::MissingAttribute { element: "line".to_string(), attribute: "y1" })
+        );
+        assert_eq!(
+            parse_svg(r#"<polygon points="1,1 2,x"/>"#),
+            Err(SvgError::BadNumber { element: "polygon".to_string(), value: "x".to_string() })
+        );
+    }
+
+    #[test]
+    fn test_rasterize() {
+        let full = parse_svg(r#"<rect x="0" y="0" width="24" height="24" fill="white"/>"#).unwrap();
+        assert!(rasterize(&full, 8).iter().all(|alpha| *alpha == 255));
+
+        let dot = parse_svg(r#"<circle cx="12" cy="12" r="6" fill="white"/>"#).unwrap();
+        let coverage = rasterize(&dot, 24);
+        assert_eq!(coverage[12 * 24 + 12], 255);
+        assert_eq!(coverage[0], 0);
+
+        // A stroked ring leaves its middle empty
+        let ring = parse_svg(r#"<circle cx="12" cy="12" r="8" fill="none" stroke="white" stroke-width="2"/>"#).unwrap();
+        let coverage = rasterize(&ring, 24);
+        assert_eq!(coverage[12 * 24 + 12], 0);
+        assert_eq!(coverage[12 * 24 + 20], 255);
+    }
+
+    #[test]
+    fn test_render_atlas() {
+        let atlas = render_atlas(32).unwrap();
+        assert_eq!(atlas.dimensions(), (32 * ATLAS_ICONS.len() as u32, 32));
+        for index in 0..ATLAS_ICONS.len() as u32 {
+            let drawn = (0..32).flat_map(|y| (0..32).map(move |x| (x, y))).any(|(x, y)| atlas.get_pixel(index * 32 + x, y).0[3] == 255);
+            assert!(drawn, "cell {} is empty", index);
+        }
+    }
+}
@@ -0,0 +1,44 @@
+//! Token icon tests
+//!
+//! Covers the checks around downloaded icons:
+//! - Only PNG files within the size limit are accepted
+//! - Cache file names are stable per URL
+//! - Placeholder letters for tokens without an icon
+
+use crate::icons::{check_icon, icon_cache_path, placeholder_letter, IconError, ICON_MAX_BYTES};
+use std::path::Path;
+
+#[cfg(test)]
+mod icon_tests {
+    use super::*;
+
+    const PNG_HEADER: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+
+    #[test]
+    fn test_check_icon() {
+        assert_eq!(check_icon(&PNG_HEADER), Ok(()));
+        assert_eq!(check_icon(b"<svg xmlns=\"http://www.w3.org/2000/svg\"/>"), Err(IconError::UnsupportedFormat));
+        assert_eq!(check_icon(&[]), Err(IconError::UnsupportedFormat));
+
+        let mut huge = PNG_HEADER.to_vec();
+        huge.resize(ICON_MAX_BYTES + 1, 0);
+        assert_eq!(check_icon(&huge), Err(IconError::TooLarge));
+    }
+
+    #[test]
+    fn test_icon_cache_path_is_per_url() {
+        let dir = Path::new("/tmp/icons");
+        let gala = icon_cache_path(dir, "https://example.com/gala.png?size=64");
+        assert_eq!(gala, icon_cache_path(dir, "https://example.com/gala.png?size=64"));
+        assert_ne!(gala, icon_cache_path(dir, "https://example.com/town.png"));
+        assert_eq!(gala.parent(), Some(dir));
+        assert_eq!(gala.extension().and_then(|ext| ext.to_str()), Some("png"));
+    }
+
+    #[test]
+    fn test_placeholder_letter() {
+        assert_eq!(placeholder_letter("gala"), "G");
+        assert_eq!(placeholder_letter("$TOWN"), "T");
+        assert_eq!(placeholder_letter(""), "?");
+    }
+}
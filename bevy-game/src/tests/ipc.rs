@@ -0,0 +1,147 @@
+//! Local signing server tests
+//!
+//! Covers the JSON-RPC protocol in ipc.rs:
+//! - Method and parameter parsing, with JSON-RPC error codes for bad requests
+//! - Result and error response bodies
+//! - A call over HTTP answered from the wallet side
+//! - Requests from web pages (with an Origin header) refused before they're queued
+//! - Oversized headers, stalled requests and connections past the cap are refused
+//! - The optional approval PIN: digits only, kept hashed
+
+use super::harness::post_json;
+use crate::ipc::{
+    parse_request, response_body, IpcServer, IpcSettings, PinError, RpcCall, RpcError, INVALID_PARAMS, MAX_CONNECTIONS, MAX_HEADER_BYTES,
+    METHOD_NOT_FOUND, PARSE_ERROR, REQUEST_TIMEOUT, USER_REJECTED,
+};
+use serde_json::{json, Value};
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::time::{Duration, Instant};
+
+fn read_response(stream: &mut TcpStream) -> String {
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    response
+}
+
+#[cfg(test)]
+mod ipc_tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_requests() {
+        assert_eq!(parse_request(r#"{"jsonrpc":"2.0","id":1,"method":"getAddress"}"#), Ok((json!(1), RpcCall::GetAddress)));
+        assert_eq!(
+            parse_request(r#"{"id":"a","method":"signMessage","params":{"message":" hi "}}"#),
+            Ok((json!("a"), RpcCall::SignMessage { message: " hi ".to_string() }))
+        );
+        assert_eq!(
+            parse_request(r#"{"id":2,"method":"submitTransfer","params":{"to":"eth|abc","quantity":"1.5","token":"GALA"}}"#),
+            Ok((json!(2), RpcCall::SubmitTransfer { to: "eth|abc".to_string(), quantity: "1.5".parse().unwrap(), token: Some("GALA".to_string()) }))
+        );
+
+        let code = |body: &str| parse_request(body).unwrap_err().1.code;
+        assert_eq!(code("{"), PARSE_ERROR);
+        assert_eq!(code(r#"{"id":3,"method":"eth_sendTransaction"}"#), METHOD_NOT_FOUND);
+        assert_eq!(code(r#"{"id":4,"method":"signMessage","params":{}}"#), INVALID_PARAMS);
+        // Quantities must be strings, so nothing is rounded through a float
+        assert_eq!(code(r#"{"id":5,"method":"submitTransfer","params":{"to":"eth|abc","quantity":1.5}}"#), INVALID_PARAMS);
+        assert_eq!(code(r#"{"id":6,"method":"submitTransfer","params":{"to":"eth|abc","quantity":"0"}}"#), INVALID_PARAMS);
+        assert_eq!(parse_request(r#"{"id":7,"method":"nope"}"#).unwrap_err().0, json!(7));
+    }
+
+    #[test]
+    fn test_response_bodies() {
+        let ok: Value = serde_json::from_str(&response_body(&json!(1), &Ok(json!({ "signature": "0x01" })))).unwrap();
+        assert_eq!(ok, json!({ "jsonrpc": "2.0", "id": 1, "result": { "signature": "0x01" } }));
+
+        let refused: Value = serde_json::from_str(&response_body(&json!("x"), &Err(RpcError::new(USER_REJECTED, "Rejected by the user")))).unwrap();
+        assert_eq!(refused["error"], json!({ "code": 4001, "message": "Rejected by the user" }));
+    }
+
+    #[test]
+    fn test_call_over_http() {
+        let server = IpcServer::start(0).unwrap();
+        let addr = server.addr;
+        let caller = std::thread::spawn(move || post_json(addr, r#"{"jsonrpc":"2.0","id":9,"method":"getAddress"}"#, ""));
+
+        let call = loop {
+            if let Some(call) = server.try_next() {
+                break call;
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        };
+        assert_eq!(call.call, RpcCall::GetAddress);
+        call.answer(Ok(json!({ "address": "0xabc" })));
+
+        let response = caller.join().unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.ends_with(r#"{"id":9,"jsonrpc":"2.0","result":{"address":"0xabc"}}"#));
+    }
+
+    #[test]
+    fn test_web_pages_refused() {
+        let server = IpcServer::start(0).unwrap();
+        let response = post_json(server.addr, r#"{"id":1,"method":"getAddress"}"#, "Origin: https://evil.example\r\n");
+        assert!(response.starts_with("HTTP/1.1 403 Forbidden"));
+        assert!(server.try_next().is_none());
+    }
+
+    #[test]
+    fn test_oversized_header_refused() {
+        let server = IpcServer::start(0).unwrap();
+        let mut stream = TcpStream::connect(server.addr).unwrap();
+        // Exactly the limit with no end of line in sight, so nothing is left unread
+        let request_line = "POST / HTTP/1.1\r\n";
+        let padding = "a".repeat(MAX_HEADER_BYTES - request_line.len() - "X-Pad: ".len());
+        write!(stream, "{}X-Pad: {}", request_line, padding).unwrap();
+        assert!(read_response(&mut stream).starts_with("HTTP/1.1 431"));
+        assert!(server.try_next().is_none());
+    }
+
+    #[test]
+    fn test_stalled_request_times_out() {
+        let server = IpcServer::start(0).unwrap();
+        let mut stream = TcpStream::connect(server.addr).unwrap();
+        let started = Instant::now();
+        write!(stream, "POST / HTTP/1.1\r\nContent-Length: 10\r\n").unwrap();
+        assert!(read_response(&mut stream).starts_with("HTTP/1.1 408 Request Timeout"));
+        assert!(started.elapsed() < REQUEST_TIMEOUT + Duration::from_secs(2));
+        assert!(server.try_next().is_none());
+    }
+
+    #[test]
+    fn test_connections_capped() {
+        let server = IpcServer::start(0).unwrap();
+        let held: Vec<TcpStream> = (0..MAX_CONNECTIONS).map(|_| TcpStream::connect(server.addr).unwrap()).collect();
+        let mut extra = TcpStream::connect(server.addr).unwrap();
+        assert!(read_response(&mut extra).starts_with("HTTP/1.1 503 Service Unavailable"));
+
+        // Closing them frees their places
+        drop(held);
+        std::thread::sleep(Duration::from_millis(200));
+        let response = post_json(server.addr, r#"{"id":1,"method":"getAddress"}"#, "Origin: https://evil.example\r\n");
+        assert!(response.starts_with("HTTP/1.1 403 Forbidden"));
+    }
+
+    #[test]
+    fn test_approval_pin() {
+        let mut settings = IpcSettings::default();
+        assert!(settings.pin_accepts(""));
+        assert_eq!(settings.set_pin_with_iterations("123", 10), Err(PinError));
+        assert_eq!(settings.set_pin_with_iterations("12a4", 10), Err(PinError));
+        assert_eq!(settings.set_pin_with_iterations("123456789", 10), Err(PinError));
+        assert!(settings.pin.is_none());
+
+        settings.set_pin_with_iterations("4821", 10).unwrap();
+        assert!(settings.pin_accepts("4821"));
+        assert!(!settings.pin_accepts(""));
+        assert!(!settings.pin_accepts("4822"));
+
+        let path = std::env::temp_dir().join(format!("galachain-ipc-pin-{}.json", std::process::id()));
+        settings.save(Some(path.clone())).unwrap();
+        assert!(!std::fs::read_to_string(&path).unwrap().contains("4821"));
+        assert!(IpcSettings::load(Some(path.clone())).pin_accepts("4821"));
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,166 @@
+//! Keychain entry tests
+//!
+//! Covers the JSON stored in the OS keychain:
+//! - Entries written before wallet types existed still load as mnemonic wallets
+//! - Watch-only entries round-trip their address without a mnemonic
+//! - Replacing a wallet archives the old entry, and restoring swaps it back
+//! - A restored encrypted backup brings its archive along and loads its wallet
+//! - A profile's wallet takes over the shared entry older builds saved, but never replaces its own
+
+use super::harness::MemorySecretStore;
+use super::test_utils::*;
+use crate::wallet_archive::{ArchivedWallet, WalletArchive, MAX_ARCHIVED};
+use crate::wallet_bundle::BundleContents;
+use crate::{restore_bundle, watch_only_address, KeychainManager, SecretStore, SecureWalletData, WalletData, WalletType};
+
+#[cfg(test)]
+mod keychain_tests {
+    use super::*;
+
+    #[test]
+    fn test_legacy_entry_is_a_mnemonic_wallet() {
+        let json = format!(r#"{{"mnemonic":"{}","created_at":1234567890}}"#, TestVectors::TEST_MNEMONIC_12);
+        let data = SecureWalletData::from_json(&json).unwrap();
+        assert_eq!(data.wallet_type, WalletType::Mnemonic);
+        assert_eq!(data.mnemonic, TestVectors::TEST_MNEMONIC_12);
+        assert_eq!(data.address, None);
+    }
+
+    #[test]
+    fn test_mnemonic_entry_round_trips() {
+        let json = create_test_wallet_data().to_json().unwrap();
+        let data = SecureWalletData::from_json(&json).unwrap();
+        assert_eq!(data.wallet_type, WalletType::Mnemonic);
+        assert_eq!(data.mnemonic, TestVectors::TEST_MNEMONIC_12);
+        assert_eq!(data.created_at, 1234567890);
+    }
+
+    #[test]
+    fn test_watch_only_entry_round_trips() {
+        let address = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed".to_string();
+        let json = SecureWalletData::watch_only(address.clone(), 42).to_json().unwrap();
+        let data = SecureWalletData::from_json(&json).unwrap();
+        assert_eq!(data.wallet_type, WalletType::WatchOnly);
+        assert_eq!(data.address, Some(address));
+        assert!(data.mnemonic.is_empty());
+    }
+
+    #[test]
+    fn test_invalid_entries_are_rejected() {
+        assert!(SecureWalletData::from_json(r#"{"wallet_type":"watch_only","mnemonic":"","created_at":1}"#).is_err());
+        assert!(SecureWalletData::from_json(r#"{"wallet_type":"hardware","mnemonic":"x","created_at":1}"#).is_err());
+    }
+
+    #[test]
+    fn test_watch_only_needs_an_ethereum_address() {
+        assert_eq!(
+            watch_only_address("eth|5aaeb6053f3e94c9b9a09f33669435e7ef1beaed"),
+            Ok("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed".to_string())
+        );
+        assert!(watch_only_address("client|game-server").is_err());
+        assert!(watch_only_address("0x1234").is_err());
+    }
+
+    #[test]
+    fn test_replace_archives_and_restore_swaps_back() {
+        let (wallet, archive) = (MemorySecretStore::default(), MemorySecretStore::default());
+        let keychain = KeychainManager::with_store(wallet.clone()).with_archive(archive.clone());
+        let first = create_test_wallet_data();
+        let watched = SecureWalletData::watch_only("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed".to_string(), 42);
+
+        assert_eq!(keychain.replace_wallet(&first).unwrap(), None);
+        assert!(archive.contents().is_none());
+        let (_, first_address) = keychain.generate_wallet_from_mnemonic(TestVectors::TEST_MNEMONIC_12).unwrap();
+        assert_eq!(keychain.replace_wallet(&watched).unwrap(), Some(first_address.clone()));
+        assert_eq!(keychain.archived_wallets().unwrap().latest().unwrap().entry, first.to_json().unwrap());
+
+        let restored = keychain.restore_previous_wallet().unwrap();
+        assert_eq!(restored.mnemonic, TestVectors::TEST_MNEMONIC_12);
+        assert_eq!(wallet.get().unwrap(), first.to_json().unwrap());
+        // The watch-only wallet it replaced is now the one to restore
+        let archived = keychain.archived_wallets().unwrap();
+        assert_eq!(archived.wallets.len(), 1);
+        assert_eq!(archived.latest().unwrap().address, watched.address);
+    }
+
+    #[test]
+    fn test_restore_bundle_merges_archive_and_loads_wallet() {
+        let (wallet, archive) = (MemorySecretStore::default(), MemorySecretStore::default());
+        let keychain = KeychainManager::with_store(wallet.clone()).with_archive(archive);
+        let watched = SecureWalletData::watch_only("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed".to_string(), 42);
+        keychain.store_wallet(&watched).unwrap();
+
+        let mut bundled = WalletArchive::default();
+        bundled.push(ArchivedWallet { archived_at: 7, address: None, entry: "older-entry".to_string() });
+        let contents = BundleContents {
+            created_at: 100,
+            wallet: create_test_wallet_data().to_json().unwrap(),
+            archive: bundled,
+            dashboard: Default::default(),
+            profile: Default::default(),
+        };
+        let mut wallet_data = WalletData { wallet_type: WalletType::WatchOnly, private_key: None, address: watched.address.clone(), mnemonic: None };
+
+        let address = restore_bundle(&contents, &keychain, &mut wallet_data).unwrap();
+        let (_, expected) = keychain.generate_wallet_from_mnemonic(TestVectors::TEST_MNEMONIC_12).unwrap();
+        assert_eq!(address, expected);
+        assert_eq!(wallet_data.wallet_type, WalletType::Mnemonic);
+        assert!(wallet_data.private_key.is_some());
+        assert_eq!(wallet.get().unwrap(), contents.wallet);
+        // The bundle's archive, then the watched wallet it replaced
+        let archived = keychain.archived_wallets().unwrap();
+        assert_eq!(archived.wallets.len(), 2);
+        assert_eq!(archived.wallets[0].entry, "older-entry");
+        assert_eq!(archived.latest().unwrap().address, watched.address);
+
+        // An entry whose seed phrase doesn't derive leaves the current wallet alone
+        let broken = BundleContents { wallet: r#"{"mnemonic":"not a seed phrase","created_at":1}"#.to_string(), ..contents.clone() };
+        assert!(restore_bundle(&broken, &keychain, &mut wallet_data).is_err());
+        assert_eq!(wallet.get().unwrap(), contents.wallet);
+    }
+
+    #[test]
+    fn test_archive_drops_duplicates_and_oldest() {
+        let mut archive = WalletArchive::default();
+        for n in 0..MAX_ARCHIVED + 2 {
+            archive.push(ArchivedWallet { archived_at: n as u64, address: None, entry: format!("entry-{}", n) });
+        }
+        archive.push(ArchivedWallet { archived_at: 99, address: None, entry: format!("entry-{}", MAX_ARCHIVED) });
+
+        assert_eq!(archive.wallets.len(), MAX_ARCHIVED);
+        assert_eq!(archive.wallets[0].entry, "entry-2");
+        assert_eq!(archive.latest().unwrap().archived_at, 99);
+        assert_eq!(WalletArchive::from_json(&archive.to_json()).unwrap(), archive);
+    }
+
+    #[test]
+    fn test_take_over_legacy_entries() {
+        let (legacy_wallet, legacy_archive) = (MemorySecretStore::default(), MemorySecretStore::default());
+        let legacy = KeychainManager::with_store(legacy_wallet.clone()).with_archive(legacy_archive.clone());
+        let (wallet, archive) = (MemorySecretStore::default(), MemorySecretStore::default());
+        let keychain = KeychainManager::with_store(wallet.clone()).with_archive(archive);
+
+        // Nothing to move
+        assert!(!keychain.take_over(&legacy).unwrap());
+        assert!(wallet.contents().is_none());
+
+        let saved = create_test_wallet_data();
+        legacy.store_wallet(&saved).unwrap();
+        let mut archived = WalletArchive::default();
+        archived.push(ArchivedWallet { archived_at: 7, address: None, entry: "older-entry".to_string() });
+        legacy_archive.set(&archived.to_json()).unwrap();
+
+        assert!(keychain.take_over(&legacy).unwrap());
+        assert_eq!(wallet.get().unwrap(), saved.to_json().unwrap());
+        assert_eq!(keychain.archived_wallets().unwrap().wallets[0].entry, "older-entry");
+        assert!(legacy_wallet.contents().is_none());
+        assert!(legacy_archive.contents().is_none());
+
+        // A wallet of its own is never replaced
+        let watched = SecureWalletData::watch_only("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed".to_string(), 42);
+        legacy.store_wallet(&watched).unwrap();
+        assert!(!keychain.take_over(&legacy).unwrap());
+        assert_eq!(wallet.get().unwrap(), saved.to_json().unwrap());
+        assert_eq!(legacy_wallet.get().unwrap(), watched.to_json().unwrap());
+    }
+}
@@ -0,0 +1,59 @@
+//! Keychain backend and diagnostics tests
+//!
+//! Covers what Settings → Keychain makes of a session bus probe:
+//! - The problem shown for each backend
+//! - The suggested backend and who provides the Secret Service
+//! - The saved backend choice
+
+use crate::keyring_backend::{BackendConfig, KeyringBackend, Probe};
+
+#[cfg(test)]
+mod keyring_backend_tests {
+    use super::*;
+
+    #[test]
+    fn test_probe_problems_and_suggestion() {
+        let no_bus = Probe { bus_error: Some("no DBUS_SESSION_BUS_ADDRESS".to_string()), ..Probe::default() };
+        assert!(no_bus.problem(KeyringBackend::SecretService).unwrap().contains("no D-Bus session bus"));
+        assert_eq!(no_bus.problem(KeyringBackend::EncryptedFile), None);
+        assert_eq!(no_bus.recommended(), KeyringBackend::EncryptedFile);
+
+        let gnome = Probe { secret_service: true, gnome_keyring: true, default_locked: Some(true), ..Probe::default() };
+        assert_eq!(gnome.provider(), "GNOME Keyring");
+        assert!(gnome.problem(KeyringBackend::SecretService).unwrap().contains("locked"));
+        assert_eq!(gnome.problem(KeyringBackend::KWallet).as_deref(), Some("KWallet isn't running"));
+        assert_eq!(gnome.recommended(), KeyringBackend::SecretService);
+
+        // KWallet running with its Secret Service interface switched off
+        let kwallet = Probe { kwallet: true, ..Probe::default() };
+        assert_eq!(kwallet.provider(), "none");
+        assert!(kwallet.problem(KeyringBackend::KWallet).unwrap().contains("interface is off"));
+        assert_eq!(kwallet.recommended(), KeyringBackend::EncryptedFile);
+
+        let kwallet = Probe { secret_service: true, kwallet: true, default_locked: Some(false), kwallet_locked: Some(false), ..Probe::default() };
+        assert_eq!(kwallet.provider(), "KWallet");
+        assert_eq!(kwallet.problem(KeyringBackend::KWallet), None);
+        assert_eq!(kwallet.problem(KeyringBackend::SecretService), None);
+        assert!(kwallet.problem(KeyringBackend::KernelKeyring).is_some());
+    }
+
+    #[test]
+    fn test_backend_choice_cycles_and_persists() {
+        let mut backend = KeyringBackend::default();
+        assert_eq!(backend, KeyringBackend::SecretService);
+        for _ in 0..KeyringBackend::ALL.len() {
+            backend = backend.next();
+        }
+        assert_eq!(backend, KeyringBackend::SecretService);
+        assert_eq!(KeyringBackend::KWallet.collection(), Some("kdewallet"));
+        assert_eq!(KeyringBackend::SecretService.collection(), None);
+
+        let path = std::env::temp_dir().join(format!("galachain-keyring-backend-{}.json", std::process::id()));
+        let config = BackendConfig { backend: KeyringBackend::EncryptedFile };
+        config.save(Some(path.clone())).unwrap();
+        assert!(std::fs::read_to_string(&path).unwrap().contains("encrypted_file"));
+        assert_eq!(BackendConfig::load(Some(path.clone())), config);
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(BackendConfig::load(Some(path)), BackendConfig::default());
+    }
+}
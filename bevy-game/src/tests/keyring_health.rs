@@ -0,0 +1,130 @@
+//! Keychain degradation tests
+//!
+//! Covers a keychain that stops answering mid-session:
+//! - Access errors mark it unavailable, and the next answer clears that
+//! - Memory-only mode holds writes and deletions, and reads see them
+//! - Retry writes the held entries and ends memory-only mode, or keeps them if it fails
+
+use crate::keyring_health::{GuardedStore, KeyringHealth, KeyringStatus};
+use crate::{KeychainError, SecretStore};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+#[cfg(test)]
+mod keyring_health_tests {
+    use super::*;
+
+    /// A keychain of named entries that can be taken down, like a locked login keyring.
+    #[derive(Clone, Default)]
+    struct Keychain {
+        entries: Arc<Mutex<HashMap<String, String>>>,
+        down: Arc<AtomicBool>,
+    }
+
+    struct Entry {
+        name: String,
+        keychain: Keychain,
+    }
+
+    impl Keychain {
+        fn entry(&self, name: &str) -> Entry {
+            Entry { name: name.to_string(), keychain: self.clone() }
+        }
+
+        fn set_down(&self, down: bool) {
+            self.down.store(down, Ordering::SeqCst);
+        }
+
+        fn contents(&self, name: &str) -> Option<String> {
+            self.entries.lock().unwrap().get(name).cloned()
+        }
+
+        fn check(&self) -> Result<(), KeychainError> {
+            match self.down.load(Ordering::SeqCst) {
+                true => Err(KeychainError::Access("the collection is locked".to_string())),
+                false => Ok(()),
+            }
+        }
+    }
+
+    impl SecretStore for Entry {
+        fn get(&self) -> Result<String, KeychainError> {
+            self.keychain.check()?;
+            self.keychain.contents(&self.name).ok_or(KeychainError::NotFound)
+        }
+
+        fn set(&self, secret: &str) -> Result<(), KeychainError> {
+            self.keychain.check()?;
+            self.keychain.entries.lock().unwrap().insert(self.name.clone(), secret.to_string());
+            Ok(())
+        }
+
+        fn delete(&self) -> Result<(), KeychainError> {
+            self.keychain.check()?;
+            self.keychain.entries.lock().unwrap().remove(&self.name).map(|_| ()).ok_or(KeychainError::NotFound)
+        }
+    }
+
+    #[test]
+    fn test_access_errors_mark_keychain_unavailable() {
+        let keychain = Keychain::default();
+        let health = KeyringHealth::default();
+        let wallet = GuardedStore::new("wallet", keychain.entry("wallet"), &health);
+        assert!(matches!(wallet.get(), Err(KeychainError::NotFound)));
+        assert_eq!(health.status(), KeyringStatus::Available);
+
+        keychain.set_down(true);
+        assert!(matches!(wallet.set("entry"), Err(KeychainError::Access(_))));
+        assert_eq!(health.status(), KeyringStatus::Unavailable("the collection is locked".to_string()));
+
+        keychain.set_down(false);
+        wallet.set("entry").unwrap();
+        assert_eq!(health.status(), KeyringStatus::Available);
+    }
+
+    #[test]
+    fn test_memory_only_holds_writes_until_retry() {
+        let keychain = Keychain::default();
+        keychain.entries.lock().unwrap().insert("gateway".to_string(), "old credential".to_string());
+        let health = KeyringHealth::default();
+        let wallet = GuardedStore::new("wallet", keychain.entry("wallet"), &health);
+        let gateway = GuardedStore::new("gateway", keychain.entry("gateway"), &health);
+
+        keychain.set_down(true);
+        health.use_memory_only();
+        wallet.set("entry").unwrap();
+        gateway.delete().unwrap();
+        assert_eq!(wallet.get().unwrap(), "entry");
+        assert!(matches!(gateway.get(), Err(KeychainError::NotFound)));
+        assert_eq!(health.status(), KeyringStatus::MemoryOnly { held: 2 });
+
+        // Still locked: nothing is lost and memory-only mode goes on
+        assert!(health.retry("wallet", |name| keychain.entry(name)).is_err());
+        assert_eq!(health.status(), KeyringStatus::MemoryOnly { held: 2 });
+
+        keychain.set_down(false);
+        health.retry("wallet", |name| keychain.entry(name)).unwrap();
+        assert_eq!(health.status(), KeyringStatus::Available);
+        assert_eq!(keychain.contents("wallet").as_deref(), Some("entry"));
+        assert_eq!(keychain.contents("gateway"), None);
+        assert_eq!(wallet.get().unwrap(), "entry");
+    }
+
+    #[test]
+    fn test_retry_with_nothing_held_probes_the_keychain() {
+        let keychain = Keychain::default();
+        let health = KeyringHealth::default();
+        let wallet = GuardedStore::new("wallet", keychain.entry("wallet"), &health);
+
+        keychain.set_down(true);
+        assert!(wallet.get().is_err());
+        assert!(health.retry("wallet", |name| keychain.entry(name)).is_err());
+        assert!(matches!(health.status(), KeyringStatus::Unavailable(_)));
+
+        // A missing entry is still an answer
+        keychain.set_down(false);
+        health.retry("wallet", |name| keychain.entry(name)).unwrap();
+        assert_eq!(health.status(), KeyringStatus::Available);
+    }
+}
@@ -0,0 +1,61 @@
+//! Locale tests
+//!
+//! Covers number and date formatting in locale.rs:
+//! - Separators are swapped into amounts.rs's "1,234.5" per locale
+//! - Dates follow the locale's order and stay in UTC
+//! - System locale names map to the closest format
+//! - The Settings choice cycles and is saved with the UI state
+
+use crate::locale::{DateOrder, Locale, LocaleSetting};
+use crate::ui_state::UiState;
+use std::time::{Duration, UNIX_EPOCH};
+
+#[cfg(test)]
+mod locale_tests {
+    use super::*;
+
+    #[test]
+    fn test_numbers_use_locale_separators() {
+        assert_eq!(Locale::STANDARD.number("1,234,567.89"), "1,234,567.89");
+        assert_eq!(LocaleSetting::German.resolve(None).number("1,234,567.89"), "1.234.567,89");
+        assert_eq!(LocaleSetting::French.resolve(None).number("-1,234.5"), "-1 234,5");
+        assert_eq!(LocaleSetting::EnglishUk.resolve(None).number("12.00"), "12.00");
+        assert_eq!(Locale::from_tag("de_CH.UTF-8").number("1,234.5"), "1'234.5");
+    }
+
+    #[test]
+    fn test_dates_follow_locale_order() {
+        // 2023-11-14T22:13:20Z
+        let at = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        assert_eq!(Locale::STANDARD.date_time(at), "2023-11-14T22:13:20Z");
+        assert_eq!(LocaleSetting::EnglishUs.resolve(None).date_time(at), "11/14/2023 22:13:20 UTC");
+        assert_eq!(LocaleSetting::EnglishUk.resolve(None).date_time(at), "14/11/2023 22:13:20 UTC");
+        assert_eq!(LocaleSetting::German.resolve(None).date_time(at), "14.11.2023 22:13:20 UTC");
+    }
+
+    #[test]
+    fn test_system_locale_names() {
+        assert_eq!(Locale::from_tag("en_US.UTF-8"), LocaleSetting::EnglishUs.resolve(None));
+        assert_eq!(Locale::from_tag("en-GB"), LocaleSetting::EnglishUk.resolve(None));
+        assert_eq!(Locale::from_tag("de_DE.UTF-8@euro"), LocaleSetting::German.resolve(None));
+        assert_eq!(Locale::from_tag("fr_CA"), LocaleSetting::French.resolve(None));
+        assert_eq!(Locale::from_tag("ja_JP.UTF-8"), Locale::STANDARD);
+        assert_eq!(LocaleSetting::System.resolve(None), Locale::STANDARD);
+        assert_eq!(LocaleSetting::System.resolve(Some("nl_NL")).dates, DateOrder::DayMonthYear('.'));
+    }
+
+    #[test]
+    fn test_setting_cycles_and_persists() {
+        let mut setting = LocaleSetting::System;
+        for _ in 0..LocaleSetting::ALL.len() {
+            setting = setting.next();
+        }
+        assert_eq!(setting, LocaleSetting::System);
+
+        let state = UiState { locale: LocaleSetting::German, ..UiState::default() };
+        let json = serde_json::to_string(&state).unwrap();
+        assert_eq!(serde_json::from_str::<UiState>(&json).unwrap().locale, LocaleSetting::German);
+        // Files saved before the setting existed follow the system
+        assert_eq!(serde_json::from_str::<UiState>("{}").unwrap().locale, LocaleSetting::System);
+    }
+}
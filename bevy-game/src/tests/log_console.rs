@@ -0,0 +1,67 @@
+//! Log console tests
+//!
+//! Covers capturing tracing output for the in-app console:
+//! - Events recorded with their level, target, message and fields
+//! - The ring buffer keeping only the newest lines
+//! - Level and target filtering, and the copied text
+
+use crate::log_console::{crate_targets, format_lines, CaptureLayer, LogBuffer, LogFilter, LogLine, LOG_CAPACITY};
+use bevy::log::tracing_subscriber::layer::SubscriberExt;
+use bevy::log::tracing_subscriber::Registry;
+use bevy::log::Level;
+use bevy::utils::tracing;
+use std::time::{Duration, SystemTime};
+
+#[cfg(test)]
+mod log_console_tests {
+    use super::*;
+
+    fn line(level: Level, target: &str, message: &str) -> LogLine {
+        LogLine { time: SystemTime::UNIX_EPOCH + Duration::from_secs(3723), level, target: target.to_string(), message: message.to_string() }
+    }
+
+    #[test]
+    fn test_capture_layer_records_events() {
+        let buffer = LogBuffer::default();
+        let subscriber = Registry::default().with(CaptureLayer(buffer.clone()));
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!(target: "bevy_game::wallet", "Balance fetched");
+            tracing::warn!(target: "bevy_game", attempt = 2, "Retrying {}", "FetchBalances");
+        });
+
+        let lines = buffer.snapshot();
+        assert_eq!(lines.len(), 2);
+        assert_eq!((lines[0].level, lines[0].target.as_str(), lines[0].message.as_str()), (Level::INFO, "bevy_game::wallet", "Balance fetched"));
+        assert_eq!(lines[1].message, "Retrying FetchBalances attempt=2");
+        assert_eq!(buffer.version(), 2);
+    }
+
+    #[test]
+    fn test_buffer_keeps_newest_lines() {
+        let buffer = LogBuffer::default();
+        for i in 0..LOG_CAPACITY + 5 {
+            buffer.push(line(Level::INFO, "bevy_game", &i.to_string()));
+        }
+        let lines = buffer.snapshot();
+        assert_eq!(lines.len(), LOG_CAPACITY);
+        assert_eq!(lines[0].message, "5");
+    }
+
+    #[test]
+    fn test_filter_and_copy() {
+        let lines = vec![
+            line(Level::DEBUG, "wgpu_core::device", "noise"),
+            line(Level::INFO, "bevy_game", "Wallet loaded"),
+            line(Level::ERROR, "bevy_game::api", "HTTP 500"),
+            line(Level::WARN, "bevy_render", "Slow frame"),
+        ];
+
+        let shown = |filter: &LogFilter| lines.iter().filter(|line| filter.matches(line)).map(|line| line.message.as_str()).collect::<Vec<_>>();
+        assert_eq!(shown(&LogFilter::default()), ["Wallet loaded", "HTTP 500", "Slow frame"]);
+        assert_eq!(shown(&LogFilter { min_level: Level::WARN, target: None }), ["HTTP 500", "Slow frame"]);
+        assert_eq!(shown(&LogFilter { min_level: Level::TRACE, target: Some("bevy_game".to_string()) }), ["Wallet loaded", "HTTP 500"]);
+
+        assert_eq!(crate_targets(&lines), ["bevy_game", "bevy_render", "wgpu_core"]);
+        assert_eq!(format_lines(&lines[1..3]), "01:02:03  INFO bevy_game: Wallet loaded\n01:02:03 ERROR bevy_game::api: HTTP 500\n");
+    }
+}
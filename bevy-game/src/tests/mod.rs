@@ -5,6 +5,76 @@
 //! - Input validation (mnemonics, addresses, amounts)
 //! - UI focus system functionality
 //! - Security and error handling
+//! - Printable seed backup helpers
+//! - Update check version comparison
+//! - Error codes and remediation hints
+//! - Request inspector redaction and ring buffer
+//! - Background API task results and cancellation
+//! - Request coalescing and rate limiting
+//! - Response cache TTL and persistence
+//! - Exact token quantity parsing and formatting
+//! - Transfer recipient parsing and checksum warnings
+//! - Batch transfer CSV import and per-row checks
+//! - Balance export to CSV and JSON
+//! - Keychain entry schema and watch-only wallets
+//! - Address dashboard list and sorting
+//! - Token registry specs and selection
+//! - Token icon checks and cache file names
+//! - Request signing, uniqueKeys and duplicate submission
+//! - Canonical DTO serialization and signature fixtures
+//! - Demo mode's in-memory chain
+//! - Chain backends behind GalaChainClient
+//! - Launch options from flags and GALA_* variables
+//! - galachain:// payment links
+//! - Local JSON-RPC signing server
+//! - Log capture and filtering for the log console
+//! - Crash report redaction and the pending-crash marker
+//! - Large-transfer approval policy and queue
+//! - Daily and weekly spending limits
+//! - Local transaction history, notes and CSV export
+//! - Burn-and-claim event definitions and entries
+//! - Token hold parsing and expiry countdowns
+//! - HTTP client options: proxy, TLS, timeout and extra headers
+//! - Gateway credentials and how they're kept out of config files
+//! - Circuit breaker for failing endpoints
+//! - Balances for every registry token, fetched a few at a time
+//! - Last screen and window geometry saved across restarts
+//! - Wallet domain events handled without a screen
+//! - Seed phrase derivations and the stored derivation choice
+//! - Address parsing and display shared by every address field
+//! - Environment profile files shared between machines
+//! - Endpoint template checks for the Settings editor
+//! - Local dev and public gateway API modes
+//! - Contract response envelopes and error-key mapping
+//! - Retry-After parsing and rate-limit pauses
+//! - Local usage stats for the Stats screen
+//! - Balance snapshots and the Balance screen chart
+//! - Bundled UI font and the icon glyphs that replace emoji
+//! - SVG icons and the UI texture atlas
+//! - Sorting files dropped onto the window
+//! - Passphrase strength estimates and the meter line
+//! - Wrong-PIN counts, doubling waits and the lockout
+//! - Encrypted wallet backup bundles
+//! - Uploading encrypted backups to a WebDAV or S3 backup target
+//! - Relative "N seconds ago" labels and the switch to timestamps
+//! - Keychain failures mid-session, memory-only mode and retry
+//! - Linux keychain backends and the encrypted keyring file
+//! - Chunked keychain entries for Windows' credential size limit
+//! - Touch ID / Windows Hello check settings and the fallback passphrase
+//! - The audit log of decisions on signing requests from local apps
+//! - Plain-English summaries of DTOs for confirmation dialogs
+//! - Switching token endpoints between saved channel/contract pairs
+//! - NFT instance metadata addresses and metadata JSON
+//! - Sweep plans: everything available less the fee, and their summaries
+//! - Locale-aware number separators and date formats
+//! - Keyboard shortcut chords, defaults and hotkeys.json overrides
+//! - Transfer reviews: what they list and the form they belong to
+//! - Connection reuse: the keep-alive pool estimate and host pre-resolution
+//! - Portable mode data folder
+//! - Named wallets: per-profile keychain entries, wallets.json and the picker
+//! - Chain Explorer: composite keys, DTOs, pretty-printing and bookmarks
+//! - Contract method discovery: GetContractAPI methods, schema fields and DTOs from typed values
+//! - Headless UI flows (generate, overview, balance) via the test harness
 
 #[cfg(test)]
 pub mod crypto;
@@ -16,4 +86,220 @@ pub mod validation;
 pub mod focus;
 
 #[cfg(test)]
-pub mod test_utils;
\ No newline at end of file
+pub mod test_utils;
+
+#[cfg(test)]
+pub mod backup;
+
+#[cfg(test)]
+pub mod updates;
+
+#[cfg(test)]
+pub mod errors;
+
+#[cfg(test)]
+pub mod http_log;
+
+#[cfg(test)]
+pub mod api_tasks;
+
+#[cfg(test)]
+pub mod coalesce;
+
+#[cfg(test)]
+pub mod cache;
+
+#[cfg(test)]
+pub mod amounts;
+
+#[cfg(test)]
+pub mod recipient;
+
+#[cfg(test)]
+pub mod batch;
+
+#[cfg(test)]
+pub mod export;
+
+#[cfg(test)]
+pub mod keychain;
+
+#[cfg(test)]
+pub mod dashboard;
+
+#[cfg(test)]
+pub mod tokens;
+
+#[cfg(test)]
+pub mod icons;
+
+#[cfg(test)]
+pub mod signing;
+
+#[cfg(test)]
+pub mod dto;
+
+#[cfg(test)]
+pub mod demo;
+
+#[cfg(test)]
+pub mod chain;
+
+#[cfg(test)]
+pub mod cli;
+
+#[cfg(test)]
+pub mod deeplink;
+
+#[cfg(test)]
+pub mod ipc;
+
+#[cfg(test)]
+pub mod log_console;
+
+#[cfg(test)]
+pub mod crash;
+
+#[cfg(test)]
+pub mod approvals;
+
+#[cfg(test)]
+pub mod spending;
+
+#[cfg(test)]
+pub mod history;
+
+#[cfg(test)]
+pub mod events;
+
+#[cfg(test)]
+pub mod holds;
+
+#[cfg(test)]
+pub mod http_options;
+
+#[cfg(test)]
+pub mod credentials;
+
+#[cfg(test)]
+pub mod circuit;
+
+#[cfg(test)]
+pub mod balance_queue;
+
+#[cfg(test)]
+pub mod ui_state;
+
+#[cfg(test)]
+pub mod domain;
+
+#[cfg(test)]
+pub mod derivation;
+
+#[cfg(test)]
+pub mod address;
+
+#[cfg(test)]
+pub mod endpoints;
+
+#[cfg(test)]
+pub mod environment;
+
+#[cfg(test)]
+pub mod api_mode;
+
+#[cfg(test)]
+pub mod envelope;
+
+#[cfg(test)]
+pub mod rate_limit;
+
+#[cfg(test)]
+pub mod stats;
+
+#[cfg(test)]
+pub mod balance_history;
+
+#[cfg(test)]
+pub mod fonts;
+
+#[cfg(test)]
+pub mod icon_atlas;
+
+#[cfg(test)]
+pub mod file_drop;
+
+#[cfg(test)]
+pub mod passphrase_strength;
+
+#[cfg(test)]
+pub mod pin_lockout;
+
+#[cfg(test)]
+pub mod wallet_bundle;
+
+#[cfg(test)]
+pub mod backup_sync;
+
+#[cfg(test)]
+pub mod relative_time;
+
+#[cfg(test)]
+pub mod keyring_health;
+
+#[cfg(test)]
+pub mod keyring_backend;
+
+#[cfg(test)]
+pub mod file_keyring;
+
+#[cfg(test)]
+pub mod chunked_store;
+
+#[cfg(test)]
+pub mod biometric;
+
+#[cfg(test)]
+pub mod audit_log;
+
+#[cfg(test)]
+pub mod explain;
+
+#[cfg(test)]
+pub mod chain_targets;
+
+#[cfg(test)]
+pub mod nft;
+
+#[cfg(test)]
+pub mod sweep;
+
+#[cfg(test)]
+pub mod locale;
+
+#[cfg(test)]
+pub mod hotkeys;
+
+#[cfg(test)]
+pub mod review;
+
+#[cfg(test)]
+pub mod connections;
+
+#[cfg(test)]
+pub mod portable;
+
+#[cfg(test)]
+pub mod wallets;
+
+#[cfg(test)]
+pub mod explorer;
+
+#[cfg(test)]
+pub mod contract_api;
+
+#[cfg(test)]
+pub mod harness;
+
+#[cfg(test)]
+pub mod ui_flows;
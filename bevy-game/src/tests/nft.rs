@@ -0,0 +1,54 @@
+//! NFT instance tests
+//!
+//! Covers the Balance screen's drill-down helpers in nft.rs:
+//! - Metadata addresses with `{id}`, a trailing slash, or neither
+//! - `ipfs://` links go through the gateway
+//! - Metadata attributes as a trait list or a plain object, and instance ids as strings or numbers
+
+use crate::nft::{metadata_url, parse_metadata, resolve_uri, NftInstance, IPFS_GATEWAY};
+use rust_decimal::Decimal;
+
+#[cfg(test)]
+mod nft_tests {
+    use super::*;
+
+    #[test]
+    fn test_metadata_urls() {
+        assert_eq!(metadata_url("https://game.example/nft/{id}.json", "42").as_deref(), Some("https://game.example/nft/42.json"));
+        assert_eq!(metadata_url("https://game.example/nft/", "42").as_deref(), Some("https://game.example/nft/42"));
+        assert_eq!(metadata_url("https://game.example/class.json", "42").as_deref(), Some("https://game.example/class.json"));
+        assert_eq!(metadata_url("ipfs://Qm123/", "7"), Some(format!("{}Qm123/7", IPFS_GATEWAY)));
+        assert_eq!(metadata_url("  ", "7"), None);
+
+        assert_eq!(resolve_uri("ipfs://ipfs/Qm123/a.png"), format!("{}Qm123/a.png", IPFS_GATEWAY));
+        assert_eq!(resolve_uri("https://game.example/a.png"), "https://game.example/a.png");
+    }
+
+    #[test]
+    fn test_parse_metadata() {
+        let listed = parse_metadata(r#"{"name": " Dragon ", "description": "", "image": "ipfs://Qm1/d.png",
+            "attributes": [{"trait_type": "Color", "value": "Red"}, {"value": 7}, {"trait_type": "Broken"}]}"#)
+        .unwrap();
+        assert_eq!(listed.name.as_deref(), Some("Dragon"));
+        assert_eq!(listed.description, None);
+        assert_eq!(listed.image, Some(format!("{}Qm1/d.png", IPFS_GATEWAY)));
+        assert_eq!(listed.attributes, vec![("Color".to_string(), "Red".to_string()), ("Trait".to_string(), "7".to_string())]);
+
+        let object = parse_metadata(r#"{"image_url": "https://game.example/d.png", "attributes": {"Speed": 3, "Rare": true}}"#).unwrap();
+        assert_eq!(object.image.as_deref(), Some("https://game.example/d.png"));
+        assert_eq!(object.attributes.len(), 2);
+        assert!(object.attributes.contains(&("Rare".to_string(), "true".to_string())));
+
+        assert!(parse_metadata("[1, 2]").is_err());
+        assert!(parse_metadata("<html>").is_err());
+    }
+
+    #[test]
+    fn test_instance_ids_as_strings_or_numbers() {
+        let instances: Vec<NftInstance> = serde_json::from_str(r#"[{"instance": "12", "quantity": "1"}, {"instance": 13, "quantity": "1", "owner": "client|alice"}]"#).unwrap();
+        assert_eq!(instances[0].instance, "12");
+        assert_eq!(instances[1].instance, "13");
+        assert_eq!(instances[1].quantity, Decimal::ONE);
+        assert_eq!(instances[1].owner.as_deref(), Some("client|alice"));
+    }
+}
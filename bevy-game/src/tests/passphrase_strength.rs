@@ -0,0 +1,57 @@
+//! Passphrase strength tests
+//!
+//! Covers the zxcvbn-style estimate in passphrase_strength.rs:
+//! - Common passwords, l33t spellings, repeats and sequences are weak however long
+//! - Phrases of several words and random characters are accepted
+//! - The meter line, and the hint it gives for a weak passphrase
+
+use crate::passphrase_strength::{estimate, Strength, Weakness};
+
+#[cfg(test)]
+mod passphrase_strength_tests {
+    use super::*;
+
+    #[test]
+    fn test_guessable_patterns_are_weak() {
+        for (passphrase, weakness) in [
+            ("Password1234", Weakness::CommonPassword),
+            ("P@ssw0rd2024!", Weakness::CommonPassword),
+            ("qwertyuiop123456", Weakness::CommonPassword),
+            ("aaaaaaaaaaaaaaaa", Weakness::Repeated),
+            ("abcdefghijklmnop", Weakness::Sequence),
+            ("asdfghjkl;987654", Weakness::Sequence),
+        ] {
+            let estimate = estimate(passphrase);
+            assert_eq!(estimate.strength, Strength::Weak, "{}", passphrase);
+            assert!(!estimate.acceptable(), "{}", passphrase);
+            assert_eq!(estimate.weakness, Some(weakness), "{}", passphrase);
+        }
+    }
+
+    #[test]
+    fn test_words_and_random_characters_pass() {
+        let three_words = estimate("correct horse battery");
+        assert_eq!(three_words.strength, Strength::Fair);
+        assert!(three_words.acceptable());
+        assert_eq!(three_words.weakness, None);
+
+        // Another word is worth more than a capital letter
+        let four_words = estimate("correct horse battery staple");
+        assert_eq!(four_words.strength, Strength::Strong);
+        assert!(estimate("Correct horse battery").bits < four_words.bits);
+
+        assert!(estimate("k8#Vq2!nR5z@Lw").acceptable());
+        assert!(estimate("k8#Vq2!nR5z@Lw").bits > estimate("k8#Vq2!nR5z@").bits);
+    }
+
+    #[test]
+    fn test_meter() {
+        assert_eq!(estimate("aaaaaaaaaaaaaaaa").meter(), "Strength: ■□□□ Weak (~9 bits) - repeats a character");
+        assert_eq!(estimate("correct horse battery").meter(), "Strength: ■■□□ Fair (~58 bits)");
+        assert_eq!(estimate("correct horse battery staple").meter(), "Strength: ■■■□ Strong (~78 bits)");
+        // A pattern found in a strong passphrase isn't worth a hint
+        assert_eq!(estimate("correct horse battery staple 123").weakness, Some(Weakness::Sequence));
+        assert!(!estimate("correct horse battery staple 123").meter().contains(" - "));
+        assert_eq!(estimate("").strength, Strength::Weak);
+    }
+}
@@ -0,0 +1,53 @@
+//! PIN lockout tests
+//!
+//! Covers the wrong-PIN counter in pin_lockout.rs:
+//! - The first few wrong PINs are free, then each doubles the wait up to an hour
+//! - The count survives a restart
+//! - Enough wrong PINs refuse the PIN however long the wait
+
+use crate::pin_lockout::PinLockout;
+use std::time::{Duration, SystemTime};
+
+#[cfg(test)]
+mod pin_lockout_tests {
+    use super::*;
+
+    #[test]
+    fn test_pin_lockout() {
+        let start = SystemTime::now();
+        let mut lockout = PinLockout::default();
+        for _ in 0..2 {
+            lockout.fail(start);
+            assert_eq!(lockout.delay(), Duration::ZERO);
+            assert_eq!(lockout.refusal(start), None);
+        }
+        lockout.fail(start);
+        assert_eq!(lockout.delay(), Duration::from_secs(30));
+        assert_eq!(lockout.refusal(start + Duration::from_secs(10)), Some("Too many wrong PINs - try again in 20 s".to_string()));
+        assert_eq!(lockout.refusal(start + Duration::from_secs(30)), None);
+        lockout.fail(start);
+        assert_eq!(lockout.delay(), Duration::from_secs(60));
+
+        // Counted across restarts
+        let path = std::env::temp_dir().join(format!("galachain-pin-lockout-{}.json", std::process::id()));
+        lockout.save(Some(path.clone())).unwrap();
+        let mut lockout = PinLockout::load(Some(path.clone()));
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(lockout.failures, 4);
+        assert!(lockout.refusal(start).is_some());
+
+        for _ in 0..5 {
+            lockout.fail(start);
+        }
+        assert_eq!(lockout.delay(), Duration::from_secs(30 * 64));
+        assert!(!lockout.locked_out());
+        lockout.fail(start);
+        assert!(lockout.locked_out());
+        assert_eq!(lockout.delay(), Duration::from_secs(60 * 60));
+        // Waiting doesn't help, only the fallback passphrase or seed phrase does
+        assert_eq!(
+            lockout.refusal(start + Duration::from_secs(24 * 60 * 60)),
+            Some("10 wrong PINs in a row - unlock with the fallback passphrase or import the seed phrase again before the PIN is accepted".to_string())
+        );
+    }
+}
@@ -0,0 +1,26 @@
+//! Portable mode tests
+//!
+//! Covers where portable.rs keeps the wallet's files:
+//! - Next to a plain executable
+//! - Next to the .app bundle on macOS, not inside it
+
+use crate::portable::{data_dir_for, DATA_DIR};
+use std::path::{Path, PathBuf};
+
+#[cfg(test)]
+mod portable_tests {
+    use super::*;
+
+    #[test]
+    fn test_data_dir_beside_executable() {
+        assert_eq!(data_dir_for(Path::new("/media/usb/wallet/galachain-wallet")), Some(PathBuf::from("/media/usb/wallet").join(DATA_DIR)));
+        assert_eq!(data_dir_for(Path::new("E:/Wallet/galachain-wallet.exe")), Some(PathBuf::from("E:/Wallet").join(DATA_DIR)));
+    }
+
+    #[test]
+    fn test_data_dir_beside_macos_bundle() {
+        assert_eq!(data_dir_for(Path::new("/Volumes/USB/Wallet.app/Contents/MacOS/wallet")), Some(PathBuf::from("/Volumes/USB/galachain-wallet-data")));
+        // Not a bundle: stays beside the executable
+        assert_eq!(data_dir_for(Path::new("/opt/Wallet/Contents/MacOS/wallet")), Some(PathBuf::from("/opt/Wallet/Contents/MacOS").join(DATA_DIR)));
+    }
+}
@@ -0,0 +1,50 @@
+//! Rate limit tests
+//!
+//! Covers how a 429 pauses requests:
+//! - Retry-After as seconds and as an HTTP date
+//! - Pauses per endpoint URL, extended but never shortened
+//! - The request class shown in the banner
+
+use crate::rate_limit::{class_label, parse_retry_after, RateLimits};
+use std::time::{Duration, UNIX_EPOCH};
+
+#[cfg(test)]
+mod rate_limit_tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_after_values() {
+        // Sun, 06 Nov 1994 08:49:37 GMT
+        let date = UNIX_EPOCH + Duration::from_secs(784_111_777);
+        assert_eq!(parse_retry_after("120", date), Some(Duration::from_secs(120)));
+        assert_eq!(parse_retry_after(" 0 ", date), Some(Duration::ZERO));
+        assert_eq!(parse_retry_after("Sun, 06 Nov 1994 08:50:07 GMT", date), Some(Duration::from_secs(30)));
+        assert_eq!(parse_retry_after("Sun, 06 Nov 1994 08:49:00 GMT", date), Some(Duration::ZERO));
+        assert_eq!(parse_retry_after("Thu, 29 Feb 2024 00:00:00 GMT", UNIX_EPOCH), Some(Duration::from_secs(1_709_164_800)));
+        assert_eq!(parse_retry_after("soon", date), None);
+        assert_eq!(parse_retry_after("-5", date), None);
+        assert_eq!(parse_retry_after("Sun, 06 Nov 1994 08:49:37 PST", date), None);
+    }
+
+    #[test]
+    fn test_pauses_per_url() {
+        let limits = RateLimits::default();
+        let balance = "http://localhost:3000/api/product/GalaChainToken/FetchBalances";
+        let transfer = "http://localhost:3000/api/product/GalaChainToken/TransferToken";
+        assert_eq!(limits.remaining(balance), None);
+
+        limits.pause(balance, Duration::from_secs(30));
+        limits.pause(balance, Duration::from_secs(5));
+        assert!(limits.remaining(balance).unwrap() > Duration::from_secs(25));
+        assert_eq!(limits.remaining(transfer), None);
+
+        limits.clone().pause(transfer, Duration::from_secs(10));
+        let paused = limits.paused();
+        assert_eq!(paused.iter().map(|(url, _)| url.as_str()).collect::<Vec<_>>(), vec![balance, transfer]);
+
+        limits.pause("http://localhost:3000/expired", Duration::ZERO);
+        assert_eq!(limits.paused().len(), 2);
+        assert_eq!(class_label(balance), "FetchBalances");
+        assert_eq!(class_label("http://localhost:4000/api/identities/register/"), "register");
+    }
+}
@@ -0,0 +1,74 @@
+//! Transfer recipient tests
+//!
+//! Covers parsing of the addresses accepted on the Transfer screen:
+//! - 0x addresses and eth| aliases normalized to checksummed eth| aliases
+//! - EIP-55 checksum mismatches flagged as likely typos
+//! - client| aliases and malformed input
+//! - Public keys for a sponsored registration must belong to the recipient
+
+use crate::recipient::{parse_recipient, sponsor_public_key, RecipientError};
+use crate::GalaChainClient;
+use secp256k1::{PublicKey, Secp256k1, SecretKey};
+use sha3::{Digest, Keccak256};
+
+#[cfg(test)]
+mod recipient_tests {
+    use super::*;
+
+    const CHECKSUMMED: &str = "5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+
+    #[test]
+    fn test_ethereum_forms_normalize_to_one_alias() {
+        let expected = format!("eth|{}", CHECKSUMMED);
+        for input in [
+            format!("0x{}", CHECKSUMMED),
+            format!("0x{}", CHECKSUMMED.to_lowercase()),
+            format!("eth|{}", CHECKSUMMED.to_uppercase()),
+            format!("  eth|0x{}  ", CHECKSUMMED),
+        ] {
+            let recipient = parse_recipient(&input).unwrap();
+            assert_eq!(recipient.gala_address, expected, "{:?}", input);
+            assert!(!recipient.checksum_mismatch, "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn test_wrong_checksum_is_flagged() {
+        // Last letter's case flipped
+        let recipient = parse_recipient("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD").unwrap();
+        assert!(recipient.checksum_mismatch);
+        assert_eq!(recipient.gala_address, format!("eth|{}", CHECKSUMMED));
+    }
+
+    #[test]
+    fn test_client_aliases() {
+        assert_eq!(parse_recipient("client|game-server_1").unwrap().gala_address, "client|game-server_1");
+        assert_eq!(parse_recipient("client|"), Err(RecipientError::InvalidClientId));
+        assert_eq!(parse_recipient("client|a b"), Err(RecipientError::InvalidClientId));
+    }
+
+    #[test]
+    fn test_malformed_recipients() {
+        assert_eq!(parse_recipient("   "), Err(RecipientError::Empty));
+        assert_eq!(parse_recipient("0x1234"), Err(RecipientError::InvalidHex));
+        assert_eq!(parse_recipient(&format!("0x{}", "g".repeat(40))), Err(RecipientError::InvalidHex));
+        assert_eq!(parse_recipient(CHECKSUMMED), Err(RecipientError::UnknownFormat));
+        assert_eq!(parse_recipient("vitalik.eth"), Err(RecipientError::UnknownFormat));
+    }
+
+    #[test]
+    fn test_sponsor_public_key() {
+        let key = PublicKey::from_secret_key(&Secp256k1::new(), &SecretKey::from_slice(&[7; 32]).unwrap());
+        let uncompressed = hex::encode(key.serialize_uncompressed());
+        let address = GalaChainClient::ethereum_to_galachain_address(&hex::encode(&Keccak256::digest(&key.serialize_uncompressed()[1..])[12..]));
+
+        assert_eq!(sponsor_public_key(&address, &uncompressed), Ok(uncompressed.clone()));
+        // Compressed and 0x-prefixed keys come back uncompressed
+        assert_eq!(sponsor_public_key(&address, &format!("0x{}", hex::encode(key.serialize()))), Ok(uncompressed.clone()));
+
+        let other = format!("eth|{}", CHECKSUMMED);
+        assert_eq!(sponsor_public_key(&other, &uncompressed), Err(RecipientError::KeyMismatch));
+        assert_eq!(sponsor_public_key(&address, "04abcd"), Err(RecipientError::InvalidPublicKey));
+        assert_eq!(sponsor_public_key("client|game-server", &uncompressed), Err(RecipientError::NotSponsorable));
+    }
+}
@@ -0,0 +1,46 @@
+//! Relative time label tests
+//!
+//! Covers the "Last updated" style labels that tick while a screen is open:
+//! - Seconds, then minutes, with singular and plural units
+//! - The UTC timestamp once the time is over an hour old
+//! - Times ahead of the clock read "just now"
+//! - Labels keep their prefix and suffix
+
+use crate::relative_time::{format_relative, RelativeTime};
+use std::time::{Duration, UNIX_EPOCH};
+
+#[cfg(test)]
+mod relative_time_tests {
+    use super::*;
+
+    #[test]
+    fn test_seconds_then_minutes() {
+        let at = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let after = |secs: u64| format_relative(at, at + Duration::from_secs(secs));
+        assert_eq!(after(0), "just now");
+        assert_eq!(after(1), "1 second ago");
+        assert_eq!(after(59), "59 seconds ago");
+        assert_eq!(after(60), "1 minute ago");
+        assert_eq!(after(3_599), "59 minutes ago");
+    }
+
+    #[test]
+    fn test_absolute_after_an_hour() {
+        let at = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        assert_eq!(format_relative(at, at + Duration::from_secs(3_600)), "2023-11-14T22:13:20Z");
+        assert_eq!(format_relative(at, at + Duration::from_secs(86_400 * 30)), "2023-11-14T22:13:20Z");
+    }
+
+    #[test]
+    fn test_future_time_is_just_now() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        assert_eq!(format_relative(now + Duration::from_secs(90), now), "just now");
+    }
+
+    #[test]
+    fn test_label_keeps_prefix_and_suffix() {
+        let at = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let label = RelativeTime::new("Last updated: ", at).with_suffix(" · key-1");
+        assert_eq!(label.label(at + Duration::from_secs(12)), "Last updated: 12 seconds ago · key-1");
+    }
+}
@@ -0,0 +1,67 @@
+//! Transfer review tests
+//!
+//! Covers the review step in review.rs:
+//! - The review lists recipient, resolved alias, token, amount, fee, total and memo
+//! - The fee and total read differently for GALA and for other tokens
+//! - A review only matches the form it was made from
+
+use crate::recipient::parse_recipient;
+use crate::review::TransferReview;
+use super::test_utils::town;
+use crate::tokens::TokenInfo;
+use rust_decimal::Decimal;
+
+#[cfg(test)]
+mod review_tests {
+    use super::*;
+
+    const ADDRESS: &str = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+
+    #[test]
+    fn test_gala_review_lines() {
+        let recipient = parse_recipient(ADDRESS).unwrap();
+        let review = TransferReview::new(ADDRESS, &recipient, Some(true), &TokenInfo::gala(), None, Decimal::new(125, 1), "  rent  ");
+        let lines = review.lines();
+        assert_eq!(lines[0], format!("Recipient: {}", ADDRESS));
+        assert!(lines[1].starts_with("Sends to: eth|"));
+        assert!(lines[1].ends_with("(registered)"));
+        assert_eq!(lines[2], "Token: GALA");
+        assert_eq!(lines[3], "Amount: 12.50 GALA");
+        assert_eq!(lines[4], "Network fee: 1.00 GALA");
+        assert_eq!(lines[5], "Estimated total: 13.50 GALA");
+        assert_eq!(lines[6], "Memo: rent (kept in your history, not sent)");
+    }
+
+    #[test]
+    fn test_other_token_review_lines() {
+        let recipient = parse_recipient("client|alice").unwrap();
+        let review = TransferReview::new("client|alice", &recipient, None, &town(0), Some("2"), Decimal::ONE, "");
+        let lines = review.lines();
+        assert_eq!(lines[1], "Sends to: client|alice (registration couldn't be checked)");
+        assert_eq!(lines[2], "Token: TOWN #2");
+        assert_eq!(lines[4], "Network fee: 1.00 GALA, paid from your GALA balance");
+        assert_eq!(lines[5], "Estimated total: 1 TOWN + 1.00 GALA");
+        assert_eq!(lines[6], "Memo: none");
+    }
+
+    #[test]
+    fn test_checksum_warning() {
+        let typo = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD";
+        let recipient = parse_recipient(typo).unwrap();
+        let review = TransferReview::new(typo, &recipient, Some(true), &TokenInfo::gala(), None, Decimal::ONE, "");
+        assert!(review.lines().iter().any(|line| line.contains("doesn't match its checksum")));
+    }
+
+    #[test]
+    fn test_review_matches_its_form() {
+        let recipient = parse_recipient("client|alice").unwrap();
+        let gala = TokenInfo::gala();
+        let review = TransferReview::new("client|alice", &recipient, Some(true), &gala, None, Decimal::TEN, "rent");
+        assert!(review.matches("client|alice", &gala, None, Decimal::TEN, " rent"));
+        assert!(!review.matches("client|bob", &gala, None, Decimal::TEN, "rent"));
+        assert!(!review.matches("client|alice", &town(0), None, Decimal::TEN, "rent"));
+        assert!(!review.matches("client|alice", &gala, Some("1"), Decimal::TEN, "rent"));
+        assert!(!review.matches("client|alice", &gala, None, Decimal::ONE, "rent"));
+        assert!(!review.matches("client|alice", &gala, None, Decimal::TEN, "deposit"));
+    }
+}
@@ -0,0 +1,192 @@
+//! Request signing tests
+//!
+//! Covers the signing middleware for submitted DTOs:
+//! - ULID encoding and uniqueKey format
+//! - Signatures that recover to the signing key
+//! - Refusing a uniqueKey that was already submitted
+//! - Form keys kept while a send form's contents stay the same
+//! - DER, compact and 65-byte encodings, low-s, per-endpoint selection
+//! - Comparing public keys across hex/base64 and compressed/uncompressed encodings
+//! - personal_sign (EIP-191) message signatures
+
+use crate::dto::payload_to_sign;
+use crate::signing::{decode_public_key, FormKey, new_unique_key, same_public_key, sign_dto, sign_payload, sign_personal_message, ulid, SignatureFormat, UsedKeys};
+use crate::ApiSettings;
+use secp256k1::ecdsa::{RecoverableSignature, RecoveryId, Signature};
+use secp256k1::{Message, PublicKey, Secp256k1, SecretKey};
+use serde_json::json;
+use sha3::{Digest, Keccak256};
+
+#[cfg(test)]
+mod signing_tests {
+    use super::*;
+
+    fn test_key() -> SecretKey {
+        SecretKey::from_slice(&[7u8; 32]).unwrap()
+    }
+
+    #[test]
+    fn test_ulid_encoding() {
+        // Timestamp example from the ULID spec
+        assert_eq!(ulid(1469918176385, 0), "01ARYZ6S410000000000000000");
+        assert_eq!(ulid(0, u128::MAX), "0000000000ZZZZZZZZZZZZZZZZ");
+        assert!(ulid(1_000, 0) < ulid(1_001, 0));
+    }
+
+    #[test]
+    fn test_unique_keys_differ_within_a_millisecond() {
+        let first = new_unique_key("transfer");
+        let second = new_unique_key("transfer");
+        assert!(first.starts_with("transfer-"));
+        assert_eq!(first.len(), "transfer-".len() + 26);
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn test_signature_recovers_to_signing_key() {
+        let key = test_key();
+        let signed = sign_dto(&json!({"owner": "eth|a", "quantity": "1"}), &key, "burn", SignatureFormat::Recoverable).unwrap();
+        assert!(signed.unique_key.starts_with("burn-"));
+        assert_eq!(signed.body["uniqueKey"], signed.unique_key.as_str());
+        assert_eq!(signed.body["signature"], signed.signature.as_str());
+
+        let bytes = hex::decode(&signed.signature).unwrap();
+        assert_eq!(bytes.len(), 65);
+        let recovery_id = RecoveryId::from_i32(bytes[64] as i32 - 27).unwrap();
+        let signature = RecoverableSignature::from_compact(&bytes[..64], recovery_id).unwrap();
+        let hash = Keccak256::digest(payload_to_sign(&signed.body).unwrap().as_bytes());
+        let message = Message::from_slice(&hash).unwrap();
+        let secp = Secp256k1::new();
+        assert_eq!(secp.recover_ecdsa(&message, &signature).unwrap(), PublicKey::from_secret_key(&secp, &key));
+    }
+
+    #[test]
+    fn test_existing_unique_key_is_kept() {
+        let signed = sign_dto(&json!({"uniqueKey": "january-2025-event-1"}), &test_key(), "burn", SignatureFormat::Recoverable).unwrap();
+        assert_eq!(signed.unique_key, "january-2025-event-1");
+    }
+
+    #[test]
+    fn test_used_keys_are_claimed_once() {
+        let used = UsedKeys::default();
+        let shared = used.clone();
+        assert!(used.claim("transfer-1"));
+        assert!(!shared.claim("transfer-1"));
+        assert!(shared.claim("transfer-2"));
+    }
+
+    fn message_for(payload: &str) -> Message {
+        Message::from_slice(&Keccak256::digest(payload.as_bytes())).unwrap()
+    }
+
+    #[test]
+    fn test_all_formats_verify_with_low_s() {
+        let key = test_key();
+        let secp = Secp256k1::new();
+        let public_key = PublicKey::from_secret_key(&secp, &key);
+        let payload = r#"{"owner":"eth|a","uniqueKey":"k"}"#;
+        let message = message_for(payload);
+
+        let der = hex::decode(sign_payload(payload, &key, SignatureFormat::Der)).unwrap();
+        let compact = hex::decode(sign_payload(payload, &key, SignatureFormat::Compact)).unwrap();
+        let recoverable = hex::decode(sign_payload(payload, &key, SignatureFormat::Recoverable)).unwrap();
+        assert!((70..=72).contains(&der.len()) && der[0] == 0x30);
+        assert_eq!(compact.len(), 64);
+        assert_eq!(recoverable.len(), 65);
+
+        let from_der = Signature::from_der(&der).unwrap();
+        let from_compact = Signature::from_compact(&compact).unwrap();
+        assert_eq!(from_der, from_compact);
+        assert_eq!(&recoverable[..64], &compact[..]);
+        for signature in [from_der, from_compact] {
+            secp.verify_ecdsa(&message, &signature, &public_key).unwrap();
+            let mut normalized = signature;
+            normalized.normalize_s();
+            assert_eq!(normalized, signature, "s must already be in the lower half");
+        }
+    }
+
+    #[test]
+    fn test_recovery_id_follows_normalized_s() {
+        // A high-s signature recovers the right key only if v is flipped along with s
+        let key = test_key();
+        let secp = Secp256k1::new();
+        let message = message_for("payload");
+        let signature = secp.sign_ecdsa_recoverable(&message, &key);
+        let (recovery_id, compact) = signature.serialize_compact();
+
+        let order = hex::decode("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141").unwrap();
+        let mut high_s = compact;
+        let mut borrow = 0i16;
+        for i in (0..32).rev() {
+            let diff = order[i] as i16 - compact[32 + i] as i16 - borrow;
+            high_s[32 + i] = diff.rem_euclid(256) as u8;
+            borrow = i16::from(diff < 0);
+        }
+        let flipped = RecoveryId::from_i32(recovery_id.to_i32() ^ 1).unwrap();
+        let high = RecoverableSignature::from_compact(&high_s, flipped).unwrap();
+        assert_eq!(secp.recover_ecdsa(&message, &high).unwrap(), PublicKey::from_secret_key(&secp, &key));
+
+        let encoded = crate::signing::encode_signature(&high, SignatureFormat::Recoverable);
+        assert_eq!(&encoded[..64], &compact[..]);
+        assert_eq!(encoded[64], 27 + recovery_id.to_i32() as u8);
+    }
+
+    #[test]
+    fn test_format_is_chosen_per_endpoint() {
+        let mut settings = ApiSettings::default();
+        settings.signature_formats.insert(settings.burn_endpoint.clone(), SignatureFormat::Der);
+        assert_eq!(settings.signature_format(&settings.burn_endpoint), SignatureFormat::Der);
+        assert_eq!(settings.signature_format(&settings.transfer_endpoint), SignatureFormat::Recoverable);
+
+        let reloaded: ApiSettings = serde_json::from_str(&serde_json::to_string(&settings).unwrap()).unwrap();
+        assert_eq!(reloaded.signature_format(&settings.burn_endpoint), SignatureFormat::Der);
+    }
+
+    #[test]
+    fn test_public_keys_compare_across_encodings() {
+        use base64::Engine;
+        let public_key = PublicKey::from_secret_key(&Secp256k1::new(), &test_key());
+        let uncompressed_hex = hex::encode(public_key.serialize_uncompressed());
+        // How GetPublicKey returns it
+        let compressed_base64 = base64::engine::general_purpose::STANDARD.encode(public_key.serialize());
+
+        assert_eq!(decode_public_key(&compressed_base64), Some(public_key));
+        assert!(same_public_key(&compressed_base64, &uncompressed_hex));
+        assert!(same_public_key(&format!("0x{}", uncompressed_hex), &hex::encode(public_key.serialize())));
+
+        let other = PublicKey::from_secret_key(&Secp256k1::new(), &SecretKey::from_slice(&[8u8; 32]).unwrap());
+        assert!(!same_public_key(&compressed_base64, &hex::encode(other.serialize_uncompressed())));
+        assert!(!same_public_key("mock", "mock"));
+    }
+
+    #[test]
+    fn test_personal_message_recovers_to_the_key() {
+        let message = "Log in to Space Game #1234";
+        let signature = hex::decode(sign_personal_message(message, &test_key()).strip_prefix("0x").unwrap()).unwrap();
+        assert_eq!(signature.len(), 65);
+
+        let hash = Keccak256::digest(format!("\x19Ethereum Signed Message:\n{}{}", message.len(), message).as_bytes());
+        let recovery_id = RecoveryId::from_i32(i32::from(signature[64] - 27)).unwrap();
+        let recoverable = RecoverableSignature::from_compact(&signature[..64], recovery_id).unwrap();
+        let recovered = Secp256k1::new().recover_ecdsa(&Message::from_slice(&hash).unwrap(), &recoverable).unwrap();
+        assert_eq!(recovered, PublicKey::from_secret_key(&Secp256k1::new(), &test_key()));
+    }
+
+    #[test]
+    fn test_form_key_follows_form_contents() {
+        let mut form_key = FormKey::default();
+        let first = form_key.validated("eth|1|GALA|5", "transfer").to_string();
+        assert!(first.starts_with("transfer-"));
+        assert_eq!(form_key.validated("eth|1|GALA|5", "transfer"), first);
+
+        let changed = form_key.validated("eth|1|GALA|6", "transfer").to_string();
+        assert_ne!(changed, first);
+        // Changing the form back is a new submission, not the first one again
+        assert_ne!(form_key.validated("eth|1|GALA|5", "transfer"), first);
+
+        let before_clear = form_key.validated("eth|1|GALA|5", "transfer").to_string();
+        form_key.clear();
+        assert_ne!(form_key.validated("eth|1|GALA|5", "transfer"), before_clear);
+    }
+}
@@ -0,0 +1,79 @@
+//! Spending limit tests
+//!
+//! Covers the local ledger behind the daily and weekly limits:
+//! - Spending counts per token over rolling 24-hour and 7-day windows
+//! - The daily limit is reported before the weekly one, with the amount over
+//! - Limit options cycle back to no limit
+//! - Limits and records survive a save/load round trip
+
+use crate::spending::{next_limit, Period, SpendingLedger, LIMIT_OPTIONS};
+use rust_decimal::Decimal;
+
+#[cfg(test)]
+mod spending_tests {
+    use super::*;
+
+    const NOW: u64 = 1_700_000_000;
+    const HOUR: u64 = 60 * 60;
+
+    #[test]
+    fn test_windows_are_rolling_and_per_token() {
+        let mut ledger = SpendingLedger::default();
+        ledger.record("GALA|Unit|none|none", Decimal::from(300), NOW - 30 * HOUR);
+        ledger.record("GALA|Unit|none|none", Decimal::from(50), NOW - HOUR);
+        ledger.record("TOWN|Unit|none|none", Decimal::from(7), NOW - HOUR);
+
+        assert_eq!(ledger.spent("GALA|Unit|none|none", Period::Day, NOW), Decimal::from(50));
+        assert_eq!(ledger.spent("GALA|Unit|none|none", Period::Week, NOW), Decimal::from(350));
+        assert_eq!(ledger.spent("TOWN|Unit|none|none", Period::Week, NOW), Decimal::from(7));
+
+        // Recording drops what no window can see any more
+        ledger.record("GALA|Unit|none|none", Decimal::ONE, NOW + 7 * 24 * HOUR - 2 * HOUR);
+        assert_eq!(ledger.entries.len(), 3);
+    }
+
+    #[test]
+    fn test_breach_reports_daily_first() {
+        let mut ledger = SpendingLedger::default();
+        assert_eq!(ledger.breach("GALA", Decimal::from(1_000_000), NOW), None);
+
+        ledger.limits.daily = Some(Decimal::from(100));
+        ledger.limits.weekly = Some(Decimal::from(150));
+        ledger.record("GALA", Decimal::from(80), NOW - 2 * 24 * HOUR);
+        ledger.record("GALA", Decimal::from(30), NOW - HOUR);
+
+        assert_eq!(ledger.remaining("GALA", Period::Day, NOW), Some(Decimal::from(70)));
+        assert_eq!(ledger.remaining("GALA", Period::Week, NOW), Some(Decimal::from(40)));
+        assert_eq!(ledger.breach("GALA", Decimal::from(40), NOW), None);
+
+        let breach = ledger.breach("GALA", Decimal::from(90), NOW).unwrap();
+        assert_eq!((breach.period, breach.over_by), (Period::Day, Decimal::from(20)));
+        let breach = ledger.breach("GALA", Decimal::from(50), NOW).unwrap();
+        assert_eq!((breach.period, breach.remaining, breach.over_by), (Period::Week, Decimal::from(40), Decimal::from(10)));
+    }
+
+    #[test]
+    fn test_limit_options_cycle() {
+        let mut limit = None;
+        for _ in 0..LIMIT_OPTIONS.len() {
+            limit = next_limit(limit);
+        }
+        assert_eq!(limit, None);
+        assert_eq!(next_limit(None), Some(Decimal::from(100)));
+        assert_eq!(next_limit(Some(Decimal::from(42))), None);
+    }
+
+    #[test]
+    fn test_ledger_round_trip() {
+        let path = std::env::temp_dir().join(format!("galachain-spending-{}.json", std::process::id()));
+        let mut ledger = SpendingLedger::default();
+        assert!(ledger.limits.unlock_to_override);
+        ledger.limits.weekly = Some(Decimal::from(1000));
+        ledger.record("GALA", "12.5".parse().unwrap(), NOW);
+        ledger.save(Some(path.clone())).unwrap();
+
+        assert_eq!(SpendingLedger::load(Some(path.clone())), ledger);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(SpendingLedger::load(Some(path)), SpendingLedger::default());
+    }
+}
@@ -0,0 +1,80 @@
+//! Usage stats tests
+//!
+//! Covers the local counts behind the Stats screen:
+//! - Error rates and average latency per endpoint and overall
+//! - Transfers, burns and burned totals, bucketed by day for the chart
+//! - Counts survive a save/load round trip and can be reset
+
+use crate::history::TxKind;
+use crate::stats::{format_latency, DayCounts, UsageData, UsageStats};
+use std::time::Duration;
+
+#[cfg(test)]
+mod stats_tests {
+    use super::*;
+
+    const BALANCES: &str = "http://localhost:3000/api/product/GalaChainToken/FetchBalances";
+    const TRANSFER: &str = "http://localhost:3000/api/product/GalaChainToken/TransferToken";
+    // 2023-11-14T22:13:20Z
+    const NOW: u64 = 1_700_000_000;
+
+    #[test]
+    fn test_endpoint_rates_and_latency() {
+        let mut usage = UsageData::default();
+        usage.record_request(BALANCES, Duration::from_millis(100), true);
+        usage.record_request(BALANCES, Duration::from_millis(300), false);
+        usage.record_request(TRANSFER, Duration::from_millis(1_400), true);
+
+        let balances = &usage.endpoints[BALANCES];
+        assert_eq!((balances.requests, balances.errors), (2, 1));
+        assert_eq!(balances.error_rate(), 0.5);
+        assert_eq!(balances.average_latency(), Duration::from_millis(200));
+
+        let totals = usage.totals();
+        assert_eq!((totals.requests, totals.errors), (3, 1));
+        assert_eq!(totals.average_latency(), Duration::from_millis(600));
+        assert_eq!(UsageData::default().totals().error_rate(), 0.0);
+        assert_eq!(format_latency(Duration::from_millis(85)), "85 ms");
+        assert_eq!(format_latency(Duration::from_millis(1_250)), "1.2 s");
+    }
+
+    #[test]
+    fn test_operations_by_day() {
+        let mut usage = UsageData::default();
+        usage.record_operation(TxKind::Transfer, "GALA", "5".parse().unwrap(), NOW);
+        usage.record_operation(TxKind::Burn, "GALA", "1.5".parse().unwrap(), NOW);
+        usage.record_operation(TxKind::Burn, "GALA", "2".parse().unwrap(), NOW - 2 * 86_400);
+        usage.record_operation(TxKind::Burn, "SILK", "10".parse().unwrap(), NOW - 30 * 86_400);
+
+        assert_eq!((usage.transfers_sent, usage.burns), (1, 3));
+        assert_eq!(usage.burned["GALA"], "3.5".parse().unwrap());
+        assert_eq!(usage.burned["SILK"], "10".parse().unwrap());
+
+        let days = usage.recent_days(NOW, 3);
+        let counts: Vec<DayCounts> = days.iter().map(|(_, counts)| *counts).collect();
+        assert_eq!(
+            counts,
+            vec![DayCounts { transfers: 0, burns: 1 }, DayCounts::default(), DayCounts { transfers: 1, burns: 1 }]
+        );
+        assert_eq!(days[2].0, NOW / 86_400);
+    }
+
+    #[test]
+    fn test_stats_round_trip_and_reset() {
+        let path = std::env::temp_dir().join(format!("galachain-stats-{}.json", std::process::id()));
+        let stats = UsageStats::default();
+        stats.record_request(BALANCES, Duration::from_millis(120), true);
+        stats.clone().record_operation(TxKind::Burn, "GALA", "0.25".parse().unwrap(), NOW);
+        stats.snapshot().save(Some(path.clone())).unwrap();
+
+        let loaded = UsageData::load(Some(path.clone()));
+        assert_eq!(loaded, stats.snapshot());
+        assert_eq!(loaded.burned["GALA"], "0.25".parse().unwrap());
+        std::fs::remove_file(&path).unwrap();
+
+        let version = stats.version();
+        stats.reset();
+        assert!(stats.version() > version);
+        assert_eq!(stats.snapshot(), UsageData::default());
+    }
+}
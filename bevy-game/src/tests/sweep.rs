@@ -0,0 +1,62 @@
+//! Sweep tests
+//!
+//! Covers the "Sweep to address" plan in sweep.rs:
+//! - GALA sends everything but the network fee; other tokens send it all
+//! - Balances that can't cover anything aren't swept
+//! - The summary says what moves, what the fee comes out of and what stays
+//! - Sweeping every token keeps back the GALA for each transfer's fee
+
+use crate::amounts::NETWORK_FEE;
+use crate::sweep::{sweep_all, SweepPlan};
+use super::test_utils::town;
+use crate::tokens::TokenInfo;
+use rust_decimal::Decimal;
+
+#[cfg(test)]
+mod sweep_tests {
+    use super::*;
+
+    #[test]
+    fn test_sweep_amounts() {
+        let gala = SweepPlan::new("client|alice", &TokenInfo::gala(), Decimal::new(10015, 1), Decimal::ZERO).unwrap();
+        assert_eq!(gala.amount, Decimal::new(10005, 1));
+        assert_eq!(gala.amount + NETWORK_FEE, gala.available);
+        assert_eq!(gala.left(), Decimal::ZERO);
+
+        let whole = SweepPlan::new("client|alice", &town(0), Decimal::from(7), Decimal::ZERO).unwrap();
+        assert_eq!(whole.amount, Decimal::from(7));
+
+        assert_eq!(SweepPlan::new("client|alice", &TokenInfo::gala(), NETWORK_FEE, Decimal::ZERO).unwrap_err(), "Nothing to sweep - 1.00 GALA doesn't cover the network fee");
+        assert_eq!(SweepPlan::new("client|alice", &town(0), Decimal::ZERO, Decimal::ONE).unwrap_err(), "Nothing to sweep - no available TOWN");
+    }
+
+    #[test]
+    fn test_sweep_summary() {
+        let plan = SweepPlan::new("client|alice", &town(0), Decimal::from(7), Decimal::from(2)).unwrap();
+        assert_eq!(
+            plan.summary(),
+            [
+                "Send 7 TOWN to client|alice",
+                "Network fee: 1 GALA, paid from your GALA balance",
+                "Left available afterwards: 0 TOWN",
+                "Locked 2 TOWN stays behind until its holds expire",
+            ]
+        );
+        let gala = SweepPlan::new("client|alice", &TokenInfo::gala(), Decimal::from(1500), Decimal::ZERO).unwrap();
+        assert_eq!(gala.summary()[0], "Send 1,499.00 GALA to client|alice");
+        assert_eq!(gala.summary()[1], "Network fee: 1 GALA, taken from this balance");
+    }
+
+    #[test]
+    fn test_sweep_all_keeps_fees() {
+        let gala = TokenInfo::gala();
+        let planned = sweep_all(&[(gala.clone(), Decimal::new(105, 1)), (town(0), Decimal::from(7)), (town(0), Decimal::ZERO)]);
+        // One fee for GALA's own transfer and one for TOWN's
+        assert_eq!(planned, [(gala.clone(), Decimal::new(85, 1)), (town(0), Decimal::from(7))]);
+        let fees: Decimal = planned.iter().map(|_| NETWORK_FEE).sum();
+        assert_eq!(planned[0].1 + fees, Decimal::new(105, 1));
+
+        // GALA that only covers the fees isn't moved at all
+        assert_eq!(sweep_all(&[(gala, Decimal::from(2)), (town(0), Decimal::from(7))]), [(town(0), Decimal::from(7))]);
+    }
+}
@@ -1,6 +1,8 @@
 //! Test utilities and helper functions for GalaChain wallet tests
 
-use crate::SecureWalletData;
+use crate::derivation::Derivation;
+use crate::tokens::TokenInfo;
+use crate::{SecureWalletData, WalletType};
 use secp256k1::{SecretKey, Secp256k1};
 use std::str::FromStr;
 
@@ -60,9 +62,8 @@ impl MockKeychainManager {
     }
 }
 
-// Note: We can't implement the KeychainManager trait methods here because
-// they're defined in the main module. We'll need to add a trait later
-// or use dependency injection for proper testing.
+// Note: App-level tests don't need this mock - harness.rs puts a
+// MemorySecretStore behind the real KeychainManager instead.
 
 /// Helper function to create a deterministic SecretKey for testing
 pub fn create_test_secret_key() -> SecretKey {
@@ -74,11 +75,19 @@ pub fn create_test_secret_key() -> SecretKey {
 /// Helper function to create test wallet data
 pub fn create_test_wallet_data() -> SecureWalletData {
     SecureWalletData {
+        wallet_type: WalletType::Mnemonic,
         mnemonic: TestVectors::TEST_MNEMONIC_12.to_string(),
+        address: None,
         created_at: 1234567890, // Fixed timestamp for deterministic tests
+        derivation: Derivation::Legacy,
     }
 }
 
+/// A fungible token other than GALA, with `decimals` places
+pub fn town(decimals: u32) -> TokenInfo {
+    TokenInfo { collection: "TOWN".to_string(), category: "Unit".to_string(), r#type: "none".to_string(), additional_key: "none".to_string(), symbol: "TOWN".to_string(), decimals, icon_url: None }
+}
+
 /// Verify that a mnemonic contains exactly 12 valid words
 pub fn is_valid_word_count(mnemonic: &str) -> bool {
     mnemonic.split_whitespace().count() == 12
@@ -0,0 +1,84 @@
+//! Token registry tests
+//!
+//! Covers the Settings token list:
+//! - Token specs typed into Settings
+//! - Selection when tokens are removed
+//! - Falling back to GALA when the saved list is unusable
+//! - Decimals corrected from the chain's token classes
+
+use crate::amounts::NETWORK_FEE;
+use crate::tokens::{parse_token_spec, TokenError, TokenInfo, TokenRegistry};
+use rust_decimal::Decimal;
+
+#[cfg(test)]
+mod token_tests {
+    use super::*;
+
+    fn token(collection: &str) -> TokenInfo {
+        parse_token_spec(&format!("{}|Unit|none|none", collection)).unwrap()
+    }
+
+    #[test]
+    fn test_parse_token_spec() {
+        assert_eq!(parse_token_spec("GALA|Unit|none|none").unwrap(), TokenInfo::gala());
+
+        let town = parse_token_spec(" TOWN|Unit|none|none, TOWN ,6,https://example.com/town.png").unwrap();
+        assert_eq!(town.class_key(), "TOWN|Unit|none|none");
+        assert_eq!(town.decimals, 6);
+        assert_eq!(town.icon_url.as_deref(), Some("https://example.com/town.png"));
+
+        assert_eq!(parse_token_spec("GALA|Unit|none"), Err(TokenError::InvalidClassKey));
+        assert_eq!(parse_token_spec("GALA||none|none"), Err(TokenError::InvalidClassKey));
+        assert_eq!(parse_token_spec("GALA|Unit|none|none,GALA,eight"), Err(TokenError::InvalidDecimals));
+        assert_eq!(parse_token_spec("GALA|Unit|none|none,GALA,19"), Err(TokenError::InvalidDecimals));
+    }
+
+    #[test]
+    fn test_only_gala_holds_back_the_fee() {
+        assert_eq!(TokenInfo::gala().fee(), NETWORK_FEE);
+        assert_eq!(token("TOWN").fee(), Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_remove_keeps_a_valid_selection() {
+        let mut registry = TokenRegistry::default();
+        assert_eq!(registry.add(TokenInfo::gala()), Err(TokenError::Duplicate));
+        registry.add(token("TOWN")).unwrap();
+        registry.add(token("MUSIC")).unwrap();
+
+        registry.selected = 2;
+        assert!(registry.remove(0));
+        assert_eq!(registry.selected().collection, "MUSIC");
+
+        assert!(registry.remove(1));
+        assert_eq!(registry.selected().collection, "TOWN");
+        assert!(!registry.remove(0), "the last token stays");
+    }
+
+    #[test]
+    fn test_apply_chain_decimals() {
+        let mut registry = TokenRegistry::default();
+        registry.add(token("TOWN")).unwrap();
+        let chain_town = parse_token_spec("TOWN|Unit|none|none,TOWN,0").unwrap();
+        let unlisted = parse_token_spec("SILK|Unit|none|none,SILK,2").unwrap();
+
+        assert!(registry.apply_decimals(&[TokenInfo::gala(), chain_town.clone(), unlisted]));
+        assert_eq!(registry.tokens[1].decimals, 0);
+        assert_eq!(registry.tokens.len(), 2);
+        assert!(!registry.apply_decimals(&[chain_town]), "nothing left to change");
+    }
+
+    #[test]
+    fn test_load_falls_back_to_gala() {
+        let path = std::env::temp_dir().join(format!("galachain-tokens-{}.json", std::process::id()));
+        std::fs::write(&path, r#"{"tokens": [], "selected": 3}"#).unwrap();
+        assert_eq!(TokenRegistry::load(Some(path.clone())), TokenRegistry::default());
+
+        let mut registry = TokenRegistry::default();
+        registry.add(token("TOWN")).unwrap();
+        registry.selected = 1;
+        registry.save(Some(path.clone())).unwrap();
+        assert_eq!(TokenRegistry::load(Some(path.clone())), registry);
+        std::fs::remove_file(path).unwrap();
+    }
+}
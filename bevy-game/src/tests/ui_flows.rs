@@ -0,0 +1,840 @@
+//! UI flow tests
+//!
+//! Drives whole screens through the headless harness:
+//! - First launch routes to onboarding
+//! - A stored wallet is loaded from the secret store at startup
+//! - Generate → overview → balance against the mock chain server
+//! - Registration waits for the consent prompt before sending anything
+//! - A registered key that isn't the wallet's is flagged and can be replaced
+//! - A held transfer is approved by unlocking again, then signed from Approvals
+//! - A burn over the daily limit only warns until Burn is pressed again
+//! - A burn's note shows on the History screen
+//! - A payment link is confirmed before it fills in Transfer
+//! - A dropped seed phrase file is confirmed before it fills in Import Wallet
+//! - A local app's call waits for approval on the signing prompt
+//! - Burn to enter an event submits the burn, then claims the entry
+//! - An unregistered recipient blocks Transfer until it's registered for them
+//! - Gateway credentials are saved to the keychain, reach the client and pass the connection test
+//! - A failing endpoint shows the circuit banner until Retry now closes the circuit
+//! - Balance lists every registry token once there's more than one
+//! - Token decimals come from the chain and limit typed amounts
+//! - Settings previews each endpoint's URL and can test it
+//! - The last wallet screen is saved and reopened at the next launch
+//! - The tray's Refresh balance opens Balance and fetches from the chain
+//! - Auto refresh fetches when Balance or Registration opens, and only for the screens it's on for
+//! - The header bar shows the wallet's address, network and balance, draws its QR code and locks the session
+//! - Unlock only lifts the approval PIN lockout when it's checked with the fallback passphrase
+//! - Wallet menu icons are drawn from the icon atlas
+//! - Each wallet screen tears down its entities on exit and rebuilds when the wallet menu reopens
+//! - Generating over a wallet archives it, and Overview restores it
+//! - Import previews the phrase's addresses and adopts the derivation picked
+//! - A legacy wallet's balances move to its BIP44 address before the wallet switches over
+//! - Balance switches to a saved channel/contract pair and fetches from that contract
+//! - An NFT class drills down to its instances' metadata and opens Transfer for one
+//! - Too little GALA for the network fee is warned about and keeps Transfer and Burn disabled
+//! - Sweep to address sends the whole balance less the fee once its summary is confirmed
+//! - Ctrl+number switches wallet screens, F1 toggles the shortcut sheet and Escape goes back
+//! - Transfer and Import keep what was typed across screens until Clear is pressed
+//! - Review Transfer shows the review; Edit goes back and Confirm & Sign sends it
+
+use super::harness::{post_json, HeadlessApp};
+use super::test_utils::{create_test_wallet_data, TestVectors};
+use crate::address;
+use crate::approvals::ApprovalStatus;
+use crate::chain_targets::ChainTarget;
+use crate::circuit::CircuitState;
+use crate::biometric::GatePurpose;
+use crate::derivation::Derivation;
+use crate::endpoints::Endpoint;
+use crate::fonts::Icon;
+use crate::errors::ApiService;
+use crate::tokens::{parse_token_spec, TokenInfo};
+use crate::{
+    ApprovalButton, ApprovalState, ChainTargetButton, ClearFormButton, FileDrop, FileDropButton, ConfirmMigrationButton, DerivationChoice, MigrationState, OpenMigrationButton, ImportState, ImportWalletButton, InstanceButton, PreviewAddressesButton, AppState, BurnButton, BurnState, CheckRegistrationButton, SpendingState, GenerateWalletButton, IdentityWrite, KeychainManager, RefreshBalanceButton, RegisterIdentityButton, RegistrationConsentButton,
+    EndpointTestButton, EventButton, HeaderBarButton, HeaderBarState, EventsState, GalaChainClient, GatewayAuth, GatewayAuthButton, ApiSettings, HistoryState, RecipientLookup, SponsorButton, IpcCallButton, TrayAction, TransferButton, TransferReviewButton, WalletScreen, IpcState, PaymentLink, PaymentLinkButton, PresenceButton, PresenceGate, SweepButton, ConnectivityState, Hotkeys, RetryCircuitButton, Tokens, UiStateStore, RegistrationState, RestorePreviousWalletButton, TransferState, UnlockSessionButton, WalletData, WalletMenuAction, WalletMenuButton, WalletState, WalletType,
+};
+use bevy::prelude::KeyCode;
+use rust_decimal::Decimal;
+
+#[cfg(test)]
+mod ui_flow_tests {
+    use super::*;
+
+    #[test]
+    fn test_first_launch_starts_onboarding() {
+        let harness = HeadlessApp::new();
+        assert_eq!(harness.state::<AppState>(), AppState::Onboarding);
+        assert!(harness.secrets.contents().is_none());
+    }
+
+    #[test]
+    fn test_stored_wallet_loads_at_startup() {
+        let entry = create_test_wallet_data().to_json().unwrap();
+        let harness = HeadlessApp::with_wallet_entry(&entry);
+
+        assert_eq!(harness.state::<AppState>(), AppState::MainMenu);
+        let (_, expected_address) = KeychainManager::new().generate_wallet_from_mnemonic(TestVectors::TEST_MNEMONIC_12).unwrap();
+        let wallet = harness.resource::<WalletData>();
+        assert_eq!(wallet.wallet_type, WalletType::Mnemonic);
+        assert!(wallet.private_key.is_some());
+        assert_eq!(wallet.address.as_deref(), Some(expected_address.as_str()));
+    }
+
+    #[test]
+    fn test_generate_overview_balance() {
+        let mut harness = HeadlessApp::new();
+        harness.set_state(AppState::WalletMenu);
+
+        harness.press::<WalletMenuButton>(|button| button.0 == WalletMenuAction::Generate);
+        assert_eq!(harness.state::<WalletState>(), WalletState::Generate);
+        harness.press::<GenerateWalletButton>(|_| true);
+        assert!(harness.shows("Wallet Generated Successfully"));
+        assert!(harness.secrets.contents().unwrap().contains("\"mnemonic\""));
+        let address = harness.resource::<WalletData>().address.clone().unwrap();
+
+        harness.press::<WalletMenuButton>(|button| button.0 == WalletMenuAction::Overview);
+        assert_eq!(harness.state::<WalletState>(), WalletState::Overview);
+        assert!(harness.shows(&address));
+
+        harness.press::<WalletMenuButton>(|button| button.0 == WalletMenuAction::Balance);
+        assert_eq!(harness.state::<WalletState>(), WalletState::Balance);
+        harness.press::<RefreshBalanceButton>(|_| true);
+        harness.update_until(|harness| harness.shows("1,234.5"));
+        // The holds table comes from the same FetchBalances call
+        harness.update_until(|harness| harness.shows("mock-stake"));
+        assert!(harness.shows("until released"));
+        assert_eq!(harness.server.request_count("/FetchBalances"), 1);
+    }
+
+    #[test]
+    fn test_registration_needs_consent() {
+        let entry = create_test_wallet_data().to_json().unwrap();
+        let mut harness = HeadlessApp::with_wallet_entry(&entry);
+        harness.set_state(AppState::WalletMenu);
+        harness.press::<WalletMenuButton>(|button| button.0 == WalletMenuAction::Registration);
+        // The mock server reports every address as registered
+        harness.app.world_mut().resource_mut::<RegistrationState>().is_registered = Some(false);
+        harness.update();
+
+        harness.press::<RegisterIdentityButton>(|button| button.0 == IdentityWrite::Register);
+        assert!(harness.shows("Register this wallet?"));
+        assert!(harness.shows("/api/identities/register"));
+        harness.press::<RegistrationConsentButton>(|button| matches!(button, RegistrationConsentButton::Cancel));
+        assert!(!harness.shows("Register this wallet?"));
+        assert_eq!(harness.server.request_count("/register"), 0);
+
+        harness.press::<RegisterIdentityButton>(|button| button.0 == IdentityWrite::Register);
+        harness.press::<RegistrationConsentButton>(|button| matches!(button, RegistrationConsentButton::Confirm));
+        harness.update_until(|harness| harness.shows("Registration accepted"));
+        assert_eq!(harness.server.request_count("/register"), 1);
+    }
+
+    #[test]
+    fn test_registered_key_mismatch_offers_update() {
+        let entry = create_test_wallet_data().to_json().unwrap();
+        let mut harness = HeadlessApp::with_wallet_entry(&entry);
+        harness.set_state(AppState::WalletMenu);
+        harness.press::<WalletMenuButton>(|button| button.0 == WalletMenuAction::Registration);
+
+        harness.press::<CheckRegistrationButton>(|_| true);
+        harness.update_until(|harness| harness.shows("A different public key is registered"));
+        assert!(harness.resource::<RegistrationState>().registered_key.is_some());
+
+        harness.press::<RegisterIdentityButton>(|button| button.0 == IdentityWrite::UpdateKey);
+        assert!(harness.shows("Replace the registered key?"));
+        harness.press::<RegistrationConsentButton>(|button| matches!(button, RegistrationConsentButton::Confirm));
+        harness.update_until(|harness| harness.shows("Registered key updated"));
+        assert_eq!(harness.server.request_count("/UpdatePublicKey"), 1);
+        assert!(harness.resource::<RegistrationState>().registered_key.is_none());
+    }
+
+    #[test]
+    fn test_held_transfer_needs_reauthentication() {
+        let entry = create_test_wallet_data().to_json().unwrap();
+        let mut harness = HeadlessApp::with_wallet_entry(&entry);
+        let from = GalaChainClient::ethereum_to_galachain_address(harness.resource::<WalletData>().address.as_deref().unwrap());
+        let id = {
+            let mut approvals = harness.app.world_mut().resource_mut::<ApprovalState>();
+            approvals.queue.policy.enabled = true;
+            let to = "eth|0000000000000000000000000000000000000001".to_string();
+            approvals.queue.add("approval-test".to_string(), from, to, TokenInfo::gala(), Decimal::from(5000), None).id.clone()
+        };
+        harness.set_state(AppState::WalletMenu);
+        harness.press::<WalletMenuButton>(|button| button.0 == WalletMenuAction::Approvals);
+        harness.update_until(|harness| harness.shows("Waiting for approval"));
+        assert!(!harness.shows("➜ Send"));
+
+        // Without a fallback passphrase there's no presence check to approve with
+        harness.press::<ApprovalButton>(|button| *button == ApprovalButton::Approve(id.clone()));
+        assert!(!harness.shows("Approved"));
+
+        harness.app.world_mut().resource_mut::<PresenceGate>().settings.set_fallback_with_iterations("correct horse battery staple", 10).unwrap();
+        harness.press::<ApprovalButton>(|button| *button == ApprovalButton::Approve(id.clone()));
+        assert!(!harness.shows("Approved"));
+        harness.app.world_mut().resource_mut::<PresenceGate>().passphrase = "correct horse battery staple".to_string();
+        harness.press::<PresenceButton>(|button| *button == PresenceButton::Verify);
+        harness.press::<ApprovalButton>(|button| *button == ApprovalButton::Approve(id.clone()));
+        assert!(harness.shows("Approved"));
+        harness.press::<ApprovalButton>(|button| *button == ApprovalButton::Send(id.clone()));
+        assert!(harness.shows("Unique Key"));
+        let approvals = harness.resource::<ApprovalState>();
+        assert_eq!(approvals.queue.items[0].status, ApprovalStatus::Submitted);
+        // Nothing goes to the chain until the transfer endpoint is wired up
+        assert_eq!(harness.server.request_count("/TransferToken"), 0);
+    }
+
+    #[test]
+    fn test_burn_over_limit_needs_second_press() {
+        let entry = create_test_wallet_data().to_json().unwrap();
+        let mut harness = HeadlessApp::with_wallet_entry(&entry);
+        harness.app.world_mut().resource_mut::<SpendingState>().ledger.limits.daily = Some(Decimal::from(100));
+        harness.set_state(AppState::WalletMenu);
+        harness.press::<WalletMenuButton>(|button| button.0 == WalletMenuAction::Burn);
+        harness.app.world_mut().resource_mut::<BurnState>().amount = "250".to_string();
+        harness.update_until(|harness| harness.shows("over your daily limit"));
+
+        harness.press::<BurnButton>(|_| true);
+        assert!(!harness.shows("Burn Result"));
+        assert!(harness.resource::<SpendingState>().ledger.entries.is_empty());
+
+        // The second press unlocks again from the keychain and goes ahead
+        harness.press::<BurnButton>(|_| true);
+        assert!(harness.shows("Burn Result"));
+        assert_eq!(harness.resource::<SpendingState>().ledger.entries.len(), 1);
+    }
+
+    #[test]
+    fn test_burn_note_shows_in_history() {
+        let entry = create_test_wallet_data().to_json().unwrap();
+        let mut harness = HeadlessApp::with_wallet_entry(&entry);
+        harness.set_state(AppState::WalletMenu);
+        harness.press::<WalletMenuButton>(|button| button.0 == WalletMenuAction::Burn);
+        {
+            let mut burn = harness.app.world_mut().resource_mut::<BurnState>();
+            burn.amount = "5".to_string();
+            burn.note = "  event prize pool  ".to_string();
+        }
+        harness.press::<BurnButton>(|_| true);
+        assert!(harness.shows("Burn Result"));
+
+        let history = harness.resource::<HistoryState>();
+        assert_eq!(history.history.entries.len(), 1);
+        assert_eq!(history.history.entries[0].note.as_deref(), Some("event prize pool"));
+
+        harness.press::<WalletMenuButton>(|button| button.0 == WalletMenuAction::History);
+        harness.update_until(|harness| harness.shows("✎ event prize pool"));
+        assert!(harness.shows("♨ Burned"));
+    }
+
+    #[test]
+    fn test_payment_link_needs_confirmation() {
+        let entry = create_test_wallet_data().to_json().unwrap();
+        let mut harness = HeadlessApp::with_wallet_entry(&entry);
+        let link = "galachain://transfer?to=eth%7C0000000000000000000000000000000000000001&amount=7.5";
+        harness.app.world_mut().resource_mut::<PaymentLink>().pending = Some(crate::deeplink::parse_payment_link(link));
+        harness.update_until(|harness| harness.shows("Payment Request"));
+        assert!(harness.shows("send 7.50 GALA to"));
+        assert_eq!(harness.state::<AppState>(), AppState::MainMenu);
+
+        harness.press::<PaymentLinkButton>(|button| *button == PaymentLinkButton::Review);
+        assert_eq!(harness.state::<WalletState>(), WalletState::Transfer);
+        assert!(!harness.shows("Payment Request"));
+        let transfer = harness.resource::<TransferState>();
+        assert_eq!(transfer.recipient_address, "eth|0000000000000000000000000000000000000001");
+        assert_eq!(transfer.amount, "7.5");
+        // Filled in only; nothing signed or sent
+        assert!(!harness.shows("Transfer Result"));
+        assert!(harness.resource::<HistoryState>().history.entries.is_empty());
+    }
+
+    #[test]
+    fn test_dropped_seed_phrase_needs_confirmation() {
+        let entry = create_test_wallet_data().to_json().unwrap();
+        let mut harness = HeadlessApp::with_wallet_entry(&entry);
+        let path = std::env::temp_dir().join(format!("galachain-dropped-seed-{}.txt", std::process::id()));
+        std::fs::write(&path, TestVectors::TEST_MNEMONIC_12).unwrap();
+        harness.app.world_mut().send_event(bevy::window::FileDragAndDrop::DroppedFile {
+            window: bevy::prelude::Entity::PLACEHOLDER,
+            path_buf: path.clone(),
+        });
+        harness.update_until(|harness| harness.shows("Dropped File"));
+        std::fs::remove_file(&path).unwrap();
+        assert!(harness.shows("looks like a seed phrase"));
+        assert!(harness.shows("already holds a wallet"));
+
+        harness.press::<FileDropButton>(|button| *button == FileDropButton::Open);
+        harness.update_until(|harness| harness.shows("Import Existing Wallet"));
+        assert_eq!(harness.state::<WalletState>(), WalletState::Import);
+        assert!(harness.resource::<FileDrop>().pending.is_none());
+        assert_eq!(harness.resource::<ImportState>().seed_words.join(" "), TestVectors::TEST_MNEMONIC_12);
+        // Filled in only; the stored wallet is untouched until Import Wallet is pressed
+        assert_eq!(harness.secrets.contents(), Some(entry));
+    }
+
+    #[test]
+    fn test_local_app_call_needs_approval() {
+        let entry = create_test_wallet_data().to_json().unwrap();
+        let mut harness = HeadlessApp::with_wallet_entry(&entry);
+        {
+            let mut ipc = harness.app.world_mut().resource_mut::<IpcState>();
+            ipc.settings.enabled = true;
+            ipc.settings.port = 0;
+        }
+        harness.update();
+        let addr = harness.resource::<IpcState>().server.as_ref().expect("server started").addr;
+        let caller = std::thread::spawn(move || {
+            post_json(addr, r#"{"jsonrpc":"2.0","id":1,"method":"signMessage","params":{"message":"Log in to Space Game"}}"#, "")
+        });
+
+        harness.update_until(|harness| harness.shows("Log in to Space Game"));
+        assert!(harness.shows("Signing Request"));
+        harness.press::<IpcCallButton>(|button| *button == IpcCallButton::Approve);
+        assert!(!harness.shows("Signing Request"));
+
+        let response = caller.join().unwrap();
+        assert!(response.contains(r#""signature":"0x"#), "{}", response);
+    }
+
+    #[test]
+    fn test_burn_to_enter_event_then_claim() {
+        let entry = create_test_wallet_data().to_json().unwrap();
+        let mut harness = HeadlessApp::with_wallet_entry(&entry);
+        let claim_endpoint = format!("{}/claim", harness.server.base_url);
+        harness.app.world_mut().resource_mut::<EventsState>().book.events[0].claim_endpoint = claim_endpoint;
+        harness.set_state(AppState::WalletMenu);
+        harness.press::<WalletMenuButton>(|button| button.0 == WalletMenuAction::Events);
+        harness.update_until(|harness| harness.shows("Not entered"));
+
+        harness.press::<EventButton>(|button| *button == EventButton::Enter("january-2025-event".to_string()));
+        harness.update_until(|harness| harness.shows("✔ Entered and claimed"));
+        assert_eq!(harness.server.request_count("/BurnTokens"), 1);
+        assert_eq!(harness.server.request_count("/claim"), 1);
+
+        let events = harness.resource::<EventsState>();
+        assert!(events.book.entries[0].unique_key.starts_with("january-2025-event-"));
+        let history = harness.resource::<HistoryState>();
+        assert_eq!(history.history.entries[0].note.as_deref(), Some("Entry: January 2025 Event"));
+    }
+
+    #[test]
+    fn test_unregistered_recipient_is_registered_for_them() {
+        let entry = create_test_wallet_data().to_json().unwrap();
+        let mut harness = HeadlessApp::with_wallet_entry(&entry);
+        let mnemonic = "legal winner thank year wave sausage worth useful legal winner thank yellow";
+        let (secret, address) = KeychainManager::new().generate_wallet_from_mnemonic(mnemonic).unwrap();
+        let recipient = GalaChainClient::ethereum_to_galachain_address(&address);
+        harness.set_state(AppState::WalletMenu);
+        harness.press::<WalletMenuButton>(|button| button.0 == WalletMenuAction::Transfer);
+        harness.app.world_mut().resource_mut::<TransferState>().recipient_address = recipient.clone();
+        harness.update_until(|harness| harness.shows("Registered on GalaChain"));
+
+        // The mock server reports every address as registered
+        {
+            let mut transfer = harness.app.world_mut().resource_mut::<TransferState>();
+            transfer.recipient_lookup = Some(RecipientLookup::Done { gala_address: recipient.clone(), registered: Some(false) });
+            transfer.sponsor_key = GalaChainClient::get_public_key_from_private(&secret);
+        }
+        harness.update();
+        assert!(!harness.resource::<TransferState>().ready_to_send());
+
+        harness.press::<SponsorButton>(|_| true);
+        harness.update_until(|harness| harness.resource::<TransferState>().ready_to_send());
+        assert_eq!(harness.server.request_count("/register"), 1);
+    }
+
+    #[test]
+    fn test_gateway_credentials_saved_and_tested() {
+        let mut harness = HeadlessApp::new();
+        harness.set_state(AppState::Settings);
+        assert!(harness.shows("No credentials saved for profile default"));
+
+        harness.app.world_mut().resource_mut::<GatewayAuth>().draft_secret = "sk-live-0123456789abcd".to_string();
+        harness.press::<GatewayAuthButton>(|button| matches!(button, GatewayAuthButton::Save));
+        assert!(harness.shows("API key in X-API-Key (••••abcd)"));
+        assert!(!harness.shows("sk-live"));
+        let auth = harness.resource::<GatewayAuth>();
+        assert!(auth.store.get().unwrap().contains("sk-live-0123456789abcd"));
+        assert!(auth.draft_secret.is_empty());
+        let header = harness.resource::<ApiSettings>().http.auth.clone().unwrap();
+        assert_eq!((header.name.as_str(), header.value.as_str()), ("X-API-Key", "sk-live-0123456789abcd"));
+
+        harness.press::<GatewayAuthButton>(|button| matches!(button, GatewayAuthButton::Test));
+        harness.update_until(|harness| harness.shows("credentials were accepted"));
+        assert_eq!(harness.server.request_count("/GetPublicKey"), 1);
+    }
+
+    #[test]
+    fn test_circuit_banner_retry_now() {
+        let mut harness = HeadlessApp::new();
+        let base_url = harness.server.base_url.clone();
+        let circuit = harness.resource::<GalaChainClient>().circuit.clone();
+        for _ in 0..crate::circuit::FAILURE_THRESHOLD {
+            circuit.record(&base_url, false);
+        }
+        harness.update();
+        assert!(harness.shows("The Operations and Identity APIs kept failing, so requests are paused"));
+        assert!(harness.resource::<ConnectivityState>().endpoint(ApiService::Operations).degraded);
+
+        harness.press::<RetryCircuitButton>(|_| true);
+        assert_eq!(circuit.state(&base_url), CircuitState::Closed);
+        assert!(!harness.resource::<ConnectivityState>().endpoint(ApiService::Identity).degraded);
+    }
+
+    #[test]
+    fn test_balance_lists_all_tokens() {
+        let entry = create_test_wallet_data().to_json().unwrap();
+        let mut harness = HeadlessApp::with_wallet_entry(&entry);
+        let town = parse_token_spec("TOWN|Unit|none|none,TOWN").unwrap();
+        harness.app.world_mut().resource_mut::<Tokens>().registry.add(town).unwrap();
+        harness.set_state(AppState::WalletMenu);
+
+        harness.press::<WalletMenuButton>(|button| button.0 == WalletMenuAction::Balance);
+        harness.update_until(|harness| harness.shows("All tokens - 2 of 2 tokens loaded"));
+        assert!(harness.shows("TOWN"));
+        assert_eq!(harness.server.request_count("/FetchBalances"), 2);
+    }
+
+    #[test]
+    fn test_endpoint_editor_previews_and_tests() {
+        let mut harness = HeadlessApp::new();
+        let balance_url = format!("→ {}/api/product/GalaChainToken/FetchBalances", harness.server.base_url);
+        harness.set_state(AppState::Settings);
+        harness.update_until(|harness| harness.shows(&balance_url));
+
+        harness.press::<EndpointTestButton>(|button| button.0 == Endpoint::Balance);
+        harness.update_until(|harness| harness.shows(&format!("{}\nHTTP 200 - the endpoint answered", balance_url)));
+        assert_eq!(harness.server.request_count("/FetchBalances"), 1);
+    }
+
+    #[test]
+    fn test_registry_takes_chain_decimals() {
+        let entry = create_test_wallet_data().to_json().unwrap();
+        let mut harness = HeadlessApp::with_wallet_entry(&entry);
+        // Settings guesses 8 decimals; the chain says TOWN is whole units
+        let town = parse_token_spec("TOWN|Unit|none|none,TOWN").unwrap();
+        harness.app.world_mut().resource_mut::<Tokens>().registry.add(town).unwrap();
+        harness.update_until(|harness| harness.resource::<Tokens>().registry.tokens[1].decimals == 0);
+
+        harness.app.world_mut().resource_mut::<Tokens>().registry.selected = 1;
+        harness.set_state(AppState::WalletMenu);
+        harness.set_state(WalletState::Transfer);
+        harness.app.world_mut().resource_mut::<TransferState>().amount = "2.5".to_string();
+        harness.update_until(|harness| harness.shows("This token only comes in whole units"));
+    }
+
+    #[test]
+    fn test_last_screen_restored() {
+        use bevy::ecs::system::RunSystemOnce;
+
+        let entry = create_test_wallet_data().to_json().unwrap();
+        let mut harness = HeadlessApp::with_wallet_entry(&entry);
+        harness.set_state(AppState::WalletMenu);
+        harness.press::<WalletMenuButton>(|button| button.0 == WalletMenuAction::History);
+        let saved = crate::ui_state::UiState::load(crate::app_config_path("ui_state.json"));
+        assert_eq!(saved.restore_target(), Some((AppState::WalletMenu, WalletState::History)));
+
+        // As at the next launch: back to the main menu, then the startup restore
+        harness.set_state(AppState::MainMenu);
+        harness.app.world_mut().resource_mut::<UiStateStore>().state = saved;
+        harness.app.world_mut().run_system_once(crate::restore_ui_state).unwrap();
+        harness.update();
+        harness.update();
+        assert_eq!(harness.state::<AppState>(), AppState::WalletMenu);
+        assert_eq!(harness.state::<WalletState>(), WalletState::History);
+    }
+
+    #[test]
+    fn test_tray_refresh_balance() {
+        let entry = create_test_wallet_data().to_json().unwrap();
+        let mut harness = HeadlessApp::with_wallet_entry(&entry);
+        harness.set_state(AppState::MainMenu);
+
+        harness.app.world_mut().send_event(TrayAction::RefreshBalance);
+        harness.update_until(|harness| harness.server.request_count("/FetchBalances") == 1);
+        assert_eq!(harness.state::<AppState>(), AppState::WalletMenu);
+        assert_eq!(harness.state::<WalletState>(), WalletState::Balance);
+        assert!(!harness.resource::<crate::BalanceState>().refresh_requested);
+    }
+
+    #[test]
+    fn test_auto_refresh_on_open() {
+        let entry = create_test_wallet_data().to_json().unwrap();
+        let mut harness = HeadlessApp::with_wallet_entry(&entry);
+        harness.app.world_mut().resource_mut::<crate::ApiCache>().cache.auto_refresh_registration = true;
+        harness.set_state(AppState::WalletMenu);
+
+        harness.press::<WalletMenuButton>(|button| button.0 == WalletMenuAction::Balance);
+        harness.update();
+        assert_eq!(harness.server.request_count("/FetchBalances"), 0);
+
+        harness.press::<WalletMenuButton>(|button| button.0 == WalletMenuAction::Registration);
+        harness.update_until(|harness| harness.resource::<RegistrationState>().is_registered == Some(true));
+        assert_eq!(harness.server.request_count("/GetPublicKey"), 1);
+
+        harness.app.world_mut().resource_mut::<crate::ApiCache>().cache.auto_refresh_balance = true;
+        harness.press::<WalletMenuButton>(|button| button.0 == WalletMenuAction::Balance);
+        harness.update_until(|harness| harness.server.request_count("/FetchBalances") == 1);
+    }
+
+    #[test]
+    fn test_header_bar() {
+        let entry = create_test_wallet_data().to_json().unwrap();
+        let mut harness = HeadlessApp::with_wallet_entry(&entry);
+        harness.set_state(AppState::WalletMenu);
+        let wallet_address = harness.resource::<WalletData>().address.clone().unwrap();
+        assert!(harness.shows(&address::truncated(&wallet_address)));
+        assert!(harness.shows("Local dev server"));
+        assert!(harness.shows("— GALA"));
+
+        // A balance fetched anywhere shows in the header from then on
+        harness.press::<WalletMenuButton>(|button| button.0 == WalletMenuAction::Balance);
+        harness.press::<RefreshBalanceButton>(|_| true);
+        harness.update_until(|harness| harness.shows("1,224.50 GALA · 10.00 locked"));
+
+        harness.press::<HeaderBarButton>(|button| *button == HeaderBarButton::Qr);
+        let qr = harness.resource::<HeaderBarState>().qr.as_ref().map(|(address, _)| address.clone());
+        assert_eq!(qr, Some(wallet_address));
+
+        harness.press::<HeaderBarButton>(|button| *button == HeaderBarButton::Lock);
+        assert!(harness.resource::<WalletData>().private_key.is_none());
+        assert_eq!(harness.state::<AppState>(), AppState::MainMenu);
+    }
+
+    #[test]
+    fn test_unlock_needs_passphrase_to_lift_pin_lockout() {
+        let entry = create_test_wallet_data().to_json().unwrap();
+        let mut harness = HeadlessApp::with_wallet_entry(&entry);
+        {
+            let mut ipc = harness.app.world_mut().resource_mut::<IpcState>();
+            for _ in 0..crate::pin_lockout::LOCKOUT_FAILURES {
+                ipc.pin_lockout.fail(std::time::SystemTime::now());
+            }
+        }
+        harness.app.world_mut().resource_mut::<WalletData>().private_key = None;
+
+        // With no presence check, Unlock reads the keychain again but the PIN stays refused
+        harness.press::<UnlockSessionButton>(|_| true);
+        assert!(harness.resource::<WalletData>().private_key.is_some());
+        assert!(harness.resource::<IpcState>().pin_lockout.locked_out());
+
+        harness.app.world_mut().resource_mut::<WalletData>().private_key = None;
+        {
+            let mut gate = harness.app.world_mut().resource_mut::<PresenceGate>();
+            gate.settings.set_fallback_with_iterations("correct horse battery staple", 10).unwrap();
+            gate.settings.set_gate(GatePurpose::Unlock, true).unwrap();
+        }
+        harness.press::<UnlockSessionButton>(|_| true);
+        assert!(harness.resource::<WalletData>().private_key.is_none());
+        harness.app.world_mut().resource_mut::<PresenceGate>().passphrase = "correct horse battery staple".to_string();
+        harness.press::<PresenceButton>(|button| *button == PresenceButton::Verify);
+        assert!(harness.resource::<WalletData>().private_key.is_some());
+        assert_eq!(harness.resource::<IpcState>().pin_lockout.failures, 0);
+    }
+
+    #[test]
+    fn test_menu_icons_from_atlas() {
+        let entry = create_test_wallet_data().to_json().unwrap();
+        let mut harness = HeadlessApp::with_wallet_entry(&entry);
+        harness.set_state(AppState::WalletMenu);
+        let world = harness.app.world_mut();
+        let mut drawn: Vec<(Icon, usize)> = world
+            .query::<(&Icon, &bevy::prelude::ImageNode)>()
+            .iter(world)
+            .filter_map(|(icon, image)| image.texture_atlas.as_ref().map(|atlas| (*icon, atlas.index)))
+            .collect();
+        drawn.sort_by_key(|(_, index)| *index);
+        drawn.dedup();
+        // Overview and the header share the wallet icon; the Danger Zone heading has the warning
+        assert_eq!(drawn, vec![(Icon::Wallet, 0), (Icon::Send, 1), (Icon::Burn, 2), (Icon::Warning, 4)]);
+    }
+
+    #[test]
+    fn test_wallet_screen_lifecycle() {
+        let entry = create_test_wallet_data().to_json().unwrap();
+        let mut harness = HeadlessApp::with_wallet_entry(&entry);
+        harness.set_state(AppState::WalletMenu);
+        harness.press::<WalletMenuButton>(|button| button.0 == WalletMenuAction::Transfer);
+        let world = harness.app.world_mut();
+        assert_eq!(world.query::<&TransferButton>().iter(world).count(), 1);
+
+        harness.press::<WalletMenuButton>(|button| button.0 == WalletMenuAction::History);
+        let world = harness.app.world_mut();
+        assert_eq!(world.query::<&TransferButton>().iter(world).count(), 0);
+        assert_eq!(world.query::<&WalletScreen>().iter(world).count(), 1);
+
+        // Back from Settings, the same screen is built again rather than an empty content area
+        harness.set_state(AppState::Settings);
+        harness.set_state(AppState::WalletMenu);
+        assert!(harness.shows("Transaction History"));
+        let world = harness.app.world_mut();
+        assert_eq!(world.query::<&WalletScreen>().iter(world).count(), 1);
+    }
+
+    #[test]
+    fn test_generate_over_wallet_can_be_undone() {
+        let entry = create_test_wallet_data().to_json().unwrap();
+        let mut harness = HeadlessApp::with_wallet_entry(&entry);
+        let original = harness.resource::<WalletData>().address.clone().unwrap();
+        harness.set_state(AppState::WalletMenu);
+
+        harness.press::<WalletMenuButton>(|button| button.0 == WalletMenuAction::Generate);
+        harness.press::<GenerateWalletButton>(|_| true);
+        assert!(harness.shows(&format!("The wallet this replaced ({}) is archived", address::display(&original))));
+        assert_ne!(harness.resource::<WalletData>().address.as_ref(), Some(&original));
+
+        harness.press::<WalletMenuButton>(|button| button.0 == WalletMenuAction::Overview);
+        assert!(harness.shows("Previous wallet"));
+        harness.press::<RestorePreviousWalletButton>(|_| true);
+        assert!(harness.shows(&format!("Restored {}", original)));
+        assert_eq!(harness.resource::<WalletData>().address.as_ref(), Some(&original));
+        assert_eq!(harness.secrets.contents().unwrap(), entry);
+    }
+
+    #[test]
+    fn test_import_adopts_previewed_derivation() {
+        let mut harness = HeadlessApp::new();
+        harness.set_state(AppState::WalletMenu);
+        harness.press::<WalletMenuButton>(|button| button.0 == WalletMenuAction::Import);
+        harness.app.world_mut().resource_mut::<ImportState>().seed_words =
+            TestVectors::TEST_MNEMONIC_12.split_whitespace().map(str::to_string).collect();
+
+        harness.press::<PreviewAddressesButton>(|_| true);
+        let bip44_address = TestVectors::EXPECTED_ETH_ADDRESS.to_string();
+        harness.update_until(|harness| harness.resource::<ImportState>().preview.iter().all(|row| row.balance.is_some()));
+        assert!(harness.shows(&bip44_address));
+        assert!(harness.shows("GALA"));
+
+        harness.press::<DerivationChoice>(|choice| choice.0 == Derivation::Bip44(0));
+        harness.press::<ImportWalletButton>(|_| true);
+        assert!(harness.shows("Wallet Imported Successfully"));
+        assert_eq!(harness.resource::<WalletData>().address, Some(bip44_address));
+        assert!(harness.secrets.contents().unwrap().contains(r#""derivation":"m/44'/60'/0'/0/0""#));
+    }
+
+    #[test]
+    fn test_legacy_wallet_migrates_to_bip44() {
+        let entry = create_test_wallet_data().to_json().unwrap();
+        let mut harness = HeadlessApp::with_wallet_entry(&entry);
+        let legacy_address = harness.resource::<WalletData>().address.clone().unwrap();
+        let bip44_address = TestVectors::EXPECTED_ETH_ADDRESS.to_string();
+        harness.set_state(AppState::WalletMenu);
+        assert!(harness.shows(&format!("Its standard address (m/44'/60'/0'/0/0) is {}", bip44_address)));
+
+        harness.press::<OpenMigrationButton>(|_| true);
+        assert_eq!(harness.state::<WalletState>(), WalletState::Migrate);
+        harness.update_until(|harness| harness.resource::<MigrationState>().loaded());
+        assert!(harness.shows("Confirm once to send all of the above"));
+
+        harness.press::<ConfirmMigrationButton>(|_| true);
+        assert!(harness.shows("Wallet Migrated"));
+        assert_eq!(harness.resource::<WalletData>().address, Some(bip44_address));
+        assert!(harness.secrets.contents().unwrap().contains(r#""derivation":"m/44'/60'/0'/0/0""#));
+        let history = &harness.resource::<HistoryState>().history;
+        assert_eq!(history.entries.len(), 1);
+        assert_eq!(history.entries[0].note.as_deref(), Some("Migration to m/44'/60'/0'/0/0"));
+        assert!(harness.shows(&format!("The wallet this replaced ({}) is archived", address::display(&legacy_address))));
+    }
+
+    #[test]
+    fn test_balance_switches_contract() {
+        let entry = create_test_wallet_data().to_json().unwrap();
+        let mut harness = HeadlessApp::with_wallet_entry(&entry);
+        harness.app.world_mut().resource_mut::<ApiSettings>().chain_targets = vec![ChainTarget::new("product", "MyGame")];
+        harness.set_state(AppState::WalletMenu);
+        harness.press::<WalletMenuButton>(|button| button.0 == WalletMenuAction::Balance);
+        assert!(harness.shows("Contract: product/GalaChainToken ▸"));
+
+        harness.press::<ChainTargetButton>(|_| true);
+        assert!(harness.shows("Contract: product/MyGame ▸"));
+        assert!(!harness.shows("Contract: product/GalaChainToken"));
+        harness.press::<RefreshBalanceButton>(|_| true);
+        harness.update_until(|harness| harness.shows("1,234.5"));
+        assert_eq!(harness.server.request_count("/product/MyGame/FetchBalances"), 1);
+        assert_eq!(harness.server.request_count("/GalaChainToken/FetchBalances"), 0);
+    }
+
+    #[test]
+    fn test_nft_instance_drilldown() {
+        let entry = create_test_wallet_data().to_json().unwrap();
+        let mut harness = HeadlessApp::with_wallet_entry(&entry);
+        let town = parse_token_spec("TOWN|Unit|none|none,TOWN").unwrap();
+        harness.app.world_mut().resource_mut::<Tokens>().registry.add(town).unwrap();
+        harness.update_until(|harness| harness.resource::<Tokens>().registry.tokens[1].decimals == 0);
+        harness.app.world_mut().resource_mut::<Tokens>().registry.selected = 1;
+        harness.set_state(AppState::WalletMenu);
+        harness.press::<WalletMenuButton>(|button| button.0 == WalletMenuAction::Balance);
+        // The screen is rebuilt as the token list's balances arrive, taking the panel's buttons with it
+        harness.update_until(|harness| harness.shows("2 of 2 tokens loaded"));
+
+        harness.press::<InstanceButton>(|button| *button == InstanceButton::Load);
+        harness.update_until(|harness| harness.shows("2 TOWN instances held"));
+        assert_eq!(harness.server.request_count("/FetchTokenInstances"), 1);
+
+        harness.press::<InstanceButton>(|button| *button == InstanceButton::Open("2".to_string()));
+        harness.update_until(|harness| harness.shows("TOWN #2 - Town Hall"));
+        assert!(harness.shows("Level: 3"));
+        assert_eq!(harness.server.request_count("/metadata/2"), 1);
+
+        harness.press::<InstanceButton>(|button| *button == InstanceButton::Transfer);
+        harness.update_until(|harness| harness.shows("Sending TOWN #2 only"));
+        assert_eq!(harness.state::<WalletState>(), WalletState::Transfer);
+        assert_eq!(harness.resource::<TransferState>().instance.as_deref(), Some("2"));
+    }
+
+    #[test]
+    fn test_insufficient_gala_for_fee() {
+        let entry = create_test_wallet_data().to_json().unwrap();
+        let mut harness = HeadlessApp::with_wallet_entry(&entry);
+        {
+            let mut balance = harness.app.world_mut().resource_mut::<crate::BalanceState>();
+            balance.available = Decimal::new(5, 1);
+            balance.last_updated = Some(std::time::SystemTime::now());
+        }
+        harness.set_state(AppState::WalletMenu);
+        harness.set_state(WalletState::Burn);
+        harness.update_until(|harness| harness.shows("⚠ Insufficient GALA for network fee (1.00 GALA needed)"));
+        assert!(harness.resource::<BurnState>().fee_short);
+
+        harness.app.world_mut().resource_mut::<BurnState>().amount = "0.1".to_string();
+        harness.update_until(|harness| harness.shows("✘ Insufficient GALA for network fee"));
+        harness.press::<BurnButton>(|_| true);
+        assert!(!harness.resource::<BurnState>().is_processing);
+
+        harness.set_state(WalletState::Transfer);
+        harness.update_until(|harness| harness.shows("⚠ Insufficient GALA for network fee"));
+        harness.app.world_mut().resource_mut::<TransferState>().recipient_address = "client|alice".to_string();
+        harness.update();
+        assert!(!harness.resource::<TransferState>().ready_to_send());
+    }
+
+    #[test]
+    fn test_sweep_to_address() {
+        let entry = create_test_wallet_data().to_json().unwrap();
+        let mut harness = HeadlessApp::with_wallet_entry(&entry);
+        {
+            let mut balance = harness.app.world_mut().resource_mut::<crate::BalanceState>();
+            balance.available = Decimal::from(100);
+            balance.last_updated = Some(std::time::SystemTime::now());
+        }
+        harness.set_state(AppState::WalletMenu);
+        harness.press::<WalletMenuButton>(|button| button.0 == WalletMenuAction::Transfer);
+        harness.press::<SweepButton>(|button| *button == SweepButton::Review);
+        assert!(harness.shows("Enter the address to sweep to first"));
+
+        harness.app.world_mut().resource_mut::<TransferState>().recipient_address = "client|alice".to_string();
+        harness.update_until(|harness| harness.resource::<TransferState>().ready_to_send());
+        harness.press::<SweepButton>(|button| *button == SweepButton::Review);
+        assert!(harness.shows("Send 99.00 GALA to client|alice"));
+        assert!(harness.shows("Network fee: 1 GALA, taken from this balance"));
+        assert!(harness.resource::<HistoryState>().history.entries.is_empty());
+
+        harness.press::<SweepButton>(|button| *button == SweepButton::Confirm);
+        harness.update();
+        let history = harness.resource::<HistoryState>();
+        assert_eq!(history.history.entries.len(), 1);
+        assert_eq!(history.history.entries[0].quantity, Decimal::from(99));
+        assert_eq!(history.history.entries[0].to.as_deref(), Some("client|alice"));
+    }
+
+    #[test]
+    fn test_hotkeys() {
+        let entry = create_test_wallet_data().to_json().unwrap();
+        let mut harness = HeadlessApp::with_wallet_entry(&entry);
+        harness.set_state(AppState::WalletMenu);
+
+        harness.press_keys(&[KeyCode::ControlLeft, KeyCode::Digit3]);
+        assert_eq!(harness.state::<WalletState>(), WalletState::Transfer);
+        harness.press_keys(&[KeyCode::ControlLeft, KeyCode::Digit7]);
+        assert_eq!(harness.state::<WalletState>(), WalletState::History);
+        // Without Ctrl a digit is just a digit
+        harness.press_keys(&[KeyCode::Digit2]);
+        assert_eq!(harness.state::<WalletState>(), WalletState::History);
+
+        harness.press_keys(&[KeyCode::F1]);
+        assert!(harness.resource::<Hotkeys>().sheet_shown);
+        assert!(harness.shows("Go to Balance"));
+        // Escape closes the sheet first, then goes back
+        harness.press_keys(&[KeyCode::Escape]);
+        assert!(!harness.resource::<Hotkeys>().sheet_shown);
+        assert_eq!(harness.state::<AppState>(), AppState::WalletMenu);
+        harness.press_keys(&[KeyCode::Escape]);
+        assert_eq!(harness.state::<AppState>(), AppState::MainMenu);
+    }
+
+    #[test]
+    fn test_form_drafts_survive_navigation() {
+        let entry = create_test_wallet_data().to_json().unwrap();
+        let mut harness = HeadlessApp::with_wallet_entry(&entry);
+        harness.set_state(AppState::WalletMenu);
+        harness.press::<WalletMenuButton>(|button| button.0 == WalletMenuAction::Transfer);
+        {
+            let mut transfer = harness.app.world_mut().resource_mut::<TransferState>();
+            transfer.recipient_address = "client|alice".to_string();
+            transfer.amount = "12".to_string();
+        }
+        harness.update();
+
+        harness.press::<WalletMenuButton>(|button| button.0 == WalletMenuAction::Balance);
+        harness.press::<WalletMenuButton>(|button| button.0 == WalletMenuAction::Transfer);
+        assert!(harness.shows("client|alice"));
+        assert_eq!(harness.resource::<TransferState>().amount, "12");
+        // The kept recipient is looked up again
+        harness.update_until(|harness| matches!(harness.resource::<TransferState>().recipient_lookup, Some(RecipientLookup::Done { .. })));
+
+        harness.press::<ClearFormButton>(|button| *button == ClearFormButton::Transfer);
+        harness.update();
+        assert!(harness.resource::<TransferState>().recipient_address.is_empty());
+        assert!(harness.shows("Click to enter recipient address..."));
+        assert!(!harness.shows("client|alice"));
+
+        harness.press::<WalletMenuButton>(|button| button.0 == WalletMenuAction::Import);
+        harness.app.world_mut().resource_mut::<ImportState>().seed_words[0] = "abandon".to_string();
+        harness.press::<WalletMenuButton>(|button| button.0 == WalletMenuAction::Overview);
+        harness.press::<WalletMenuButton>(|button| button.0 == WalletMenuAction::Import);
+        assert_eq!(harness.resource::<ImportState>().seed_words[0], "abandon");
+        harness.press::<ClearFormButton>(|button| *button == ClearFormButton::Import);
+        assert!(harness.resource::<ImportState>().seed_words.iter().all(String::is_empty));
+    }
+
+    #[test]
+    fn test_transfer_review_then_confirm() {
+        let entry = create_test_wallet_data().to_json().unwrap();
+        let mut harness = HeadlessApp::with_wallet_entry(&entry);
+        {
+            let mut balance = harness.app.world_mut().resource_mut::<crate::BalanceState>();
+            balance.available = Decimal::from(100);
+            balance.last_updated = Some(std::time::SystemTime::now());
+        }
+        harness.set_state(AppState::WalletMenu);
+        harness.press::<WalletMenuButton>(|button| button.0 == WalletMenuAction::Transfer);
+        {
+            let mut transfer = harness.app.world_mut().resource_mut::<TransferState>();
+            transfer.recipient_address = "client|alice".to_string();
+            transfer.amount = "10".to_string();
+            transfer.note = "rent".to_string();
+        }
+        harness.update_until(|harness| harness.resource::<TransferState>().ready_to_send());
+
+        harness.press::<TransferButton>(|_| true);
+        assert!(harness.shows("Sends to: client|alice (registered)"));
+        assert!(harness.shows("Estimated total: 11.00 GALA"));
+        assert!(harness.shows("Memo: rent"));
+        assert!(harness.resource::<HistoryState>().history.entries.is_empty());
+
+        // Edit goes back to the form, and a changed amount needs a new review
+        harness.press::<TransferReviewButton>(|button| *button == TransferReviewButton::Edit);
+        assert!(!harness.shows("Estimated total"));
+        harness.press::<TransferButton>(|_| true);
+        harness.app.world_mut().resource_mut::<TransferState>().amount = "12".to_string();
+        harness.update();
+        assert!(harness.resource::<TransferState>().review.is_none());
+
+        harness.press::<TransferButton>(|_| true);
+        assert!(harness.shows("Amount: 12.00 GALA"));
+        harness.press::<TransferReviewButton>(|button| *button == TransferReviewButton::Confirm);
+        harness.update();
+        let history = harness.resource::<HistoryState>();
+        assert_eq!(history.history.entries.len(), 1);
+        assert_eq!(history.history.entries[0].quantity, Decimal::from(12));
+    }
+}
@@ -0,0 +1,62 @@
+//! Saved UI state tests
+//!
+//! Covers where the app reopens:
+//! - The last screen round-trips through `ui_state.json`
+//! - Screens with secrets or unfinished forms reopen as the wallet overview
+//! - Damaged files and tiny saved windows fall back to something usable
+
+use crate::ui_state::{UiState, WindowGeometry, MIN_WINDOW_HEIGHT, MIN_WINDOW_WIDTH};
+use crate::{AppState, WalletState};
+
+#[cfg(test)]
+mod ui_state_tests {
+    use super::*;
+
+    fn temp_file(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("galachain-ui-state-{}-{}.json", std::process::id(), name))
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let path = temp_file("round-trip");
+        let mut state = UiState::default();
+        state.record_screen(&AppState::WalletMenu, &WalletState::Balance);
+        state.window = Some(WindowGeometry { width: 1024.0, height: 768.0, position: Some((-1200, 40)) });
+        state.save(Some(path.clone())).unwrap();
+
+        let loaded = UiState::load(Some(path.clone()));
+        assert_eq!(loaded, state);
+        assert_eq!(loaded.restore_target(), Some((AppState::WalletMenu, WalletState::Balance)));
+
+        std::fs::write(&path, "{not json").unwrap();
+        assert_eq!(UiState::load(Some(path.clone())), UiState::default());
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_restore_target() {
+        let target = |app_state: AppState, wallet_state: WalletState| {
+            let mut state = UiState::default();
+            state.record_screen(&app_state, &wallet_state);
+            state.restore_target()
+        };
+        assert_eq!(UiState::default().restore_target(), None);
+        assert_eq!(target(AppState::MainMenu, WalletState::Balance), None);
+        assert_eq!(target(AppState::Settings, WalletState::History), Some((AppState::Settings, WalletState::Overview)));
+        for hidden in [WalletState::Export, WalletState::Import, WalletState::Generate, WalletState::Transfer, WalletState::Burn] {
+            assert_eq!(target(AppState::WalletMenu, hidden), Some((AppState::WalletMenu, WalletState::Overview)));
+        }
+
+        // Onboarding keeps the screen the user had before it
+        let mut state = UiState::default();
+        state.record_screen(&AppState::WalletMenu, &WalletState::History);
+        state.record_screen(&AppState::Onboarding, &WalletState::History);
+        assert_eq!(state.restore_target(), Some((AppState::WalletMenu, WalletState::History)));
+    }
+
+    #[test]
+    fn test_small_window_is_clamped() {
+        let geometry = WindowGeometry { width: 10.0, height: 0.0, position: None }.clamped();
+        assert_eq!((geometry.width, geometry.height), (MIN_WINDOW_WIDTH, MIN_WINDOW_HEIGHT));
+    }
+}
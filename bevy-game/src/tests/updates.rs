@@ -0,0 +1,51 @@
+//! Update checker tests
+//!
+//! Covers the pure helpers behind the opt-in update banner:
+//! - Version parsing from release tags
+//! - Newer-release comparison
+//! - Release notes excerpts
+
+use crate::updates::{ReleaseInfo, is_newer_release, parse_version};
+
+#[cfg(test)]
+mod update_check_tests {
+    use super::*;
+
+    fn release(tag: &str, body: &str) -> ReleaseInfo {
+        ReleaseInfo {
+            tag_name: tag.to_string(),
+            name: None,
+            body: Some(body.to_string()),
+            html_url: "https://github.com/GalaChain/examples/releases".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_version_from_tags() {
+        assert_eq!(parse_version("v1.2.3").unwrap().to_string(), "1.2.3");
+        assert_eq!(parse_version("1.2.3").unwrap().to_string(), "1.2.3");
+        assert_eq!(parse_version("bevy-game-v0.2.0").unwrap().to_string(), "0.2.0");
+        assert!(parse_version("latest").is_none());
+        assert!(parse_version("v1.2").is_none());
+    }
+
+    #[test]
+    fn test_is_newer_release() {
+        assert!(is_newer_release("0.1.0", "v0.1.1"));
+        assert!(is_newer_release("0.1.0", "v1.0.0"));
+        assert!(!is_newer_release("0.1.0", "v0.1.0"));
+        assert!(!is_newer_release("0.2.0", "v0.1.9"));
+        // Pre-releases sort before the final version
+        assert!(!is_newer_release("0.2.0", "v0.2.0-rc.1"));
+        // Unparseable tags never trigger the banner
+        assert!(!is_newer_release("0.1.0", "nightly"));
+    }
+
+    #[test]
+    fn test_notes_excerpt() {
+        let notes = release("v0.2.0", "## Changes\n\n- One\n- Two\n- Three\n");
+        assert_eq!(notes.notes_excerpt(10), "## Changes\n- One\n- Two\n- Three");
+        assert_eq!(notes.notes_excerpt(2), "## Changes\n- One\n...");
+        assert_eq!(release("v0.2.0", "").notes_excerpt(3), "");
+    }
+}
@@ -0,0 +1,71 @@
+//! Encrypted wallet backup tests
+//!
+//! Covers the single-file backup bundle:
+//! - New passphrases need a minimum length and strength and a matching confirmation
+//! - Contents survive a seal/open round trip with the right passphrase
+//! - A wrong passphrase, a changed file or another kind of file is refused
+
+use crate::dashboard::DashboardConfig;
+use crate::environment::EnvironmentProfile;
+use crate::wallet_archive::{ArchivedWallet, WalletArchive};
+use crate::wallet_bundle::{check_passphrase, open, seal_with_iterations, BundleContents, BundleError, BUNDLE_VERSION};
+
+#[cfg(test)]
+mod wallet_bundle_tests {
+    use super::*;
+
+    const PASSPHRASE: &str = "correct horse battery staple";
+    // Keeps the tests fast; real bundles use KDF_ITERATIONS
+    const ITERATIONS: u32 = 1_000;
+
+    fn contents() -> BundleContents {
+        let mut dashboard = DashboardConfig::default();
+        dashboard.add("0x0000000000000000000000000000000000000001".to_string());
+        BundleContents {
+            created_at: 1_700_000_000,
+            wallet: r#"{"wallet_type":"mnemonic","mnemonic":"abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about","created_at":0}"#.to_string(),
+            archive: WalletArchive {
+                wallets: vec![ArchivedWallet { archived_at: 1_600_000_000, address: None, entry: "{}".to_string() }],
+            },
+            dashboard,
+            profile: EnvironmentProfile::default(),
+        }
+    }
+
+    #[test]
+    fn test_check_passphrase() {
+        assert_eq!(check_passphrase("short", "short"), Err(BundleError::PassphraseTooShort));
+        assert_eq!(check_passphrase("Password1234", "Password1234"), Err(BundleError::PassphraseTooWeak));
+        assert_eq!(check_passphrase(PASSPHRASE, "correct horse"), Err(BundleError::PassphraseMismatch));
+        assert_eq!(check_passphrase(PASSPHRASE, PASSPHRASE), Ok(()));
+    }
+
+    #[test]
+    fn test_seal_open_round_trip() {
+        let sealed = seal_with_iterations(&contents(), PASSPHRASE, ITERATIONS).unwrap();
+        // Nothing readable outside the ciphertext
+        assert!(!sealed.contains("abandon"));
+        assert_eq!(open(&sealed, PASSPHRASE), Ok(contents()));
+
+        // Fresh salt and nonce every time
+        assert_ne!(seal_with_iterations(&contents(), PASSPHRASE, ITERATIONS).unwrap(), sealed);
+    }
+
+    #[test]
+    fn test_open_refuses_bad_input() {
+        let sealed = seal_with_iterations(&contents(), PASSPHRASE, ITERATIONS).unwrap();
+        assert_eq!(open(&sealed, "correct horse battery stapler"), Err(BundleError::WrongPassphrase));
+
+        let mut file: serde_json::Value = serde_json::from_str(&sealed).unwrap();
+        let ciphertext = file["ciphertext"].as_str().unwrap().to_string();
+        let flipped = if ciphertext.starts_with('A') { "B" } else { "A" };
+        file["ciphertext"] = format!("{}{}", flipped, &ciphertext[1..]).into();
+        assert_eq!(open(&file.to_string(), PASSPHRASE), Err(BundleError::WrongPassphrase));
+
+        file["version"] = (BUNDLE_VERSION + 1).into();
+        assert_eq!(open(&file.to_string(), PASSPHRASE), Err(BundleError::UnsupportedVersion(BUNDLE_VERSION + 1)));
+        file["kind"] = "galachain-environment".into();
+        assert!(matches!(open(&file.to_string(), PASSPHRASE), Err(BundleError::NotABundle(_))));
+        assert!(matches!(open("not json", PASSPHRASE), Err(BundleError::NotABundle(_))));
+    }
+}
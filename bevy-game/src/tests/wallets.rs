@@ -0,0 +1,134 @@
+//! Named wallet tests
+//!
+//! Covers the keychain scoping and wallets.json in wallets.rs:
+//! - The default wallet without a profile keeps the entry names older builds used
+//! - Profiles and wallet names each get their own entries, which never collide
+//! - Only a profile's default wallet has older builds' shared entries to take over
+//! - wallets.json: adding, opening and noting addresses, and files that don't fit
+//! - The picker lists this profile's wallets first, tagged with their profile
+
+use crate::wallets::{is_valid_wallet_name, picker_entries, WalletIndex, WalletScope, DEFAULT_WALLET, WALLETS_FILE};
+use std::collections::HashSet;
+
+#[cfg(test)]
+mod wallets_tests {
+    use super::*;
+
+    #[test]
+    fn test_default_scope_keeps_legacy_entries() {
+        let scope = WalletScope::default();
+        assert_eq!(scope.keychain_username(), "default-wallet");
+        assert_eq!(scope.archive_username(), "previous-wallets");
+        assert_eq!(scope.label(), "default · default");
+    }
+
+    #[test]
+    fn test_profiles_and_names_get_their_own_entries() {
+        let testnet = WalletScope::new(Some("testnet"), DEFAULT_WALLET);
+        assert_eq!(testnet.keychain_username(), "default-wallet@testnet");
+        assert_eq!(testnet.archive_username(), "previous-wallets@testnet");
+
+        let savings = WalletScope::new(Some("mainnet"), "savings");
+        assert_eq!(savings.keychain_username(), "wallet-savings@mainnet");
+        assert_eq!(savings.archive_username(), "previous-wallets-savings@mainnet");
+        assert_eq!(savings.label(), "savings · mainnet");
+    }
+
+    #[test]
+    fn test_legacy_entries() {
+        assert_eq!(WalletScope::new(Some("testnet"), DEFAULT_WALLET).legacy(), Some(WalletScope::default()));
+        assert_eq!(WalletScope::default().legacy(), None);
+        // Named wallets came with scoped entries
+        assert_eq!(WalletScope::new(Some("testnet"), "savings").legacy(), None);
+        assert_eq!(WalletScope::new(None, "savings").legacy(), None);
+    }
+
+    #[test]
+    fn test_entries_never_collide() {
+        // Hyphens in either name can't make two scopes share an entry
+        let scopes = [
+            WalletScope::new(None, DEFAULT_WALLET),
+            WalletScope::new(None, "savings-testnet"),
+            WalletScope::new(Some("testnet"), "savings"),
+            WalletScope::new(Some("savings-testnet"), DEFAULT_WALLET),
+            WalletScope::new(Some("testnet"), DEFAULT_WALLET),
+            WalletScope::new(None, "wallet"),
+        ];
+        let mut usernames = HashSet::new();
+        for scope in &scopes {
+            assert!(usernames.insert(scope.keychain_username()), "{:?}", scope);
+            assert!(usernames.insert(scope.archive_username()), "{:?}", scope);
+        }
+    }
+
+    #[test]
+    fn test_wallet_names() {
+        assert!(is_valid_wallet_name("savings"));
+        assert!(is_valid_wallet_name("cold_storage-2"));
+        assert!(!is_valid_wallet_name(""));
+        assert!(!is_valid_wallet_name("my wallet"));
+        assert!(!is_valid_wallet_name("a@b"));
+        assert!(!is_valid_wallet_name(&"x".repeat(33)));
+    }
+
+    #[test]
+    fn test_add_open_and_note_addresses() {
+        let mut index = WalletIndex::default();
+        assert_eq!(index.active, DEFAULT_WALLET);
+        assert!(index.set_address(Some("eth|0000000000000000000000000000000000000001")));
+        assert!(!index.set_address(Some("eth|0000000000000000000000000000000000000001")));
+
+        index.add("savings").unwrap();
+        assert!(index.add("savings").is_err());
+        assert!(index.add("no spaces").is_err());
+        assert!(!index.open("missing"));
+        assert!(index.open("savings"));
+        assert!(index.set_address(Some("eth|0000000000000000000000000000000000000002")));
+
+        assert_eq!(index.record(DEFAULT_WALLET).unwrap().address.as_deref(), Some("eth|0000000000000000000000000000000000000001"));
+        assert_eq!(index.record("savings").unwrap().address.as_deref(), Some("eth|0000000000000000000000000000000000000002"));
+    }
+
+    #[test]
+    fn test_index_round_trip_and_repair() {
+        let dir = std::env::temp_dir().join(format!("galachain-wallets-test-{}", std::process::id()));
+        let path = dir.join(WALLETS_FILE);
+        assert_eq!(WalletIndex::load(Some(path.clone())), WalletIndex::default());
+
+        let mut index = WalletIndex::default();
+        index.add("savings").unwrap();
+        index.open("savings");
+        index.save(Some(path.clone())).unwrap();
+        assert_eq!(WalletIndex::load(Some(path.clone())), index);
+
+        // An active wallet the list lost is put back rather than opening another
+        std::fs::write(&path, r#"{"active": "trading", "wallets": []}"#).unwrap();
+        let repaired = WalletIndex::load(Some(path.clone()));
+        assert!(!repaired.legacy_checked);
+        assert_eq!(repaired.active, "trading");
+        assert!(repaired.record("trading").is_some());
+
+        std::fs::write(&path, r#"{"active": "../escape", "wallets": []}"#).unwrap();
+        assert_eq!(WalletIndex::load(Some(path)).active, DEFAULT_WALLET);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_picker_lists_this_profile_first() {
+        let mut testnet = WalletIndex::default();
+        testnet.add("faucet").unwrap();
+        let mut mainnet = WalletIndex::default();
+        mainnet.set_address(Some("eth|0000000000000000000000000000000000000003"));
+        let indexes = vec![(None, WalletIndex::default()), (Some("testnet".to_string()), testnet), (Some("mainnet".to_string()), mainnet)];
+
+        let current = WalletScope::new(Some("testnet"), "faucet");
+        let entries = picker_entries(&current, &indexes);
+        let labels: Vec<String> = entries.iter().map(|entry| entry.scope.label()).collect();
+        assert_eq!(labels, ["default · testnet", "faucet · testnet", "default · default", "default · mainnet"]);
+
+        assert!(entries[0].openable && !entries[0].open);
+        assert!(entries[1].openable && entries[1].open);
+        assert!(!entries[2].openable && !entries[2].open);
+        assert_eq!(entries[3].address.as_deref(), Some("eth|0000000000000000000000000000000000000003"));
+    }
+}
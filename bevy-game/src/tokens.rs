@@ -0,0 +1,191 @@
+// Token registry: the token classes the wallet can show and send.
+//
+// GalaChain identifies a fungible token class by collection, category, type and
+// additionalKey. The registry keeps those together with display metadata and is
+// saved to `tokens.json`; Balance, Transfer and Burn use the selected entry.
+// Decimals typed into Settings are only a guess until the chain's token class
+// confirms them, since they decide what amounts can be sent.
+
+use crate::amounts::{GALA_DECIMALS, MAX_DECIMALS, NETWORK_FEE};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::PathBuf;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TokenInfo {
+    pub collection: String,
+    pub category: String,
+    #[serde(rename = "type")]
+    pub r#type: String,
+    #[serde(rename = "additionalKey")]
+    pub additional_key: String,
+    pub symbol: String,
+    pub decimals: u32,
+    #[serde(default, rename = "iconUrl", skip_serializing_if = "Option::is_none")]
+    pub icon_url: Option<String>,
+}
+
+impl TokenInfo {
+    pub fn gala() -> Self {
+        Self {
+            collection: "GALA".to_string(),
+            category: "Unit".to_string(),
+            r#type: "none".to_string(),
+            additional_key: "none".to_string(),
+            symbol: "GALA".to_string(),
+            decimals: GALA_DECIMALS,
+            icon_url: None,
+        }
+    }
+
+    /// "collection|category|type|additionalKey", the token class key GalaChain uses.
+    pub fn class_key(&self) -> String {
+        format!("{}|{}|{}|{}", self.collection, self.category, self.r#type, self.additional_key)
+    }
+
+    /// What to hold back from this token's balance for the network fee, which is paid in GALA.
+    pub fn fee(&self) -> Decimal {
+        if self.class_key() == TokenInfo::gala().class_key() {
+            NETWORK_FEE
+        } else {
+            Decimal::ZERO
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenError {
+    InvalidClassKey,
+    InvalidDecimals,
+    Duplicate,
+}
+
+impl fmt::Display for TokenError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TokenError::InvalidClassKey => write!(f, "Token class must be collection|category|type|additionalKey"),
+            TokenError::InvalidDecimals => write!(f, "Decimals must be a whole number from 0 to 18"),
+            TokenError::Duplicate => write!(f, "That token is already in the list"),
+        }
+    }
+}
+
+/// Parses `collection|category|type|additionalKey[,symbol[,decimals[,iconUrl]]]`.
+/// The symbol defaults to the collection and decimals to 8, as for GALA.
+pub fn parse_token_spec(input: &str) -> Result<TokenInfo, TokenError> {
+    let mut fields = input.trim().splitn(4, ',').map(str::trim);
+    let class: Vec<&str> = fields.next().unwrap_or_default().split('|').collect();
+    let [collection, category, r#type, additional_key] = class[..] else {
+        return Err(TokenError::InvalidClassKey);
+    };
+    if [collection, category, r#type, additional_key].iter().any(|part| part.is_empty()) {
+        return Err(TokenError::InvalidClassKey);
+    }
+
+    let symbol = fields.next().filter(|symbol| !symbol.is_empty()).unwrap_or(collection);
+    let decimals = match fields.next().filter(|decimals| !decimals.is_empty()) {
+        Some(decimals) => decimals.parse().ok().filter(|decimals| *decimals <= MAX_DECIMALS).ok_or(TokenError::InvalidDecimals)?,
+        None => GALA_DECIMALS,
+    };
+    Ok(TokenInfo {
+        collection: collection.to_string(),
+        category: category.to_string(),
+        r#type: r#type.to_string(),
+        additional_key: additional_key.to_string(),
+        symbol: symbol.to_string(),
+        decimals,
+        icon_url: fields.next().filter(|url| !url.is_empty()).map(str::to_string),
+    })
+}
+
+pub const DEFAULT_CONCURRENCY: usize = 4;
+/// What the Settings button cycles through
+pub const CONCURRENCY_OPTIONS: [usize; 4] = [1, 2, 4, 8];
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct TokenRegistry {
+    pub tokens: Vec<TokenInfo>,
+    /// Index into `tokens`
+    pub selected: usize,
+    /// How many token balances the Balance screen fetches at once
+    pub concurrency: usize,
+}
+
+impl Default for TokenRegistry {
+    fn default() -> Self {
+        Self {
+            tokens: vec![TokenInfo::gala()],
+            selected: 0,
+            concurrency: DEFAULT_CONCURRENCY,
+        }
+    }
+}
+
+impl TokenRegistry {
+    /// Falls back to GALA only when the file is missing, unreadable or empty.
+    pub fn load(path: Option<PathBuf>) -> Self {
+        let registry: Self = path
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        if registry.tokens.is_empty() {
+            return Self::default();
+        }
+        let selected = registry.selected.min(registry.tokens.len() - 1);
+        Self { selected, ..registry }
+    }
+
+    pub fn save(&self, path: Option<PathBuf>) -> std::io::Result<()> {
+        let Some(path) = path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self).unwrap_or_default())
+    }
+
+    pub fn selected(&self) -> &TokenInfo {
+        &self.tokens[self.selected]
+    }
+
+    pub fn next_concurrency(&self) -> usize {
+        let index = CONCURRENCY_OPTIONS.iter().position(|limit| *limit == self.concurrency);
+        index.map_or(DEFAULT_CONCURRENCY, |index| CONCURRENCY_OPTIONS[(index + 1) % CONCURRENCY_OPTIONS.len()])
+    }
+
+    pub fn add(&mut self, token: TokenInfo) -> Result<(), TokenError> {
+        if self.tokens.iter().any(|listed| listed.class_key() == token.class_key()) {
+            return Err(TokenError::Duplicate);
+        }
+        self.tokens.push(token);
+        Ok(())
+    }
+
+    /// Takes the chain's decimals for each listed token class. Returns true if any changed.
+    pub fn apply_decimals(&mut self, classes: &[TokenInfo]) -> bool {
+        let mut changed = false;
+        for class in classes.iter().filter(|class| class.decimals <= MAX_DECIMALS) {
+            for token in self.tokens.iter_mut().filter(|token| token.class_key() == class.class_key()) {
+                changed |= token.decimals != class.decimals;
+                token.decimals = class.decimals;
+            }
+        }
+        changed
+    }
+
+    /// The last token can't be removed. Removing the selected token selects the
+    /// next one (or the new last one); otherwise the selection stays on its token.
+    pub fn remove(&mut self, index: usize) -> bool {
+        if self.tokens.len() <= 1 || index >= self.tokens.len() {
+            return false;
+        }
+        self.tokens.remove(index);
+        if self.selected > index || self.selected == self.tokens.len() {
+            self.selected -= 1;
+        }
+        true
+    }
+}
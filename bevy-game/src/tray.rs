@@ -0,0 +1,150 @@
+// System tray icon with quick actions, built with the `tray` feature.
+//
+// The menu turns clicks into `TrayAction` events; what they do lives in main.rs
+// so it works the same with or without the feature. Clicks are forwarded from
+// tray-icon's handlers, which also wake the event loop - with the window hidden
+// nothing else would run a frame. On Linux the icon needs a GTK main loop,
+// which gets a thread of its own. Everywhere else it's created on the main
+// thread once the event loop is running, and kept as a non-send resource.
+// `TrayStatus` says whether it came up, so closing the window only hides it
+// when there's an icon to bring it back from.
+
+use crate::{TrayAction, TrayStatus};
+use bevy::prelude::*;
+use std::sync::atomic::Ordering;
+use std::sync::{mpsc, Mutex};
+use tray_icon::menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem};
+use tray_icon::{Icon, TrayIcon, TrayIconBuilder, TrayIconEvent};
+
+const MENU_ITEMS: &[(&str, &str)] = &[
+    ("toggle-window", "Show / hide window"),
+    ("copy-address", "Copy address"),
+    ("refresh-balance", "Refresh balance"),
+];
+const QUIT_ID: &str = "quit";
+const ICON_SIZE: u32 = 32;
+
+pub struct TrayPlugin;
+
+#[derive(Resource)]
+struct TrayEvents {
+    menu: Mutex<mpsc::Receiver<MenuEvent>>,
+    icon: Mutex<mpsc::Receiver<TrayIconEvent>>,
+}
+
+impl Plugin for TrayPlugin {
+    fn build(&self, app: &mut App) {
+        let (menu_sender, menu) = mpsc::channel();
+        MenuEvent::set_event_handler(Some(move |event| {
+            let _ = menu_sender.send(event);
+            crate::redraw::wake_event_loop();
+        }));
+        let (icon_sender, icon) = mpsc::channel();
+        TrayIconEvent::set_event_handler(Some(move |event| {
+            let _ = icon_sender.send(event);
+            crate::redraw::wake_event_loop();
+        }));
+        app.insert_resource(TrayEvents { menu: Mutex::new(menu), icon: Mutex::new(icon) });
+
+        #[cfg(target_os = "linux")]
+        app.add_systems(Startup, spawn_gtk_tray);
+        #[cfg(not(target_os = "linux"))]
+        app.add_systems(Startup, create_tray);
+        app.add_systems(PreUpdate, tray_event_system);
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn spawn_gtk_tray(status: Res<TrayStatus>) {
+    let available = status.available.clone();
+    let spawned = std::thread::Builder::new().name("tray".to_string()).spawn(move || {
+        if let Err(e) = gtk::init() {
+            warn!("Tray icon unavailable, GTK failed to start: {}", e);
+            return;
+        }
+        match build_tray() {
+            Ok(_tray) => {
+                available.store(true, Ordering::Relaxed);
+                gtk::main();
+            }
+            Err(e) => warn!("Tray icon unavailable: {}", e),
+        }
+    });
+    if let Err(e) = spawned {
+        warn!("Tray icon unavailable: {}", e);
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+struct TrayHandle(#[allow(dead_code)] TrayIcon);
+
+#[cfg(not(target_os = "linux"))]
+fn create_tray(world: &mut World) {
+    match build_tray() {
+        Ok(tray) => {
+            world.resource::<TrayStatus>().available.store(true, Ordering::Relaxed);
+            world.insert_non_send_resource(TrayHandle(tray));
+        }
+        Err(e) => warn!("Tray icon unavailable: {}", e),
+    }
+}
+
+fn build_tray() -> Result<TrayIcon, String> {
+    let menu = Menu::new();
+    for (id, label) in MENU_ITEMS {
+        menu.append(&MenuItem::with_id(*id, *label, true, None)).map_err(|e| e.to_string())?;
+    }
+    menu.append(&PredefinedMenuItem::separator()).map_err(|e| e.to_string())?;
+    menu.append(&MenuItem::with_id(QUIT_ID, "Quit", true, None)).map_err(|e| e.to_string())?;
+
+    TrayIconBuilder::new()
+        .with_menu(Box::new(menu))
+        .with_tooltip(crate::WINDOW_TITLE)
+        .with_icon(icon()?)
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+// A filled circle in the Settings accent colour; there's no icon asset to load yet
+fn icon() -> Result<Icon, String> {
+    let center = (ICON_SIZE as f32 - 1.0) / 2.0;
+    let radius = ICON_SIZE as f32 / 2.0 - 1.0;
+    let mut rgba = Vec::with_capacity((ICON_SIZE * ICON_SIZE * 4) as usize);
+    for y in 0..ICON_SIZE {
+        for x in 0..ICON_SIZE {
+            let distance = ((x as f32 - center).powi(2) + (y as f32 - center).powi(2)).sqrt();
+            // One pixel of falloff keeps the edge from looking jagged
+            let alpha = (radius - distance + 0.5).clamp(0.0, 1.0);
+            rgba.extend_from_slice(&[102, 102, 204, (alpha * 255.0) as u8]);
+        }
+    }
+    Icon::from_rgba(rgba, ICON_SIZE, ICON_SIZE).map_err(|e| e.to_string())
+}
+
+fn action_for(id: &str) -> Option<TrayAction> {
+    match id {
+        "toggle-window" => Some(TrayAction::ToggleWindow),
+        "copy-address" => Some(TrayAction::CopyAddress),
+        "refresh-balance" => Some(TrayAction::RefreshBalance),
+        QUIT_ID => Some(TrayAction::Quit),
+        _ => None,
+    }
+}
+
+fn tray_event_system(events: Res<TrayEvents>, mut actions: EventWriter<TrayAction>) {
+    if let Ok(menu) = events.menu.lock() {
+        for event in menu.try_iter() {
+            if let Some(action) = action_for(event.id.as_ref()) {
+                actions.send(action);
+            }
+        }
+    }
+    // Only Windows reports double clicks; elsewhere a click opens the menu
+    if let Ok(icon) = events.icon.lock() {
+        for event in icon.try_iter() {
+            if let TrayIconEvent::DoubleClick { button: tray_icon::MouseButton::Left, .. } = event {
+                actions.send(TrayAction::ToggleWindow);
+            }
+        }
+    }
+}
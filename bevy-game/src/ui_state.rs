@@ -0,0 +1,107 @@
+// Where the user left off: the last screen and the window's size and position,
+// plus how the window behaves when it's closed and the locale amounts and
+// dates are formatted in.
+//
+// Saved to `ui_state.json` as the user moves around and restored at the next
+// launch, so someone who closed the app on Balance comes back to Balance. The
+// selected token needs nothing here; `tokens.json` already keeps it. Screens
+// that show secrets or half-finished work (seed export, import, transfer and
+// burn forms) aren't reopened - the wallet menu's overview is shown instead.
+
+use crate::locale::LocaleSetting;
+use crate::{AppState, WalletState};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Smallest the window can be resized to, and so the smallest saved size that's restored.
+pub const MIN_WINDOW_WIDTH: f32 = 640.0;
+pub const MIN_WINDOW_HEIGHT: f32 = 480.0;
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiState {
+    /// None until the user has left onboarding
+    pub app_state: Option<AppState>,
+    pub wallet_state: WalletState,
+    pub window: Option<WindowGeometry>,
+    /// Closing the window hides it to the tray icon instead of quitting
+    pub close_to_tray: bool,
+    /// Number and date formatting, see locale.rs
+    pub locale: LocaleSetting,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    /// Logical pixels
+    pub width: f32,
+    pub height: f32,
+    /// Top-left corner in physical pixels; None lets the OS place the window
+    #[serde(default)]
+    pub position: Option<(i32, i32)>,
+}
+
+impl WindowGeometry {
+    pub fn clamped(self) -> Self {
+        Self {
+            width: self.width.max(MIN_WINDOW_WIDTH),
+            height: self.height.max(MIN_WINDOW_HEIGHT),
+            ..self
+        }
+    }
+}
+
+impl UiState {
+    /// Falls back to the defaults when the file is missing or unreadable.
+    pub fn load(path: Option<PathBuf>) -> Self {
+        path.and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: Option<PathBuf>) -> std::io::Result<()> {
+        let Some(path) = path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self).unwrap_or_default())
+    }
+
+    /// Records the current screen. Onboarding runs its own resume logic, so it isn't kept.
+    pub fn record_screen(&mut self, app_state: &AppState, wallet_state: &WalletState) {
+        if *app_state != AppState::Onboarding {
+            self.app_state = Some(app_state.clone());
+        }
+        self.wallet_state = wallet_state.clone();
+    }
+
+    /// The screen to open at launch, if it's somewhere other than the main menu.
+    pub fn restore_target(&self) -> Option<(AppState, WalletState)> {
+        match self.app_state.clone()? {
+            AppState::MainMenu | AppState::Onboarding => None,
+            AppState::WalletMenu => Some((AppState::WalletMenu, restorable(&self.wallet_state))),
+            app_state => Some((app_state, WalletState::Overview)),
+        }
+    }
+}
+
+fn restorable(wallet_state: &WalletState) -> WalletState {
+    match wallet_state {
+        WalletState::Overview
+        | WalletState::Registration
+        | WalletState::Balance
+        | WalletState::Dashboard
+        | WalletState::Approvals
+        | WalletState::History
+        | WalletState::Events
+        | WalletState::Stats
+        | WalletState::Explorer => wallet_state.clone(),
+        WalletState::Generate
+        | WalletState::Import
+        | WalletState::Export
+        | WalletState::Transfer
+        | WalletState::Burn
+        | WalletState::Migrate => WalletState::Overview,
+    }
+}
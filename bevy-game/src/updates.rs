@@ -0,0 +1,96 @@
+// Opt-in check for newer wallet builds published as GitHub releases.
+//
+// The latest release's tag is compared (as semver) with the version this binary
+// was built from. Only the public releases API is contacted; no wallet data is sent.
+
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+pub const RELEASES_API_URL: &str = "https://api.github.com/repos/GalaChain/examples/releases/latest";
+pub const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ReleaseInfo {
+    pub tag_name: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub body: Option<String>,
+    pub html_url: String,
+}
+
+impl ReleaseInfo {
+    /// First few lines of the release notes, for the banner.
+    pub fn notes_excerpt(&self, max_lines: usize) -> String {
+        let body = self.body.as_deref().unwrap_or_default();
+        let mut lines: Vec<&str> = body.lines().map(str::trim_end).filter(|line| !line.is_empty()).collect();
+        let truncated = lines.len() > max_lines;
+        lines.truncate(max_lines);
+        let mut excerpt = lines.join("\n");
+        if truncated {
+            excerpt.push_str("\n...");
+        }
+        excerpt
+    }
+}
+
+/// Accepts tags like "v1.2.3", "1.2.3" or "bevy-game-v1.2.3".
+pub fn parse_version(tag: &str) -> Option<Version> {
+    let start = tag.find(|c: char| c.is_ascii_digit())?;
+    Version::parse(&tag[start..]).ok()
+}
+
+/// True when `tag` names a strictly newer version than `current`.
+pub fn is_newer_release(current: &str, tag: &str) -> bool {
+    match (parse_version(current), parse_version(tag)) {
+        (Some(current), Some(latest)) => latest > current,
+        _ => false,
+    }
+}
+
+pub fn fetch_latest_release() -> Result<ReleaseInfo, String> {
+    let runtime = tokio::runtime::Runtime::new().map_err(|e| e.to_string())?;
+    runtime.block_on(async {
+        let response = reqwest::Client::new()
+            .get(RELEASES_API_URL)
+            // GitHub rejects API requests without a User-Agent
+            .header("User-Agent", concat!("galachain-desktop-wallet/", env!("CARGO_PKG_VERSION")))
+            .header("Accept", "application/vnd.github+json")
+            .timeout(std::time::Duration::from_secs(15))
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if !response.status().is_success() {
+            return Err(format!("Releases API returned {}", response.status()));
+        }
+        response.json::<ReleaseInfo>().await.map_err(|e| e.to_string())
+    })
+}
+
+/// Persisted opt-in flag plus the release the user chose to skip.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct UpdatePreferences {
+    pub enabled: bool,
+    pub skipped_version: Option<String>,
+}
+
+impl UpdatePreferences {
+    pub fn load(path: Option<PathBuf>) -> Self {
+        path.and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: Option<PathBuf>) -> std::io::Result<()> {
+        let Some(path) = path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self).unwrap_or_default())
+    }
+}
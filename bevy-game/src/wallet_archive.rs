@@ -0,0 +1,55 @@
+// Wallets replaced by a generate or import, kept so the replacement can be undone.
+//
+// Before a new wallet is written over the keychain entry, the old entry is
+// copied here as it was stored, with the time it was replaced and its address
+// for display. Overview's "Restore previous wallet" swaps the newest one back
+// in and archives the wallet it replaces, so a restore can be undone as well.
+// The list is a keychain entry of its own next to the wallet's; keychains cap
+// entry sizes, so only the last `MAX_ARCHIVED` replaced wallets are kept.
+
+use serde::{Deserialize, Serialize};
+
+pub const MAX_ARCHIVED: usize = 5;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ArchivedWallet {
+    /// Unix seconds
+    pub archived_at: u64,
+    /// None when the entry couldn't be read, which doesn't stop it being kept
+    pub address: Option<String>,
+    /// The keychain entry exactly as it was stored
+    pub entry: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct WalletArchive {
+    /// Oldest first
+    pub wallets: Vec<ArchivedWallet>,
+}
+
+impl WalletArchive {
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json).map_err(|e| format!("Invalid wallet archive: {}", e))
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    /// Adds `wallet` as the newest, dropping an older copy of the same entry and anything past the limit.
+    pub fn push(&mut self, wallet: ArchivedWallet) {
+        self.wallets.retain(|archived| archived.entry != wallet.entry);
+        self.wallets.push(wallet);
+        let excess = self.wallets.len().saturating_sub(MAX_ARCHIVED);
+        self.wallets.drain(..excess);
+    }
+
+    pub fn latest(&self) -> Option<&ArchivedWallet> {
+        self.wallets.last()
+    }
+
+    pub fn pop_latest(&mut self) -> Option<ArchivedWallet> {
+        self.wallets.pop()
+    }
+}
@@ -0,0 +1,164 @@
+// Encrypted wallet backup bundles: everything needed to move the wallet to
+// another machine, in one file.
+//
+// A bundle holds the keychain entry (seed phrase and derivation, or a watched
+// address), the wallets kept in the archive, the Address Dashboard list and
+// the environment profile (endpoints, contracts and tokens - no credentials,
+// as in environment.rs). It's serialized to JSON and sealed with
+// ChaCha20-Poly1305 under a key stretched from the passphrase with
+// PBKDF2-HMAC-SHA256; the file carries the salt, nonce and iteration count
+// next to the ciphertext, and the format version is bound in as associated
+// data. A wrong passphrase and a damaged file look the same: authentication
+// fails and nothing is decrypted.
+
+use crate::dashboard::DashboardConfig;
+use crate::passphrase_strength;
+use crate::environment::EnvironmentProfile;
+use crate::wallet_archive::WalletArchive;
+use base64::Engine;
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::fmt;
+
+pub const BUNDLE_KIND: &str = "galachain-wallet-backup";
+pub const BUNDLE_VERSION: u32 = 1;
+pub const BUNDLE_FILE_NAME: &str = "galachain-wallet-backup.json";
+/// Shortest passphrase a bundle is sealed with
+pub const MIN_PASSPHRASE_CHARS: usize = 12;
+/// PBKDF2 rounds for new bundles, per OWASP's 2023 guidance for SHA-256
+pub const KDF_ITERATIONS: u32 = 600_000;
+// Bundles asking for more are refused rather than hanging the UI
+pub const MAX_KDF_ITERATIONS: u32 = 10_000_000;
+const KDF: &str = "pbkdf2-sha256";
+const SALT_BYTES: usize = 16;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BundleError {
+    PassphraseTooShort,
+    /// Long enough, but below `passphrase_strength::MIN_BITS`
+    PassphraseTooWeak,
+    PassphraseMismatch,
+    NotABundle(String),
+    UnsupportedVersion(u32),
+    /// Authentication failed: the passphrase is wrong or the file was changed
+    WrongPassphrase,
+    Damaged(String),
+}
+
+impl fmt::Display for BundleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BundleError::PassphraseTooShort => write!(f, "The passphrase needs at least {} characters", MIN_PASSPHRASE_CHARS),
+            BundleError::PassphraseTooWeak => write!(f, "The passphrase is too easy to guess - add another word, or some digits and symbols"),
+            BundleError::PassphraseMismatch => write!(f, "The passphrases don't match"),
+            BundleError::NotABundle(reason) => write!(f, "Not a wallet backup: {}", reason),
+            BundleError::UnsupportedVersion(version) => write!(f, "Backup format {} is newer than this wallet understands", version),
+            BundleError::WrongPassphrase => write!(f, "Wrong passphrase, or the backup file was changed"),
+            BundleError::Damaged(reason) => write!(f, "The backup is damaged: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for BundleError {}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BundleContents {
+    /// Unix seconds
+    pub created_at: u64,
+    /// The keychain entry exactly as it was stored
+    pub wallet: String,
+    #[serde(default)]
+    pub archive: WalletArchive,
+    #[serde(default)]
+    pub dashboard: DashboardConfig,
+    #[serde(default)]
+    pub profile: EnvironmentProfile,
+}
+
+// The file on disk; binary fields are base64
+#[derive(Serialize, Deserialize)]
+struct SealedBundle {
+    kind: String,
+    version: u32,
+    kdf: String,
+    iterations: u32,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// Checks a new passphrase - its length, then how guessable it is - and its
+/// confirmation before anything is sealed with it.
+pub fn check_passphrase(passphrase: &str, confirmation: &str) -> Result<(), BundleError> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_CHARS {
+        return Err(BundleError::PassphraseTooShort);
+    }
+    if !passphrase_strength::estimate(passphrase).acceptable() {
+        return Err(BundleError::PassphraseTooWeak);
+    }
+    if passphrase != confirmation {
+        return Err(BundleError::PassphraseMismatch);
+    }
+    Ok(())
+}
+
+pub fn seal(contents: &BundleContents, passphrase: &str) -> Result<String, BundleError> {
+    seal_with_iterations(contents, passphrase, KDF_ITERATIONS)
+}
+
+/// `seal` with a chosen PBKDF2 round count; tests use a low one.
+pub fn seal_with_iterations(contents: &BundleContents, passphrase: &str, iterations: u32) -> Result<String, BundleError> {
+    let salt: [u8; SALT_BYTES] = rand::random();
+    let nonce: [u8; 12] = rand::random();
+    let plaintext = serde_json::to_vec(contents).map_err(|e| BundleError::Damaged(e.to_string()))?;
+    let ciphertext = cipher(passphrase, &salt, iterations)
+        .encrypt(Nonce::from_slice(&nonce), Payload { msg: &plaintext, aad: &associated_data(BUNDLE_VERSION) })
+        .map_err(|_| BundleError::Damaged("encryption failed".to_string()))?;
+    let base64 = base64::engine::general_purpose::STANDARD;
+    let sealed = SealedBundle {
+        kind: BUNDLE_KIND.to_string(),
+        version: BUNDLE_VERSION,
+        kdf: KDF.to_string(),
+        iterations,
+        salt: base64.encode(salt),
+        nonce: base64.encode(nonce),
+        ciphertext: base64.encode(ciphertext),
+    };
+    serde_json::to_string_pretty(&sealed).map_err(|e| BundleError::Damaged(e.to_string()))
+}
+
+pub fn open(file: &str, passphrase: &str) -> Result<BundleContents, BundleError> {
+    let sealed: SealedBundle = serde_json::from_str(file).map_err(|e| BundleError::NotABundle(e.to_string()))?;
+    if sealed.kind != BUNDLE_KIND {
+        return Err(BundleError::NotABundle(format!("this file is a '{}'", sealed.kind)));
+    }
+    if sealed.version > BUNDLE_VERSION {
+        return Err(BundleError::UnsupportedVersion(sealed.version));
+    }
+    if sealed.kdf != KDF || sealed.iterations == 0 || sealed.iterations > MAX_KDF_ITERATIONS {
+        return Err(BundleError::Damaged(format!("unexpected key derivation {} x{}", sealed.kdf, sealed.iterations)));
+    }
+    let decode = |field: &str, value: &str| {
+        base64::engine::general_purpose::STANDARD.decode(value).map_err(|e| BundleError::Damaged(format!("{}: {}", field, e)))
+    };
+    let (salt, nonce, ciphertext) = (decode("salt", &sealed.salt)?, decode("nonce", &sealed.nonce)?, decode("ciphertext", &sealed.ciphertext)?);
+    if nonce.len() != 12 {
+        return Err(BundleError::Damaged("nonce: wrong length".to_string()));
+    }
+    let plaintext = cipher(passphrase, &salt, sealed.iterations)
+        .decrypt(Nonce::from_slice(&nonce), Payload { msg: &ciphertext, aad: &associated_data(sealed.version) })
+        .map_err(|_| BundleError::WrongPassphrase)?;
+    serde_json::from_slice(&plaintext).map_err(|e| BundleError::Damaged(e.to_string()))
+}
+
+fn cipher(passphrase: &str, salt: &[u8], iterations: u32) -> ChaCha20Poly1305 {
+    let mut key = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, iterations, &mut key);
+    ChaCha20Poly1305::new(Key::from_slice(&key))
+}
+
+fn associated_data(version: u32) -> Vec<u8> {
+    format!("{}:{}", BUNDLE_KIND, version).into_bytes()
+}
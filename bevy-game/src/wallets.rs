@@ -0,0 +1,208 @@
+// Named wallets, kept apart per profile so a testnet wallet and a mainnet one
+// never end up in the same keychain entry.
+//
+// Each `--profile` (the default one too) has its own wallets.json in its config
+// directory listing the wallets created under it and which one is open; the
+// `WalletScope` of a profile and wallet name gives its keychain entries their
+// usernames, the profile after an '@' - which neither names can contain. The
+// default wallet of the default profile keeps the entry names older builds
+// used, so a wallet stored before there were profiles still opens without
+// `--profile`. The wallet picker lists the wallets of every profile, tagged
+// with the profile they belong to; only the current profile's can be opened
+// from it, the others need a launch with their `--profile`.
+//
+// Older builds kept one set of entries for every profile, so a wallet saved
+// under `--profile` is still under the default profile's names. The first
+// launch of a profile moves that wallet to the profile's default wallet when it
+// has none - unless the default profile has launched since, which makes the old
+// entries its own (`WalletIndex::legacy_checked`).
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+pub const WALLETS_FILE: &str = "wallets.json";
+/// The wallet every profile starts with
+pub const DEFAULT_WALLET: &str = "default";
+/// The label for running without `--profile`
+pub const DEFAULT_PROFILE: &str = "default";
+
+const WALLET_USERNAME: &str = "default-wallet";
+const ARCHIVE_USERNAME: &str = "previous-wallets";
+
+/// Wallet names follow the profile name rules: letters, digits, '-' and '_'.
+pub fn is_valid_wallet_name(name: &str) -> bool {
+    !name.is_empty() && name.len() <= 32 && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+pub fn profile_label(profile: Option<&str>) -> &str {
+    profile.unwrap_or(DEFAULT_PROFILE)
+}
+
+/// Which profile and wallet the keychain entries belong to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WalletScope {
+    pub profile: Option<String>,
+    pub name: String,
+}
+
+impl Default for WalletScope {
+    fn default() -> Self {
+        Self { profile: None, name: DEFAULT_WALLET.to_string() }
+    }
+}
+
+impl WalletScope {
+    pub fn new(profile: Option<&str>, name: &str) -> Self {
+        Self { profile: profile.map(str::to_string), name: name.to_string() }
+    }
+
+    /// The wallet entry's username: "default-wallet", "wallet-savings", "default-wallet@testnet"...
+    pub fn keychain_username(&self) -> String {
+        let wallet = if self.name == DEFAULT_WALLET { WALLET_USERNAME.to_string() } else { format!("wallet-{}", self.name) };
+        self.scoped(wallet)
+    }
+
+    /// The username of the archive of wallets this one's generates and imports replaced.
+    pub fn archive_username(&self) -> String {
+        let archive = if self.name == DEFAULT_WALLET { ARCHIVE_USERNAME.to_string() } else { format!("{}-{}", ARCHIVE_USERNAME, self.name) };
+        self.scoped(archive)
+    }
+
+    /// Whose entries this wallet used before they were scoped: a profile's default wallet shared the default profile's.
+    pub fn legacy(&self) -> Option<WalletScope> {
+        (self.profile.is_some() && self.name == DEFAULT_WALLET).then(WalletScope::default)
+    }
+
+    /// "savings · testnet"
+    pub fn label(&self) -> String {
+        format!("{} · {}", self.name, profile_label(self.profile.as_deref()))
+    }
+
+    fn scoped(&self, username: String) -> String {
+        match &self.profile {
+            Some(profile) => format!("{}@{}", username, profile),
+            None => username,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct WalletRecord {
+    pub name: String,
+    /// Noted once the wallet has one, so the picker can show it without the keychain
+    #[serde(default)]
+    pub address: Option<String>,
+}
+
+/// A profile's wallets.json.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct WalletIndex {
+    /// The wallet opened at launch
+    pub active: String,
+    pub wallets: Vec<WalletRecord>,
+    /// Set once the profile has launched with scoped entries and dealt with the shared ones older builds left
+    #[serde(default)]
+    pub legacy_checked: bool,
+}
+
+impl Default for WalletIndex {
+    fn default() -> Self {
+        Self {
+            active: DEFAULT_WALLET.to_string(),
+            wallets: vec![WalletRecord { name: DEFAULT_WALLET.to_string(), address: None }],
+            legacy_checked: false,
+        }
+    }
+}
+
+impl WalletIndex {
+    /// The file's index, or the default one; an active wallet missing from the list is added to it.
+    pub fn load(path: Option<PathBuf>) -> Self {
+        let mut index: Self = path
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        if !is_valid_wallet_name(&index.active) {
+            index.active = DEFAULT_WALLET.to_string();
+        }
+        if index.record(&index.active).is_none() {
+            index.wallets.insert(0, WalletRecord { name: index.active.clone(), address: None });
+        }
+        index
+    }
+
+    pub fn save(&self, path: Option<PathBuf>) -> std::io::Result<()> {
+        let Some(path) = path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self).unwrap_or_default())
+    }
+
+    pub fn record(&self, name: &str) -> Option<&WalletRecord> {
+        self.wallets.iter().find(|record| record.name == name)
+    }
+
+    /// Adds an empty wallet; the reason when the name is taken or not allowed.
+    pub fn add(&mut self, name: &str) -> Result<(), String> {
+        let name = name.trim();
+        if !is_valid_wallet_name(name) {
+            return Err("Wallet names are up to 32 letters, digits, '-' and '_'".to_string());
+        }
+        if self.record(name).is_some() {
+            return Err(format!("There's already a wallet called {}", name));
+        }
+        self.wallets.push(WalletRecord { name: name.to_string(), address: None });
+        Ok(())
+    }
+
+    /// False if `name` isn't listed.
+    pub fn open(&mut self, name: &str) -> bool {
+        if self.record(name).is_none() {
+            return false;
+        }
+        self.active = name.to_string();
+        true
+    }
+
+    /// Notes the active wallet's address; true if that changed anything.
+    pub fn set_address(&mut self, address: Option<&str>) -> bool {
+        let active = self.active.clone();
+        match self.wallets.iter_mut().find(|record| record.name == active) {
+            Some(record) if record.address.as_deref() != address => {
+                record.address = address.map(str::to_string);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// One row of the wallet picker.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PickerEntry {
+    pub scope: WalletScope,
+    pub address: Option<String>,
+    /// In the profile this run uses, so it can be opened from here
+    pub openable: bool,
+    /// The wallet open now
+    pub open: bool,
+}
+
+/// The picker's rows: the current profile's wallets first, in the order they
+/// were added, then every other profile's by profile name.
+pub fn picker_entries(current: &WalletScope, indexes: &[(Option<String>, WalletIndex)]) -> Vec<PickerEntry> {
+    let mut indexes: Vec<&(Option<String>, WalletIndex)> = indexes.iter().collect();
+    indexes.sort_by_key(|(profile, _)| (*profile != current.profile, profile.clone()));
+    let mut entries = Vec::new();
+    for (profile, index) in indexes {
+        let openable = *profile == current.profile;
+        for record in &index.wallets {
+            let scope = WalletScope::new(profile.as_deref(), &record.name);
+            entries.push(PickerEntry { open: scope == *current, scope, address: record.address.clone(), openable });
+        }
+    }
+    entries
+}